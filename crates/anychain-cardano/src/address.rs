@@ -1,6 +1,12 @@
 use {
-    crate::{format::CardanoFormat, public_key::CardanoPublicKey},
+    crate::{
+        format::CardanoFormat,
+        network::CardanoNetwork,
+        public_key::{CardanoPublicKey, KeyRole},
+    },
     anychain_core::{Address, AddressError, PublicKey},
+    cml_chain::byron::ByronAddress,
+    cml_crypto::Bip32PublicKey,
     core::{
         fmt::{Display, Formatter, Result as FmtResult},
         str::FromStr,
@@ -32,28 +38,53 @@ impl Address for CardanoAddress {
     }
 }
 
+impl CardanoAddress {
+    /// Returns the base address of the external key `index` and the stake
+    /// key 0 of the extended public key of a CIP-1852 account, as wallets do
+    pub fn from_account_key(
+        account_key: &Bip32PublicKey,
+        index: u32,
+        network: &CardanoNetwork,
+    ) -> Result<Self, AddressError> {
+        let payment = CardanoPublicKey::from_account_key(account_key, KeyRole::External, index)
+            .map_err(|e| AddressError::Message(e.to_string()))?;
+        let stake = CardanoPublicKey::from_account_key(account_key, KeyRole::Stake, 0)
+            .map_err(|e| AddressError::Message(e.to_string()))?;
+        Ok(payment.to_base_address(&stake, network))
+    }
+}
+
 impl FromStr for CardanoAddress {
     type Err = AddressError;
 
+    /// Parses a bech32 Shelley address or a base58 Byron address
     fn from_str(addr: &str) -> Result<Self, Self::Err> {
-        let address = cml_chain::address::Address::from_bech32(addr)
-            .map_err(|error| AddressError::InvalidAddress(format!("{:?}", error)))?;
-        Ok(Self(address))
+        match cml_chain::address::Address::from_bech32(addr) {
+            Ok(address) => Ok(Self(address)),
+            Err(error) => match ByronAddress::from_base58(addr) {
+                Ok(address) => Ok(Self(address.to_address())),
+                Err(_) => Err(AddressError::InvalidAddress(format!("{:?}", error))),
+            },
+        }
     }
 }
 
 impl Display for CardanoAddress {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{}", self.0.to_bech32(None).unwrap())
+        match &self.0 {
+            cml_chain::address::Address::Byron(address) => write!(f, "{}", address.to_base58()),
+            address => write!(f, "{}", address.to_bech32(None).unwrap()),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::CardanoAddress;
-    use crate::{format::CardanoFormat, network::CardanoNetwork};
-    use anychain_core::Address;
+    use crate::{CardanoFormat, CardanoNetwork, CardanoPointer, CardanoPublicKey};
+    use anychain_core::{Address, PublicKey};
     use curve25519_dalek::Scalar;
+    use std::str::FromStr;
 
     #[test]
     fn test() {
@@ -77,4 +108,44 @@ mod tests {
 
         println!("from: {}\nto: {}\n", from, to);
     }
+
+    #[test]
+    fn test_address_formats() {
+        let sk = Scalar::from_bytes_mod_order([7u8; 32]);
+        let pk = CardanoPublicKey::from_secret_key(&sk);
+        let stake = CardanoPublicKey::from_secret_key(&Scalar::from_bytes_mod_order([8u8; 32]));
+        let network = CardanoNetwork::Mainnet;
+
+        let enterprise = pk
+            .to_address(&CardanoFormat::Enterprise(network.clone()))
+            .unwrap();
+        assert!(enterprise.to_string().starts_with("addr1v"));
+
+        let pointer = CardanoPointer {
+            slot: 2498243,
+            tx_index: 27,
+            cert_index: 3,
+        };
+        let pointer = pk
+            .to_address(&CardanoFormat::Pointer(network.clone(), pointer))
+            .unwrap();
+        assert!(pointer.to_string().starts_with("addr1g"));
+
+        let base = pk.to_base_address(&stake, &network);
+        assert!(base.to_string().starts_with("addr1q"));
+
+        // Byron addresses are base58 and round-trip through FromStr
+        let byron = pk.to_address(&CardanoFormat::Byron(network)).unwrap();
+        assert!(byron.to_string().starts_with("Ae2"));
+        let testnet = pk
+            .to_byron_address(&[1u8; 32], &CardanoNetwork::Preprod)
+            .unwrap();
+        assert_ne!(byron, testnet);
+
+        for address in [enterprise, pointer, base, byron, testnet] {
+            let parsed = CardanoAddress::from_str(&address.to_string()).unwrap();
+            assert_eq!(address.to_string(), parsed.to_string());
+        }
+        assert!(CardanoAddress::from_str("addr1invalid").is_err());
+    }
 }
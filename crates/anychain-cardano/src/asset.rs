@@ -0,0 +1,101 @@
+use anychain_core::TransactionError;
+use cml_chain::{
+    assets::{AssetName, MultiAsset, Value},
+    PolicyId,
+};
+use cml_core::serialization::RawBytesEncoding;
+
+/// Represents an amount of a native asset, which is identified by the hex
+/// policy id of its minting script and its asset name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CardanoAsset {
+    pub policy_id: String,
+    pub asset_name: Vec<u8>,
+    pub amount: u64,
+}
+
+impl CardanoAsset {
+    pub fn new(policy_id: &str, asset_name: &[u8], amount: u64) -> Self {
+        CardanoAsset {
+            policy_id: policy_id.to_string(),
+            asset_name: asset_name.to_vec(),
+            amount,
+        }
+    }
+}
+
+/// Returns the multi-asset of `assets`, where the amounts of the same asset
+/// are added up
+pub fn to_multiasset(assets: &[CardanoAsset]) -> Result<MultiAsset, TransactionError> {
+    let mut multiasset = MultiAsset::new();
+    for asset in assets {
+        let policy_id = PolicyId::from_hex(&asset.policy_id)
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        let asset_name = AssetName::try_from(asset.asset_name.clone())
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        let amount = multiasset
+            .get(&policy_id, &asset_name)
+            .unwrap_or(0)
+            .checked_add(asset.amount)
+            .ok_or(TransactionError::Message(format!(
+                "Amount of asset {} overflows",
+                asset.policy_id
+            )))?;
+        multiasset.set(policy_id, asset_name, amount);
+    }
+    Ok(multiasset)
+}
+
+pub fn from_multiasset(multiasset: &MultiAsset) -> Vec<CardanoAsset> {
+    multiasset
+        .iter()
+        .flat_map(|(policy_id, assets)| {
+            assets.iter().map(|(asset_name, amount)| CardanoAsset {
+                policy_id: policy_id.to_hex(),
+                asset_name: asset_name.to_raw_bytes().to_vec(),
+                amount: *amount,
+            })
+        })
+        .collect()
+}
+
+/// Returns the value of `coin` lovelace and `assets`
+pub fn to_value(coin: u64, assets: &[CardanoAsset]) -> Result<Value, TransactionError> {
+    Ok(Value::new(coin, to_multiasset(assets)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY_ID: &str = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235";
+
+    #[test]
+    fn test_multiasset() {
+        let assets = vec![
+            CardanoAsset::new(POLICY_ID, b"HOSKY", 100),
+            CardanoAsset::new(POLICY_ID, b"", 1),
+            CardanoAsset::new(POLICY_ID, b"HOSKY", 50),
+        ];
+        let value = to_value(2_000_000, &assets).unwrap();
+        assert!(value.has_multiassets());
+
+        let mut assets = from_multiasset(&value.multiasset);
+        assets.sort();
+        assert_eq!(
+            vec![
+                CardanoAsset::new(POLICY_ID, b"", 1),
+                CardanoAsset::new(POLICY_ID, b"HOSKY", 150),
+            ],
+            assets
+        );
+
+        assert!(to_multiasset(&[CardanoAsset::new("00", b"", 1)]).is_err());
+        assert!(to_multiasset(&[CardanoAsset::new(POLICY_ID, &[0u8; 33], 1)]).is_err());
+        assert!(to_multiasset(&[
+            CardanoAsset::new(POLICY_ID, b"", u64::MAX),
+            CardanoAsset::new(POLICY_ID, b"", 1),
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use crate::CardanoAddress;
+use anychain_core::TransactionError;
+use cml_chain::{
+    address::{Address, RewardAddress},
+    certs::{Certificate, Credential, DRep, StakeCredential},
+};
+use cml_crypto::{Ed25519KeyHash, ScriptHash};
+
+/// The deposit of a stake key registration on mainnet and the testnets
+pub const KEY_DEPOSIT: u64 = 2_000_000;
+
+/// Represents the DRep a stake credential delegates its votes to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoDRep {
+    /// The hex or bech32 (drep1...) key hash of a DRep
+    Key(String),
+    /// The hex script hash of a DRep
+    Script(String),
+    AlwaysAbstain,
+    AlwaysNoConfidence,
+}
+
+/// Represents a certificate of the stake credential of a reward address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoCertificate {
+    /// Registers the stake credential with the deposit of the protocol
+    /// parameters. It needs no witness
+    StakeRegistration { stake_address: CardanoAddress },
+    /// Conway `reg_cert`, which registers the stake credential with an
+    /// explicit deposit
+    Registration {
+        stake_address: CardanoAddress,
+        deposit: u64,
+    },
+    /// Deregisters the stake credential, refunding the deposit of the
+    /// protocol parameters
+    StakeDeregistration { stake_address: CardanoAddress },
+    /// Conway `unreg_cert`, which refunds an explicit deposit
+    Unregistration {
+        stake_address: CardanoAddress,
+        deposit: u64,
+    },
+    /// Delegates the stake to the pool of a hex or bech32 (pool1...) id
+    StakeDelegation {
+        stake_address: CardanoAddress,
+        pool: String,
+    },
+    /// Delegates the voting power of the stake to a DRep
+    VoteDelegation {
+        stake_address: CardanoAddress,
+        drep: CardanoDRep,
+    },
+}
+
+/// Represents a withdrawal of the rewards of a reward address, which has to
+/// withdraw the whole balance of the account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardanoWithdrawal {
+    pub reward_address: CardanoAddress,
+    pub amount: u64,
+}
+
+fn key_hash(hash: &str) -> Result<Ed25519KeyHash, TransactionError> {
+    Ed25519KeyHash::from_hex(hash)
+        .map_err(|e| TransactionError::Message(e.to_string()))
+        .or_else(|_| {
+            Ed25519KeyHash::from_bech32(hash).map_err(|e| TransactionError::Message(e.to_string()))
+        })
+}
+
+/// Returns the reward address of a `CardanoAddress`
+pub(crate) fn reward_address(address: &CardanoAddress) -> Result<RewardAddress, TransactionError> {
+    match &address.0 {
+        Address::Reward(address) => Ok(address.clone()),
+        _ => Err(TransactionError::Message(format!(
+            "{address} is not a reward address"
+        ))),
+    }
+}
+
+/// Returns the key hash that has to witness a credential, if it is a key
+pub(crate) fn witness_key_hash(credential: &StakeCredential) -> Option<Ed25519KeyHash> {
+    match credential {
+        Credential::PubKey { hash, .. } => Some(*hash),
+        Credential::Script { .. } => None,
+    }
+}
+
+impl CardanoDRep {
+    pub fn to_drep(&self) -> Result<DRep, TransactionError> {
+        match self {
+            Self::Key(hash) => Ok(DRep::new_key(key_hash(hash)?)),
+            Self::Script(hash) => Ok(DRep::new_script(
+                ScriptHash::from_hex(hash).map_err(|e| TransactionError::Message(e.to_string()))?,
+            )),
+            Self::AlwaysAbstain => Ok(DRep::new_always_abstain()),
+            Self::AlwaysNoConfidence => Ok(DRep::new_always_no_confidence()),
+        }
+    }
+
+    pub fn from_drep(drep: &DRep) -> Self {
+        match drep {
+            DRep::Key { pool, .. } => Self::Key(pool.to_hex()),
+            DRep::Script { script_hash, .. } => Self::Script(script_hash.to_hex()),
+            DRep::AlwaysAbstain { .. } => Self::AlwaysAbstain,
+            DRep::AlwaysNoConfidence { .. } => Self::AlwaysNoConfidence,
+        }
+    }
+}
+
+impl CardanoCertificate {
+    pub fn stake_address(&self) -> &CardanoAddress {
+        match self {
+            Self::StakeRegistration { stake_address }
+            | Self::Registration { stake_address, .. }
+            | Self::StakeDeregistration { stake_address }
+            | Self::Unregistration { stake_address, .. }
+            | Self::StakeDelegation { stake_address, .. }
+            | Self::VoteDelegation { stake_address, .. } => stake_address,
+        }
+    }
+
+    /// Returns the stake key hash that has to sign the certificate, which is
+    /// None for a legacy registration or a script credential
+    pub fn signer(&self) -> Result<Option<Ed25519KeyHash>, TransactionError> {
+        let credential = reward_address(self.stake_address())?.payment;
+        match self {
+            Self::StakeRegistration { .. } => Ok(None),
+            _ => Ok(witness_key_hash(&credential)),
+        }
+    }
+
+    pub fn to_certificate(&self) -> Result<Certificate, TransactionError> {
+        let credential = reward_address(self.stake_address())?.payment;
+        Ok(match self {
+            Self::StakeRegistration { .. } => Certificate::new_stake_registration(credential),
+            Self::Registration { deposit, .. } => Certificate::new_reg_cert(credential, *deposit),
+            Self::StakeDeregistration { .. } => Certificate::new_stake_deregistration(credential),
+            Self::Unregistration { deposit, .. } => {
+                Certificate::new_unreg_cert(credential, *deposit)
+            }
+            Self::StakeDelegation { pool, .. } => {
+                Certificate::new_stake_delegation(credential, key_hash(pool)?)
+            }
+            Self::VoteDelegation { drep, .. } => {
+                Certificate::new_vote_deleg_cert(credential, drep.to_drep()?)
+            }
+        })
+    }
+
+    /// Returns the certificate of a stake credential on the network of
+    /// `network_id`, or None for pool and governance certificates
+    pub fn from_certificate(certificate: &Certificate, network_id: u8) -> Option<Self> {
+        let stake_address = |credential: &StakeCredential| {
+            CardanoAddress(RewardAddress::new(network_id, credential.clone()).to_address())
+        };
+        match certificate {
+            Certificate::StakeRegistration(cert) => Some(Self::StakeRegistration {
+                stake_address: stake_address(&cert.stake_credential),
+            }),
+            Certificate::RegCert(cert) => Some(Self::Registration {
+                stake_address: stake_address(&cert.stake_credential),
+                deposit: cert.deposit,
+            }),
+            Certificate::StakeDeregistration(cert) => Some(Self::StakeDeregistration {
+                stake_address: stake_address(&cert.stake_credential),
+            }),
+            Certificate::UnregCert(cert) => Some(Self::Unregistration {
+                stake_address: stake_address(&cert.stake_credential),
+                deposit: cert.deposit,
+            }),
+            Certificate::StakeDelegation(cert) => Some(Self::StakeDelegation {
+                stake_address: stake_address(&cert.stake_credential),
+                pool: cert.pool.to_hex(),
+            }),
+            Certificate::VoteDelegCert(cert) => Some(Self::VoteDelegation {
+                stake_address: stake_address(&cert.stake_credential),
+                drep: CardanoDRep::from_drep(&cert.d_rep),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CardanoFormat, CardanoNetwork, CardanoPublicKey};
+    use anychain_core::PublicKey;
+    use curve25519_dalek::Scalar;
+
+    const POOL: &str = "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735";
+
+    #[test]
+    fn test_certificates() {
+        let stake_key = CardanoPublicKey::from_secret_key(&Scalar::from_bytes_mod_order([5u8; 32]));
+        let stake_address = stake_key
+            .to_address(&CardanoFormat::Reward(CardanoNetwork::Mainnet))
+            .unwrap();
+        let certificates = vec![
+            CardanoCertificate::StakeRegistration {
+                stake_address: stake_address.clone(),
+            },
+            CardanoCertificate::Registration {
+                stake_address: stake_address.clone(),
+                deposit: KEY_DEPOSIT,
+            },
+            CardanoCertificate::StakeDelegation {
+                stake_address: stake_address.clone(),
+                pool: POOL.to_string(),
+            },
+            CardanoCertificate::VoteDelegation {
+                stake_address: stake_address.clone(),
+                drep: CardanoDRep::AlwaysAbstain,
+            },
+            CardanoCertificate::Unregistration {
+                stake_address: stake_address.clone(),
+                deposit: KEY_DEPOSIT,
+            },
+        ];
+        for certificate in certificates {
+            let cert = certificate.to_certificate().unwrap();
+            assert_eq!(
+                Some(certificate.clone()),
+                CardanoCertificate::from_certificate(&cert, 1)
+            );
+        }
+
+        // Pool ids may be bech32
+        let pool = Ed25519KeyHash::from_hex(POOL).unwrap();
+        let delegation = CardanoCertificate::StakeDelegation {
+            stake_address: stake_address.clone(),
+            pool: pool.to_bech32("pool").unwrap(),
+        };
+        let cert = delegation.to_certificate().unwrap();
+        assert_eq!(
+            Some(CardanoCertificate::StakeDelegation {
+                stake_address: stake_address.clone(),
+                pool: pool.to_hex(),
+            }),
+            CardanoCertificate::from_certificate(&cert, 1)
+        );
+
+        // Only the legacy registration needs no witness of the stake key
+        let hash = witness_key_hash(&stake_key.credential());
+        let registration = CardanoCertificate::StakeRegistration {
+            stake_address: stake_address.clone(),
+        };
+        assert_eq!(None, registration.signer().unwrap());
+        let deregistration = CardanoCertificate::StakeDeregistration { stake_address };
+        assert_eq!(hash, deregistration.signer().unwrap());
+
+        // A payment address has no stake credential to certify
+        let enterprise = stake_key
+            .to_address(&CardanoFormat::Enterprise(CardanoNetwork::Mainnet))
+            .unwrap();
+        let certificate = CardanoCertificate::StakeDeregistration {
+            stake_address: enterprise,
+        };
+        assert!(certificate.to_certificate().is_err());
+        assert!(CardanoDRep::Key("drep".to_string()).to_drep().is_err());
+    }
+}
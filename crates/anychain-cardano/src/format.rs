@@ -9,8 +9,19 @@ pub enum CardanoFormat {
     Base(CardanoNetwork),
     Enterprise(CardanoNetwork),
     Reward(CardanoNetwork),
+    /// An address whose stake credential is the certificate at the pointer
+    Pointer(CardanoNetwork, CardanoPointer),
+    /// An Icarus-style bootstrap address
     Byron(CardanoNetwork),
 }
+
+/// Points to a stake registration certificate on the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CardanoPointer {
+    pub slot: u64,
+    pub tx_index: u16,
+    pub cert_index: u64,
+}
 impl CardanoFormat {}
 
 impl Default for CardanoFormat {
@@ -27,6 +38,7 @@ impl fmt::Display for CardanoFormat {
             CardanoFormat::Base(_) => write!(f, "base"),
             CardanoFormat::Enterprise(_) => write!(f, "enterprise"),
             CardanoFormat::Reward(_) => write!(f, "reward"),
+            CardanoFormat::Pointer(..) => write!(f, "pointer"),
             CardanoFormat::Byron(_) => write!(f, "byron"),
         }
     }
@@ -50,6 +62,18 @@ mod tests {
             CardanoFormat::Reward(CardanoNetwork::Mainnet).to_string(),
             "reward"
         );
+        assert_eq!(
+            CardanoFormat::Pointer(
+                CardanoNetwork::Mainnet,
+                CardanoPointer {
+                    slot: 1,
+                    tx_index: 2,
+                    cert_index: 3
+                }
+            )
+            .to_string(),
+            "pointer"
+        );
         assert_eq!(
             CardanoFormat::Byron(CardanoNetwork::Mainnet).to_string(),
             "byron"
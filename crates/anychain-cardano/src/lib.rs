@@ -1,14 +1,26 @@
 pub mod address;
 pub mod amount;
+pub mod asset;
+pub mod certificate;
 pub mod format;
+pub mod message;
+pub mod metadata;
 pub mod network;
+pub mod plutus;
+pub mod protocol_params;
 pub mod public_key;
 pub mod transaction;
 mod util;
 
 pub use self::address::*;
 pub use self::amount::*;
+pub use self::asset::*;
+pub use self::certificate::*;
 pub use self::format::*;
+pub use self::message::*;
+pub use self::metadata::*;
 pub use self::network::*;
+pub use self::plutus::*;
+pub use self::protocol_params::*;
 pub use self::public_key::*;
 pub use self::transaction::*;
@@ -0,0 +1,383 @@
+use crate::{certificate::witness_key_hash, CardanoAddress, CardanoPublicKey};
+use anychain_core::TransactionError;
+use cbor_event::{de::Deserializer, se::Serializer, Len, Special, Type};
+use cml_chain::address::Address;
+use cml_crypto::{blake2b224, Ed25519KeyHash};
+use std::io::BufRead;
+
+/// The COSE algorithm id of EdDSA
+pub const COSE_ALGORITHM_EDDSA: i128 = -8;
+
+/// The COSE key type of octet key pairs
+const COSE_KEY_TYPE_OKP: i128 = 1;
+
+/// The COSE curve id of Ed25519
+const COSE_CURVE_ED25519: i128 = 6;
+
+/// The CBOR tag of a COSE_Sign1 structure
+const COSE_SIGN1_TAG: u64 = 18;
+
+/// Represents a label or a value of a COSE header map
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HeaderValue {
+    Int(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Bool(bool),
+}
+
+fn cbor_error(e: cbor_event::Error) -> TransactionError {
+    TransactionError::Crate("cbor_event", format!("{e:?}"))
+}
+
+fn read_value<R: BufRead>(raw: &mut Deserializer<R>) -> cbor_event::Result<HeaderValue> {
+    match raw.cbor_type()? {
+        Type::UnsignedInteger => Ok(HeaderValue::Int(raw.unsigned_integer()? as i128)),
+        Type::NegativeInteger => Ok(HeaderValue::Int(raw.negative_integer()? as i128)),
+        Type::Bytes => Ok(HeaderValue::Bytes(raw.bytes()?)),
+        Type::Text => Ok(HeaderValue::Text(raw.text()?)),
+        Type::Special => Ok(HeaderValue::Bool(raw.bool()?)),
+        other => Err(cbor_event::Error::CustomError(format!(
+            "Unsupported header value of type {other:?}"
+        ))),
+    }
+}
+
+fn read_map<R: BufRead>(
+    raw: &mut Deserializer<R>,
+) -> cbor_event::Result<Vec<(HeaderValue, HeaderValue)>> {
+    let Len::Len(len) = raw.map()? else {
+        return Err(cbor_event::Error::IndefiniteLenNotSupported(Type::Map));
+    };
+    (0..len)
+        .map(|_| Ok((read_value(raw)?, read_value(raw)?)))
+        .collect()
+}
+
+fn write_value(
+    serializer: &mut Serializer<Vec<u8>>,
+    value: &HeaderValue,
+) -> cbor_event::Result<()> {
+    match value {
+        HeaderValue::Int(int) if *int >= 0 => serializer.write_unsigned_integer(*int as u64)?,
+        HeaderValue::Int(int) => serializer.write_negative_integer(*int as i64)?,
+        HeaderValue::Bytes(bytes) => serializer.write_bytes(bytes)?,
+        HeaderValue::Text(text) => serializer.write_text(text)?,
+        HeaderValue::Bool(bool) => serializer.write_special(Special::Bool(*bool))?,
+    };
+    Ok(())
+}
+
+fn write_map(entries: &[(HeaderValue, HeaderValue)]) -> Vec<u8> {
+    let mut serializer = Serializer::new_vec();
+    // Writing to a vector cannot fail
+    serializer
+        .write_map(Len::Len(entries.len() as u64))
+        .unwrap();
+    for (label, value) in entries {
+        write_value(&mut serializer, label).unwrap();
+        write_value(&mut serializer, value).unwrap();
+    }
+    serializer.finalize()
+}
+
+fn find<'a>(
+    entries: &'a [(HeaderValue, HeaderValue)],
+    label: &HeaderValue,
+) -> Option<&'a HeaderValue> {
+    entries
+        .iter()
+        .find(|(key, _)| key == label)
+        .map(|(_, value)| value)
+}
+
+/// Represents a CIP-8 message signed by the key of an address, which is the
+/// COSE_Sign1 structure returned by the CIP-30 `signData` of wallets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardanoMessage {
+    /// The serialized protected header, which holds the algorithm and the
+    /// address and is covered by the signature
+    protected: Vec<u8>,
+    address: CardanoAddress,
+    payload: Vec<u8>,
+    hashed: bool,
+    signature: Option<Vec<u8>>,
+}
+
+impl CardanoMessage {
+    /// Returns an unsigned message of `address`. If `hashed`, the payload is
+    /// the blake2b-224 hash of `message` instead of the message itself
+    pub fn new(address: &CardanoAddress, message: &[u8], hashed: bool) -> Self {
+        let protected = write_map(&[
+            (HeaderValue::Int(1), HeaderValue::Int(COSE_ALGORITHM_EDDSA)),
+            (
+                HeaderValue::Text("address".to_string()),
+                HeaderValue::Bytes(address.0.to_raw_bytes()),
+            ),
+        ]);
+        let payload = match hashed {
+            true => blake2b224(message).to_vec(),
+            false => message.to_vec(),
+        };
+        CardanoMessage {
+            protected,
+            address: address.clone(),
+            payload,
+            hashed,
+            signature: None,
+        }
+    }
+
+    pub fn address(&self) -> &CardanoAddress {
+        &self.address
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn is_hashed(&self) -> bool {
+        self.hashed
+    }
+
+    pub fn signature(&self) -> Option<&[u8]> {
+        self.signature.as_deref()
+    }
+
+    /// Returns the Sig_structure ["Signature1", protected, h'', payload],
+    /// which is signed by the Ed25519 key of the address
+    pub fn signed_data(&self) -> Vec<u8> {
+        let mut serializer = Serializer::new_vec();
+        serializer.write_array(Len::Len(4)).unwrap();
+        serializer.write_text("Signature1").unwrap();
+        serializer.write_bytes(&self.protected).unwrap();
+        serializer.write_bytes([]).unwrap();
+        serializer.write_bytes(&self.payload).unwrap();
+        serializer.finalize()
+    }
+
+    /// Sets the 64-byte signature of the signed data and returns the
+    /// COSE_Sign1 structure
+    pub fn sign(&mut self, signature: Vec<u8>) -> Result<Vec<u8>, TransactionError> {
+        if signature.len() != 64 {
+            return Err(TransactionError::Message(format!(
+                "Invalid signature length {}",
+                signature.len()
+            )));
+        }
+        self.signature = Some(signature);
+        self.to_bytes()
+    }
+
+    /// Returns true if the message is signed by `public_key` and the key is
+    /// the payment or stake key of the address
+    pub fn verify(&self, public_key: &CardanoPublicKey) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+        let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+            return false;
+        };
+        let key_hash = Ed25519KeyHash::from(blake2b224(public_key.0.as_bytes()));
+        let credential = match &self.address.0 {
+            Address::Base(address) => [Some(&address.payment), Some(&address.stake)],
+            address => [address.payment_cred(), None],
+        };
+        credential
+            .into_iter()
+            .flatten()
+            .any(|credential| witness_key_hash(credential) == Some(key_hash))
+            && public_key
+                .0
+                .verify_strict(&self.signed_data(), &signature)
+                .is_ok()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let signature = self.signature.as_ref().ok_or(TransactionError::Message(
+            "The message is not signed".to_string(),
+        ))?;
+        let unprotected = write_map(&[(
+            HeaderValue::Text("hashed".to_string()),
+            HeaderValue::Bool(self.hashed),
+        )]);
+        let mut serializer = Serializer::new_vec();
+        serializer.write_array(Len::Len(4)).unwrap();
+        serializer.write_bytes(&self.protected).unwrap();
+        serializer.write_raw_bytes(&unprotected).unwrap();
+        serializer.write_bytes(&self.payload).unwrap();
+        serializer.write_bytes(signature).unwrap();
+        Ok(serializer.finalize())
+    }
+
+    /// Returns the message of a COSE_Sign1 structure, which may be tagged
+    pub fn from_bytes(stream: &[u8]) -> Result<Self, TransactionError> {
+        let mut raw = Deserializer::from(std::io::Cursor::new(stream));
+        if raw.cbor_type().map_err(cbor_error)? == Type::Tag {
+            let tag = raw.tag().map_err(cbor_error)?;
+            if tag != COSE_SIGN1_TAG {
+                return Err(TransactionError::Message(format!(
+                    "Invalid COSE_Sign1 tag {tag}"
+                )));
+            }
+        }
+        if raw.array().map_err(cbor_error)? != Len::Len(4) {
+            return Err(TransactionError::Message(
+                "A COSE_Sign1 structure has 4 elements".to_string(),
+            ));
+        }
+        let protected = raw.bytes().map_err(cbor_error)?;
+        let unprotected = read_map(&mut raw).map_err(cbor_error)?;
+        let payload = raw.bytes().map_err(cbor_error)?;
+        let signature = raw.bytes().map_err(cbor_error)?;
+
+        let headers = read_map(&mut Deserializer::from(std::io::Cursor::new(&protected)))
+            .map_err(cbor_error)?;
+        if find(&headers, &HeaderValue::Int(1)) != Some(&HeaderValue::Int(COSE_ALGORITHM_EDDSA)) {
+            return Err(TransactionError::Message(
+                "The message is not signed with EdDSA".to_string(),
+            ));
+        }
+        let Some(HeaderValue::Bytes(address)) =
+            find(&headers, &HeaderValue::Text("address".to_string()))
+        else {
+            return Err(TransactionError::Message(
+                "The protected header has no address".to_string(),
+            ));
+        };
+        let address = Address::from_raw_bytes(address)
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        let hashed = find(&unprotected, &HeaderValue::Text("hashed".to_string()))
+            == Some(&HeaderValue::Bool(true));
+
+        Ok(CardanoMessage {
+            protected,
+            address: CardanoAddress(address),
+            payload,
+            hashed,
+            signature: Some(signature),
+        })
+    }
+}
+
+impl CardanoPublicKey {
+    /// Returns the COSE_Key {1: OKP, 3: EdDSA, -1: Ed25519, -2: key} of the
+    /// key, which CIP-30 `signData` returns along with the signature
+    pub fn to_cose_key(&self) -> Vec<u8> {
+        write_map(&[
+            (HeaderValue::Int(1), HeaderValue::Int(COSE_KEY_TYPE_OKP)),
+            (HeaderValue::Int(3), HeaderValue::Int(COSE_ALGORITHM_EDDSA)),
+            (HeaderValue::Int(-1), HeaderValue::Int(COSE_CURVE_ED25519)),
+            (
+                HeaderValue::Int(-2),
+                HeaderValue::Bytes(self.0.as_bytes().to_vec()),
+            ),
+        ])
+    }
+
+    pub fn from_cose_key(cose_key: &[u8]) -> Result<Self, TransactionError> {
+        let entries = read_map(&mut Deserializer::from(std::io::Cursor::new(cose_key)))
+            .map_err(cbor_error)?;
+        if find(&entries, &HeaderValue::Int(1)) != Some(&HeaderValue::Int(COSE_KEY_TYPE_OKP))
+            || find(&entries, &HeaderValue::Int(-1)) != Some(&HeaderValue::Int(COSE_CURVE_ED25519))
+        {
+            return Err(TransactionError::Message(
+                "The COSE key is not an Ed25519 key".to_string(),
+            ));
+        }
+        let Some(HeaderValue::Bytes(key)) = find(&entries, &HeaderValue::Int(-2)) else {
+            return Err(TransactionError::Message(
+                "The COSE key has no public key".to_string(),
+            ));
+        };
+        let key: [u8; 32] = key.as_slice().try_into().map_err(|_| {
+            TransactionError::Message(format!("Invalid public key length {}", key.len()))
+        })?;
+        let key = ed25519_dalek::VerifyingKey::from_bytes(&key)
+            .map_err(|e| TransactionError::Crate("ed25519_dalek", format!("{e:?}")))?;
+        Ok(CardanoPublicKey(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CardanoFormat, CardanoNetwork};
+    use anychain_core::PublicKey;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_cose_sign1() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = CardanoPublicKey(signing_key.verifying_key());
+        let address = public_key
+            .to_address(&CardanoFormat::Enterprise(CardanoNetwork::Preprod))
+            .unwrap();
+
+        let mut message = CardanoMessage::new(&address, b"Login to anychain", false);
+        assert!(message.to_bytes().is_err());
+        assert!(!message.verify(&public_key));
+
+        // The protected header is {1: -8, "address": h'...'}
+        let signed_data = message.signed_data();
+        assert_eq!(
+            [
+                &[0x84, 0x6a][..],
+                b"Signature1",
+                &[0x58, 0x2a, 0xa2, 0x01, 0x27, 0x67]
+            ]
+            .concat(),
+            signed_data[..18]
+        );
+
+        let signature = signing_key.sign(&signed_data).to_bytes().to_vec();
+        let cose_sign1 = message.sign(signature).unwrap();
+        assert!(message.verify(&public_key));
+
+        let decoded = CardanoMessage::from_bytes(&cose_sign1).unwrap();
+        assert_eq!(message, decoded);
+        assert_eq!(b"Login to anychain", decoded.payload());
+        assert!(!decoded.is_hashed());
+        let tagged = [&[0xd2][..], &cose_sign1].concat();
+        assert_eq!(message, CardanoMessage::from_bytes(&tagged).unwrap());
+
+        // Another key, or the key of another address, does not verify
+        let other = CardanoPublicKey(SigningKey::from_bytes(&[8u8; 32]).verifying_key());
+        assert!(!message.verify(&other));
+        let mut forged = CardanoMessage::new(
+            &other
+                .to_address(&CardanoFormat::Enterprise(CardanoNetwork::Preprod))
+                .unwrap(),
+            b"Login to anychain",
+            false,
+        );
+        forged.signature = message.signature.clone();
+        assert!(!forged.verify(&public_key));
+
+        let hashed = CardanoMessage::new(&address, b"Login to anychain", true);
+        assert_eq!(28, hashed.payload().len());
+        assert_ne!(message.signed_data(), hashed.signed_data());
+
+        assert!(CardanoMessage::from_bytes(&cose_sign1[1..]).is_err());
+        assert!(message.clone().sign(vec![0u8; 63]).is_err());
+    }
+
+    #[test]
+    fn test_cose_key() {
+        let public_key = CardanoPublicKey(SigningKey::from_bytes(&[7u8; 32]).verifying_key());
+        let cose_key = public_key.to_cose_key();
+        assert_eq!(
+            vec![0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20],
+            cose_key[..10]
+        );
+        assert_eq!(
+            public_key,
+            CardanoPublicKey::from_cose_key(&cose_key).unwrap()
+        );
+
+        let mut x25519 = cose_key.clone();
+        x25519[6] = 0x04;
+        assert!(CardanoPublicKey::from_cose_key(&x25519).is_err());
+        assert!(CardanoPublicKey::from_cose_key(&cose_key[..20]).is_err());
+    }
+}
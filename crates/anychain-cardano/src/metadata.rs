@@ -0,0 +1,167 @@
+use anychain_core::TransactionError;
+use cml_chain::auxdata::{Metadata, MetadatumMap, TransactionMetadatum};
+use cml_core::Int;
+
+/// The metadata label of CIP-20 transaction messages
+pub const CIP20_LABEL: u64 = 674;
+
+/// The maximum length in bytes of a metadata text or byte string
+pub const MAX_METADATUM_LENGTH: usize = 64;
+
+/// Represents a value of the auxiliary metadata of a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoMetadatum {
+    /// An integer in the range of -2^64 to 2^64 - 1
+    Int(i128),
+    /// A byte string of at most 64 bytes
+    Bytes(Vec<u8>),
+    /// A text of at most 64 bytes
+    Text(String),
+    List(Vec<CardanoMetadatum>),
+    Map(Vec<(CardanoMetadatum, CardanoMetadatum)>),
+}
+
+impl CardanoMetadatum {
+    /// Returns a CIP-20 message to be set under `CIP20_LABEL`, whose text is
+    /// split into lines of at most 64 bytes
+    pub fn cip20(message: &str) -> Self {
+        let mut lines = vec![];
+        let mut line = String::new();
+        for c in message.chars() {
+            if line.len() + c.len_utf8() > MAX_METADATUM_LENGTH {
+                lines.push(CardanoMetadatum::Text(std::mem::take(&mut line)));
+            }
+            line.push(c);
+        }
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(CardanoMetadatum::Text(line));
+        }
+        CardanoMetadatum::Map(vec![(
+            CardanoMetadatum::Text("msg".to_string()),
+            CardanoMetadatum::List(lines),
+        )])
+    }
+
+    /// Returns the message of a CIP-20 metadatum
+    pub fn cip20_message(&self) -> Option<String> {
+        let CardanoMetadatum::Map(entries) = self else {
+            return None;
+        };
+        let (_, lines) = entries
+            .iter()
+            .find(|(key, _)| *key == CardanoMetadatum::Text("msg".to_string()))?;
+        let CardanoMetadatum::List(lines) = lines else {
+            return None;
+        };
+        lines
+            .iter()
+            .map(|line| match line {
+                CardanoMetadatum::Text(line) => Some(line.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn to_metadatum(&self) -> Result<TransactionMetadatum, TransactionError> {
+        match self {
+            Self::Int(int) => {
+                let int = Int::try_from(*int)
+                    .map_err(|e| TransactionError::Crate("cml_core", format!("{e:?}")))?;
+                Ok(TransactionMetadatum::new_int(int))
+            }
+            Self::Bytes(bytes) => TransactionMetadatum::new_bytes(bytes.clone())
+                .map_err(|e| TransactionError::Message(e.to_string())),
+            Self::Text(text) => TransactionMetadatum::new_text(text.clone())
+                .map_err(|e| TransactionError::Message(e.to_string())),
+            Self::List(elements) => Ok(TransactionMetadatum::new_list(
+                elements
+                    .iter()
+                    .map(|element| element.to_metadatum())
+                    .collect::<Result<_, _>>()?,
+            )),
+            Self::Map(entries) => {
+                let mut map = MetadatumMap::new();
+                for (key, value) in entries {
+                    map.set(key.to_metadatum()?, value.to_metadatum()?);
+                }
+                Ok(TransactionMetadatum::new_map(map))
+            }
+        }
+    }
+
+    pub fn from_metadatum(metadatum: &TransactionMetadatum) -> Self {
+        match metadatum {
+            TransactionMetadatum::Int(int) => Self::Int(i128::from(int)),
+            TransactionMetadatum::Bytes { bytes, .. } => Self::Bytes(bytes.clone()),
+            TransactionMetadatum::Text { text, .. } => Self::Text(text.clone()),
+            TransactionMetadatum::List { elements, .. } => {
+                Self::List(elements.iter().map(Self::from_metadatum).collect())
+            }
+            TransactionMetadatum::Map(map) => Self::Map(
+                map.entries
+                    .iter()
+                    .map(|(key, value)| (Self::from_metadatum(key), Self::from_metadatum(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Returns the auxiliary metadata of labelled metadata
+pub fn to_metadata(metadata: &[(u64, CardanoMetadatum)]) -> Result<Metadata, TransactionError> {
+    let mut result = Metadata::new();
+    for (label, metadatum) in metadata {
+        result.set(*label, metadatum.to_metadatum()?);
+    }
+    Ok(result)
+}
+
+pub fn from_metadata(metadata: &Metadata) -> Vec<(u64, CardanoMetadatum)> {
+    metadata
+        .entries
+        .iter()
+        .map(|(label, metadatum)| (*label, CardanoMetadatum::from_metadatum(metadatum)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cip20() {
+        let message = "Invoice-No: 1234567890, ".repeat(4) + "ünïcödé";
+        let metadatum = CardanoMetadatum::cip20(&message);
+        let CardanoMetadatum::Map(entries) = &metadatum else {
+            panic!("CIP-20 metadatum is not a map");
+        };
+        let CardanoMetadatum::List(lines) = &entries[0].1 else {
+            panic!("CIP-20 message is not a list");
+        };
+        assert_eq!(2, lines.len());
+        for line in lines {
+            assert!(matches!(line, CardanoMetadatum::Text(line) if line.len() <= 64));
+        }
+        assert_eq!(Some(message.clone()), metadatum.cip20_message());
+
+        let metadata = vec![
+            (CIP20_LABEL, metadatum),
+            (
+                1,
+                CardanoMetadatum::List(vec![
+                    CardanoMetadatum::Int(-1),
+                    CardanoMetadatum::Int(u64::MAX as i128),
+                    CardanoMetadatum::Bytes(vec![1, 2, 3]),
+                ]),
+            ),
+        ];
+        let cml = to_metadata(&metadata).unwrap();
+        assert_eq!(metadata, from_metadata(&cml));
+
+        assert!(CardanoMetadatum::Text("a".repeat(65))
+            .to_metadatum()
+            .is_err());
+        assert!(CardanoMetadatum::Int(i128::MAX).to_metadatum().is_err());
+        assert_eq!(None, CardanoMetadatum::Int(1).cip20_message());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{CardanoAddress, CardanoNetwork, CardanoPublicKey};
+use anychain_core::TransactionError;
+use cml_chain::{
+    address::EnterpriseAddress,
+    builders::witness_builder::{PartialPlutusWitness, PlutusScriptWitness},
+    certs::Credential,
+    crypto::hash::hash_plutus_data,
+    plutus::{ExUnits, PlutusData, PlutusScript, PlutusV2Script, PlutusV3Script},
+    transaction::DatumOption,
+    Deserialize,
+};
+use cml_crypto::{blake2b224, Ed25519KeyHash, ScriptHash};
+
+/// Represents a Plutus validator by the compiled code of the script, such
+/// as the `compiledCode` of a CIP-57 `plutus.json` blueprint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoPlutusScript {
+    V2(Vec<u8>),
+    V3(Vec<u8>),
+}
+
+/// Represents the datum of a script output, as CBOR encoded Plutus data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardanoDatum {
+    /// A datum of which the output holds the hash, so it is supplied in the
+    /// witness set of the spending transaction
+    Hash(Vec<u8>),
+    /// A datum held by the output itself
+    Inline(Vec<u8>),
+}
+
+/// Represents the execution units of a script, as evaluated by the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CardanoExUnits {
+    pub mem: u64,
+    pub steps: u64,
+}
+
+/// Represents the spend of an output locked by a Plutus script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardanoPlutusSpend {
+    pub script: CardanoPlutusScript,
+    pub datum: CardanoDatum,
+    /// The CBOR encoded Plutus data of the redeemer
+    pub redeemer: Vec<u8>,
+    pub ex_units: CardanoExUnits,
+    /// The keys the script expects among the signatories of the transaction,
+    /// which have to sign it
+    pub required_signers: Vec<CardanoPublicKey>,
+}
+
+pub(crate) fn plutus_data(cbor: &[u8]) -> Result<PlutusData, TransactionError> {
+    PlutusData::from_cbor_bytes(cbor).map_err(|e| TransactionError::Message(e.to_string()))
+}
+
+impl CardanoPlutusScript {
+    pub fn to_script(&self) -> PlutusScript {
+        match self {
+            Self::V2(code) => PlutusScript::PlutusV2(PlutusV2Script::new(code.clone())),
+            Self::V3(code) => PlutusScript::PlutusV3(PlutusV3Script::new(code.clone())),
+        }
+    }
+
+    /// Returns the name of the language of the script in the cost models of
+    /// the protocol parameters
+    pub fn language(&self) -> &'static str {
+        match self {
+            Self::V2(_) => "PlutusV2",
+            Self::V3(_) => "PlutusV3",
+        }
+    }
+
+    pub fn hash(&self) -> ScriptHash {
+        self.to_script().hash()
+    }
+
+    /// Returns the enterprise address of the script, which outputs are
+    /// locked at
+    pub fn to_address(&self, network: &CardanoNetwork) -> CardanoAddress {
+        let credential = Credential::new_script(self.hash());
+        CardanoAddress(EnterpriseAddress::new(network.info().network_id(), credential).to_address())
+    }
+}
+
+impl CardanoDatum {
+    /// Returns the datum option of the spent output
+    pub fn to_datum_option(&self) -> Result<DatumOption, TransactionError> {
+        match self {
+            Self::Hash(datum) => Ok(DatumOption::new_hash(hash_plutus_data(&plutus_data(
+                datum,
+            )?))),
+            Self::Inline(datum) => Ok(DatumOption::new_datum(plutus_data(datum)?)),
+        }
+    }
+}
+
+impl CardanoExUnits {
+    pub fn new(mem: u64, steps: u64) -> Self {
+        CardanoExUnits { mem, steps }
+    }
+
+    pub fn to_ex_units(&self) -> ExUnits {
+        ExUnits::new(self.mem, self.steps)
+    }
+}
+
+impl CardanoPlutusSpend {
+    pub fn partial_witness(&self) -> Result<PartialPlutusWitness, TransactionError> {
+        Ok(PartialPlutusWitness::new(
+            PlutusScriptWitness::Script(self.script.to_script()),
+            plutus_data(&self.redeemer)?,
+        ))
+    }
+
+    pub fn required_signers(&self) -> Vec<Ed25519KeyHash> {
+        self.required_signers
+            .iter()
+            .map(|pk| Ed25519KeyHash::from(blake2b224(pk.0.as_bytes())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The compiled code of the always succeeding validator of the
+    // cardano-node examples
+    const ALWAYS_SUCCEEDS: &str = "4d01000033222220051200120011";
+
+    #[test]
+    fn test_script_address() {
+        let code = hex::decode(ALWAYS_SUCCEEDS).unwrap();
+        let v3 = CardanoPlutusScript::V3(code.clone());
+        let v2 = CardanoPlutusScript::V2(code);
+        // The hash commits to the language of the script
+        assert_ne!(v2.hash(), v3.hash());
+        let address = v3.to_address(&CardanoNetwork::Preprod);
+        assert_eq!(
+            Some(&Credential::new_script(v3.hash())),
+            address.0.payment_cred()
+        );
+        assert!(address.to_string().starts_with("addr_test1w"));
+
+        let datum = CardanoDatum::Inline(vec![0xd8, 0x79, 0x80]);
+        assert!(matches!(
+            datum.to_datum_option().unwrap(),
+            DatumOption::Datum { .. }
+        ));
+        let datum = CardanoDatum::Hash(vec![0xd8, 0x79, 0x80]);
+        assert!(matches!(
+            datum.to_datum_option().unwrap(),
+            DatumOption::Hash { .. }
+        ));
+        assert!(CardanoDatum::Hash(vec![0xff]).to_datum_option().is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{COINS_PER_UTXO_BYTE, KEY_DEPOSIT};
+use anychain_core::TransactionError;
+use cml_chain::{
+    builders::tx_builder::{TransactionBuilderConfig, TransactionBuilderConfigBuilder},
+    fees::LinearFee,
+    plutus::{CostModels, ExUnitPrices},
+    Rational,
+};
+use cml_core::ordered_hash_map::OrderedHashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Represents the prices of the memory and steps of Plutus scripts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardanoExUnitPrices {
+    pub price_memory: f64,
+    pub price_steps: f64,
+}
+
+/// Represents the protocol parameters a transaction is built with, as in the
+/// `protocol-parameters.json` of `cardano-cli query protocol-parameters`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardanoProtocolParams {
+    /// The lovelace per byte of a transaction, minfee_a
+    pub tx_fee_per_byte: u64,
+    /// The lovelace of any transaction, minfee_b
+    pub tx_fee_fixed: u64,
+    #[serde(default)]
+    pub min_fee_ref_script_cost_per_byte: f64,
+    pub max_tx_size: u32,
+    pub max_value_size: u32,
+    pub stake_address_deposit: u64,
+    pub stake_pool_deposit: u64,
+    /// The lovelace per byte of an output, from which its minimum ADA is
+    /// computed
+    pub utxo_cost_per_byte: u64,
+    pub collateral_percentage: u32,
+    pub max_collateral_inputs: u32,
+    pub execution_unit_prices: CardanoExUnitPrices,
+    /// The cost models of PlutusV1, PlutusV2 and PlutusV3 scripts
+    #[serde(default)]
+    pub cost_models: BTreeMap<String, Vec<i64>>,
+}
+
+impl Default for CardanoProtocolParams {
+    /// Returns the parameters of mainnet and the testnets, without cost models
+    fn default() -> Self {
+        CardanoProtocolParams {
+            tx_fee_per_byte: 44,
+            tx_fee_fixed: 155381,
+            min_fee_ref_script_cost_per_byte: 15.0,
+            max_tx_size: 16384,
+            max_value_size: 5000,
+            stake_address_deposit: KEY_DEPOSIT,
+            stake_pool_deposit: 500_000_000,
+            utxo_cost_per_byte: COINS_PER_UTXO_BYTE,
+            collateral_percentage: 150,
+            max_collateral_inputs: 3,
+            execution_unit_prices: CardanoExUnitPrices {
+                price_memory: 0.0577,
+                price_steps: 0.0000721,
+            },
+            cost_models: BTreeMap::new(),
+        }
+    }
+}
+
+/// Returns the rational of a decimal price such as 0.0577
+fn rational(price: f64) -> Result<Rational, TransactionError> {
+    if !price.is_finite() || price < 0.0 {
+        return Err(TransactionError::Message(format!("Invalid price {price}")));
+    }
+    let mut denominator = 1u64;
+    let scaled = |denominator: u64| price * denominator as f64;
+    while (scaled(denominator) - scaled(denominator).round()).abs() > 1e-6
+        && denominator < 1_000_000_000_000
+    {
+        denominator *= 10;
+    }
+    let numerator = scaled(denominator).round() as u64;
+    Ok(Rational::new(numerator, denominator))
+}
+
+fn language(name: &str) -> Result<u64, TransactionError> {
+    match name {
+        "PlutusV1" => Ok(0),
+        "PlutusV2" => Ok(1),
+        "PlutusV3" => Ok(2),
+        _ => Err(TransactionError::Message(format!(
+            "Unknown Plutus language {name}"
+        ))),
+    }
+}
+
+impl CardanoProtocolParams {
+    pub fn from_json(json: &str) -> Result<Self, TransactionError> {
+        serde_json::from_str(json).map_err(|e| TransactionError::Crate("serde_json", e.to_string()))
+    }
+
+    /// Returns the minimum fee of a transaction of `size` bytes without
+    /// scripts
+    pub fn min_fee(&self, size: usize) -> u64 {
+        self.tx_fee_per_byte * size as u64 + self.tx_fee_fixed
+    }
+
+    pub fn to_builder_config(&self) -> Result<TransactionBuilderConfig, TransactionError> {
+        let mut cost_models = OrderedHashMap::new();
+        for (name, costs) in &self.cost_models {
+            cost_models.insert(language(name)?, costs.clone());
+        }
+        TransactionBuilderConfigBuilder::default()
+            .fee_algo(LinearFee::new(
+                self.tx_fee_per_byte,
+                self.tx_fee_fixed,
+                self.min_fee_ref_script_cost_per_byte.ceil() as u64,
+            ))
+            .pool_deposit(self.stake_pool_deposit)
+            .key_deposit(self.stake_address_deposit)
+            .max_value_size(self.max_value_size)
+            .max_tx_size(self.max_tx_size)
+            .coins_per_utxo_byte(self.utxo_cost_per_byte)
+            .ex_unit_prices(ExUnitPrices::new(
+                rational(self.execution_unit_prices.price_memory)?,
+                rational(self.execution_unit_prices.price_steps)?,
+            ))
+            .collateral_percentage(self.collateral_percentage)
+            .max_collateral_inputs(self.max_collateral_inputs)
+            .cost_models(CostModels::new(cost_models))
+            .build()
+            .map_err(|e| TransactionError::Message(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An excerpt of the output of cardano-cli on preprod
+    const PROTOCOL_PARAMETERS: &str = r#"{
+        "collateralPercentage": 150,
+        "committeeMaxTermLength": 365,
+        "costModels": {
+            "PlutusV1": [100788, 420, 1, 1, 1000],
+            "PlutusV2": [100788, 420, 1, 1, 1000, 173]
+        },
+        "executionUnitPrices": {
+            "priceMemory": 5.77e-2,
+            "priceSteps": 7.21e-5
+        },
+        "maxCollateralInputs": 3,
+        "maxTxSize": 16384,
+        "maxValueSize": 5000,
+        "minFeeRefScriptCostPerByte": 15,
+        "protocolVersion": {
+            "major": 9,
+            "minor": 0
+        },
+        "stakeAddressDeposit": 2000000,
+        "stakePoolDeposit": 500000000,
+        "txFeeFixed": 155381,
+        "txFeePerByte": 44,
+        "utxoCostPerByte": 4310
+    }"#;
+
+    #[test]
+    fn test_protocol_params() {
+        let params = CardanoProtocolParams::from_json(PROTOCOL_PARAMETERS).unwrap();
+        assert_eq!(44, params.tx_fee_per_byte);
+        assert_eq!(2, params.cost_models.len());
+        assert_eq!(
+            CardanoProtocolParams {
+                cost_models: BTreeMap::new(),
+                ..params.clone()
+            },
+            CardanoProtocolParams::default()
+        );
+        assert_eq!(155381 + 44 * 200, params.min_fee(200));
+
+        assert!(params.to_builder_config().is_ok());
+
+        let price = rational(0.0577).unwrap();
+        assert_eq!((577, 10000), (price.numerator, price.denominator));
+        let price = rational(7.21e-5).unwrap();
+        assert_eq!((721, 10_000_000), (price.numerator, price.denominator));
+        assert!(rational(-1.0).is_err());
+
+        let mut params = params;
+        params.cost_models.insert("PlutusV9".to_string(), vec![]);
+        assert!(params.to_builder_config().is_err());
+        assert!(CardanoProtocolParams::from_json("{}").is_err());
+    }
+}
@@ -1,16 +1,34 @@
 use {
-    crate::{address::CardanoAddress, format::CardanoFormat},
+    crate::{address::CardanoAddress, format::CardanoFormat, network::CardanoNetwork},
     anychain_core::{AddressError, PublicKey, PublicKeyError},
     cml_chain::{
-        address::{EnterpriseAddress, RewardAddress},
+        address::{BaseAddress, EnterpriseAddress, Pointer, PointerAddress, RewardAddress},
+        byron::AddressContent,
         certs::StakeCredential,
     },
-    cml_crypto::{blake2b224, Ed25519KeyHash},
+    cml_crypto::{blake2b224, Bip32PublicKey, Ed25519KeyHash, RawBytesEncoding},
     core::{fmt, str::FromStr},
     curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE as G, Scalar},
     group::GroupEncoding,
 };
 
+/// The purpose of CIP-1852 derivation paths m/1852'/1815'/account'/role/index
+pub const CIP1852_PURPOSE: u32 = 1852;
+
+/// The coin type of Cardano in derivation paths
+pub const CARDANO_COIN_TYPE: u32 = 1815;
+
+/// Represents the role of the keys of a CIP-1852 account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyRole {
+    /// Payment keys of receiving addresses
+    External = 0,
+    /// Payment keys of change addresses
+    Internal = 1,
+    Stake = 2,
+    DRep = 3,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CardanoPublicKey(pub ed25519_dalek::VerifyingKey);
 
@@ -27,10 +45,7 @@ impl PublicKey for CardanoPublicKey {
     }
 
     fn to_address(&self, format: &Self::Format) -> Result<Self::Address, AddressError> {
-        let bytes = self.0.as_bytes();
-        let hash = blake2b224(bytes);
-        let hash = Ed25519KeyHash::from(hash);
-        let cred = StakeCredential::new_pub_key(hash);
+        let cred = self.credential();
 
         match format {
             CardanoFormat::Enterprise(network) => {
@@ -42,11 +57,70 @@ impl PublicKey for CardanoPublicKey {
                 let address = RewardAddress::new(network.info().network_id(), cred).to_address();
                 Ok(CardanoAddress(address))
             }
+            CardanoFormat::Pointer(network, pointer) => {
+                let pointer = Pointer::new(pointer.slot, pointer.tx_index, pointer.cert_index);
+                let address =
+                    PointerAddress::new(network.info().network_id(), cred, pointer).to_address();
+                Ok(CardanoAddress(address))
+            }
+            CardanoFormat::Byron(network) => self.to_byron_address(&[0u8; 32], network),
             _ => Err(AddressError::Message("unsupported format".to_string())),
         }
     }
 }
 
+impl CardanoPublicKey {
+    /// Returns the key at `role/index` of the extended public key of a
+    /// CIP-1852 account m/1852'/1815'/account', both of which are soft
+    pub fn from_account_key(
+        account_key: &Bip32PublicKey,
+        role: KeyRole,
+        index: u32,
+    ) -> Result<Self, PublicKeyError> {
+        let key = account_key
+            .derive(role as u32)
+            .and_then(|key| key.derive(index))
+            .map_err(|e| PublicKeyError::Crate("ed25519_bip32", format!("{e:?}")))?;
+        let key = ed25519_dalek::VerifyingKey::try_from(key.to_raw_key().to_raw_bytes())
+            .map_err(|e| PublicKeyError::Crate("ed25519_dalek", format!("{e:?}")))?;
+        Ok(Self(key))
+    }
+
+    /// Returns the stake credential of the blake2b-224 hash of the key
+    pub fn credential(&self) -> StakeCredential {
+        let hash = blake2b224(self.0.as_bytes());
+        StakeCredential::new_pub_key(Ed25519KeyHash::from(hash))
+    }
+
+    /// Returns the base address whose payment key is this key and whose
+    /// rewards are paid to `stake_key`
+    pub fn to_base_address(
+        &self,
+        stake_key: &CardanoPublicKey,
+        network: &CardanoNetwork,
+    ) -> CardanoAddress {
+        let address = BaseAddress::new(
+            network.info().network_id(),
+            self.credential(),
+            stake_key.credential(),
+        );
+        CardanoAddress(address.to_address())
+    }
+
+    /// Returns the Icarus-style bootstrap address of the extended key made of
+    /// this key and `chain_code`. `CardanoFormat::Byron` uses a zero chain code
+    pub fn to_byron_address(
+        &self,
+        chain_code: &[u8; 32],
+        network: &CardanoNetwork,
+    ) -> Result<CardanoAddress, AddressError> {
+        let xpub = Bip32PublicKey::from_raw_bytes(&[self.0.as_bytes(), &chain_code[..]].concat())
+            .map_err(|e| AddressError::Message(e.to_string()))?;
+        let content = AddressContent::icarus_from_key(xpub, network.info().protocol_magic().into());
+        Ok(CardanoAddress(content.to_address().to_address()))
+    }
+}
+
 impl FromStr for CardanoPublicKey {
     type Err = PublicKeyError;
 
@@ -1,50 +1,125 @@
-use crate::util::create_default_tx_builder;
-use crate::{CardanoAddress, CardanoFormat, CardanoPublicKey};
+use crate::certificate::{reward_address, witness_key_hash};
+use crate::plutus::plutus_data;
+use crate::util::create_tx_builder;
+use crate::{
+    from_metadata, from_multiasset, to_metadata, to_multiasset, to_value, CardanoAddress,
+    CardanoAsset, CardanoCertificate, CardanoDatum, CardanoFormat, CardanoMetadatum,
+    CardanoPlutusSpend, CardanoProtocolParams, CardanoPublicKey, CardanoWithdrawal,
+};
 use anychain_core::{Transaction, TransactionError, TransactionId};
 use cml_chain::Deserialize;
 use cml_chain::{
-    assets::Value,
+    address::{Address, RewardAddress},
+    auxdata::AuxiliaryData,
     builders::{
-        input_builder::SingleInputBuilder, output_builder::TransactionOutputBuilder,
-        tx_builder::ChangeSelectionAlgo,
-    },
-    crypto::{Vkey, Vkeywitness},
-    transaction::{
-        Transaction as SignedTransaction, TransactionInput, TransactionOutput,
-        TransactionWitnessSet,
+        certificate_builder::SingleCertificateBuilder, input_builder::SingleInputBuilder,
+        output_builder::TransactionOutputBuilder, redeemer_builder::RedeemerWitnessKey,
+        tx_builder::ChangeSelectionAlgo, withdrawal_builder::SingleWithdrawalBuilder,
     },
+    crypto::{BootstrapWitness, Vkey, Vkeywitness},
+    min_ada::min_ada_required,
+    plutus::RedeemerTag,
+    transaction::{Transaction as SignedTransaction, TransactionInput, TransactionOutput},
     utils::NetworkId,
 };
 use cml_core::serialization::{RawBytesEncoding, Serialize};
-use cml_crypto::{blake2b256, Ed25519Signature, TransactionHash};
+use cml_crypto::{
+    blake2b224, blake2b256, Bip32PublicKey, Ed25519KeyHash, Ed25519Signature, TransactionHash,
+};
 use std::{fmt, str::FromStr};
 
+/// The lovelace per byte of an output of the current protocol parameters,
+/// from which the minimum ADA of an output is computed
+pub const COINS_PER_UTXO_BYTE: u64 = 4310;
+
+/// The number of slots after the current slot a transaction is valid until,
+/// unless a TTL is given
+pub const DEFAULT_TTL: u64 = 200;
+
 #[derive(Debug, Clone)]
 pub struct Input {
     pub txid: String,
     pub index: u64,
     pub address: Option<CardanoAddress>,
     pub amount: Option<u64>,
+    /// The native assets held by the spent output
+    pub assets: Vec<CardanoAsset>,
+    /// The script, datum and redeemer of an output locked by a Plutus script
+    pub plutus: Option<CardanoPlutusSpend>,
+}
+
+impl Input {
+    fn to_input(&self) -> Result<TransactionInput, TransactionError> {
+        let txid = TransactionHash::from_hex(&self.txid)
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        Ok(TransactionInput::new(txid, self.index))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Output {
     pub address: CardanoAddress,
+    /// The lovelace sent, where 0 sends the minimum ADA of the output
     pub amount: u64,
+    pub assets: Vec<CardanoAsset>,
+}
+
+impl Output {
+    fn to_output(&self, amount: u64) -> Result<TransactionOutput, TransactionError> {
+        TransactionOutputBuilder::new()
+            .with_address(self.address.0.clone())
+            .next()
+            .map_err(|e| TransactionError::Message(e.to_string()))?
+            .with_value(to_value(amount, &self.assets)?)
+            .build()
+            .map(|output| output.output)
+            .map_err(|e| TransactionError::Message(e.to_string()))
+    }
+
+    /// Returns the minimum lovelace the output has to hold, which grows with
+    /// the size of its address and assets
+    pub fn min_ada(&self, coins_per_utxo_byte: u64) -> Result<u64, TransactionError> {
+        let output = self.to_output(self.amount)?;
+        min_ada_required(&output, coins_per_utxo_byte)
+            .map_err(|e| TransactionError::Message(e.to_string()))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct CardanoTransactionParameters {
     pub inputs: Vec<Input>,
     pub outputs: Vec<Output>,
+    /// The address the change is sent to, which is the address of the
+    /// first input if None
+    pub change_address: Option<CardanoAddress>,
+    /// The current slot
     pub slot: u64,
+    /// The first slot the transaction is valid in
+    pub validity_start: Option<u64>,
+    /// The slot the transaction is valid until, `slot + DEFAULT_TTL` if None
+    pub ttl: Option<u64>,
     pub network: u8,
+    pub protocol_params: CardanoProtocolParams,
+    /// The auxiliary metadata of the transaction by label, such as a
+    /// CIP-20 message under `CIP20_LABEL`
+    pub metadata: Vec<(u64, CardanoMetadatum)>,
+    /// The stake certificates, whose deposits and refunds are part of the
+    /// balance of the transaction
+    pub certificates: Vec<CardanoCertificate>,
+    pub withdrawals: Vec<CardanoWithdrawal>,
+    /// The payment key inputs forfeited if a script fails, which are needed
+    /// to spend script inputs. No collateral is returned, so they should
+    /// hold little more than the collateral percentage of the fee
+    pub collateral: Vec<Input>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CardanoSignature {
     pub public_key: Vec<u8>,
     pub rs: Vec<u8>,
+    /// The chain code of the extended public key of a Byron input, whose
+    /// signature is a bootstrap witness
+    pub chain_code: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone)]
@@ -72,10 +147,57 @@ impl fmt::Display for CardanoTransactionId {
 impl TransactionId for CardanoTransactionId {}
 
 impl CardanoTransaction {
+    /// Signs the transaction with the signatures of the payment keys of the
+    /// inputs and of the stake keys of the certificates and withdrawals
     pub fn sign(&mut self, sigs: Vec<CardanoSignature>) -> Result<Vec<u8>, TransactionError> {
+        let signed: Vec<Ed25519KeyHash> = sigs
+            .iter()
+            .map(|sig| Ed25519KeyHash::from(blake2b224(&sig.public_key)))
+            .collect();
+        for signer in self.signers()? {
+            if !signed.contains(&signer) {
+                return Err(TransactionError::Message(format!(
+                    "Missing the signature of key hash {}",
+                    signer.to_hex()
+                )));
+            }
+        }
         self.signatures = Some(sigs);
         self.to_bytes()
     }
+
+    /// Returns the hashes of the keys that have to sign the transaction,
+    /// which are the payment keys of the Shelley inputs and collateral, the
+    /// required signers of the scripts and the stake keys of the
+    /// certificates and withdrawals. Byron inputs are signed with bootstrap
+    /// witnesses instead
+    pub fn signers(&self) -> Result<Vec<Ed25519KeyHash>, TransactionError> {
+        let mut signers = vec![];
+        for input in self.params.inputs.iter().chain(&self.params.collateral) {
+            if let Some(hash) = input
+                .address
+                .as_ref()
+                .and_then(|address| address.0.payment_cred())
+                .and_then(witness_key_hash)
+            {
+                signers.push(hash);
+            }
+            if let Some(plutus) = &input.plutus {
+                signers.extend(plutus.required_signers());
+            }
+        }
+        for certificate in &self.params.certificates {
+            signers.extend(certificate.signer()?);
+        }
+        for withdrawal in &self.params.withdrawals {
+            signers.extend(witness_key_hash(
+                &reward_address(&withdrawal.reward_address)?.payment,
+            ));
+        }
+        signers.sort();
+        signers.dedup();
+        Ok(signers)
+    }
 }
 
 impl Transaction for CardanoTransaction {
@@ -97,35 +219,139 @@ impl Transaction for CardanoTransaction {
     }
 
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        let mut builder = create_default_tx_builder();
-        let change_address = self.params.inputs[0].address.clone();
-        let change_address = &change_address.unwrap().0;
+        let protocol_params = &self.params.protocol_params;
+        let mut builder = create_tx_builder(protocol_params)?;
+        if self.params.inputs.is_empty() {
+            return Err(TransactionError::Message("No inputs are given".to_string()));
+        }
+        let change_address = match &self.params.change_address {
+            Some(address) => address.clone(),
+            None => self.params.inputs[0]
+                .address
+                .clone()
+                .ok_or(TransactionError::Message(
+                    "No change address is given".to_string(),
+                ))?,
+        };
 
         for input in &self.params.inputs {
-            let txid = TransactionHash::from_hex(&input.txid)
+            let address = input
+                .address
+                .clone()
+                .ok_or(TransactionError::Message(
+                    "No address of the input is given".to_string(),
+                ))?
+                .0;
+            let amount = input.amount.ok_or(TransactionError::Message(
+                "No amount of the input is given".to_string(),
+            ))?;
+            let amount = to_value(amount, &input.assets)?;
+            let datum = match &input.plutus {
+                Some(plutus) => Some(plutus.datum.to_datum_option()?),
+                None => None,
+            };
+
+            let output = TransactionOutput::new(address, amount, datum, None);
+            let builder_input = SingleInputBuilder::new(input.to_input()?, output);
+
+            let input = match &input.plutus {
+                Some(plutus) => {
+                    let language = plutus.script.language();
+                    if !protocol_params.cost_models.contains_key(language) {
+                        return Err(TransactionError::Message(format!(
+                            "No cost model of {language} is given"
+                        )));
+                    }
+                    let witness = plutus.partial_witness()?;
+                    let required_signers = plutus.required_signers().into();
+                    match &plutus.datum {
+                        CardanoDatum::Hash(datum) => builder_input.plutus_script(
+                            witness,
+                            required_signers,
+                            plutus_data(datum)?,
+                        ),
+                        CardanoDatum::Inline(_) => {
+                            builder_input.plutus_script_inline_datum(witness, required_signers)
+                        }
+                    }
+                }
+                None => builder_input.payment_key(),
+            }
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+
+            builder
+                .add_input(input)
                 .map_err(|e| TransactionError::Message(e.to_string()))?;
-            let address = input.address.clone().unwrap().0.clone();
-            let amount = Value::from(input.amount.unwrap());
+        }
 
-            let input = TransactionInput::new(txid, input.index);
+        // Redeemers are indexed by the position of their input among the
+        // sorted inputs of the transaction
+        let mut sorted_inputs = self
+            .params
+            .inputs
+            .iter()
+            .map(Input::to_input)
+            .collect::<Result<Vec<_>, _>>()?;
+        sorted_inputs.sort();
+        for input in &self.params.inputs {
+            if let Some(plutus) = &input.plutus {
+                let index = sorted_inputs
+                    .binary_search(&input.to_input()?)
+                    .map_err(|_| TransactionError::Message("Input not found".to_string()))?;
+                builder.set_exunits(
+                    RedeemerWitnessKey::new(RedeemerTag::Spend, index as u64),
+                    plutus.ex_units.to_ex_units(),
+                );
+            }
+        }
 
+        let has_scripts = self
+            .params
+            .inputs
+            .iter()
+            .any(|input| input.plutus.is_some());
+        if has_scripts && self.params.collateral.is_empty() {
+            return Err(TransactionError::Message(
+                "Script inputs need collateral".to_string(),
+            ));
+        }
+        for collateral in &self.params.collateral {
+            let address = collateral
+                .address
+                .clone()
+                .ok_or(TransactionError::Message(
+                    "No address of the collateral is given".to_string(),
+                ))?
+                .0;
+            let amount = collateral.amount.ok_or(TransactionError::Message(
+                "No amount of the collateral is given".to_string(),
+            ))?;
+            let amount = to_value(amount, &collateral.assets)?;
             let output = TransactionOutput::new(address, amount, None, None);
-
-            let input = SingleInputBuilder::new(input, output)
+            let collateral = SingleInputBuilder::new(collateral.to_input()?, output)
                 .payment_key()
                 .map_err(|e| TransactionError::Message(e.to_string()))?;
-
             builder
-                .add_input(input)
+                .add_collateral(collateral)
                 .map_err(|e| TransactionError::Message(e.to_string()))?;
         }
 
         for output in &self.params.outputs {
+            let min_ada = output.min_ada(protocol_params.utxo_cost_per_byte)?;
+            let amount = match output.amount {
+                0 => min_ada,
+                amount if amount < min_ada => {
+                    return Err(TransactionError::Message(format!(
+                        "Output of {amount} lovelace is below the minimum of {min_ada}"
+                    )))
+                }
+                amount => amount,
+            };
             let output = TransactionOutputBuilder::new()
                 .with_address(output.address.0.clone())
                 .next()
                 .map_err(|e| TransactionError::Message(e.to_string()))?
-                .with_value(output.amount)
+                .with_value(to_value(amount, &output.assets)?)
                 .build()
                 .map_err(|e| TransactionError::Message(e.to_string()))?;
 
@@ -134,53 +360,106 @@ impl Transaction for CardanoTransaction {
                 .map_err(|e| TransactionError::Message(e.to_string()))?;
         }
 
-        let input_amount = builder
-            .get_explicit_input()
-            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        for certificate in &self.params.certificates {
+            let certificate = SingleCertificateBuilder::new(certificate.to_certificate()?);
+            builder.add_cert(certificate.skip_witness());
+        }
 
-        let output_amount = builder
-            .get_explicit_output()
-            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        for withdrawal in &self.params.withdrawals {
+            let address = reward_address(&withdrawal.reward_address)?;
+            let withdrawal = SingleWithdrawalBuilder::new(address, withdrawal.amount)
+                .payment_key()
+                .map_err(|e| TransactionError::Message(e.to_string()))?;
+            builder.add_withdrawal(withdrawal);
+        }
 
-        let fee = input_amount.coin - output_amount.coin;
-        builder.set_fee(fee);
+        let input_assets: Vec<CardanoAsset> = self
+            .params
+            .inputs
+            .iter()
+            .flat_map(|input| input.assets.clone())
+            .collect();
+        let output_assets: Vec<CardanoAsset> = self
+            .params
+            .outputs
+            .iter()
+            .flat_map(|output| output.assets.clone())
+            .collect();
+        // The assets not sent to the outputs are sent to the change address
+        to_multiasset(&input_assets)?
+            .checked_sub(&to_multiasset(&output_assets)?)
+            .map_err(|_| {
+                TransactionError::Message("The outputs exceed the assets of the inputs".to_string())
+            })?;
+
+        if !self.params.metadata.is_empty() {
+            let auxiliary_data = AuxiliaryData::new_shelley(to_metadata(&self.params.metadata)?);
+            builder.set_auxiliary_data(auxiliary_data);
+        }
 
         let network = NetworkId::from(self.params.network as u64);
         builder.set_network_id(network);
 
-        builder.set_ttl(self.params.slot + 200);
+        builder.set_ttl(self.params.ttl.unwrap_or(self.params.slot + DEFAULT_TTL));
+        if let Some(validity_start) = self.params.validity_start {
+            builder.set_validity_start_interval(validity_start);
+        }
 
-        let tx = builder
-            .build(ChangeSelectionAlgo::Default, change_address)
-            .map_err(|e| TransactionError::Message(e.to_string()))?
-            .body();
+        // The fee is the minimum fee of the transaction with the witnesses
+        // of its signers and the execution units of its scripts, and the
+        // rest of the inputs is sent back as change
+        let mut tx = builder
+            .build(ChangeSelectionAlgo::Default, &change_address.0)
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
 
         match &self.signatures {
             Some(sigs) => {
-                let mut witnesses = vec![];
-
                 for sig in sigs {
                     let pk = sig.public_key.as_slice();
                     let rs = sig.rs.as_slice();
 
-                    let pk = Vkey::from_raw_bytes(pk)
+                    let vkey = Vkey::from_raw_bytes(pk)
                         .map_err(|e| TransactionError::Message(e.to_string()))?;
                     let rs = Ed25519Signature::from_raw_bytes(rs)
                         .map_err(|e| TransactionError::Message(e.to_string()))?;
 
-                    let witness = Vkeywitness::new(pk, rs);
-
-                    witnesses.push(witness);
+                    match &sig.chain_code {
+                        Some(chain_code) => {
+                            let xpub = Bip32PublicKey::from_raw_bytes(&[pk, chain_code].concat())
+                                .map_err(|e| TransactionError::Message(e.to_string()))?;
+                            let address = self
+                                .params
+                                .inputs
+                                .iter()
+                                .find_map(|input| match input.address.as_ref().map(|a| &a.0) {
+                                    Some(Address::Byron(address))
+                                        if address.content.identical_with_pubkey(xpub.clone()) =>
+                                    {
+                                        Some(address)
+                                    }
+                                    _ => None,
+                                })
+                                .ok_or(TransactionError::Message(
+                                    "No Byron input is spent by the extended key".to_string(),
+                                ))?;
+                            let witness = BootstrapWitness::new(
+                                vkey,
+                                rs,
+                                chain_code.clone(),
+                                address.content.addr_attributes.clone(),
+                            )
+                            .map_err(|e| TransactionError::Message(e.to_string()))?;
+                            tx.add_bootstrap(witness);
+                        }
+                        None => tx.add_vkey(Vkeywitness::new(vkey, rs)),
+                    }
                 }
 
-                let mut witness_set = TransactionWitnessSet::new();
-                witness_set.vkeywitnesses = Some(witnesses.into());
-
-                let signed_tx = SignedTransaction::new(tx, witness_set, true, None);
-
-                Ok(signed_tx.to_cbor_bytes())
+                // The witness set holds the scripts, datums and redeemers
+                // along with the signatures
+                Ok(tx.build_unchecked().to_cbor_bytes())
             }
-            None => Ok(tx.to_cbor_bytes()),
+            None => Ok(tx.body().to_cbor_bytes()),
         }
     }
 
@@ -201,23 +480,96 @@ impl Transaction for CardanoTransaction {
                 index,
                 address: None,
                 amount: None,
+                assets: vec![],
+                plutus: None,
             });
         }
 
+        // The network id is optional in the body, in which case it is that of
+        // the addresses of the outputs or withdrawals
+        let network_id = match &tx.network_id {
+            Some(network_id) => network_id.network as u8,
+            None => tx
+                .outputs
+                .iter()
+                .map(|output| output.address().clone())
+                .chain(
+                    tx.withdrawals
+                        .iter()
+                        .flat_map(|withdrawals| withdrawals.iter())
+                        .map(|(address, _)| address.clone().to_address()),
+                )
+                .find_map(|address| address.network_id().ok())
+                .ok_or(TransactionError::Message(
+                    "The network of the transaction is unknown".to_string(),
+                ))?,
+        };
+
+        let collateral = tx
+            .collateral_inputs
+            .iter()
+            .flat_map(|inputs| inputs.iter())
+            .map(|input| Input {
+                txid: input.transaction_id.to_hex(),
+                index: input.index,
+                address: None,
+                amount: None,
+                assets: vec![],
+                plutus: None,
+            })
+            .collect();
+
         for output in tx.outputs {
             let address = CardanoAddress(output.address().clone());
             let amount = output.amount().coin;
+            let assets = from_multiasset(&output.amount().multiasset);
 
-            outputs.push(Output { address, amount });
+            outputs.push(Output {
+                address,
+                amount,
+                assets,
+            });
         }
 
-        let network_id = tx.network_id.unwrap().network as u8;
+        let metadata = signed_tx
+            .auxiliary_data
+            .as_ref()
+            .and_then(|auxiliary_data| auxiliary_data.metadata())
+            .map(from_metadata)
+            .unwrap_or_default();
+
+        let certificates = tx
+            .certs
+            .iter()
+            .flat_map(|certs| certs.iter())
+            .filter_map(|cert| CardanoCertificate::from_certificate(cert, network_id))
+            .collect();
+
+        let withdrawals = tx
+            .withdrawals
+            .iter()
+            .flat_map(|withdrawals| withdrawals.iter())
+            .map(
+                |(address, amount): (&RewardAddress, &u64)| CardanoWithdrawal {
+                    reward_address: CardanoAddress(address.clone().to_address()),
+                    amount: *amount,
+                },
+            )
+            .collect();
 
         Self::new(&CardanoTransactionParameters {
             inputs,
             outputs,
+            change_address: None,
             slot: 0,
+            validity_start: tx.validity_interval_start,
+            ttl: tx.ttl,
             network: network_id,
+            protocol_params: CardanoProtocolParams::default(),
+            metadata,
+            certificates,
+            withdrawals,
+            collateral,
         })
     }
 
@@ -227,6 +579,413 @@ impl Transaction for CardanoTransaction {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CardanoDRep, CardanoExUnits, CardanoNetwork, CardanoPlutusScript, CIP20_LABEL, KEY_DEPOSIT,
+    };
+    use anychain_core::PublicKey;
+    use curve25519_dalek::Scalar;
+
+    const TXID: &str = "6f7f4c7e3a2d9dfd5a1b2b4c54a6e7c3bdb1d7f9f0d1d7c54b1ea7e0c1a9b001";
+    const POLICY_ID: &str = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235";
+
+    fn public_key(seed: u8) -> CardanoPublicKey {
+        CardanoPublicKey::from_secret_key(&Scalar::from_bytes_mod_order([seed; 32]))
+    }
+
+    fn address(seed: u8) -> CardanoAddress {
+        let format = CardanoFormat::Enterprise(CardanoNetwork::Preprod);
+        public_key(seed).to_address(&format).unwrap()
+    }
+
+    fn input(address: CardanoAddress, amount: u64, assets: Vec<CardanoAsset>) -> Input {
+        Input {
+            txid: TXID.to_string(),
+            index: 0,
+            address: Some(address),
+            amount: Some(amount),
+            assets,
+            plutus: None,
+        }
+    }
+
+    /// Checks that the fee of a signed transaction covers its size, without
+    /// overpaying by more than a few bytes, and returns the fee and the
+    /// lovelace of the outputs
+    fn check_fee(signed: &[u8]) -> (u64, u64) {
+        let tx = SignedTransaction::from_cbor_bytes(signed).unwrap();
+        let min_fee = CardanoProtocolParams::default().min_fee(signed.len());
+        assert!(tx.body.fee >= min_fee);
+        assert!(tx.body.fee < min_fee + 44 * 16);
+        let outputs = tx.body.outputs.iter().map(|output| output.amount().coin);
+        (tx.body.fee, outputs.sum())
+    }
+
+    #[test]
+    fn test_assets_and_metadata() {
+        let hosky = CardanoAsset::new(POLICY_ID, b"HOSKY", 1000);
+        let output = Output {
+            address: address(2),
+            amount: 0,
+            assets: vec![CardanoAsset::new(POLICY_ID, b"HOSKY", 400)],
+        };
+        let min_ada = output.min_ada(COINS_PER_UTXO_BYTE).unwrap();
+        assert!(
+            min_ada
+                > Output {
+                    assets: vec![],
+                    ..output.clone()
+                }
+                .min_ada(COINS_PER_UTXO_BYTE)
+                .unwrap()
+        );
+
+        let metadata = vec![(
+            CIP20_LABEL,
+            CardanoMetadatum::cip20("Thanks for the coffee"),
+        )];
+        let params = CardanoTransactionParameters {
+            inputs: vec![input(address(1), 10_000_000, vec![hosky])],
+            outputs: vec![output.clone()],
+            change_address: None,
+            slot: 1000,
+            validity_start: None,
+            ttl: None,
+            network: 0,
+            protocol_params: CardanoProtocolParams::default(),
+            metadata: metadata.clone(),
+            certificates: vec![],
+            withdrawals: vec![],
+            collateral: vec![],
+        };
+        let mut tx = CardanoTransaction::new(&params).unwrap();
+        let sig = CardanoSignature {
+            public_key: public_key(1).0.to_bytes().to_vec(),
+            rs: vec![1u8; 64],
+            chain_code: None,
+        };
+        let signed = tx.sign(vec![sig]).unwrap();
+
+        let (fee, outputs) = check_fee(&signed);
+        assert_eq!(10_000_000, outputs + fee);
+
+        // The assets not sent are returned to the address of the input
+        let decoded = CardanoTransaction::from_bytes(&signed).unwrap();
+        let change = &decoded.params.outputs[1];
+        assert_eq!(min_ada, decoded.params.outputs[0].amount);
+        assert_eq!(output.assets, decoded.params.outputs[0].assets);
+        assert_eq!(address(1), change.address);
+        assert_eq!(10_000_000 - min_ada - fee, change.amount);
+        assert_eq!(
+            vec![CardanoAsset::new(POLICY_ID, b"HOSKY", 600)],
+            change.assets
+        );
+        assert_eq!(metadata, decoded.params.metadata);
+        assert_eq!(Some(1000 + DEFAULT_TTL), decoded.params.ttl);
+
+        // The decoded inputs have no address or amount to build from
+        assert!(decoded.to_bytes().is_err());
+        let mut decoded_params = decoded.params.clone();
+        decoded_params.change_address = Some(address(1));
+        assert!(CardanoTransaction::new(&decoded_params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        decoded_params.inputs[0].address = Some(address(1));
+        assert!(CardanoTransaction::new(&decoded_params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        decoded_params.inputs.clear();
+        assert!(CardanoTransaction::new(&decoded_params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+
+        // Outputs exceeding the assets or below the minimum ADA are rejected
+        let mut unbalanced = params.clone();
+        unbalanced.outputs[0].assets[0].amount = 1001;
+        assert!(CardanoTransaction::new(&unbalanced)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        let mut dust = params;
+        dust.outputs[0].amount = 1_000;
+        assert!(CardanoTransaction::new(&dust).unwrap().to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_staking() {
+        let stake_key = public_key(5);
+        let stake_address = stake_key
+            .to_address(&CardanoFormat::Reward(CardanoNetwork::Preprod))
+            .unwrap();
+        let pool = "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735";
+        let params = CardanoTransactionParameters {
+            inputs: vec![input(address(1), 10_000_000, vec![])],
+            outputs: vec![Output {
+                address: public_key(1).to_base_address(&stake_key, &CardanoNetwork::Preprod),
+                amount: 5_000_000,
+                assets: vec![],
+            }],
+            change_address: None,
+            slot: 1000,
+            validity_start: None,
+            ttl: None,
+            network: 0,
+            protocol_params: CardanoProtocolParams::default(),
+            metadata: vec![],
+            certificates: vec![
+                CardanoCertificate::Registration {
+                    stake_address: stake_address.clone(),
+                    deposit: KEY_DEPOSIT,
+                },
+                CardanoCertificate::StakeDelegation {
+                    stake_address: stake_address.clone(),
+                    pool: pool.to_string(),
+                },
+                CardanoCertificate::VoteDelegation {
+                    stake_address: stake_address.clone(),
+                    drep: CardanoDRep::AlwaysAbstain,
+                },
+            ],
+            withdrawals: vec![CardanoWithdrawal {
+                reward_address: stake_address,
+                amount: 1_000_000,
+            }],
+            collateral: vec![],
+        };
+        let mut tx = CardanoTransaction::new(&params).unwrap();
+        assert_eq!(
+            [public_key(1), stake_key.clone()]
+                .iter()
+                .map(|pk| witness_key_hash(&pk.credential()).unwrap())
+                .collect::<std::collections::BTreeSet<_>>(),
+            tx.signers().unwrap().into_iter().collect()
+        );
+
+        let sig = |pk: &CardanoPublicKey| CardanoSignature {
+            public_key: pk.0.to_bytes().to_vec(),
+            rs: vec![1u8; 64],
+            chain_code: None,
+        };
+        // The stake key has to sign along with the payment key
+        assert!(tx.sign(vec![sig(&public_key(1))]).is_err());
+        let signed = tx.sign(vec![sig(&public_key(1)), sig(&stake_key)]).unwrap();
+
+        // 10 ADA in and 1 ADA withdrawn, with a deposit of 2 ADA
+        let (fee, outputs) = check_fee(&signed);
+        assert_eq!(11_000_000, outputs + fee + KEY_DEPOSIT);
+        let decoded = SignedTransaction::from_cbor_bytes(&signed).unwrap();
+        assert_eq!(2, decoded.witness_set.vkeywitnesses.unwrap().len());
+
+        let decoded = CardanoTransaction::from_bytes(&signed).unwrap();
+        assert_eq!(params.certificates, decoded.params.certificates);
+        assert_eq!(params.withdrawals, decoded.params.withdrawals);
+
+        // Without a network id in the body, the network is that of the outputs
+        let mut signed_tx = SignedTransaction::from_cbor_bytes(&signed).unwrap();
+        signed_tx.body.network_id = None;
+        let decoded = CardanoTransaction::from_bytes(&signed_tx.to_cbor_bytes()).unwrap();
+        assert_eq!(0, decoded.params.network);
+        assert_eq!(params.certificates, decoded.params.certificates);
+        signed_tx.body.outputs.clear();
+        signed_tx.body.withdrawals = None;
+        assert!(CardanoTransaction::from_bytes(&signed_tx.to_cbor_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_plutus_spend() {
+        // The compiled code of the always succeeding validator of the
+        // cardano-node examples
+        let script = CardanoPlutusScript::V3(hex::decode("4d01000033222220051200120011").unwrap());
+        let unit = vec![0xd8, 0x79, 0x80];
+        let spend = CardanoPlutusSpend {
+            script: script.clone(),
+            datum: CardanoDatum::Inline(unit.clone()),
+            redeemer: unit.clone(),
+            ex_units: CardanoExUnits::new(14_000_000, 10_000_000_000),
+            required_signers: vec![public_key(3)],
+        };
+        // The script input sorts after the key input of the same transaction
+        let script_input = Input {
+            index: 1,
+            plutus: Some(spend.clone()),
+            ..input(
+                script.to_address(&CardanoNetwork::Preprod),
+                5_000_000,
+                vec![],
+            )
+        };
+        let collateral = Input {
+            index: 2,
+            ..input(address(2), 5_000_000, vec![])
+        };
+        let mut protocol_params = CardanoProtocolParams::default();
+        // Placeholder costs, of which only the encoding is hashed
+        protocol_params
+            .cost_models
+            .insert("PlutusV3".to_string(), vec![100788, 420, 1, 1, 1000]);
+        let params = CardanoTransactionParameters {
+            inputs: vec![script_input.clone(), input(address(1), 10_000_000, vec![])],
+            outputs: vec![Output {
+                address: address(4),
+                amount: 8_000_000,
+                assets: vec![],
+            }],
+            change_address: Some(address(1)),
+            slot: 1000,
+            validity_start: None,
+            ttl: None,
+            network: 0,
+            protocol_params: protocol_params.clone(),
+            metadata: vec![],
+            certificates: vec![],
+            withdrawals: vec![],
+            collateral: vec![collateral],
+        };
+        let mut tx = CardanoTransaction::new(&params).unwrap();
+        let signers: Vec<CardanoPublicKey> = vec![public_key(1), public_key(2), public_key(3)];
+        assert_eq!(
+            signers
+                .iter()
+                .map(|pk| witness_key_hash(&pk.credential()).unwrap())
+                .collect::<std::collections::BTreeSet<_>>(),
+            tx.signers().unwrap().into_iter().collect()
+        );
+
+        let sig = |pk: &CardanoPublicKey| CardanoSignature {
+            public_key: pk.0.to_bytes().to_vec(),
+            rs: vec![1u8; 64],
+            chain_code: None,
+        };
+        // The required signer of the script has to sign
+        assert!(tx
+            .sign(vec![sig(&public_key(1)), sig(&public_key(2))])
+            .is_err());
+        let signed = tx.sign(signers.iter().map(sig).collect()).unwrap();
+
+        // The fee covers the execution units along with the size
+        let decoded = SignedTransaction::from_cbor_bytes(&signed).unwrap();
+        let ex_units_fee = (14_000_000f64 * 0.0577 + 10_000_000_000f64 * 0.0000721).ceil() as u64;
+        let fee = decoded.body.fee;
+        assert!(fee >= protocol_params.min_fee(signed.len()) + ex_units_fee);
+        let outputs: u64 = decoded.body.outputs.iter().map(|o| o.amount().coin).sum();
+        assert_eq!(15_000_000, outputs + fee);
+
+        let body = &decoded.body;
+        let script_data_hash = body.script_data_hash.unwrap();
+        assert_ne!([0u8; 32], script_data_hash.to_raw_bytes());
+        assert_eq!(1, body.collateral_inputs.as_ref().unwrap().len());
+        assert_eq!(
+            vec![witness_key_hash(&public_key(3).credential()).unwrap()],
+            body.required_signers.clone().unwrap().to_vec()
+        );
+        let witness_set = decoded.witness_set;
+        assert_eq!(3, witness_set.vkeywitnesses.unwrap().len());
+        assert_eq!(1, witness_set.plutus_v3_scripts.unwrap().len());
+        assert!(witness_set.plutus_datums.is_none());
+        let redeemers = witness_set.redeemers.unwrap().to_flat_format();
+        assert_eq!(1, redeemers.len());
+        assert_eq!(1, redeemers[0].index);
+        assert_eq!(14_000_000, redeemers[0].ex_units.mem);
+
+        // A datum of which the output holds the hash is supplied in the
+        // witness set
+        let mut datum_hash = params.clone();
+        datum_hash.inputs[0].plutus = Some(CardanoPlutusSpend {
+            datum: CardanoDatum::Hash(unit),
+            ..spend
+        });
+        let mut tx = CardanoTransaction::new(&datum_hash).unwrap();
+        let signed = tx.sign(signers.iter().map(sig).collect()).unwrap();
+        let decoded = SignedTransaction::from_cbor_bytes(&signed).unwrap();
+        assert_eq!(1, decoded.witness_set.plutus_datums.unwrap().len());
+        assert_ne!(Some(script_data_hash), decoded.body.script_data_hash);
+
+        let decoded = CardanoTransaction::from_bytes(&signed).unwrap();
+        assert_eq!(1, decoded.params.collateral.len());
+        assert_eq!(2, decoded.params.collateral[0].index);
+
+        // Script inputs need collateral and the cost model of their language
+        let mut no_collateral = params.clone();
+        no_collateral.collateral = vec![];
+        assert!(CardanoTransaction::new(&no_collateral)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        // The collateral is spent by its payment key, so its address is needed
+        let mut no_collateral_address = params.clone();
+        no_collateral_address.collateral[0].address = None;
+        assert!(CardanoTransaction::new(&no_collateral_address)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        let mut no_collateral_amount = params.clone();
+        no_collateral_amount.collateral[0].amount = None;
+        assert!(CardanoTransaction::new(&no_collateral_amount)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        let mut no_cost_model = params;
+        no_cost_model.protocol_params.cost_models.clear();
+        assert!(CardanoTransaction::new(&no_cost_model)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_witness() {
+        let pk = public_key(3);
+        let chain_code = [9u8; 32];
+        let byron = pk
+            .to_byron_address(&chain_code, &CardanoNetwork::Preprod)
+            .unwrap();
+        let params = CardanoTransactionParameters {
+            inputs: vec![input(byron, 3_000_000, vec![])],
+            outputs: vec![Output {
+                address: address(2),
+                amount: 1_500_000,
+                assets: vec![],
+            }],
+            change_address: None,
+            slot: 1000,
+            validity_start: Some(900),
+            ttl: Some(5000),
+            network: 0,
+            protocol_params: CardanoProtocolParams::default(),
+            metadata: vec![],
+            certificates: vec![],
+            withdrawals: vec![],
+            collateral: vec![],
+        };
+        let mut tx = CardanoTransaction::new(&params).unwrap();
+        let sig = CardanoSignature {
+            public_key: pk.0.to_bytes().to_vec(),
+            rs: vec![1u8; 64],
+            chain_code: Some(chain_code.to_vec()),
+        };
+        let signed = tx.sign(vec![sig.clone()]).unwrap();
+        let (fee, outputs) = check_fee(&signed);
+        assert_eq!(3_000_000, outputs + fee);
+        let signed = SignedTransaction::from_cbor_bytes(&signed).unwrap();
+        assert_eq!(Some(900), signed.body.validity_interval_start);
+        assert_eq!(Some(5000), signed.body.ttl);
+        assert_eq!(1, signed.witness_set.bootstrap_witnesses.unwrap().len());
+        assert!(signed.witness_set.vkeywitnesses.is_none());
+
+        // The chain code has to match that of the Byron input
+        let sig = CardanoSignature {
+            chain_code: Some(vec![8u8; 32]),
+            ..sig
+        };
+        assert!(tx.sign(vec![sig]).is_err());
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::{CardanoTransaction, CardanoTransactionParameters, Input, Output};
@@ -1,10 +1,11 @@
+use anychain_cardano::{CardanoAddress, CardanoNetwork, CardanoPublicKey, KeyRole};
 use bip39::Mnemonic;
 use cml_chain::{
     address::{Address, AddressKind, BaseAddress},
     certs::StakeCredential,
     genesis::network_info::NetworkInfo,
 };
-use cml_crypto::{Bip32PrivateKey, Bip32PublicKey};
+use cml_crypto::{Bip32PrivateKey, Bip32PublicKey, RawBytesEncoding};
 
 fn harden(index: u32) -> u32 {
     index | 0x80_00_00_00
@@ -101,3 +102,28 @@ fn test_from_address() {
         NetworkInfo::testnet().network_id()
     );
 }
+
+#[test]
+fn test_account_key() {
+    let mnemonic_str = "lazy habit orient public finger other absorb shine cause mind general spend exit innocent drama";
+    let mnemonic = Mnemonic::parse_normalized(mnemonic_str).unwrap();
+    let bip32_private_key = Bip32PrivateKey::from_bip39_entropy(&mnemonic.to_entropy(), &[]);
+
+    // Wallets export the account key, from which the addresses are derived
+    let account_key = bip32_private_key
+        .derive(harden(1852))
+        .derive(harden(1815))
+        .derive(harden(0))
+        .to_public();
+    let address =
+        CardanoAddress::from_account_key(&account_key, 0, &CardanoNetwork::Preprod).unwrap();
+    assert_eq!(BASE_ADDR_ALICE, address.to_string());
+
+    let stake = CardanoPublicKey::from_account_key(&account_key, KeyRole::Stake, 0).unwrap();
+    assert_eq!(
+        derive_key(&bip32_private_key, 0, 2, 0)
+            .to_raw_key()
+            .to_raw_bytes(),
+        stake.0.as_bytes()
+    );
+}
@@ -37,6 +37,9 @@ pub enum PublicKeyError {
     #[error("invalid public key prefix: {0}")]
     InvalidPrefix(String),
 
+    #[error("invalid recovery id: {0}")]
+    InvalidRecoveryId(u8),
+
     #[error("no public spending key found")]
     NoSpendingKey,
 
@@ -0,0 +1,475 @@
+use crate::address::FilecoinAddress;
+use crate::amount::FilecoinAmount;
+use crate::transaction::{FilecoinTransactionParameters, RawBytes};
+use crate::utilities::crypto::blake2b_256;
+use anychain_core::TransactionError;
+use cid::Cid;
+use forest_encoding::tuple::*;
+use fvm_ipld_encoding::de::DeserializeOwned;
+use fvm_ipld_encoding::ser::Serialize;
+use fvm_shared::bigint::bigint_ser::{self, BigIntSer};
+use fvm_shared::MethodNum;
+
+/// The id of the init actor, which creates actors such as multisig wallets
+pub const INIT_ACTOR_ID: u64 = 1;
+
+/// The id of the datacap actor
+pub const DATACAP_ACTOR_ID: u64 = 7;
+
+pub const METHOD_INIT_EXEC: MethodNum = 2;
+
+pub const METHOD_MULTISIG_PROPOSE: MethodNum = 2;
+pub const METHOD_MULTISIG_APPROVE: MethodNum = 3;
+pub const METHOD_MULTISIG_CANCEL: MethodNum = 4;
+pub const METHOD_MULTISIG_ADD_SIGNER: MethodNum = 5;
+pub const METHOD_MULTISIG_CHANGE_NUM_APPROVALS_THRESHOLD: MethodNum = 8;
+
+pub const METHOD_MINER_WITHDRAW_BALANCE: MethodNum = 16;
+pub const METHOD_MINER_CHANGE_OWNER_ADDRESS: MethodNum = 23;
+
+/// The FRC-42 method number of the FRC-46 `Transfer` of the datacap actor
+pub const METHOD_DATACAP_TRANSFER: MethodNum = 80475954;
+
+/// A call to a method of a builtin actor, which yields the `method_num` and
+/// CBOR encoded `params` of a message
+pub trait FilecoinActorMethod: Sized {
+    fn method_num(&self) -> MethodNum;
+
+    fn params(&self) -> Result<RawBytes, TransactionError>;
+
+    /// Returns the call from the method number and params of a message
+    fn decode(method_num: MethodNum, params: &RawBytes) -> Result<Self, TransactionError>;
+}
+
+fn serialize<T: Serialize>(value: &T) -> Result<RawBytes, TransactionError> {
+    RawBytes::serialize(value)
+        .map_err(|e| TransactionError::Crate("fvm_ipld_encoding", format!("{e:?}")))
+}
+
+fn deserialize<T: DeserializeOwned>(params: &RawBytes) -> Result<T, TransactionError> {
+    params
+        .deserialize()
+        .map_err(|e| TransactionError::Crate("fvm_ipld_encoding", format!("{e:?}")))
+}
+
+fn unknown_method(actor: &str, method_num: MethodNum) -> TransactionError {
+    TransactionError::Message(format!("Unsupported {actor} method {method_num}"))
+}
+
+/// The params of the constructor of a multisig wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigConstructorParams {
+    pub signers: Vec<FilecoinAddress>,
+    pub num_approvals_threshold: u64,
+    pub unlock_duration: i64,
+    pub start_epoch: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigProposeParams {
+    pub to: FilecoinAddress,
+    #[serde(with = "bigint_ser")]
+    pub value: FilecoinAmount,
+    pub method: MethodNum,
+    pub params: RawBytes,
+}
+
+/// Identifies a pending transaction of a multisig wallet, optionally along
+/// with the hash of its proposal, which the wallet checks if not empty
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigTxnIdParams {
+    pub id: i64,
+    #[serde(with = "fvm_ipld_encoding::serde_bytes")]
+    pub proposal_hash: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigAddSignerParams {
+    pub signer: FilecoinAddress,
+    pub increase: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigChangeNumApprovalsThresholdParams {
+    pub new_threshold: u64,
+}
+
+/// The return value of `Propose`, where `applied` tells whether the
+/// transaction was executed right away
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct MultisigProposeReturn {
+    pub txn_id: i64,
+    pub applied: bool,
+    pub code: u32,
+    pub ret: RawBytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct InitExecParams {
+    pub code_cid: Cid,
+    pub constructor_params: RawBytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct InitExecReturn {
+    pub id_address: FilecoinAddress,
+    pub robust_address: FilecoinAddress,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DatacapTransferParams {
+    pub to: FilecoinAddress,
+    #[serde(with = "bigint_ser")]
+    pub amount: FilecoinAmount,
+    pub operator_data: RawBytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+struct MinerWithdrawBalanceParams {
+    #[serde(with = "bigint_ser")]
+    amount_requested: FilecoinAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultisigMethod {
+    Propose(MultisigProposeParams),
+    Approve(MultisigTxnIdParams),
+    Cancel(MultisigTxnIdParams),
+    /// Has to be proposed to the wallet itself
+    AddSigner(MultisigAddSignerParams),
+    /// Has to be proposed to the wallet itself
+    ChangeNumApprovalsThreshold(MultisigChangeNumApprovalsThresholdParams),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinerMethod {
+    /// Withdraws the requested amount of available balance to the owner
+    WithdrawBalance(FilecoinAmount),
+    /// Proposes a new owner if sent by the owner, or confirms the change if
+    /// sent by the proposed owner with the same address
+    ChangeOwnerAddress(FilecoinAddress),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitMethod {
+    Exec(InitExecParams),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatacapMethod {
+    Transfer(DatacapTransferParams),
+}
+
+impl MultisigProposeParams {
+    /// Returns the hash of the proposal of a pending transaction, which can
+    /// be passed to `Approve` or `Cancel` to make sure they apply to the
+    /// expected transaction. The multisig actor hashes the proposer as its
+    /// first approver, so `requester` must be the ID address of the proposer
+    pub fn proposal_hash(&self, requester: &FilecoinAddress) -> Result<Vec<u8>, TransactionError> {
+        requester
+            .id()
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        let data = (
+            requester,
+            &self.to,
+            BigIntSer(&self.value),
+            &self.method,
+            &self.params,
+        );
+        Ok(blake2b_256(serialize(&data)?.bytes()).to_vec())
+    }
+}
+
+impl InitExecParams {
+    /// Returns the params to create a multisig wallet, where `code_cid` is
+    /// the code of the multisig actor in the actor bundle of the network
+    pub fn new_multisig(
+        code_cid: Cid,
+        params: &MultisigConstructorParams,
+    ) -> Result<Self, TransactionError> {
+        Ok(InitExecParams {
+            code_cid,
+            constructor_params: serialize(params)?,
+        })
+    }
+
+    pub fn multisig_params(&self) -> Result<MultisigConstructorParams, TransactionError> {
+        deserialize(&self.constructor_params)
+    }
+}
+
+impl FilecoinActorMethod for MultisigMethod {
+    fn method_num(&self) -> MethodNum {
+        match self {
+            Self::Propose(_) => METHOD_MULTISIG_PROPOSE,
+            Self::Approve(_) => METHOD_MULTISIG_APPROVE,
+            Self::Cancel(_) => METHOD_MULTISIG_CANCEL,
+            Self::AddSigner(_) => METHOD_MULTISIG_ADD_SIGNER,
+            Self::ChangeNumApprovalsThreshold(_) => METHOD_MULTISIG_CHANGE_NUM_APPROVALS_THRESHOLD,
+        }
+    }
+
+    fn params(&self) -> Result<RawBytes, TransactionError> {
+        match self {
+            Self::Propose(params) => serialize(params),
+            Self::Approve(params) | Self::Cancel(params) => serialize(params),
+            Self::AddSigner(params) => serialize(params),
+            Self::ChangeNumApprovalsThreshold(params) => serialize(params),
+        }
+    }
+
+    fn decode(method_num: MethodNum, params: &RawBytes) -> Result<Self, TransactionError> {
+        match method_num {
+            METHOD_MULTISIG_PROPOSE => Ok(Self::Propose(deserialize(params)?)),
+            METHOD_MULTISIG_APPROVE => Ok(Self::Approve(deserialize(params)?)),
+            METHOD_MULTISIG_CANCEL => Ok(Self::Cancel(deserialize(params)?)),
+            METHOD_MULTISIG_ADD_SIGNER => Ok(Self::AddSigner(deserialize(params)?)),
+            METHOD_MULTISIG_CHANGE_NUM_APPROVALS_THRESHOLD => {
+                Ok(Self::ChangeNumApprovalsThreshold(deserialize(params)?))
+            }
+            _ => Err(unknown_method("multisig", method_num)),
+        }
+    }
+}
+
+impl FilecoinActorMethod for MinerMethod {
+    fn method_num(&self) -> MethodNum {
+        match self {
+            Self::WithdrawBalance(_) => METHOD_MINER_WITHDRAW_BALANCE,
+            Self::ChangeOwnerAddress(_) => METHOD_MINER_CHANGE_OWNER_ADDRESS,
+        }
+    }
+
+    fn params(&self) -> Result<RawBytes, TransactionError> {
+        match self {
+            Self::WithdrawBalance(amount) => serialize(&MinerWithdrawBalanceParams {
+                amount_requested: amount.clone(),
+            }),
+            // The params are the bare address
+            Self::ChangeOwnerAddress(owner) => serialize(owner),
+        }
+    }
+
+    fn decode(method_num: MethodNum, params: &RawBytes) -> Result<Self, TransactionError> {
+        match method_num {
+            METHOD_MINER_WITHDRAW_BALANCE => {
+                let params: MinerWithdrawBalanceParams = deserialize(params)?;
+                Ok(Self::WithdrawBalance(params.amount_requested))
+            }
+            METHOD_MINER_CHANGE_OWNER_ADDRESS => Ok(Self::ChangeOwnerAddress(deserialize(params)?)),
+            _ => Err(unknown_method("miner", method_num)),
+        }
+    }
+}
+
+impl FilecoinActorMethod for InitMethod {
+    fn method_num(&self) -> MethodNum {
+        match self {
+            Self::Exec(_) => METHOD_INIT_EXEC,
+        }
+    }
+
+    fn params(&self) -> Result<RawBytes, TransactionError> {
+        match self {
+            Self::Exec(params) => serialize(params),
+        }
+    }
+
+    fn decode(method_num: MethodNum, params: &RawBytes) -> Result<Self, TransactionError> {
+        match method_num {
+            METHOD_INIT_EXEC => Ok(Self::Exec(deserialize(params)?)),
+            _ => Err(unknown_method("init", method_num)),
+        }
+    }
+}
+
+impl FilecoinActorMethod for DatacapMethod {
+    fn method_num(&self) -> MethodNum {
+        match self {
+            Self::Transfer(_) => METHOD_DATACAP_TRANSFER,
+        }
+    }
+
+    fn params(&self) -> Result<RawBytes, TransactionError> {
+        match self {
+            Self::Transfer(params) => serialize(params),
+        }
+    }
+
+    fn decode(method_num: MethodNum, params: &RawBytes) -> Result<Self, TransactionError> {
+        match method_num {
+            METHOD_DATACAP_TRANSFER => Ok(Self::Transfer(deserialize(params)?)),
+            _ => Err(unknown_method("datacap", method_num)),
+        }
+    }
+}
+
+impl FilecoinTransactionParameters {
+    /// Sets the `method_num` and `params` of the message to the call
+    pub fn set_method<M: FilecoinActorMethod>(
+        &mut self,
+        method: &M,
+    ) -> Result<(), TransactionError> {
+        self.params = method.params()?;
+        self.method_num = method.method_num();
+        Ok(())
+    }
+
+    /// Returns the call of the message as a method of the given actor
+    pub fn method<M: FilecoinActorMethod>(&self) -> Result<M, TransactionError> {
+        M::decode(self.method_num, &self.params)
+    }
+}
+
+/// Returns the decoded return value of a method, such as `InitExecReturn`
+/// for `Exec` or `MultisigProposeReturn` for `Propose`
+pub fn decode_return<T: DeserializeOwned>(ret: &RawBytes) -> Result<T, TransactionError> {
+    deserialize(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_multisig_methods() {
+        let wallet = FilecoinAddress::new_id(1000);
+        let signer = FilecoinAddress::new_id(1234);
+
+        let add_signer = MultisigMethod::AddSigner(MultisigAddSignerParams {
+            signer,
+            increase: true,
+        });
+        let inner = add_signer.params().unwrap();
+        // [h'00d209', true]
+        assert_eq!(vec![0x82, 0x43, 0x00, 0xd2, 0x09, 0xf5], inner.to_vec());
+
+        let propose = MultisigProposeParams {
+            to: wallet,
+            value: FilecoinAmount::from(0),
+            method: add_signer.method_num(),
+            params: inner,
+        };
+        let mut params = FilecoinTransactionParameters {
+            from: FilecoinAddress::from_str("f1lhjzzj6on64czzsgfw5jxsf7y5uv5qvh7dmpevy").unwrap(),
+            to: wallet,
+            ..Default::default()
+        };
+        params
+            .set_method(&MultisigMethod::Propose(propose.clone()))
+            .unwrap();
+        assert_eq!(METHOD_MULTISIG_PROPOSE, params.method_num);
+        let decoded: MultisigMethod = params.method().unwrap();
+        assert_eq!(MultisigMethod::Propose(propose.clone()), decoded);
+        assert_eq!(
+            add_signer,
+            MultisigMethod::decode(propose.method, &propose.params).unwrap()
+        );
+
+        // The proposer is hashed by its ID address
+        assert!(propose.proposal_hash(&params.from).is_err());
+        let hash = propose
+            .proposal_hash(&FilecoinAddress::new_id(1001))
+            .unwrap();
+        assert_eq!(32, hash.len());
+        assert_ne!(
+            hash,
+            propose.proposal_hash(&FilecoinAddress::new_id(1)).unwrap()
+        );
+        let approve = MultisigMethod::Approve(MultisigTxnIdParams {
+            id: 3,
+            proposal_hash: hash,
+        });
+        let bytes = approve.params().unwrap();
+        assert_eq!(&[0x82, 0x03, 0x58, 0x20], &bytes.bytes()[..4]);
+        assert_eq!(approve, MultisigMethod::decode(3, &bytes).unwrap());
+        assert!(matches!(
+            MultisigMethod::decode(4, &bytes).unwrap(),
+            MultisigMethod::Cancel(_)
+        ));
+
+        let threshold = MultisigMethod::ChangeNumApprovalsThreshold(
+            MultisigChangeNumApprovalsThresholdParams { new_threshold: 2 },
+        );
+        assert_eq!(vec![0x81, 0x02], threshold.params().unwrap().to_vec());
+        assert!(MultisigMethod::decode(9, &threshold.params().unwrap()).is_err());
+        assert!(MultisigMethod::decode(2, &threshold.params().unwrap()).is_err());
+
+        let ret = RawBytes::new(vec![0x84, 0x03, 0xf4, 0x00, 0x40]);
+        let ret: MultisigProposeReturn = decode_return(&ret).unwrap();
+        assert_eq!(3, ret.txn_id);
+        assert!(!ret.applied);
+    }
+
+    #[test]
+    fn test_miner_methods() {
+        let withdraw = MinerMethod::WithdrawBalance(FilecoinAmount::from(1));
+        // [h'0001'], with the sign byte of the big integer
+        assert_eq!(
+            vec![0x81, 0x42, 0x00, 0x01],
+            withdraw.params().unwrap().to_vec()
+        );
+        assert_eq!(
+            withdraw,
+            MinerMethod::decode(16, &withdraw.params().unwrap()).unwrap()
+        );
+
+        let owner = MinerMethod::ChangeOwnerAddress(FilecoinAddress::new_id(1234));
+        assert_eq!(
+            vec![0x43, 0x00, 0xd2, 0x09],
+            owner.params().unwrap().to_vec()
+        );
+        assert_eq!(
+            owner,
+            MinerMethod::decode(23, &owner.params().unwrap()).unwrap()
+        );
+        assert!(MinerMethod::decode(16, &owner.params().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_init_exec() {
+        let code_cid =
+            Cid::from_str("bafk2bzacecxq2gqmdtb7hzqzlkzwqvegb2ca5ev5sgn5rhswpl5ocanfvbigy")
+                .unwrap();
+        let constructor = MultisigConstructorParams {
+            signers: vec![FilecoinAddress::new_id(1000), FilecoinAddress::new_id(1001)],
+            num_approvals_threshold: 2,
+            unlock_duration: 0,
+            start_epoch: 0,
+        };
+        let exec = InitExecParams::new_multisig(code_cid, &constructor).unwrap();
+        let method = InitMethod::Exec(exec.clone());
+        let bytes = method.params().unwrap();
+        // The code cid is encoded with the tag 42
+        assert_eq!(&[0x82, 0xd8, 0x2a], &bytes.bytes()[..3]);
+        let InitMethod::Exec(decoded) = InitMethod::decode(2, &bytes).unwrap();
+        assert_eq!(exec, decoded);
+        assert_eq!(constructor, decoded.multisig_params().unwrap());
+
+        let ret = serialize(&InitExecReturn {
+            id_address: FilecoinAddress::new_id(1002),
+            robust_address: FilecoinAddress::new_actor(b"multisig"),
+        })
+        .unwrap();
+        let ret: InitExecReturn = decode_return(&ret).unwrap();
+        assert_eq!(1002, ret.id_address.id().unwrap());
+    }
+
+    #[test]
+    fn test_datacap_transfer() {
+        let transfer = DatacapMethod::Transfer(DatacapTransferParams {
+            to: FilecoinAddress::new_id(6),
+            amount: FilecoinAmount::from(1),
+            operator_data: RawBytes::default(),
+        });
+        assert_eq!(
+            vec![0x83, 0x42, 0x00, 0x06, 0x42, 0x00, 0x01, 0x40],
+            transfer.params().unwrap().to_vec()
+        );
+        assert_eq!(
+            transfer,
+            DatacapMethod::decode(METHOD_DATACAP_TRANSFER, &transfer.params().unwrap()).unwrap()
+        );
+    }
+}
@@ -9,8 +9,10 @@ use std::hash::Hash;
 use std::str::FromStr;
 
 use crate::utilities::crypto::{blake2b_160, blake2b_checksum};
+use anychain_core::utilities::crypto::keccak256;
 use anychain_core::PublicKey;
-use anychain_core::{Address, AddressError};
+use anychain_core::{hex, Address, AddressError};
+use anychain_ethereum::EthereumAddress;
 use bls_signatures::Serialize as BlsSerialize;
 
 use data_encoding::DecodeError;
@@ -46,13 +48,21 @@ impl Address for FilecoinAddress {
     /// Returns the address corresponding to the given public key.
     fn from_public_key(
         public_key: &Self::PublicKey,
-        _: &Self::Format,
+        format: &Self::Format,
     ) -> Result<Self, AddressError> {
-        match public_key {
-            FilecoinPublicKey::Secp256k1(key) => {
+        match (public_key, format) {
+            (FilecoinPublicKey::Secp256k1(key), FilecoinFormat::Base32) => {
                 Ok(FilecoinAddress::new_secp256k1(&key.serialize()).unwrap())
             }
-            FilecoinPublicKey::Bls(key) => Ok(FilecoinAddress::new_bls(&key.as_bytes()).unwrap()),
+            (FilecoinPublicKey::Secp256k1(key), FilecoinFormat::Delegated) => {
+                Ok(FilecoinAddress::new_delegated_secp256k1(key))
+            }
+            (FilecoinPublicKey::Bls(key), FilecoinFormat::Base32) => {
+                Ok(FilecoinAddress::new_bls(&key.as_bytes()).unwrap())
+            }
+            (FilecoinPublicKey::Bls(_), FilecoinFormat::Delegated) => Err(AddressError::Message(
+                "bls keys have no delegated address".to_string(),
+            )),
         }
     }
 }
@@ -115,6 +125,53 @@ impl FilecoinAddress {
         }
     }
 
+    /// Generates new address using the Delegated protocol, i.e. a subaddress
+    /// in the namespace of an address manager actor
+    pub fn new_delegated(namespace: u64, subaddress: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            network: NETWORK_DEFAULT,
+            payload: Payload::Delegated(DelegatedAddress::new(namespace, subaddress)?),
+        })
+    }
+
+    /// Generates the f410 address of the Ethereum account of a Secp256k1
+    /// pubkey, which the EAM assigns to it
+    pub fn new_delegated_secp256k1(pubkey: &libsecp256k1::PublicKey) -> Self {
+        let hash = keccak256(&pubkey.serialize()[1..]);
+        Self::new_delegated(EAM_NAMESPACE, &hash[12..]).unwrap()
+    }
+
+    /// Returns the address of an Ethereum address, which is an ID address
+    /// for a masked ID address 0xff00..00<id> and an f410 address otherwise
+    pub fn from_ethereum_address(address: &EthereumAddress) -> Result<Self, Error> {
+        let bytes = address.to_bytes().map_err(|_| Error::InvalidPayload)?;
+        if bytes.len() != ETH_ADDRESS_LEN {
+            return Err(Error::InvalidPayloadLength(bytes.len()));
+        }
+        if bytes[0] == 0xff && bytes[1..12].iter().all(|b| *b == 0) {
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&bytes[12..]);
+            return Ok(Self::new_id(u64::from_be_bytes(id)));
+        }
+        Self::new_delegated(EAM_NAMESPACE, &bytes)
+    }
+
+    /// Returns the Ethereum address of an f410 or ID address
+    pub fn to_ethereum_address(&self) -> Result<EthereumAddress, Error> {
+        let bytes = match self.payload {
+            Payload::ID(id) => [[0xff].as_slice(), &[0u8; 11], &id.to_be_bytes()].concat(),
+            Payload::Delegated(addr)
+                if addr.namespace() == EAM_NAMESPACE
+                    && addr.subaddress().len() == ETH_ADDRESS_LEN =>
+            {
+                addr.subaddress().to_vec()
+            }
+            _ => return Err(Error::NonDelegatedAddress),
+        };
+        EthereumAddress::from_str(&format!("0x{}", hex::encode(bytes)))
+            .map_err(|_| Error::InvalidPayload)
+    }
+
     /// Generates new address using BLS pubkey
     pub fn new_bls(pubkey: &[u8]) -> Result<Self, Error> {
         if pubkey.len() != BLS_PUB_LEN {
@@ -209,6 +266,7 @@ impl FromStr for FilecoinAddress {
             "1" => Protocol::Secp256k1,
             "2" => Protocol::Actor,
             "3" => Protocol::BLS,
+            "4" => Protocol::Delegated,
             _ => {
                 return Err(Error::UnknownProtocol);
             }
@@ -228,6 +286,28 @@ impl FromStr for FilecoinAddress {
             });
         }
 
+        if protocol == Protocol::Delegated {
+            // the namespace is followed by 'f' and the encoded subaddress
+            let (namespace, raw) = raw.split_once('f').ok_or(Error::InvalidPayload)?;
+            if namespace.len() > 20 {
+                return Err(Error::InvalidLength);
+            }
+            let namespace = namespace
+                .parse::<u64>()
+                .map_err(|_| Error::InvalidPayload)?;
+            let mut subaddress = ADDRESS_ENCODER.decode(raw.as_bytes())?;
+            if subaddress.len() < CHECKSUM_HASH_LEN {
+                return Err(Error::InvalidLength);
+            }
+            let cksm = subaddress.split_off(subaddress.len() - CHECKSUM_HASH_LEN);
+            let mut addr = FilecoinAddress::new_delegated(namespace, &subaddress)?;
+            if !validate_checksum(&addr.to_bytes(), cksm) {
+                return Err(Error::InvalidChecksum);
+            }
+            addr.set_network(network);
+            return Ok(addr);
+        }
+
         // decode using byte32 encoding
         let mut payload = ADDRESS_ENCODER.decode(raw.as_bytes())?;
         // payload includes checksum at end, so split after decoding
@@ -300,6 +380,20 @@ fn encode(addr: &FilecoinAddress) -> String {
             addr.protocol(),
             from_leb_bytes(&addr.payload_bytes()).expect("should read encoded bytes"),
         ),
+        Protocol::Delegated => {
+            let Payload::Delegated(delegated) = addr.payload else {
+                unreachable!()
+            };
+            let mut bz = delegated.subaddress().to_vec();
+            bz.extend(blake2b_checksum(&addr.to_bytes()));
+            format!(
+                "{}{}{}f{}",
+                addr.network.to_prefix(),
+                addr.protocol(),
+                delegated.namespace(),
+                ADDRESS_ENCODER.encode(&bz),
+            )
+        }
     }
 }
 
@@ -335,6 +429,8 @@ pub enum Protocol {
     Actor = 2,
     /// BLS key addressing
     BLS = 3,
+    /// Delegated addressing, a namespace with an arbitrary subaddress
+    Delegated = 4,
 }
 
 impl Protocol {
@@ -364,6 +460,43 @@ pub enum Payload {
     Actor([u8; PAYLOAD_HASH_LEN]),
     /// BLS key address, full 48 byte public key
     BLS([u8; BLS_PUB_LEN]),
+    /// Delegated address, such as the f410 address of an Ethereum account
+    Delegated(DelegatedAddress),
+}
+
+/// A delegated (f4) address, which is a subaddress of at most 54 bytes in the
+/// namespace of an address manager actor
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "arb", derive(arbitrary::Arbitrary))]
+pub struct DelegatedAddress {
+    namespace: u64,
+    length: usize,
+    buffer: [u8; MAX_SUBADDRESS_LEN],
+}
+
+impl DelegatedAddress {
+    pub fn new(namespace: u64, subaddress: &[u8]) -> Result<Self, Error> {
+        let length = subaddress.len();
+        if length > MAX_SUBADDRESS_LEN {
+            return Err(Error::InvalidSubaddressLength(length));
+        }
+        let mut buffer = [0u8; MAX_SUBADDRESS_LEN];
+        buffer[..length].copy_from_slice(subaddress);
+        Ok(Self {
+            namespace,
+            length,
+            buffer,
+        })
+    }
+
+    /// Returns the actor id of the address manager of the address
+    pub fn namespace(&self) -> u64 {
+        self.namespace
+    }
+
+    pub fn subaddress(&self) -> &[u8] {
+        &self.buffer[..self.length]
+    }
 }
 
 impl Default for Payload {
@@ -381,19 +514,17 @@ impl Payload {
             Secp256k1(arr) => arr.to_vec(),
             Actor(arr) => arr.to_vec(),
             BLS(arr) => arr.to_vec(),
+            Delegated(addr) => {
+                let mut bz = to_leb_bytes(addr.namespace()).unwrap();
+                bz.extend(addr.subaddress());
+                bz
+            }
         }
     }
 
     /// Returns encoded bytes of Address including the protocol byte.
     pub fn to_bytes(self) -> Vec<u8> {
-        use Payload::*;
-        let mut bz = match self {
-            ID(i) => to_leb_bytes(i).unwrap(),
-            Secp256k1(arr) => arr.to_vec(),
-            Actor(arr) => arr.to_vec(),
-            BLS(arr) => arr.to_vec(),
-        };
-
+        let mut bz = self.to_raw_bytes();
         bz.insert(0, Protocol::from(self) as u8);
         bz
     }
@@ -417,6 +548,11 @@ impl Payload {
                     .try_into()
                     .map_err(|_| Error::InvalidPayloadLength(payload.len()))?,
             ),
+            Protocol::Delegated => {
+                let (namespace, subaddress) =
+                    unsigned_varint::decode::u64(payload).map_err(|_| Error::InvalidPayload)?;
+                Self::Delegated(DelegatedAddress::new(namespace, subaddress)?)
+            }
         };
         Ok(payload)
     }
@@ -429,6 +565,7 @@ impl From<Payload> for Protocol {
             Payload::Secp256k1(_) => Self::Secp256k1,
             Payload::Actor(_) => Self::Actor,
             Payload::BLS(_) => Self::BLS,
+            Payload::Delegated(_) => Self::Delegated,
         }
     }
 }
@@ -440,6 +577,7 @@ impl From<&Payload> for Protocol {
             Payload::Secp256k1(_) => Self::Secp256k1,
             Payload::Actor(_) => Self::Actor,
             Payload::BLS(_) => Self::BLS,
+            Payload::Delegated(_) => Self::Delegated,
         }
     }
 }
@@ -467,6 +605,10 @@ pub enum Error {
     Base32Decoding(#[from] DecodeError),
     #[error("Cannot get id from non id address")]
     NonIDAddress,
+    #[error("Invalid subaddress length, max: {} got: {0}", MAX_SUBADDRESS_LEN)]
+    InvalidSubaddressLength(usize),
+    #[error("Address has no Ethereum address")]
+    NonDelegatedAddress,
 }
 
 /// Network defines the preconfigured networks to use with address encoding
@@ -504,6 +646,15 @@ pub const SECP_PUB_LEN: usize = 65;
 /// BLS public key length used for validation of BLS addresses.
 pub const BLS_PUB_LEN: usize = 48;
 
+/// Max length of the subaddress of delegated addresses.
+pub const MAX_SUBADDRESS_LEN: usize = 54;
+
+/// The namespace of the Ethereum Address Manager, i.e. the f410 addresses.
+pub const EAM_NAMESPACE: u64 = 10;
+
+/// Length of the Ethereum addresses of f410 addresses.
+pub const ETH_ADDRESS_LEN: usize = 20;
+
 lazy_static::lazy_static! {
     static ref BLS_ZERO_ADDR_BYTES: [u8; BLS_PUB_LEN] = {
         let bz_addr = FilecoinAddress::from_str("f3yaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaby2smx7a");
@@ -518,7 +669,8 @@ lazy_static::lazy_static! {
 /// Length of the checksum hash for string encodings.
 pub const CHECKSUM_HASH_LEN: usize = 4;
 
-const MAX_ADDRESS_LEN: usize = 84 + 2;
+// The longest is a delegated address with a u64 namespace and a 54 byte subaddress
+const MAX_ADDRESS_LEN: usize = 138;
 const MAINNET_PREFIX: &str = "f";
 const TESTNET_PREFIX: &str = "t";
 
@@ -552,4 +704,78 @@ mod tests {
         assert_eq!(addr.network(), Network::Mainnet);
         assert_eq!("f1ksu3ktw4xhyaoltwr546b3epfs5wxxqfyyxipwi", addr_str);
     }
+
+    #[test]
+    fn test_delegated_address() {
+        let vectors: [(u64, &[u8], &str); 3] = [
+            (32, &[0xff; 5], "f432f77777777x32lpna"),
+            (u64::MAX, &[], "f418446744073709551615ftnkyfaq"),
+            (u64::MAX, &[0; MAX_SUBADDRESS_LEN], "f418446744073709551615faaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaafbbuagu"),
+        ];
+        for (namespace, subaddress, expected) in vectors {
+            let mut addr = FilecoinAddress::new_delegated(namespace, subaddress).unwrap();
+            addr.set_network(Network::Mainnet);
+            assert_eq!(expected, addr.to_string());
+            assert_eq!(addr, FilecoinAddress::from_str(expected).unwrap());
+            assert_eq!(
+                addr.to_bytes(),
+                FilecoinAddress::from_bytes(&addr.to_bytes())
+                    .unwrap()
+                    .to_bytes()
+            );
+        }
+        assert_eq!(
+            Err(Error::InvalidSubaddressLength(55)),
+            FilecoinAddress::new_delegated(EAM_NAMESPACE, &[0; 55])
+        );
+        assert_eq!(
+            Err(Error::InvalidChecksum),
+            FilecoinAddress::from_str("f432f77777777y32lpna")
+        );
+    }
+
+    #[test]
+    fn test_ethereum_address() {
+        let secret_key = libsecp256k1::SecretKey::parse_slice(&[1u8; 32]).unwrap();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+        let eth = anychain_ethereum::EthereumPublicKey::from_secp256k1_public_key(public_key)
+            .to_address(&anychain_ethereum::EthereumFormat::Standard)
+            .unwrap();
+
+        let addr = FilecoinPublicKey::Secp256k1(public_key)
+            .to_address(&FilecoinFormat::Delegated)
+            .unwrap();
+        assert_eq!(Protocol::Delegated, addr.protocol());
+        assert!(addr.to_string().starts_with("t410f"));
+        assert_eq!(addr, FilecoinAddress::from_ethereum_address(&eth).unwrap());
+        assert_eq!(
+            eth.to_string().to_lowercase(),
+            addr.to_ethereum_address().unwrap().to_string()
+        );
+        assert_eq!(addr, FilecoinAddress::from_str(&addr.to_string()).unwrap());
+
+        // The same address as encoded by fvm_shared
+        let mut mainnet = addr;
+        mainnet.set_network(Network::Mainnet);
+        let fvm =
+            fvm_shared::address::Address::new_delegated(EAM_NAMESPACE, &eth.to_bytes().unwrap())
+                .unwrap();
+        assert_eq!(fvm.to_string(), mainnet.to_string());
+
+        // Masked ID addresses are ID addresses
+        let masked =
+            EthereumAddress::from_str("0xff00000000000000000000000000000000000400").unwrap();
+        let id = FilecoinAddress::from_ethereum_address(&masked).unwrap();
+        assert_eq!(FilecoinAddress::new_id(1024), id);
+        assert_eq!(masked, id.to_ethereum_address().unwrap());
+
+        let f1 = FilecoinAddress::new_secp256k1(&public_key.serialize()).unwrap();
+        assert_eq!(Err(Error::NonDelegatedAddress), f1.to_ethereum_address());
+        let bls = bls_signatures::PrivateKey::from_bytes(&[7u8; 32])
+            .unwrap()
+            .public_key();
+        assert!(FilecoinPublicKey::Bls(bls)
+            .to_address(&FilecoinFormat::Delegated)
+            .is_err());
+    }
 }
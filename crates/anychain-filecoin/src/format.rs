@@ -7,12 +7,17 @@ use serde::Serialize;
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum FilecoinFormat {
     Base32,
+    /// The f410 address of the Ethereum account of a Secp256k1 key
+    Delegated,
 }
 
 impl Format for FilecoinFormat {}
 
 impl fmt::Display for FilecoinFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Base32")
+        match self {
+            FilecoinFormat::Base32 => write!(f, "Base32"),
+            FilecoinFormat::Delegated => write!(f, "Delegated"),
+        }
     }
 }
@@ -1,3 +1,6 @@
+pub mod actor;
+pub use self::actor::*;
+
 pub mod address;
 pub use self::address::*;
 
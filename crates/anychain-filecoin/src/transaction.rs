@@ -1,18 +1,21 @@
 #![allow(non_local_definitions)]
 use crate::address::ADDRESS_ENCODER as BASE32_ENCODER;
-use crate::address::{FilecoinAddress, Protocol};
+use crate::address::{FilecoinAddress, Network, Payload, Protocol, EAM_NAMESPACE};
 use crate::amount::FilecoinAmount;
 use crate::format::FilecoinFormat;
 use crate::public_key::FilecoinPublicKey;
 use crate::utilities::crypto::blake2b_256;
+use anychain_core::utilities::crypto::keccak256;
 use anychain_core::{Transaction, TransactionError, TransactionId};
+use anychain_ethereum::Eip1559TransactionParameters;
 use cid::Cid;
+use ethereum_types::U256;
 
 use anyhow::anyhow;
 use fvm_ipld_encoding::de::{Deserialize, Deserializer};
 use fvm_ipld_encoding::ser::{Serialize, Serializer};
 pub use fvm_ipld_encoding::RawBytes;
-use fvm_ipld_encoding::{de, ser, serde_bytes};
+use fvm_ipld_encoding::{de, ser, serde_bytes, BytesDe, BytesSer};
 
 use forest_encoding::tuple::*;
 use fvm_ipld_encoding::repr::*;
@@ -30,6 +33,49 @@ use self::json::FilecoinTransactionJson;
 const DAG_CBOR_CODEC: u64 = 0x71;
 const BLAKE2B_256_MULTIHASH_CODE: u64 = 0xb220;
 
+/// The method number of a plain value transfer
+pub const METHOD_SEND: MethodNum = 0;
+
+/// The method number of `InvokeContract` of EVM actors, i.e. FEVM calls
+pub const METHOD_INVOKE_CONTRACT: MethodNum = 3844450837;
+
+/// The EIP-155 chain id of Filecoin mainnet
+pub const FILECOIN_MAINNET_CHAIN_ID: u32 = 314;
+
+/// The EIP-155 chain id of the calibration testnet
+pub const FILECOIN_CALIBRATION_CHAIN_ID: u32 = 314159;
+
+/// Returns the params of an `InvokeContract` message, which are the calldata
+/// as a CBOR byte string, or none for empty calldata
+pub fn invoke_contract_params(calldata: &[u8]) -> RawBytes {
+    match calldata.is_empty() {
+        true => RawBytes::default(),
+        // Safe to unwrap here, a byte string cannot fail to serialize.
+        false => RawBytes::serialize(BytesSer(calldata)).unwrap(),
+    }
+}
+
+/// Returns the calldata of the params of an `InvokeContract` message
+pub fn invoke_contract_calldata(params: &RawBytes) -> Result<Vec<u8>, TransactionError> {
+    match params.is_empty() {
+        true => Ok(vec![]),
+        false => params
+            .deserialize::<BytesDe>()
+            .map(|calldata| calldata.into_vec())
+            .map_err(|e| TransactionError::Crate("fvm_ipld_encoding", format!("{e:?}"))),
+    }
+}
+
+fn to_u256(amount: &FilecoinAmount) -> Result<U256, TransactionError> {
+    let (sign, bytes) = amount.to_bytes_be();
+    if sign == num_bigint::Sign::Minus || bytes.len() > 32 {
+        return Err(TransactionError::Message(format!(
+            "Invalid amount {amount}"
+        )));
+    }
+    Ok(U256::from_big_endian(&bytes))
+}
+
 fn cid_from_cbor<T: ser::Serialize>(value: &T) -> anyhow::Result<Cid> {
     let data = fvm_ipld_encoding::to_vec(value)?;
     let digest = blake2b_256(&data);
@@ -67,6 +113,65 @@ impl FilecoinTransactionParameters {
         self.cid().unwrap().to_bytes()
     }
 
+    /// Returns the EIP-1559 transaction of a message sent from an f410
+    /// address, as Lotus converts it for the signature of the sender
+    pub fn to_eip1559(
+        &self,
+        chain_id: u32,
+    ) -> Result<Eip1559TransactionParameters, TransactionError> {
+        if self.version != 0 {
+            return Err(TransactionError::Message(format!(
+                "Unsupported message version {}",
+                self.version
+            )));
+        }
+        match self.from.payload() {
+            Payload::Delegated(addr) if addr.namespace() == EAM_NAMESPACE => {}
+            _ => {
+                return Err(TransactionError::Message(format!(
+                    "{} is not an f410 address",
+                    self.from
+                )))
+            }
+        }
+        let data = match self.method_num {
+            METHOD_SEND if self.params.is_empty() => vec![],
+            METHOD_INVOKE_CONTRACT => invoke_contract_calldata(&self.params)?,
+            method => {
+                return Err(TransactionError::Message(format!(
+                    "Method {method} cannot be sent from an f410 address"
+                )))
+            }
+        };
+        let to = self
+            .to
+            .to_ethereum_address()
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
+        if self.gas_limit < 0 {
+            return Err(TransactionError::Message(
+                "Message has negative gas limit".to_string(),
+            ));
+        }
+        Ok(Eip1559TransactionParameters {
+            chain_id,
+            nonce: U256::from(self.sequence),
+            max_priority_fee_per_gas: to_u256(&self.gas_premium)?,
+            max_fee_per_gas: to_u256(&self.gas_fee_cap)?,
+            gas_limit: U256::from(self.gas_limit),
+            to,
+            amount: to_u256(&self.value)?,
+            data,
+            access_list: vec![],
+        })
+    }
+
+    /// Returns the digest an f410 sender signs, i.e. the keccak256 hash of
+    /// the RLP encoded EIP-1559 transaction of the message
+    pub fn eth_digest(&self, chain_id: u32) -> Result<Vec<u8>, TransactionError> {
+        let rlp = self.to_eip1559(chain_id)?.to_rlp(9)?.out().to_vec();
+        Ok(keccak256(&[vec![2u8], rlp].concat()).to_vec())
+    }
+
     /// Does some basic checks on the Message to see if the fields are valid.
     pub fn check(self: &FilecoinTransactionParameters) -> anyhow::Result<()> {
         if self.gas_limit == 0 {
@@ -151,6 +256,9 @@ pub enum FilecoinSignatureType {
     #[default]
     Secp256k1 = 1,
     BLS = 2,
+    /// A Secp256k1 signature of the EIP-1559 transaction of a message sent
+    /// from an f410 address
+    Delegated = 3,
 }
 
 /// A cryptographic signature, represented in bytes, of any key protocol.
@@ -187,14 +295,16 @@ impl<'de> de::Deserialize<'de> for FilecoinSignature {
         }
 
         // Remove signature type byte
-        let mut sig_type = FilecoinSignatureType::Secp256k1;
-        let b = bytes[0];
-        if b == 1 {
-        } else if b == 2 {
-            sig_type = FilecoinSignatureType::BLS;
-        } else {
-            panic!("Invalid signature type byte (must be 1 or 2)")
-        }
+        let sig_type = match bytes[0] {
+            1 => FilecoinSignatureType::Secp256k1,
+            2 => FilecoinSignatureType::BLS,
+            3 => FilecoinSignatureType::Delegated,
+            b => {
+                return Err(de::Error::custom(format!(
+                    "Invalid signature type byte {b} (must be 1, 2 or 3)"
+                )))
+            }
+        };
 
         Ok(FilecoinSignature {
             bytes: bytes[1..].to_vec(),
@@ -254,6 +364,7 @@ impl Transaction for FilecoinTransaction {
             sig_type: match self.params.from.protocol() {
                 Protocol::Secp256k1 => FilecoinSignatureType::Secp256k1,
                 Protocol::BLS => FilecoinSignatureType::BLS,
+                Protocol::Delegated => FilecoinSignatureType::Delegated,
                 _ => panic!("Unrecognized signature type"),
             },
             bytes: signature,
@@ -285,8 +396,16 @@ impl FilecoinTransaction {
         cid_from_cbor(self)
     }
 
+    /// Returns the digest the sender signs, which is the EIP-1559 digest on
+    /// mainnet or the calibration testnet for an f410 sender
     pub fn digest(&self) -> Result<Vec<u8>, TransactionError> {
-        Ok(blake2b_256(&self.params.to_bytes()).to_vec())
+        match self.params.from.protocol() {
+            Protocol::Delegated => self.params.eth_digest(match self.params.from.network() {
+                Network::Mainnet => FILECOIN_MAINNET_CHAIN_ID,
+                Network::Testnet => FILECOIN_CALIBRATION_CHAIN_ID,
+            }),
+            _ => Ok(blake2b_256(&self.params.to_bytes()).to_vec()),
+        }
     }
 }
 
@@ -535,6 +654,7 @@ pub mod signature_json {
         enum JsonHelperEnum {
             Bls,
             Secp256k1,
+            Delegated,
         }
 
         #[derive(Debug, Serialize, Deserialize)]
@@ -551,6 +671,7 @@ pub mod signature_json {
             let json = match sig_type {
                 FilecoinSignatureType::BLS => JsonHelperEnum::Bls,
                 FilecoinSignatureType::Secp256k1 => JsonHelperEnum::Secp256k1,
+                FilecoinSignatureType::Delegated => JsonHelperEnum::Delegated,
             };
             json.serialize(serializer)
         }
@@ -564,6 +685,7 @@ pub mod signature_json {
             let signature_type = match json_enum {
                 JsonHelperEnum::Bls => FilecoinSignatureType::BLS,
                 JsonHelperEnum::Secp256k1 => FilecoinSignatureType::Secp256k1,
+                JsonHelperEnum::Delegated => FilecoinSignatureType::Delegated,
             };
             Ok(signature_type)
         }
@@ -708,9 +830,12 @@ mod tests {
     use fvm_ipld_encoding::RawBytes;
 
     use crate::{
-        FilecoinAddress, FilecoinAmount, FilecoinAmountConverter, FilecoinTransaction,
-        FilecoinTransactionParameters,
+        invoke_contract_calldata, invoke_contract_params, FilecoinAddress, FilecoinAmount,
+        FilecoinAmountConverter, FilecoinFormat, FilecoinPublicKey, FilecoinSignature,
+        FilecoinSignatureType, FilecoinTransaction, FilecoinTransactionParameters,
+        FILECOIN_CALIBRATION_CHAIN_ID, FILECOIN_MAINNET_CHAIN_ID, METHOD_INVOKE_CONTRACT,
     };
+    use anychain_core::{utilities::crypto::keccak256, PublicKey};
 
     #[test]
     fn filecoin_transaction_serialization_test() {
@@ -784,4 +909,79 @@ mod tests {
         // Optional extra field-level checks (nice for readable test failures)
         assert_eq!(tx.signature.sig_type as u8, 1);
     }
+
+    #[test]
+    fn test_delegated_transaction() {
+        let secret_key = libsecp256k1::SecretKey::parse_slice(&[1u8; 32]).unwrap();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+        let from = FilecoinPublicKey::Secp256k1(public_key)
+            .to_address(&FilecoinFormat::Delegated)
+            .unwrap();
+        let calldata = vec![0xa9, 0x05];
+        let params = FilecoinTransactionParameters {
+            version: 0,
+            from,
+            to: FilecoinAddress::from_str("t01024").unwrap(),
+            sequence: 1,
+            value: FilecoinAmount::from_fil("1"),
+            method_num: METHOD_INVOKE_CONTRACT,
+            params: invoke_contract_params(&calldata),
+            gas_limit: 1_000_000,
+            gas_fee_cap: FilecoinAmount::from(200),
+            gas_premium: FilecoinAmount::from(100),
+        };
+        // The calldata is a CBOR byte string
+        assert_eq!(vec![0x42, 0xa9, 0x05], params.params.to_vec());
+        assert_eq!(calldata, invoke_contract_calldata(&params.params).unwrap());
+        assert!(invoke_contract_params(&[]).is_empty());
+
+        // 0x02 || rlp([chain id, nonce, premium, fee cap, gas limit, to, value,
+        // calldata, access list])
+        let rlp = [
+            &[0x02, 0xee, 0x83, 0x04, 0xcb, 0x2f, 0x01, 0x64, 0x81, 0xc8][..],
+            &[0x83, 0x0f, 0x42, 0x40, 0x94, 0xff],
+            &[0u8; 17],
+            &[0x04, 0x00],
+            &[0x88, 0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00],
+            &[0x82, 0xa9, 0x05, 0xc0],
+        ]
+        .concat();
+        let mut tx = FilecoinTransaction::new(&params).unwrap();
+        let digest = tx.digest().unwrap();
+        assert_eq!(keccak256(&rlp).to_vec(), digest);
+        assert_eq!(
+            digest,
+            params.eth_digest(FILECOIN_CALIBRATION_CHAIN_ID).unwrap()
+        );
+        assert_ne!(
+            digest,
+            params.eth_digest(FILECOIN_MAINNET_CHAIN_ID).unwrap()
+        );
+
+        let message = libsecp256k1::Message::parse_slice(&digest).unwrap();
+        let (signature, recid) = libsecp256k1::sign(&message, &secret_key);
+        let signed = tx
+            .sign(signature.serialize().to_vec(), recid.serialize())
+            .unwrap();
+        assert_eq!(FilecoinSignatureType::Delegated, tx.signature.sig_type);
+        assert_eq!(65, tx.signature.bytes.len());
+        let json: serde_json::Value = serde_json::from_slice(&signed).unwrap();
+        assert_eq!(3, json["Signature"]["Type"]);
+        assert_eq!(tx, FilecoinTransaction::from_bytes(&signed).unwrap());
+
+        let cbor = fvm_ipld_encoding::to_vec(&tx.signature).unwrap();
+        let decoded: FilecoinSignature = fvm_ipld_encoding::from_slice(&cbor).unwrap();
+        assert_eq!(tx.signature, decoded);
+        let mut invalid = cbor.clone();
+        invalid[2] = 4;
+        assert!(fvm_ipld_encoding::from_slice::<FilecoinSignature>(&invalid).is_err());
+
+        // Only transfers and contract calls from f410 addresses are supported
+        let mut unsupported = params.clone();
+        unsupported.method_num = 2;
+        assert!(unsupported.eth_digest(FILECOIN_MAINNET_CHAIN_ID).is_err());
+        let mut f1 = params;
+        f1.from = FilecoinAddress::new_secp256k1(&public_key.serialize()).unwrap();
+        assert!(f1.eth_digest(FILECOIN_MAINNET_CHAIN_ID).is_err());
+    }
 }
@@ -1,19 +1,22 @@
 use {
-    crate::{format::HederaFormat, public_key::HederaPublicKey},
+    crate::{
+        format::HederaFormat,
+        public_key::{HederaPublicKey, HederaSecretKey},
+    },
     anychain_core::{Address, AddressError, PublicKey},
     core::{
         fmt::{Display, Formatter, Result as FmtResult},
         str::FromStr,
     },
-    ed25519_dalek::SigningKey,
 };
 
-/// Represents a Hedera address
+/// Represents a Hedera address, which is either the hex public key of an
+/// account or the 0x-prefixed EVM address alias of an ECDSA account
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct HederaAddress(pub String);
 
 impl Address for HederaAddress {
-    type SecretKey = SigningKey;
+    type SecretKey = HederaSecretKey;
     type Format = HederaFormat;
     type PublicKey = HederaPublicKey;
 
@@ -40,6 +43,13 @@ impl FromStr for HederaAddress {
     type Err = AddressError;
 
     fn from_str(addr: &str) -> Result<Self, Self::Err> {
+        if let Some(evm_address) = addr.strip_prefix("0x") {
+            let bytes = hex::decode(evm_address)?;
+            if bytes.len() != 20 {
+                return Err(AddressError::InvalidByteLength(bytes.len()));
+            }
+            return Ok(HederaAddress(format!("0x{}", hex::encode(bytes))));
+        }
         let public_key = HederaPublicKey::from_str(addr)?;
         public_key.to_address(&HederaFormat::Standard)
     }
@@ -67,8 +77,9 @@ mod tests {
         let signing_key = SigningKey::from_bytes(&private_arr);
 
         let address =
-            HederaAddress::from_secret_key(&signing_key, &HederaFormat::Standard).unwrap();
-        let public_key = HederaPublicKey::from_secret_key(&signing_key);
+            HederaAddress::from_secret_key(&signing_key.clone().into(), &HederaFormat::Standard)
+                .unwrap();
+        let public_key = HederaPublicKey::from_secret_key(&signing_key.into());
         assert_eq!(address.to_string(), public_key.to_string());
     }
 
@@ -76,7 +87,7 @@ mod tests {
         let private_key_bytes = hex::decode(private_hex).expect("private key hex should decode");
         let private_arr: [u8; 32] = private_key_bytes.try_into().unwrap();
         let signing_key = SigningKey::from_bytes(&private_arr);
-        let public_key = HederaPublicKey::from_secret_key(&signing_key);
+        let public_key = HederaPublicKey::from_secret_key(&signing_key.into());
         let address = HederaAddress::from_public_key(&public_key, &HederaFormat::Standard).unwrap();
         assert_eq!(address.to_string(), public_key.to_string());
     }
@@ -87,8 +98,9 @@ mod tests {
         let signing_key = SigningKey::from_bytes(&private_arr);
 
         let address_from_secret =
-            HederaAddress::from_secret_key(&signing_key, &HederaFormat::Standard).unwrap();
-        let public_key = HederaPublicKey::from_secret_key(&signing_key);
+            HederaAddress::from_secret_key(&signing_key.clone().into(), &HederaFormat::Standard)
+                .unwrap();
+        let public_key = HederaPublicKey::from_secret_key(&signing_key.into());
         let address_from_public =
             HederaAddress::from_public_key(&public_key, &HederaFormat::Standard).unwrap();
 
@@ -122,7 +134,7 @@ mod tests {
             hex::decode(PRIVATE_HEX_ALICE).expect("private key hex should decode");
         let private_arr: [u8; 32] = private_key_bytes.try_into().unwrap();
         let signing_key = SigningKey::from_bytes(&private_arr);
-        let public_key = HederaPublicKey::from_secret_key(&signing_key);
+        let public_key = HederaPublicKey::from_secret_key(&signing_key.into());
 
         assert!(HederaAddress::is_valid(&public_key.to_string()));
         assert!(!HederaAddress::is_valid("invalid"));
@@ -135,9 +147,29 @@ mod tests {
             hex::decode(PRIVATE_HEX_ALICE).expect("private key hex should decode");
         let private_arr: [u8; 32] = private_key_bytes.try_into().unwrap();
         let signing_key = SigningKey::from_bytes(&private_arr);
-        let public_key = HederaPublicKey::from_secret_key(&signing_key);
+        let public_key = HederaPublicKey::from_secret_key(&signing_key.into());
 
         let address = HederaAddress::from_str(&public_key.to_string()).unwrap();
         assert_eq!(address.to_string(), public_key.to_string());
     }
+
+    #[test]
+    fn test_evm_alias() {
+        let sk = libsecp256k1::SecretKey::parse(&[1u8; 32]).unwrap();
+        let address = HederaAddress::from_secret_key(&sk.into(), &HederaFormat::EvmAlias).unwrap();
+        assert_eq!(
+            "0x1a642f0e3c3af545e7acbd38b07251b3990914f1",
+            address.to_string()
+        );
+        assert_eq!(
+            address,
+            HederaAddress::from_str("0x1A642F0E3C3AF545E7ACBD38B07251B3990914F1").unwrap()
+        );
+        assert!(!HederaAddress::is_valid(
+            "0x1a642f0e3c3af545e7acbd38b07251b3990914"
+        ));
+
+        let sk = SigningKey::from_bytes(&[1u8; 32]);
+        assert!(HederaAddress::from_secret_key(&sk.into(), &HederaFormat::EvmAlias).is_err());
+    }
 }
@@ -5,15 +5,21 @@ use {
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HederaFormat {
+    /// The hex public key
     #[default]
     Standard,
+    /// The 0x-prefixed EVM address alias of an ECDSA-secp256k1 key
+    EvmAlias,
 }
 
 impl Format for HederaFormat {}
 
 impl fmt::Display for HederaFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Standard")
+        match self {
+            HederaFormat::Standard => write!(f, "Standard"),
+            HederaFormat::EvmAlias => write!(f, "EvmAlias"),
+        }
     }
 }
 
@@ -24,5 +30,6 @@ mod tests {
     #[test]
     fn test_display() {
         assert_eq!(HederaFormat::Standard.to_string(), "Standard");
+        assert_eq!(HederaFormat::EvmAlias.to_string(), "EvmAlias");
     }
 }
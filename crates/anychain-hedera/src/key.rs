@@ -0,0 +1,210 @@
+//! Hedera keys, which are either simple keys or KeyList/threshold structures of keys
+
+use {
+    crate::protobuf::{self, key::Key},
+    anychain_core::{utilities::crypto::keccak256, TransactionError},
+    ed25519_dalek::{Signature, VerifyingKey},
+};
+
+/// The DER prefix of an Ed25519 public key
+const ED25519_DER_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Represents a Hedera key. An account or entity is controlled by a key,
+/// which may require signatures from several simple keys
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HederaKey {
+    Ed25519([u8; 32]),
+    /// A compressed secp256k1 public key
+    EcdsaSecp256k1([u8; 33]),
+    /// Requires all of the keys to sign
+    KeyList(Vec<HederaKey>),
+    /// Requires at least `threshold` of the keys to sign
+    Threshold {
+        threshold: u32,
+        keys: Vec<HederaKey>,
+    },
+}
+
+impl HederaKey {
+    /// Returns a simple key given a raw Ed25519 key of 32 bytes, a
+    /// DER-encoded Ed25519 key or an secp256k1 key of 33 or 65 bytes, which
+    /// is compressed as Hedera keys are
+    pub fn from_bytes(public_key: &[u8]) -> Result<Self, TransactionError> {
+        let public_key = public_key
+            .strip_prefix(&ED25519_DER_PREFIX[..])
+            .unwrap_or(public_key);
+        match public_key.len() {
+            32 => {
+                let mut key = [0u8; 32];
+                key.copy_from_slice(public_key);
+                VerifyingKey::from_bytes(&key)
+                    .map_err(|e| TransactionError::Crate("ed25519_dalek", format!("{e:?}")))?;
+                Ok(Self::Ed25519(key))
+            }
+            33 | 65 => {
+                let key = libsecp256k1::PublicKey::parse_slice(public_key, None)
+                    .map_err(|e| TransactionError::Crate("libsecp256k1", format!("{e:?}")))?;
+                Ok(Self::EcdsaSecp256k1(key.serialize_compressed()))
+            }
+            len => Err(TransactionError::Message(format!(
+                "Invalid public key length: {len}"
+            ))),
+        }
+    }
+
+    /// Returns the bytes of a simple key, or None for a KeyList or threshold key
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Ed25519(key) => Some(key.to_vec()),
+            Self::EcdsaSecp256k1(key) => Some(key.to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if `signature` is a valid signature of `message` by this
+    /// simple key. An ECDSA signature is the 64-byte r || s of the keccak-256
+    /// hash of `message`
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Ed25519(key) => {
+                let (Ok(key), Ok(signature)) = (
+                    VerifyingKey::from_bytes(key),
+                    Signature::from_slice(signature),
+                ) else {
+                    return false;
+                };
+                key.verify_strict(message, &signature).is_ok()
+            }
+            Self::EcdsaSecp256k1(key) => {
+                let (Ok(key), Ok(signature)) = (
+                    libsecp256k1::PublicKey::parse_compressed(key),
+                    libsecp256k1::Signature::parse_standard_slice(signature),
+                ) else {
+                    return false;
+                };
+                let message = libsecp256k1::Message::parse(&keccak256(message));
+                libsecp256k1::verify(&message, &signature, &key)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the simple keys in `signers` satisfy this key
+    pub fn is_satisfied(&self, signers: &[HederaKey]) -> bool {
+        match self {
+            Self::Ed25519(_) | Self::EcdsaSecp256k1(_) => signers.contains(self),
+            Self::KeyList(keys) => {
+                !keys.is_empty() && keys.iter().all(|key| key.is_satisfied(signers))
+            }
+            Self::Threshold { threshold, keys } => {
+                *threshold > 0
+                    && keys.iter().filter(|key| key.is_satisfied(signers)).count()
+                        >= *threshold as usize
+            }
+        }
+    }
+
+    pub fn to_protobuf(&self) -> protobuf::Key {
+        let key_list = |keys: &[HederaKey]| protobuf::KeyList {
+            keys: keys.iter().map(|key| key.to_protobuf()).collect(),
+        };
+        let key = match self {
+            Self::Ed25519(key) => Key::Ed25519(key.to_vec()),
+            Self::EcdsaSecp256k1(key) => Key::EcdsaSecp256k1(key.to_vec()),
+            Self::KeyList(keys) => Key::KeyList(key_list(keys)),
+            Self::Threshold { threshold, keys } => Key::ThresholdKey(protobuf::ThresholdKey {
+                threshold: *threshold,
+                keys: Some(key_list(keys)),
+            }),
+        };
+        protobuf::Key { key: Some(key) }
+    }
+
+    pub fn from_protobuf(key: &protobuf::Key) -> Result<Self, TransactionError> {
+        let key_list = |keys: &protobuf::KeyList| {
+            keys.keys
+                .iter()
+                .map(Self::from_protobuf)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        match &key.key {
+            Some(Key::Ed25519(key)) if key.len() == 32 => Self::from_bytes(key),
+            Some(Key::EcdsaSecp256k1(key)) if key.len() == 33 => Self::from_bytes(key),
+            Some(Key::KeyList(keys)) => Ok(Self::KeyList(key_list(keys)?)),
+            Some(Key::ThresholdKey(key)) => Ok(Self::Threshold {
+                threshold: key.threshold,
+                keys: key_list(&key.keys.clone().unwrap_or_default())?,
+            }),
+            _ => Err(TransactionError::Message(
+                "Unsupported or invalid key".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use prost::Message;
+
+    #[test]
+    fn test_key_structure() {
+        let ed25519 = |seed| {
+            HederaKey::Ed25519(
+                SigningKey::from_bytes(&[seed; 32])
+                    .verifying_key()
+                    .to_bytes(),
+            )
+        };
+        let ecdsa = HederaKey::EcdsaSecp256k1(
+            libsecp256k1::PublicKey::from_secret_key(
+                &libsecp256k1::SecretKey::parse(&[3u8; 32]).unwrap(),
+            )
+            .serialize_compressed(),
+        );
+        let (a, b) = (ed25519(1), ed25519(2));
+
+        // 2-of-3 where one of the keys is itself a KeyList
+        let key = HederaKey::Threshold {
+            threshold: 2,
+            keys: vec![
+                a.clone(),
+                ecdsa.clone(),
+                HederaKey::KeyList(vec![b.clone(), ecdsa.clone()]),
+            ],
+        };
+        assert!(!key.is_satisfied(std::slice::from_ref(&a)));
+        assert!(!key.is_satisfied(&[a.clone(), b.clone()]));
+        assert!(key.is_satisfied(&[a.clone(), ecdsa.clone()]));
+        assert!(key.is_satisfied(&[b.clone(), ecdsa.clone()]));
+        assert!(!HederaKey::KeyList(vec![]).is_satisfied(std::slice::from_ref(&a)));
+
+        let bytes = key.to_protobuf().encode_to_vec();
+        let decoded = HederaKey::from_protobuf(&protobuf::Key::decode(&*bytes).unwrap()).unwrap();
+        assert_eq!(key, decoded);
+
+        let der = [&ED25519_DER_PREFIX[..], &a.to_bytes().unwrap()].concat();
+        assert_eq!(a, HederaKey::from_bytes(&der).unwrap());
+        assert!(HederaKey::from_bytes(&[0u8; 20]).is_err());
+
+        // An uncompressed secp256k1 key is compressed
+        let uncompressed = libsecp256k1::PublicKey::from_secret_key(
+            &libsecp256k1::SecretKey::parse(&[3u8; 32]).unwrap(),
+        )
+        .serialize();
+        assert_eq!(ecdsa, HederaKey::from_bytes(&uncompressed).unwrap());
+        assert_eq!(
+            ecdsa,
+            HederaKey::from_bytes(&ecdsa.to_bytes().unwrap()).unwrap()
+        );
+        assert!(HederaKey::from_bytes(&[4u8; 65]).is_err());
+
+        let signature = SigningKey::from_bytes(&[1u8; 32]).sign(b"body").to_bytes();
+        assert!(a.verify(b"body", &signature));
+        assert!(!b.verify(b"body", &signature));
+        assert!(!key.verify(b"body", &signature));
+    }
+}
@@ -1,6 +1,7 @@
 pub mod address;
 pub mod amount;
 pub mod format;
+pub mod key;
 pub mod protobuf;
 pub mod public_key;
 pub mod transaction;
@@ -8,5 +9,6 @@ pub mod transaction;
 pub use self::address::*;
 pub use self::amount::*;
 pub use self::format::*;
+pub use self::key::*;
 pub use self::public_key::*;
 pub use self::transaction::*;
@@ -34,6 +34,52 @@ pub mod account_id {
     }
 }
 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenId {
+    #[prost(int64, tag = "1")]
+    pub shard_num: i64,
+    #[prost(int64, tag = "2")]
+    pub realm_num: i64,
+    #[prost(int64, tag = "3")]
+    pub token_num: i64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContractId {
+    #[prost(int64, tag = "1")]
+    pub shard_num: i64,
+    #[prost(int64, tag = "2")]
+    pub realm_num: i64,
+    #[prost(oneof = "contract_id::Contract", tags = "3, 4")]
+    pub contract: ::core::option::Option<contract_id::Contract>,
+}
+
+pub mod contract_id {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Contract {
+        #[prost(int64, tag = "3")]
+        ContractNum(i64),
+        #[prost(bytes = "vec", tag = "4")]
+        EvmAddress(::prost::alloc::vec::Vec<u8>),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleId {
+    #[prost(int64, tag = "1")]
+    pub shard_num: i64,
+    #[prost(int64, tag = "2")]
+    pub realm_num: i64,
+    #[prost(int64, tag = "3")]
+    pub schedule_num: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UInt32Value {
+    #[prost(uint32, tag = "1")]
+    pub value: u32,
+}
+
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Duration {
     #[prost(int64, tag = "1")]
@@ -62,7 +108,7 @@ pub struct TransactionId {
 
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Key {
-    #[prost(oneof = "key::Key", tags = "2, 7")]
+    #[prost(oneof = "key::Key", tags = "2, 5, 6, 7")]
     pub key: ::core::option::Option<key::Key>,
 }
 
@@ -71,25 +117,45 @@ pub mod key {
     pub enum Key {
         #[prost(bytes = "vec", tag = "2")]
         Ed25519(::prost::alloc::vec::Vec<u8>),
+        #[prost(message, tag = "5")]
+        ThresholdKey(super::ThresholdKey),
+        #[prost(message, tag = "6")]
+        KeyList(super::KeyList),
         #[prost(bytes = "vec", tag = "7")]
         EcdsaSecp256k1(::prost::alloc::vec::Vec<u8>),
     }
 }
 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeyList {
+    #[prost(message, repeated, tag = "1")]
+    pub keys: ::prost::alloc::vec::Vec<Key>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ThresholdKey {
+    #[prost(uint32, tag = "1")]
+    pub threshold: u32,
+    #[prost(message, optional, tag = "2")]
+    pub keys: ::core::option::Option<KeyList>,
+}
+
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CryptoCreateTransactionBody {
     #[prost(message, optional, tag = "1")]
     pub key: ::core::option::Option<Key>,
     #[prost(uint64, tag = "2")]
     pub initial_balance: u64,
-    #[prost(bool, tag = "11")]
+    #[prost(bool, tag = "8")]
     pub receiver_sig_required: bool,
-    #[prost(message, optional, tag = "13")]
+    #[prost(message, optional, tag = "9")]
     pub auto_renew_period: ::core::option::Option<Duration>,
-    #[prost(string, tag = "16")]
+    #[prost(string, tag = "13")]
     pub memo: ::prost::alloc::string::String,
-    #[prost(int32, tag = "18")]
+    #[prost(int32, tag = "14")]
     pub max_automatic_token_associations: i32,
+    #[prost(bytes = "vec", tag = "18")]
+    pub alias: ::prost::alloc::vec::Vec<u8>,
 }
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -108,10 +174,135 @@ pub struct TransferList {
     pub account_amounts: ::prost::alloc::vec::Vec<AccountAmount>,
 }
 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NftTransfer {
+    #[prost(message, optional, tag = "1")]
+    pub sender_account_id: ::core::option::Option<AccountId>,
+    #[prost(message, optional, tag = "2")]
+    pub receiver_account_id: ::core::option::Option<AccountId>,
+    #[prost(int64, tag = "3")]
+    pub serial_number: i64,
+    #[prost(bool, tag = "4")]
+    pub is_approval: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenTransferList {
+    #[prost(message, optional, tag = "1")]
+    pub token: ::core::option::Option<TokenId>,
+    #[prost(message, repeated, tag = "2")]
+    pub transfers: ::prost::alloc::vec::Vec<AccountAmount>,
+    #[prost(message, repeated, tag = "3")]
+    pub nft_transfers: ::prost::alloc::vec::Vec<NftTransfer>,
+    #[prost(message, optional, tag = "4")]
+    pub expected_decimals: ::core::option::Option<UInt32Value>,
+}
+
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CryptoTransferTransactionBody {
     #[prost(message, optional, tag = "1")]
     pub transfers: ::core::option::Option<TransferList>,
+    #[prost(message, repeated, tag = "2")]
+    pub token_transfers: ::prost::alloc::vec::Vec<TokenTransferList>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CryptoUpdateTransactionBody {
+    #[prost(message, optional, tag = "2")]
+    pub account_id_to_update: ::core::option::Option<AccountId>,
+    #[prost(message, optional, tag = "3")]
+    pub key: ::core::option::Option<Key>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CryptoDeleteTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub transfer_account_id: ::core::option::Option<AccountId>,
+    #[prost(message, optional, tag = "2")]
+    pub delete_account_id: ::core::option::Option<AccountId>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenAssociateTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub account: ::core::option::Option<AccountId>,
+    #[prost(message, repeated, tag = "2")]
+    pub tokens: ::prost::alloc::vec::Vec<TokenId>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenDissociateTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub account: ::core::option::Option<AccountId>,
+    #[prost(message, repeated, tag = "2")]
+    pub tokens: ::prost::alloc::vec::Vec<TokenId>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContractCallTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub contract_id: ::core::option::Option<ContractId>,
+    #[prost(int64, tag = "2")]
+    pub gas: i64,
+    #[prost(int64, tag = "3")]
+    pub amount: i64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub function_parameters: ::prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchedulableTransactionBody {
+    #[prost(uint64, tag = "1")]
+    pub transaction_fee: u64,
+    #[prost(string, tag = "2")]
+    pub memo: ::prost::alloc::string::String,
+    #[prost(
+        oneof = "schedulable_transaction_body::Data",
+        tags = "3, 7, 8, 9, 10, 32, 33"
+    )]
+    pub data: ::core::option::Option<schedulable_transaction_body::Data>,
+}
+
+pub mod schedulable_transaction_body {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Data {
+        #[prost(message, tag = "3")]
+        ContractCall(super::ContractCallTransactionBody),
+        #[prost(message, tag = "7")]
+        CryptoCreateAccount(super::CryptoCreateTransactionBody),
+        #[prost(message, tag = "8")]
+        CryptoDelete(super::CryptoDeleteTransactionBody),
+        #[prost(message, tag = "9")]
+        CryptoTransfer(super::CryptoTransferTransactionBody),
+        #[prost(message, tag = "10")]
+        CryptoUpdateAccount(super::CryptoUpdateTransactionBody),
+        #[prost(message, tag = "32")]
+        TokenAssociate(super::TokenAssociateTransactionBody),
+        #[prost(message, tag = "33")]
+        TokenDissociate(super::TokenDissociateTransactionBody),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleCreateTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub scheduled_transaction_body: ::core::option::Option<SchedulableTransactionBody>,
+    #[prost(string, tag = "2")]
+    pub memo: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub admin_key: ::core::option::Option<Key>,
+    #[prost(message, optional, tag = "4")]
+    pub payer_account_id: ::core::option::Option<AccountId>,
+    #[prost(message, optional, tag = "5")]
+    pub expiration_time: ::core::option::Option<Timestamp>,
+    #[prost(bool, tag = "13")]
+    pub wait_for_expiry: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScheduleSignTransactionBody {
+    #[prost(message, optional, tag = "1")]
+    pub schedule_id: ::core::option::Option<ScheduleId>,
 }
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -128,17 +319,34 @@ pub struct TransactionBody {
     pub generate_record: bool,
     #[prost(string, tag = "6")]
     pub memo: ::prost::alloc::string::String,
-    #[prost(oneof = "transaction_body::Data", tags = "11, 14")]
+    #[prost(
+        oneof = "transaction_body::Data",
+        tags = "7, 11, 12, 14, 15, 40, 41, 42, 44"
+    )]
     pub data: ::core::option::Option<transaction_body::Data>,
 }
 
 pub mod transaction_body {
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Data {
+        #[prost(message, tag = "7")]
+        ContractCall(super::ContractCallTransactionBody),
         #[prost(message, tag = "11")]
         CryptoCreateAccount(super::CryptoCreateTransactionBody),
+        #[prost(message, tag = "12")]
+        CryptoDelete(super::CryptoDeleteTransactionBody),
         #[prost(message, tag = "14")]
         CryptoTransfer(super::CryptoTransferTransactionBody),
+        #[prost(message, tag = "15")]
+        CryptoUpdateAccount(super::CryptoUpdateTransactionBody),
+        #[prost(message, tag = "40")]
+        TokenAssociate(super::TokenAssociateTransactionBody),
+        #[prost(message, tag = "41")]
+        TokenDissociate(super::TokenDissociateTransactionBody),
+        #[prost(message, tag = "42")]
+        ScheduleCreate(super::ScheduleCreateTransactionBody),
+        #[prost(message, tag = "44")]
+        ScheduleSign(super::ScheduleSignTransactionBody),
     }
 }
 
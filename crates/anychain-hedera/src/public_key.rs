@@ -1,24 +1,109 @@
 use {
-    crate::{address::HederaAddress, format::HederaFormat},
-    anychain_core::{AddressError, PublicKey, PublicKeyError},
+    crate::{address::HederaAddress, format::HederaFormat, key::HederaKey},
+    anychain_core::{utilities::crypto::keccak256, AddressError, PublicKey, PublicKeyError},
     core::{fmt, str::FromStr},
     ed25519_dalek::{SigningKey, VerifyingKey},
 };
 
+/// Represents a Hedera secret key, which is either an Ed25519 or an
+/// ECDSA-secp256k1 key
+#[derive(Debug, Clone)]
+pub enum HederaSecretKey {
+    Ed25519(SigningKey),
+    Secp256k1(libsecp256k1::SecretKey),
+}
+
+impl From<SigningKey> for HederaSecretKey {
+    fn from(secret_key: SigningKey) -> Self {
+        Self::Ed25519(secret_key)
+    }
+}
+
+impl From<libsecp256k1::SecretKey> for HederaSecretKey {
+    fn from(secret_key: libsecp256k1::SecretKey) -> Self {
+        Self::Secp256k1(secret_key)
+    }
+}
+
+/// Represents a Hedera public key, which is either an Ed25519 or an
+/// ECDSA-secp256k1 key
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct HederaPublicKey(pub VerifyingKey);
+pub enum HederaPublicKey {
+    Ed25519(VerifyingKey),
+    Secp256k1(libsecp256k1::PublicKey),
+}
 
 impl PublicKey for HederaPublicKey {
-    type SecretKey = SigningKey;
+    type SecretKey = HederaSecretKey;
     type Address = HederaAddress;
     type Format = HederaFormat;
 
     fn from_secret_key(secret_key: &Self::SecretKey) -> Self {
-        HederaPublicKey(secret_key.verifying_key())
+        match secret_key {
+            HederaSecretKey::Ed25519(sk) => Self::Ed25519(sk.verifying_key()),
+            HederaSecretKey::Secp256k1(sk) => {
+                Self::Secp256k1(libsecp256k1::PublicKey::from_secret_key(sk))
+            }
+        }
     }
 
-    fn to_address(&self, _format: &Self::Format) -> Result<Self::Address, AddressError> {
-        Ok(HederaAddress(self.to_string()))
+    fn to_address(&self, format: &Self::Format) -> Result<Self::Address, AddressError> {
+        match format {
+            HederaFormat::Standard => Ok(HederaAddress(self.to_string())),
+            HederaFormat::EvmAlias => self.to_evm_alias(),
+        }
+    }
+}
+
+impl HederaPublicKey {
+    /// Serializes the public key, an secp256k1 key in compressed form
+    pub fn serialize(&self) -> Vec<u8> {
+        match self {
+            Self::Ed25519(pk) => pk.to_bytes().to_vec(),
+            Self::Secp256k1(pk) => pk.serialize_compressed().to_vec(),
+        }
+    }
+
+    /// Returns a Hedera public key given 32 bytes of an Ed25519 key, or 33 or
+    /// 65 bytes of an secp256k1 key
+    pub fn deserialize(public_key: &[u8]) -> Result<Self, PublicKeyError> {
+        match public_key.len() {
+            32 => {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(public_key);
+                let public_key = VerifyingKey::from_bytes(&bytes)
+                    .map_err(|error| PublicKeyError::Crate("VerifyingKey", format!("{error:?}")))?;
+                Ok(Self::Ed25519(public_key))
+            }
+            33 | 65 => {
+                let public_key = libsecp256k1::PublicKey::parse_slice(public_key, None)
+                    .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{error:?}")))?;
+                Ok(Self::Secp256k1(public_key))
+            }
+            len => Err(PublicKeyError::InvalidByteLength(len)),
+        }
+    }
+
+    /// Returns the simple Hedera key of this public key
+    pub fn to_key(&self) -> HederaKey {
+        match self {
+            Self::Ed25519(pk) => HederaKey::Ed25519(pk.to_bytes()),
+            Self::Secp256k1(pk) => HederaKey::EcdsaSecp256k1(pk.serialize_compressed()),
+        }
+    }
+
+    /// Returns the EVM address alias of an secp256k1 public key, which is the
+    /// last 20 bytes of the keccak-256 hash of the uncompressed key
+    pub fn to_evm_alias(&self) -> Result<HederaAddress, AddressError> {
+        match self {
+            Self::Ed25519(_) => Err(AddressError::Message(
+                "An Ed25519 key has no EVM address alias".to_string(),
+            )),
+            Self::Secp256k1(pk) => {
+                let hash = keccak256(&pk.serialize()[1..]);
+                Ok(HederaAddress(format!("0x{}", hex::encode(&hash[12..]))))
+            }
+        }
     }
 }
 
@@ -28,26 +113,13 @@ impl FromStr for HederaPublicKey {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let raw_bytes =
             hex::decode(s).map_err(|error| PublicKeyError::Crate("hex", format!("{error:?}")))?;
-
-        let raw_bytes_len = raw_bytes.len();
-        if raw_bytes_len != 32 {
-            return Err(PublicKeyError::InvalidByteLength(raw_bytes_len));
-        }
-
-        let bytes_array: [u8; 32] = raw_bytes
-            .try_into()
-            .map_err(|_| PublicKeyError::Crate("slice", "invalid slice length".to_string()))?;
-
-        let public_key = VerifyingKey::from_bytes(&bytes_array)
-            .map_err(|error| PublicKeyError::Crate("VerifyingKey", format!("{error:?}")))?;
-        Ok(HederaPublicKey(public_key))
+        Self::deserialize(&raw_bytes)
     }
 }
 
 impl fmt::Display for HederaPublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let buf = self.0.to_bytes();
-        write!(f, "{}", hex::encode(buf))
+        write!(f, "{}", hex::encode(self.serialize()))
     }
 }
 
@@ -66,13 +138,33 @@ mod tests {
         let alice_bytes = hex::decode(PRIVATE_HEX_ALICE).unwrap();
         let alice_arr: [u8; 32] = alice_bytes.try_into().unwrap();
         let alice_sk = SigningKey::from_bytes(&alice_arr);
-        let alice_pk = HederaPublicKey::from_secret_key(&alice_sk);
+        let alice_pk = HederaPublicKey::from_secret_key(&alice_sk.into());
         println!("ALICE PUBLIC KEY: {}", alice_pk);
 
         let bob_bytes = hex::decode(PRIVATE_HEX_BOB).unwrap();
         let bob_arr: [u8; 32] = bob_bytes.try_into().unwrap();
         let bob_sk = SigningKey::from_bytes(&bob_arr);
-        let bob_pk = HederaPublicKey::from_secret_key(&bob_sk);
+        let bob_pk = HederaPublicKey::from_secret_key(&bob_sk.into());
         println!("BOB PUBLIC KEY: {}", bob_pk);
     }
+
+    #[test]
+    fn test_secp256k1_public_key() {
+        let sk = libsecp256k1::SecretKey::parse(&[1u8; 32]).unwrap();
+        let pk = HederaPublicKey::from_secret_key(&sk.into());
+        assert_eq!(33, pk.serialize().len());
+        assert_eq!(pk, HederaPublicKey::from_str(&pk.to_string()).unwrap());
+        assert!(matches!(pk.to_key(), HederaKey::EcdsaSecp256k1(_)));
+
+        // The EVM address of the secret key 0x01..01
+        let alias = pk.to_address(&HederaFormat::EvmAlias).unwrap();
+        assert_eq!(
+            "0x1a642f0e3c3af545e7acbd38b07251b3990914f1",
+            alias.to_string()
+        );
+
+        let sk = SigningKey::from_bytes(&[1u8; 32]);
+        let pk = HederaPublicKey::from_secret_key(&sk.into());
+        assert!(pk.to_evm_alias().is_err());
+    }
 }
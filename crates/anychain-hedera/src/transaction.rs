@@ -1,29 +1,111 @@
 use {
     crate::protobuf::{
-        account_id::Account, key::Key, signature_pair::Signature, transaction_body::Data,
+        self, account_id::Account, contract_id::Contract, key::Key, schedulable_transaction_body,
+        signature_pair::Signature, transaction_body::Data, SignatureMap, SignaturePair,
         SignedTransaction, TransactionBody, TransactionList,
     },
-    crate::{address::HederaAddress, format::HederaFormat, public_key::HederaPublicKey},
-    anychain_core::{Transaction, TransactionError, TransactionId},
-    hiero_sdk::{AccountId, AnyTransaction, Hbar, TransactionId as HieroTxId, TransferTransaction},
+    crate::{
+        address::HederaAddress, format::HederaFormat, key::HederaKey, public_key::HederaPublicKey,
+    },
+    anychain_core::{utilities::crypto::keccak256, Transaction, TransactionError, TransactionId},
+    hiero_sdk::{
+        AccountCreateTransaction, AccountDeleteTransaction, AccountId, AccountUpdateTransaction,
+        AnyTransaction, ContractExecuteTransaction, ContractId, Hbar, NftId,
+        ScheduleCreateTransaction, ScheduleId, ScheduleSignTransaction, TokenAssociateTransaction,
+        TokenDissociateTransaction, TokenId, TransactionId as HieroTxId, TransferTransaction,
+    },
     prost::Message,
     std::fmt::{self, Display, Formatter},
     std::str::FromStr,
     time::OffsetDateTime,
 };
 
+/// Parses an account id, which is either `<shard>.<realm>.<num>`, a public
+/// key alias `<shard>.<realm>.<public key>`, or an EVM address alias `0x...`.
+/// A transfer to an alias that has no account yet auto-creates the account
 fn parse_account_id(s: &str) -> Result<AccountId, TransactionError> {
     if s.starts_with("0x") {
-        let clean_hex = s.strip_prefix("0x").unwrap();
-        let alias_str = format!("0.0.{}", clean_hex);
-        AccountId::from_str(&alias_str)
-            .map_err(|e| TransactionError::Message(format!("Invalid public key alias: {}", e)))
+        AccountId::from_str(s)
+            .map_err(|e| TransactionError::Message(format!("Invalid EVM address alias: {}", e)))
     } else {
         AccountId::from_str(s)
             .map_err(|e| TransactionError::Message(format!("Invalid account ID: {}", e)))
     }
 }
 
+fn parse_token_id(s: &str) -> Result<TokenId, TransactionError> {
+    TokenId::from_str(s).map_err(|e| TransactionError::Message(format!("Invalid token ID: {}", e)))
+}
+
+fn parse_contract_id(s: &str) -> Result<ContractId, TransactionError> {
+    match s.strip_prefix("0x") {
+        Some(evm_address) => ContractId::from_evm_address(0, 0, evm_address),
+        None => ContractId::from_str(s),
+    }
+    .map_err(|e| TransactionError::Message(format!("Invalid contract ID: {}", e)))
+}
+
+fn parse_schedule_id(s: &str) -> Result<ScheduleId, TransactionError> {
+    ScheduleId::from_str(s)
+        .map_err(|e| TransactionError::Message(format!("Invalid schedule ID: {}", e)))
+}
+
+/// Parses a raw Ed25519 key of 32 bytes, a compressed secp256k1 key of 33
+/// bytes or a DER-encoded key
+fn parse_public_key(public_key: &[u8]) -> Result<hiero_sdk::PublicKey, TransactionError> {
+    hiero_sdk::PublicKey::from_bytes(public_key)
+        .map_err(|e| TransactionError::Message(format!("Invalid public key: {}", e)))
+}
+
+fn account_id_to_string(acc: &protobuf::AccountId) -> String {
+    match &acc.account {
+        Some(Account::AccountNum(num)) => {
+            format!("{}.{}.{}", acc.shard_num, acc.realm_num, num)
+        }
+        Some(Account::Alias(alias)) => {
+            if alias.len() == 20 {
+                format!("0x{}", hex::encode(alias))
+            } else {
+                // A public key alias is a serialized Key
+                let public_key = protobuf::Key::decode(&**alias)
+                    .map(|key| public_key_from_key(Some(key)))
+                    .unwrap_or_default();
+                format!(
+                    "{}.{}.{}",
+                    acc.shard_num,
+                    acc.realm_num,
+                    hex::encode(public_key)
+                )
+            }
+        }
+        None => format!("{}.{}.0", acc.shard_num, acc.realm_num),
+    }
+}
+
+fn token_id_to_string(token: &protobuf::TokenId) -> String {
+    format!(
+        "{}.{}.{}",
+        token.shard_num, token.realm_num, token.token_num
+    )
+}
+
+fn contract_id_to_string(contract: &protobuf::ContractId) -> String {
+    match &contract.contract {
+        Some(Contract::ContractNum(num)) => {
+            format!("{}.{}.{}", contract.shard_num, contract.realm_num, num)
+        }
+        Some(Contract::EvmAddress(evm_address)) => format!("0x{}", hex::encode(evm_address)),
+        None => format!("{}.{}.0", contract.shard_num, contract.realm_num),
+    }
+}
+
+fn public_key_from_key(key: Option<protobuf::Key>) -> Vec<u8> {
+    match key.and_then(|key| key.key) {
+        Some(Key::Ed25519(pk_bytes)) | Some(Key::EcdsaSecp256k1(pk_bytes)) => pk_bytes,
+        _ => Vec::new(),
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct HederaTransactionId {
     pub txid: String,
@@ -43,10 +125,65 @@ pub enum HederaTransactionData {
         receiver_account_id: String,
         amount: i64, // amount in tinybars
     },
+    /// Creates an account. An account with an ECDSA-secp256k1 key is also
+    /// given the EVM address alias of the key
     CreateAccount {
         new_account_public_key: Vec<u8>,
         initial_balance: u64, // in tinybars
     },
+    /// Transfers a fungible HTS token from the payer. The transaction fails
+    /// if the token does not have `decimals` decimals
+    TokenTransfer {
+        token_id: String,
+        receiver_account_id: String,
+        amount: i64, // in the lowest denomination of the token
+        decimals: u32,
+    },
+    /// Transfers the NFTs of `token_id` with the given serial numbers from the payer
+    NftTransfer {
+        token_id: String,
+        receiver_account_id: String,
+        serials: Vec<u64>,
+    },
+    /// Associates an account with HTS tokens, which it must be before it can hold them
+    TokenAssociate {
+        account_id: String,
+        token_ids: Vec<String>,
+    },
+    TokenDissociate {
+        account_id: String,
+        token_ids: Vec<String>,
+    },
+    /// Rotates the key of an account, which must be signed by both the old and the new key
+    AccountUpdate {
+        account_id: String,
+        new_public_key: Vec<u8>,
+    },
+    /// Deletes an account and transfers its remaining hbars to `transfer_account_id`
+    AccountDelete {
+        account_id: String,
+        transfer_account_id: String,
+    },
+    /// Calls a smart contract, which is a `shard.realm.num` ID or a `0x` EVM address
+    ContractCall {
+        contract_id: String,
+        gas: u64,
+        amount: i64,                  // in tinybars
+        function_parameters: Vec<u8>, // the ABI-encoded calldata
+    },
+    /// Schedules a transaction, which executes once all its required signatures
+    /// are collected by `ScheduleSign` transactions
+    ScheduleCreate {
+        scheduled: Box<HederaTransactionData>,
+        /// The account that pays for the scheduled transaction, which defaults to the payer
+        payer_account_id: Option<String>,
+        memo: String,
+        expiration_seconds: Option<i64>,
+        wait_for_expiry: bool,
+    },
+    ScheduleSign {
+        schedule_id: String,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -61,11 +198,587 @@ pub struct HederaTransactionParameters {
     pub data: HederaTransactionData,
 }
 
+/// Represents a signature of the body of a transaction sent to one node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HederaSignature {
+    pub node_account_id: String,
+    pub public_key: HederaKey,
+    pub signature: Vec<u8>,
+}
+
 #[derive(Clone, Debug)]
 pub struct HederaTransaction {
     pub params: HederaTransactionParameters,
     pub tx: AnyTransaction,
-    pub signature: Option<Vec<u8>>,
+    pub signatures: Vec<HederaSignature>,
+}
+
+impl HederaTransaction {
+    fn transaction_list(&self) -> Result<TransactionList, TransactionError> {
+        let tx_bytes = self
+            .tx
+            .to_bytes()
+            .map_err(|e| TransactionError::Message(format!("to_bytes failed: {}", e)))?;
+        TransactionList::decode(&*tx_bytes)
+            .map_err(|e| TransactionError::Message(format!("decode TransactionList failed: {}", e)))
+    }
+
+    /// Returns the node account id and the body bytes to be signed of the
+    /// transaction sent to each node in `node_account_ids`
+    pub fn node_body_bytes(&self) -> Result<Vec<(String, Vec<u8>)>, TransactionError> {
+        self.transaction_list()?
+            .transaction_list
+            .iter()
+            .map(|tx| {
+                let signed_tx =
+                    SignedTransaction::decode(&*tx.signed_transaction_bytes).map_err(|e| {
+                        TransactionError::Message(format!("decode SignedTransaction failed: {}", e))
+                    })?;
+                let body = TransactionBody::decode(&*signed_tx.body_bytes).map_err(|e| {
+                    TransactionError::Message(format!("decode TransactionBody failed: {}", e))
+                })?;
+                let node = body
+                    .node_account_id
+                    .as_ref()
+                    .map(account_id_to_string)
+                    .unwrap_or_default();
+                Ok((node, signed_tx.body_bytes))
+            })
+            .collect()
+    }
+
+    /// Returns the node account id and the keccak-256 hash of the body bytes
+    /// of each node, which is what an ECDSA-secp256k1 key signs
+    pub fn node_body_hashes(&self) -> Result<Vec<(String, [u8; 32])>, TransactionError> {
+        Ok(self
+            .node_body_bytes()?
+            .into_iter()
+            .map(|(node, body_bytes)| (node, keccak256(&body_bytes)))
+            .collect())
+    }
+
+    /// Verifies the signature of `public_key` over the body bytes of the
+    /// transaction sent to `node_account_id` and adds it, replacing any
+    /// previous signature of the key for that node
+    pub fn add_signature(
+        &mut self,
+        node_account_id: &str,
+        public_key: HederaKey,
+        signature: Vec<u8>,
+    ) -> Result<(), TransactionError> {
+        let (_, body_bytes) = self
+            .node_body_bytes()?
+            .into_iter()
+            .find(|(node, _)| node == node_account_id)
+            .ok_or(TransactionError::Message(format!(
+                "The transaction is not sent to node {node_account_id}"
+            )))?;
+        if !public_key.verify(&body_bytes, &signature) {
+            return Err(TransactionError::Message(format!(
+                "Invalid signature for node {node_account_id}"
+            )));
+        }
+        self.signatures
+            .retain(|sig| sig.node_account_id != node_account_id || sig.public_key != public_key);
+        self.signatures.push(HederaSignature {
+            node_account_id: node_account_id.to_string(),
+            public_key,
+            signature,
+        });
+        Ok(())
+    }
+
+    /// Returns the simple keys that have signed the transaction for every node
+    pub fn signers(&self) -> Result<Vec<HederaKey>, TransactionError> {
+        let nodes = self.node_body_bytes()?;
+        let mut signers: Vec<HederaKey> = Vec::new();
+        for sig in &self.signatures {
+            let signed_all = nodes.iter().all(|(node, _)| {
+                self.signatures
+                    .iter()
+                    .any(|s| &s.node_account_id == node && s.public_key == sig.public_key)
+            });
+            if signed_all && !signers.contains(&sig.public_key) {
+                signers.push(sig.public_key.clone());
+            }
+        }
+        Ok(signers)
+    }
+
+    /// Returns true if the signatures of the transaction satisfy `key`, which
+    /// may be a KeyList or threshold key
+    pub fn is_signed_by_key(&self, key: &HederaKey) -> Result<bool, TransactionError> {
+        Ok(key.is_satisfied(&self.signers()?))
+    }
+
+    /// Returns the signature map of the transaction sent to `node_account_id`
+    pub fn signature_map(&self, node_account_id: &str) -> SignatureMap {
+        let sig_pair = self
+            .signatures
+            .iter()
+            .filter(|sig| sig.node_account_id == node_account_id)
+            .map(|sig| SignaturePair {
+                pub_key_prefix: sig.public_key.to_bytes().unwrap_or_default(),
+                signature: Some(match sig.public_key {
+                    HederaKey::EcdsaSecp256k1(_) => {
+                        Signature::EcdsaSecp256k1(sig.signature.clone())
+                    }
+                    _ => Signature::Ed25519(sig.signature.clone()),
+                }),
+            })
+            .collect();
+        SignatureMap { sig_pair }
+    }
+
+    /// Returns the signed transactions of all the nodes in the serialized
+    /// `TransactionList` format of the Hedera SDKs
+    pub fn to_transaction_list_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut tx_list = self.transaction_list()?;
+        for (tx, (node, body_bytes)) in tx_list
+            .transaction_list
+            .iter_mut()
+            .zip(self.node_body_bytes()?)
+        {
+            let signed_tx = SignedTransaction {
+                body_bytes,
+                sig_map: Some(self.signature_map(&node)),
+            };
+            tx.signed_transaction_bytes = signed_tx.encode_to_vec();
+        }
+        Ok(tx_list.encode_to_vec())
+    }
+}
+
+/// Returns the unfrozen transaction that carries `data`
+fn build_data(
+    data: &HederaTransactionData,
+    payer: AccountId,
+) -> Result<AnyTransaction, TransactionError> {
+    let any_tx: AnyTransaction = match data {
+        HederaTransactionData::Transfer {
+            receiver_account_id,
+            amount,
+        } => {
+            let receiver = parse_account_id(receiver_account_id)?;
+            let mut tx = TransferTransaction::new();
+            tx.hbar_transfer(payer, Hbar::from_tinybars(-amount));
+            tx.hbar_transfer(receiver, Hbar::from_tinybars(*amount));
+            tx.into()
+        }
+        HederaTransactionData::CreateAccount {
+            new_account_public_key,
+            initial_balance,
+        } => {
+            let public_key = parse_public_key(new_account_public_key)?;
+            let mut tx = AccountCreateTransaction::new();
+            tx.set_key_without_alias(public_key);
+            // An ECDSA account is reachable through the EVM address alias of its key
+            if let Some(evm_address) = public_key.to_evm_address() {
+                tx.alias(evm_address);
+            }
+            if *initial_balance > 0 {
+                tx.initial_balance(Hbar::from_tinybars(*initial_balance as i64));
+            }
+            tx.into()
+        }
+        HederaTransactionData::TokenTransfer {
+            token_id,
+            receiver_account_id,
+            amount,
+            decimals,
+        } => {
+            let token_id = parse_token_id(token_id)?;
+            let receiver = parse_account_id(receiver_account_id)?;
+            let mut tx = TransferTransaction::new();
+            tx.token_transfer_with_decimals(token_id, payer, -amount, *decimals);
+            tx.token_transfer_with_decimals(token_id, receiver, *amount, *decimals);
+            tx.into()
+        }
+        HederaTransactionData::NftTransfer {
+            token_id,
+            receiver_account_id,
+            serials,
+        } => {
+            if serials.is_empty() {
+                return Err(TransactionError::Message(
+                    "No NFT serial number to transfer".to_string(),
+                ));
+            }
+            let token_id = parse_token_id(token_id)?;
+            let receiver = parse_account_id(receiver_account_id)?;
+            let mut tx = TransferTransaction::new();
+            for serial in serials {
+                let nft_id = NftId {
+                    token_id,
+                    serial: *serial,
+                };
+                tx.nft_transfer(nft_id, payer, receiver);
+            }
+            tx.into()
+        }
+        HederaTransactionData::TokenAssociate {
+            account_id,
+            token_ids,
+        } => {
+            let mut tx = TokenAssociateTransaction::new();
+            tx.account_id(parse_account_id(account_id)?);
+            tx.token_ids(
+                token_ids
+                    .iter()
+                    .map(|id| parse_token_id(id))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+            tx.into()
+        }
+        HederaTransactionData::TokenDissociate {
+            account_id,
+            token_ids,
+        } => {
+            let mut tx = TokenDissociateTransaction::new();
+            tx.account_id(parse_account_id(account_id)?);
+            tx.token_ids(
+                token_ids
+                    .iter()
+                    .map(|id| parse_token_id(id))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+            tx.into()
+        }
+        HederaTransactionData::AccountUpdate {
+            account_id,
+            new_public_key,
+        } => {
+            let mut tx = AccountUpdateTransaction::new();
+            tx.account_id(parse_account_id(account_id)?);
+            tx.key(parse_public_key(new_public_key)?);
+            tx.into()
+        }
+        HederaTransactionData::AccountDelete {
+            account_id,
+            transfer_account_id,
+        } => {
+            let mut tx = AccountDeleteTransaction::new();
+            tx.account_id(parse_account_id(account_id)?);
+            tx.transfer_account_id(parse_account_id(transfer_account_id)?);
+            tx.into()
+        }
+        HederaTransactionData::ContractCall {
+            contract_id,
+            gas,
+            amount,
+            function_parameters,
+        } => {
+            let mut tx = ContractExecuteTransaction::new();
+            tx.contract_id(parse_contract_id(contract_id)?);
+            tx.gas(*gas);
+            if *amount > 0 {
+                tx.payable_amount(Hbar::from_tinybars(*amount));
+            }
+            tx.function_parameters(function_parameters.clone());
+            tx.into()
+        }
+        HederaTransactionData::ScheduleCreate {
+            scheduled,
+            payer_account_id,
+            memo,
+            expiration_seconds,
+            wait_for_expiry,
+        } => {
+            if matches!(
+                **scheduled,
+                HederaTransactionData::ScheduleCreate { .. }
+                    | HederaTransactionData::ScheduleSign { .. }
+            ) {
+                return Err(TransactionError::Message(
+                    "A schedule transaction cannot be scheduled".to_string(),
+                ));
+            }
+            let mut tx = ScheduleCreateTransaction::new();
+            tx.scheduled_transaction(build_data(scheduled, payer)?);
+            if let Some(payer_account_id) = payer_account_id {
+                tx.payer_account_id(parse_account_id(payer_account_id)?);
+            }
+            if !memo.is_empty() {
+                tx.schedule_memo(memo);
+            }
+            if let Some(expiration_seconds) = expiration_seconds {
+                tx.expiration_time(
+                    OffsetDateTime::from_unix_timestamp(*expiration_seconds).map_err(|e| {
+                        TransactionError::Message(format!("Invalid timestamp: {}", e))
+                    })?,
+                );
+            }
+            tx.wait_for_expiry(*wait_for_expiry);
+            tx.into()
+        }
+        HederaTransactionData::ScheduleSign { schedule_id } => {
+            let mut tx = ScheduleSignTransaction::new();
+            tx.schedule_id(parse_schedule_id(schedule_id)?);
+            tx.into()
+        }
+    };
+    Ok(any_tx)
+}
+
+/// Returns the EVM address alias the receiver of a transfer is given by, if
+/// any, which includes the transfer of a schedule transaction
+fn receiver_evm_alias(data: &HederaTransactionData) -> Result<Option<Vec<u8>>, TransactionError> {
+    let receiver = match data {
+        HederaTransactionData::ScheduleCreate { scheduled, .. } => {
+            return receiver_evm_alias(scheduled)
+        }
+        HederaTransactionData::Transfer {
+            receiver_account_id,
+            ..
+        }
+        | HederaTransactionData::TokenTransfer {
+            receiver_account_id,
+            ..
+        }
+        | HederaTransactionData::NftTransfer {
+            receiver_account_id,
+            ..
+        } => receiver_account_id,
+        _ => return Ok(None),
+    };
+    receiver
+        .strip_prefix("0x")
+        .map(|evm_address| {
+            hex::decode(evm_address)
+                .map_err(|e| TransactionError::Message(format!("Invalid EVM address alias: {}", e)))
+        })
+        .transpose()
+}
+
+/// The SDK serializes an account id given by an EVM address alias as account
+/// 0, so the alias of the receiver is written into the body of each node
+fn set_evm_alias(
+    tx: &AnyTransaction,
+    evm_address: &[u8],
+) -> Result<AnyTransaction, TransactionError> {
+    let set_alias = |account_id: &mut Option<protobuf::AccountId>| {
+        if let Some(account_id) = account_id {
+            if account_id.account == Some(Account::AccountNum(0)) {
+                account_id.account = Some(Account::Alias(evm_address.to_vec()));
+            }
+        }
+    };
+    let set_transfer_alias = |transfer: &mut protobuf::CryptoTransferTransactionBody| {
+        for aa in transfer
+            .transfers
+            .iter_mut()
+            .flat_map(|t| &mut t.account_amounts)
+        {
+            set_alias(&mut aa.account_id);
+        }
+        for token_transfers in &mut transfer.token_transfers {
+            for aa in &mut token_transfers.transfers {
+                set_alias(&mut aa.account_id);
+            }
+            for nft in &mut token_transfers.nft_transfers {
+                set_alias(&mut nft.receiver_account_id);
+            }
+        }
+    };
+
+    let tx_bytes = tx
+        .to_bytes()
+        .map_err(|e| TransactionError::Message(format!("to_bytes failed: {}", e)))?;
+    let mut tx_list = TransactionList::decode(&*tx_bytes)
+        .map_err(|e| TransactionError::Message(format!("decode TransactionList failed: {}", e)))?;
+    for tx in &mut tx_list.transaction_list {
+        let mut signed_tx =
+            SignedTransaction::decode(&*tx.signed_transaction_bytes).map_err(|e| {
+                TransactionError::Message(format!("decode SignedTransaction failed: {}", e))
+            })?;
+        let mut body = TransactionBody::decode(&*signed_tx.body_bytes).map_err(|e| {
+            TransactionError::Message(format!("decode TransactionBody failed: {}", e))
+        })?;
+        match &mut body.data {
+            Some(Data::CryptoTransfer(transfer)) => set_transfer_alias(transfer),
+            Some(Data::ScheduleCreate(schedule)) => {
+                if let Some(schedulable_transaction_body::Data::CryptoTransfer(transfer)) = schedule
+                    .scheduled_transaction_body
+                    .as_mut()
+                    .and_then(|scheduled| scheduled.data.as_mut())
+                {
+                    set_transfer_alias(transfer)
+                }
+            }
+            _ => {}
+        }
+        signed_tx.body_bytes = body.encode_to_vec();
+        tx.signed_transaction_bytes = signed_tx.encode_to_vec();
+    }
+    AnyTransaction::from_bytes(&tx_list.encode_to_vec())
+        .map_err(|e| TransactionError::Message(format!("from_bytes failed: {}", e)))
+}
+
+/// Decodes the data of a transaction body, or returns None if it is not supported
+fn data_from_body(data: Data) -> Option<HederaTransactionData> {
+    let data = match data {
+        Data::CryptoTransfer(transfer_body) => {
+            // A token transfer carries no hbar transfer
+            if let Some(token_transfers) = transfer_body.token_transfers.first() {
+                let token_id = token_transfers
+                    .token
+                    .as_ref()
+                    .map(token_id_to_string)
+                    .unwrap_or_default();
+                if !token_transfers.nft_transfers.is_empty() {
+                    let receiver_account_id = token_transfers.nft_transfers[0]
+                        .receiver_account_id
+                        .as_ref()
+                        .map(account_id_to_string)
+                        .unwrap_or_default();
+                    let serials = token_transfers
+                        .nft_transfers
+                        .iter()
+                        .map(|nft| nft.serial_number as u64)
+                        .collect();
+                    return Some(HederaTransactionData::NftTransfer {
+                        token_id,
+                        receiver_account_id,
+                        serials,
+                    });
+                }
+                let mut receiver_account_id = String::new();
+                let mut amount = 0i64;
+                for aa in &token_transfers.transfers {
+                    if let Some(acc) = &aa.account_id {
+                        if aa.amount > 0 {
+                            receiver_account_id = account_id_to_string(acc);
+                            amount = aa.amount;
+                        }
+                    }
+                }
+                return Some(HederaTransactionData::TokenTransfer {
+                    token_id,
+                    receiver_account_id,
+                    amount,
+                    decimals: token_transfers
+                        .expected_decimals
+                        .map(|decimals| decimals.value)
+                        .unwrap_or_default(),
+                });
+            }
+
+            let mut receiver_account_id = String::new();
+            let mut amount = 0i64;
+            if let Some(transfers) = transfer_body.transfers {
+                for aa in transfers.account_amounts {
+                    if let Some(acc) = aa.account_id {
+                        let acc_str = account_id_to_string(&acc);
+                        if aa.amount < 0 {
+                            // Payer (debit)
+                        } else if aa.amount > 0 {
+                            // Receiver (credit)
+                            receiver_account_id = acc_str;
+                            amount = aa.amount;
+                        }
+                    }
+                }
+            }
+            HederaTransactionData::Transfer {
+                receiver_account_id,
+                amount,
+            }
+        }
+        Data::CryptoCreateAccount(create_body) => HederaTransactionData::CreateAccount {
+            new_account_public_key: public_key_from_key(create_body.key),
+            initial_balance: create_body.initial_balance,
+        },
+        Data::TokenAssociate(body) => HederaTransactionData::TokenAssociate {
+            account_id: body
+                .account
+                .as_ref()
+                .map(account_id_to_string)
+                .unwrap_or_default(),
+            token_ids: body.tokens.iter().map(token_id_to_string).collect(),
+        },
+        Data::TokenDissociate(body) => HederaTransactionData::TokenDissociate {
+            account_id: body
+                .account
+                .as_ref()
+                .map(account_id_to_string)
+                .unwrap_or_default(),
+            token_ids: body.tokens.iter().map(token_id_to_string).collect(),
+        },
+        Data::CryptoUpdateAccount(body) => HederaTransactionData::AccountUpdate {
+            account_id: body
+                .account_id_to_update
+                .as_ref()
+                .map(account_id_to_string)
+                .unwrap_or_default(),
+            new_public_key: public_key_from_key(body.key),
+        },
+        Data::CryptoDelete(body) => HederaTransactionData::AccountDelete {
+            account_id: body
+                .delete_account_id
+                .as_ref()
+                .map(account_id_to_string)
+                .unwrap_or_default(),
+            transfer_account_id: body
+                .transfer_account_id
+                .as_ref()
+                .map(account_id_to_string)
+                .unwrap_or_default(),
+        },
+        Data::ContractCall(body) => HederaTransactionData::ContractCall {
+            contract_id: body
+                .contract_id
+                .as_ref()
+                .map(contract_id_to_string)
+                .unwrap_or_default(),
+            gas: body.gas as u64,
+            amount: body.amount,
+            function_parameters: body.function_parameters,
+        },
+        Data::ScheduleCreate(body) => {
+            let scheduled = body
+                .scheduled_transaction_body
+                .and_then(|scheduled| scheduled.data)
+                .and_then(|data| {
+                    data_from_body(match data {
+                        schedulable_transaction_body::Data::ContractCall(body) => {
+                            Data::ContractCall(body)
+                        }
+                        schedulable_transaction_body::Data::CryptoCreateAccount(body) => {
+                            Data::CryptoCreateAccount(body)
+                        }
+                        schedulable_transaction_body::Data::CryptoDelete(body) => {
+                            Data::CryptoDelete(body)
+                        }
+                        schedulable_transaction_body::Data::CryptoTransfer(body) => {
+                            Data::CryptoTransfer(body)
+                        }
+                        schedulable_transaction_body::Data::CryptoUpdateAccount(body) => {
+                            Data::CryptoUpdateAccount(body)
+                        }
+                        schedulable_transaction_body::Data::TokenAssociate(body) => {
+                            Data::TokenAssociate(body)
+                        }
+                        schedulable_transaction_body::Data::TokenDissociate(body) => {
+                            Data::TokenDissociate(body)
+                        }
+                    })
+                })?;
+            HederaTransactionData::ScheduleCreate {
+                scheduled: Box::new(scheduled),
+                payer_account_id: body.payer_account_id.as_ref().map(account_id_to_string),
+                memo: body.memo,
+                expiration_seconds: body.expiration_time.map(|time| time.seconds),
+                wait_for_expiry: body.wait_for_expiry,
+            }
+        }
+        Data::ScheduleSign(body) => HederaTransactionData::ScheduleSign {
+            schedule_id: body
+                .schedule_id
+                .map(|id| format!("{}.{}.{}", id.shard_num, id.realm_num, id.schedule_num))
+                .unwrap_or_default(),
+        },
+    };
+    Some(data)
 }
 
 impl Transaction for HederaTransaction {
@@ -90,94 +803,49 @@ impl Transaction for HederaTransaction {
             scheduled: false,
         };
 
-        let any_tx: AnyTransaction = match &parameters.data {
-            HederaTransactionData::Transfer {
-                receiver_account_id,
-                amount,
-            } => {
-                let receiver = parse_account_id(receiver_account_id)?;
-                let mut tx = TransferTransaction::new();
-                tx.hbar_transfer(payer, Hbar::from_tinybars(-amount));
-                tx.hbar_transfer(receiver, Hbar::from_tinybars(*amount));
-                tx.transaction_id(tx_id);
-                if !parameters.node_account_ids.is_empty() {
-                    let mut nodes = Vec::new();
-                    for node_str in &parameters.node_account_ids {
-                        let node = AccountId::from_str(node_str).map_err(|e| {
-                            TransactionError::Message(format!("Invalid node account ID: {}", e))
-                        })?;
-                        nodes.push(node);
-                    }
-                    tx.node_account_ids(nodes);
-                }
-                if parameters.max_transaction_fee > 0 {
-                    tx.max_transaction_fee(Hbar::from_tinybars(
-                        parameters.max_transaction_fee as i64,
-                    ));
-                }
-                if !parameters.memo.is_empty() {
-                    tx.transaction_memo(&parameters.memo);
-                }
-                tx.freeze()
-                    .map_err(|e| TransactionError::Message(format!("Freeze failed: {}", e)))?;
-                tx.into()
+        let mut tx = build_data(&parameters.data, payer)?;
+        tx.transaction_id(tx_id);
+        if !parameters.node_account_ids.is_empty() {
+            let mut nodes = Vec::new();
+            for node_str in &parameters.node_account_ids {
+                let node = AccountId::from_str(node_str).map_err(|e| {
+                    TransactionError::Message(format!("Invalid node account ID: {}", e))
+                })?;
+                nodes.push(node);
             }
-            HederaTransactionData::CreateAccount {
-                new_account_public_key,
-                initial_balance,
-            } => {
-                use hiero_sdk::AccountCreateTransaction;
-                let mut tx = AccountCreateTransaction::new();
-                let pk = hiero_sdk::PublicKey::from_bytes_ed25519(new_account_public_key)
-                    .map_err(|e| TransactionError::Message(format!("Invalid public key: {}", e)))?;
-                tx.set_key_without_alias(pk);
-                if *initial_balance > 0 {
-                    tx.initial_balance(Hbar::from_tinybars(*initial_balance as i64));
-                }
-                tx.transaction_id(tx_id);
-                if !parameters.node_account_ids.is_empty() {
-                    let mut nodes = Vec::new();
-                    for node_str in &parameters.node_account_ids {
-                        let node = AccountId::from_str(node_str).map_err(|e| {
-                            TransactionError::Message(format!("Invalid node account ID: {}", e))
-                        })?;
-                        nodes.push(node);
-                    }
-                    tx.node_account_ids(nodes);
-                }
-                if parameters.max_transaction_fee > 0 {
-                    tx.max_transaction_fee(Hbar::from_tinybars(
-                        parameters.max_transaction_fee as i64,
-                    ));
-                }
-                if !parameters.memo.is_empty() {
-                    tx.transaction_memo(&parameters.memo);
-                }
-                tx.freeze()
-                    .map_err(|e| TransactionError::Message(format!("Freeze failed: {}", e)))?;
-                tx.into()
-            }
-        };
+            tx.node_account_ids(nodes);
+        }
+        if parameters.max_transaction_fee > 0 {
+            tx.max_transaction_fee(Hbar::from_tinybars(parameters.max_transaction_fee as i64));
+        }
+        if !parameters.memo.is_empty() {
+            tx.transaction_memo(&parameters.memo);
+        }
+        tx.freeze()
+            .map_err(|e| TransactionError::Message(format!("Freeze failed: {}", e)))?;
+        if let Some(evm_address) = receiver_evm_alias(&parameters.data)? {
+            tx = set_evm_alias(&tx, &evm_address)?;
+        }
 
         Ok(Self {
             params: parameters.clone(),
-            tx: any_tx,
-            signature: None,
+            tx,
+            signatures: Vec::new(),
         })
     }
 
+    /// Adds the signature of `params.public_key` for the first node. Use
+    /// `add_signature` to sign for the other nodes or with other keys
     fn sign(&mut self, signature: Vec<u8>, _recid: u8) -> Result<Vec<u8>, TransactionError> {
-        let mut pk_bytes = self.params.public_key.clone();
-        if pk_bytes.starts_with(&[
-            0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
-        ]) {
-            pk_bytes = pk_bytes[12..].to_vec();
-        }
-        let hiero_pk = hiero_sdk::PublicKey::from_bytes_ed25519(&pk_bytes)
-            .map_err(|e| TransactionError::Message(format!("Invalid public key: {}", e)))?;
-
-        self.tx.add_signature(hiero_pk, signature.clone());
-        self.signature = Some(signature);
+        let public_key = HederaKey::from_bytes(&self.params.public_key)?;
+        let (node, _) =
+            self.node_body_bytes()?
+                .into_iter()
+                .next()
+                .ok_or(TransactionError::Message(
+                    "No transaction in transaction list".to_string(),
+                ))?;
+        self.add_signature(&node, public_key, signature)?;
 
         self.to_bytes()
     }
@@ -224,84 +892,40 @@ impl Transaction for HederaTransaction {
                     SignedTransaction::decode(&*proto_tx.signed_transaction_bytes)
                 {
                     if let Ok(body) = TransactionBody::decode(&*signed_tx.body_bytes) {
-                        if let Some(data) = body.data {
-                            match data {
-                                Data::CryptoTransfer(transfer_body) => {
-                                    let mut receiver_account_id = String::new();
-                                    let mut amount = 0i64;
-                                    if let Some(transfers) = transfer_body.transfers {
-                                        for aa in transfers.account_amounts {
-                                            if let Some(acc) = aa.account_id {
-                                                let acc_str = match &acc.account {
-                                                    Some(Account::AccountNum(num)) => {
-                                                        format!(
-                                                            "{}.{}.{}",
-                                                            acc.shard_num, acc.realm_num, num
-                                                        )
-                                                    }
-                                                    Some(Account::Alias(alias)) => {
-                                                        if alias.len() == 20 {
-                                                            format!("0x{}", hex::encode(alias))
-                                                        } else {
-                                                            format!(
-                                                                "{}.{}.{}",
-                                                                acc.shard_num,
-                                                                acc.realm_num,
-                                                                hex::encode(alias)
-                                                            )
-                                                        }
-                                                    }
-                                                    None => format!(
-                                                        "{}.{}.0",
-                                                        acc.shard_num, acc.realm_num
-                                                    ),
-                                                };
-                                                if aa.amount < 0 {
-                                                    // Payer (debit)
-                                                } else if aa.amount > 0 {
-                                                    // Receiver (credit)
-                                                    receiver_account_id = acc_str;
-                                                    amount = aa.amount;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    tx_data = Some(HederaTransactionData::Transfer {
-                                        receiver_account_id,
-                                        amount,
-                                    });
-                                }
-                                Data::CryptoCreateAccount(create_body) => {
-                                    let mut new_account_public_key = Vec::new();
-                                    if let Some(key) = create_body.key {
-                                        if let Some(Key::Ed25519(pk_bytes)) = key.key {
-                                            new_account_public_key = pk_bytes;
-                                        }
-                                    }
-                                    tx_data = Some(HederaTransactionData::CreateAccount {
-                                        new_account_public_key,
-                                        initial_balance: create_body.initial_balance,
-                                    });
-                                }
-                            }
-                        }
+                        tx_data = body.data.and_then(data_from_body);
                     }
                 }
             }
         }
 
-        let mut signature = None;
-        if let Ok(tx_list) = TransactionList::decode(&*tx_bytes) {
-            if let Some(proto_tx) = tx_list.transaction_list.first() {
-                if let Ok(signed_tx) =
-                    SignedTransaction::decode(&*proto_tx.signed_transaction_bytes)
-                {
-                    if let Some(sig_map) = signed_tx.sig_map {
-                        if let Some(sig_pair) = sig_map.sig_pair.first() {
-                            if let Some(Signature::Ed25519(sig)) = &sig_pair.signature {
-                                signature = Some(sig.clone());
-                            }
-                        }
+        // Collect the signatures of every node, skipping those whose key is
+        // abbreviated to a prefix
+        let mut signatures = Vec::new();
+        if let Ok(tx_list) = TransactionList::decode(bytes) {
+            for proto_tx in &tx_list.transaction_list {
+                let Ok(signed_tx) = SignedTransaction::decode(&*proto_tx.signed_transaction_bytes)
+                else {
+                    continue;
+                };
+                let Ok(body) = TransactionBody::decode(&*signed_tx.body_bytes) else {
+                    continue;
+                };
+                let node_account_id = body
+                    .node_account_id
+                    .as_ref()
+                    .map(account_id_to_string)
+                    .unwrap_or_default();
+                for sig_pair in signed_tx.sig_map.unwrap_or_default().sig_pair {
+                    let signature = match sig_pair.signature {
+                        Some(Signature::Ed25519(sig)) | Some(Signature::EcdsaSecp256k1(sig)) => sig,
+                        None => continue,
+                    };
+                    if let Ok(public_key) = HederaKey::from_bytes(&sig_pair.pub_key_prefix) {
+                        signatures.push(HederaSignature {
+                            node_account_id: node_account_id.clone(),
+                            public_key,
+                            signature,
+                        });
                     }
                 }
             }
@@ -326,26 +950,28 @@ impl Transaction for HederaTransaction {
         Ok(Self {
             params,
             tx: any_tx,
-            signature,
+            signatures,
         })
     }
 
+    /// Returns the body bytes to be signed for the first node if the
+    /// transaction is unsigned, or else the gRPC-framed signed transaction
+    /// sent to the first node
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        let tx_bytes = self
-            .tx
-            .to_bytes()
-            .map_err(|e| TransactionError::Message(format!("to_bytes failed: {}", e)))?;
-
-        let mut tx_list = TransactionList::decode(&*tx_bytes).map_err(|e| {
-            TransactionError::Message(format!("decode TransactionList failed: {}", e))
-        })?;
+        let mut tx_list = self.transaction_list()?;
 
         let tx = tx_list.transaction_list.first_mut().ok_or_else(|| {
             TransactionError::Message("No transaction in transaction list".to_string())
         })?;
 
-        match &self.signature {
-            Some(_) => {
+        match self.signatures.is_empty() {
+            false => {
+                let (node, body_bytes) = self.node_body_bytes()?.swap_remove(0);
+                let signed_tx = SignedTransaction {
+                    body_bytes,
+                    sig_map: Some(self.signature_map(&node)),
+                };
+                tx.signed_transaction_bytes = signed_tx.encode_to_vec();
                 let payload = tx.encode_to_vec();
 
                 // gRPC prefix
@@ -360,7 +986,7 @@ impl Transaction for HederaTransaction {
 
                 Ok(full_bytes)
             }
-            None => {
+            true => {
                 let signed_tx =
                     SignedTransaction::decode(&*tx.signed_transaction_bytes).map_err(|e| {
                         TransactionError::Message(format!("decode SignedTransaction failed: {}", e))
@@ -388,6 +1014,7 @@ impl Transaction for HederaTransaction {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anychain_core::PublicKey;
     use ed25519_dalek::Signer;
     use ed25519_dalek::SigningKey;
     use hiero_sdk::{Client, PrivateKey};
@@ -400,6 +1027,220 @@ mod tests {
     const PRIVATE_HEX_BOB: &str =
         "be16996c9f6731347d11eb59c498d8908d7ff2b0d0bef860552c6ee1da66fd3a";
 
+    fn params(data: HederaTransactionData) -> HederaTransactionParameters {
+        HederaTransactionParameters {
+            payer_account_id: "0.0.9549757".to_string(),
+            node_account_ids: vec!["0.0.3".to_string()],
+            valid_start_seconds: 1700000000,
+            valid_start_nanos: 123,
+            max_transaction_fee: 2_000_000,
+            memo: "memo".to_string(),
+            public_key: Vec::new(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_tx_data() {
+        let pk = PrivateKey::from_str_ed25519(PRIVATE_HEX_BOB)
+            .unwrap()
+            .public_key()
+            .to_bytes_raw();
+        let transfer = HederaTransactionData::TokenTransfer {
+            token_id: "0.0.456858".to_string(),
+            receiver_account_id: "0.0.8007608".to_string(),
+            amount: 1_000_000,
+            decimals: 6,
+        };
+        let data = vec![
+            HederaTransactionData::Transfer {
+                receiver_account_id: "0.0.8007608".to_string(),
+                amount: 10_000_000,
+            },
+            transfer.clone(),
+            HederaTransactionData::NftTransfer {
+                token_id: "0.0.1234".to_string(),
+                receiver_account_id: "0.0.8007608".to_string(),
+                serials: vec![1, 7],
+            },
+            HederaTransactionData::TokenAssociate {
+                account_id: "0.0.9549757".to_string(),
+                token_ids: vec!["0.0.456858".to_string(), "0.0.1234".to_string()],
+            },
+            HederaTransactionData::TokenDissociate {
+                account_id: "0.0.9549757".to_string(),
+                token_ids: vec!["0.0.1234".to_string()],
+            },
+            HederaTransactionData::AccountUpdate {
+                account_id: "0.0.9549757".to_string(),
+                new_public_key: pk.clone(),
+            },
+            HederaTransactionData::AccountDelete {
+                account_id: "0.0.9549757".to_string(),
+                transfer_account_id: "0.0.8007608".to_string(),
+            },
+            HederaTransactionData::ContractCall {
+                contract_id: "0x00000000000000000000000000000000000004d2".to_string(),
+                gas: 100_000,
+                amount: 0,
+                function_parameters: hex::decode("a9059cbb").unwrap(),
+            },
+            HederaTransactionData::ScheduleCreate {
+                scheduled: Box::new(transfer.clone()),
+                payer_account_id: Some("0.0.8007608".to_string()),
+                memo: "payroll".to_string(),
+                expiration_seconds: Some(1700003600),
+                wait_for_expiry: true,
+            },
+            HederaTransactionData::ScheduleSign {
+                schedule_id: "0.0.5005".to_string(),
+            },
+        ];
+
+        for data in data {
+            let tx = HederaTransaction::new(&params(data.clone())).unwrap();
+            let decoded = HederaTransaction::from_bytes(&tx.tx.to_bytes().unwrap()).unwrap();
+            assert_eq!(params(data), decoded.params);
+        }
+
+        let scheduled = HederaTransactionData::ScheduleSign {
+            schedule_id: "0.0.5005".to_string(),
+        };
+        assert!(
+            HederaTransaction::new(&params(HederaTransactionData::ScheduleCreate {
+                scheduled: Box::new(scheduled),
+                payer_account_id: None,
+                memo: String::new(),
+                expiration_seconds: None,
+                wait_for_expiry: false,
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_multi_node_signing() {
+        let ed25519 = SigningKey::from_bytes(&[1u8; 32]);
+        let ecdsa = libsecp256k1::SecretKey::parse(&[2u8; 32]).unwrap();
+        let ed25519_key = HederaKey::Ed25519(ed25519.verifying_key().to_bytes());
+        let ecdsa_key = HederaKey::EcdsaSecp256k1(
+            libsecp256k1::PublicKey::from_secret_key(&ecdsa).serialize_compressed(),
+        );
+        let account_key = HederaKey::Threshold {
+            threshold: 2,
+            keys: vec![ed25519_key.clone(), ecdsa_key.clone()],
+        };
+
+        let mut params = params(HederaTransactionData::Transfer {
+            receiver_account_id: "0.0.8007608".to_string(),
+            amount: 10_000_000,
+        });
+        params.node_account_ids = vec!["0.0.3".to_string(), "0.0.4".to_string()];
+        params.public_key = ed25519_key.to_bytes().unwrap();
+        let mut tx = HederaTransaction::new(&params).unwrap();
+
+        let bodies = tx.node_body_bytes().unwrap();
+        assert_eq!(2, bodies.len());
+        assert_eq!("0.0.3", bodies[0].0);
+        assert_eq!("0.0.4", bodies[1].0);
+        assert_ne!(bodies[0].1, bodies[1].1);
+        assert_eq!(bodies[0].1, tx.to_bytes().unwrap());
+
+        for (i, (node, body)) in bodies.iter().enumerate() {
+            let sig = ed25519.sign(body).to_bytes().to_vec();
+            match i {
+                0 => assert!(tx.sign(sig, 0).is_ok()),
+                _ => tx.add_signature(node, ed25519_key.clone(), sig).unwrap(),
+            }
+            // A signature over the body of another node is rejected
+            let other = &bodies[1 - i].1;
+            let sig = ed25519.sign(other).to_bytes().to_vec();
+            assert!(tx.add_signature(node, ed25519_key.clone(), sig).is_err());
+        }
+        assert!(!tx.is_signed_by_key(&account_key).unwrap());
+
+        let message = libsecp256k1::Message::parse(&keccak256(&bodies[0].1));
+        let (sig, _) = libsecp256k1::sign(&message, &ecdsa);
+        tx.add_signature("0.0.3", ecdsa_key.clone(), sig.serialize().to_vec())
+            .unwrap();
+        // The ECDSA key has not signed for node 0.0.4 yet
+        assert_eq!(vec![ed25519_key.clone()], tx.signers().unwrap());
+        assert!(!tx.is_signed_by_key(&account_key).unwrap());
+
+        let message = libsecp256k1::Message::parse(&keccak256(&bodies[1].1));
+        let (sig, _) = libsecp256k1::sign(&message, &ecdsa);
+        tx.add_signature("0.0.4", ecdsa_key.clone(), sig.serialize().to_vec())
+            .unwrap();
+        assert!(tx.is_signed_by_key(&account_key).unwrap());
+        assert_eq!(2, tx.signature_map("0.0.4").sig_pair.len());
+
+        let bytes = tx.to_transaction_list_bytes().unwrap();
+        let decoded = HederaTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(4, decoded.signatures.len());
+        assert_eq!(bytes, decoded.to_transaction_list_bytes().unwrap());
+        assert!(decoded.is_signed_by_key(&account_key).unwrap());
+    }
+
+    #[test]
+    fn test_ecdsa_alias() {
+        let sk = libsecp256k1::SecretKey::parse(&[1u8; 32]).unwrap();
+        let public_key = HederaPublicKey::from_secret_key(&sk.into());
+        let evm_alias = public_key.to_evm_alias().unwrap().to_string();
+        let key_alias = format!("0.0.{}", public_key);
+
+        let data = vec![
+            HederaTransactionData::CreateAccount {
+                new_account_public_key: public_key.serialize(),
+                initial_balance: 100_000_000,
+            },
+            // Transfers to aliases auto-create the accounts
+            HederaTransactionData::Transfer {
+                receiver_account_id: evm_alias.clone(),
+                amount: 10_000_000,
+            },
+            HederaTransactionData::ScheduleCreate {
+                scheduled: Box::new(HederaTransactionData::Transfer {
+                    receiver_account_id: evm_alias,
+                    amount: 10_000_000,
+                }),
+                payer_account_id: None,
+                memo: String::new(),
+                expiration_seconds: None,
+                wait_for_expiry: false,
+            },
+            HederaTransactionData::Transfer {
+                receiver_account_id: key_alias,
+                amount: 10_000_000,
+            },
+        ];
+        for data in data {
+            let tx = HederaTransaction::new(&params(data.clone())).unwrap();
+            let decoded = HederaTransaction::from_bytes(&tx.tx.to_bytes().unwrap()).unwrap();
+            assert_eq!(params(data), decoded.params);
+        }
+
+        // An ECDSA payer signs the keccak-256 hash of the body bytes
+        let mut params = params(HederaTransactionData::Transfer {
+            receiver_account_id: "0.0.8007608".to_string(),
+            amount: 10_000_000,
+        });
+        params.public_key = public_key.serialize();
+        let mut tx = HederaTransaction::new(&params).unwrap();
+        let (_, hash) = tx.node_body_hashes().unwrap().remove(0);
+        assert_eq!(keccak256(&tx.to_bytes().unwrap()), hash);
+
+        let (sig, _) = libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), &sk);
+        assert!(tx.sign(vec![0u8; 64], 0).is_err());
+        let signed = tx.sign(sig.serialize().to_vec(), 0).unwrap();
+        assert_eq!(0u8, signed[0]);
+        assert!(tx.is_signed_by_key(&public_key.to_key()).unwrap());
+        let sig_pair = &tx.signature_map("0.0.3").sig_pair[0];
+        assert!(matches!(
+            sig_pair.signature,
+            Some(Signature::EcdsaSecp256k1(_))
+        ));
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_transfer_alice_to_bob() {
@@ -0,0 +1,373 @@
+//! BIP32-Ed25519 hierarchical derivation of Khovratovich and Law, with the
+//! Icarus master key of CIP-3, which is what Cardano wallets such as Daedalus,
+//! Yoroi and Eternl use.
+//!
+//! See <https://cips.cardano.org/cip/CIP-0003>
+
+use crate::bip32::{ChainCode, ChildNumber, DerivationPath, Error, HmacSha512, Result, KEY_SIZE};
+use core::fmt::{self, Debug};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE as G, edwards::CompressedEdwardsY, scalar::Scalar,
+};
+use ed25519_dalek::{hazmat::ExpandedSecretKey, Signature, VerifyingKey};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// Number of PBKDF2 rounds of the Icarus master key.
+const ICARUS_PBKDF2_ROUNDS: u32 = 4096;
+
+/// Extended private BIP32-Ed25519 key, i.e. the 64-byte extended secret key
+/// (kL, kR) and the chain code.
+#[derive(Clone)]
+pub struct XprvBip32Ed25519 {
+    extended_key: [u8; 2 * KEY_SIZE],
+    chain_code: ChainCode,
+}
+
+/// Extended public BIP32-Ed25519 key, from which soft children can be
+/// derived.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XpubBip32Ed25519 {
+    public_key: [u8; KEY_SIZE],
+    chain_code: ChainCode,
+}
+
+/// Returns `kL + 8 * zL`, where `zL` is the first 28 bytes of `z`.
+fn add_28_mul8(kl: &[u8], z: &[u8]) -> [u8; KEY_SIZE] {
+    let mut out = [0u8; KEY_SIZE];
+    let mut carry = 0u16;
+    for i in 0..KEY_SIZE {
+        let zl = if i < 28 { (z[i] as u16) << 3 } else { 0 };
+        let r = kl[i] as u16 + zl + carry;
+        out[i] = r as u8;
+        carry = r >> 8;
+    }
+    out
+}
+
+/// Returns `kR + zR` modulo 2^256.
+fn add_256(kr: &[u8], zr: &[u8]) -> [u8; KEY_SIZE] {
+    let mut out = [0u8; KEY_SIZE];
+    let mut carry = 0u16;
+    for i in 0..KEY_SIZE {
+        let r = kr[i] as u16 + zr[i] as u16 + carry;
+        out[i] = r as u8;
+        carry = r >> 8;
+    }
+    out
+}
+
+fn hmac(chain_code: &[u8], parts: &[&[u8]]) -> Result<[u8; 64]> {
+    let mut hmac = HmacSha512::new_from_slice(chain_code).map_err(|_| Error::Crypto)?;
+    for part in parts {
+        hmac.update(part);
+    }
+    Ok(hmac.finalize().into_bytes().into())
+}
+
+impl XprvBip32Ed25519 {
+    /// Create the Icarus master key from the entropy of a BIP39 mnemonic,
+    /// i.e. `Mnemonic::entropy()`, and the passphrase of the wallet.
+    pub fn from_icarus_entropy(entropy: &[u8], passphrase: &[u8]) -> Self {
+        let mut bytes = [0u8; 3 * KEY_SIZE];
+        pbkdf2::pbkdf2::<Hmac<Sha512>>(passphrase, entropy, ICARUS_PBKDF2_ROUNDS, &mut bytes)
+            .expect("HMAC can be initialized with any key length");
+        bytes[0] &= 0b1111_1000;
+        bytes[31] &= 0b0001_1111;
+        bytes[31] |= 0b0100_0000;
+
+        let mut extended_key = [0u8; 2 * KEY_SIZE];
+        extended_key.copy_from_slice(&bytes[..2 * KEY_SIZE]);
+        let mut chain_code = [0u8; KEY_SIZE];
+        chain_code.copy_from_slice(&bytes[2 * KEY_SIZE..]);
+        bytes.zeroize();
+
+        XprvBip32Ed25519 {
+            extended_key,
+            chain_code,
+        }
+    }
+
+    /// Initialize this key from the 96 bytes of kL, kR and the chain code.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 3 * KEY_SIZE
+            || bytes[0] & 0b0000_0111 != 0
+            || bytes[31] & 0b1000_0000 != 0
+        {
+            return Err(Error::Decode);
+        }
+        let mut extended_key = [0u8; 2 * KEY_SIZE];
+        extended_key.copy_from_slice(&bytes[..2 * KEY_SIZE]);
+        Ok(XprvBip32Ed25519 {
+            extended_key,
+            chain_code: bytes[2 * KEY_SIZE..].try_into()?,
+        })
+    }
+
+    /// Serialize this key as the 96 bytes of kL, kR and the chain code.
+    pub fn to_bytes(&self) -> [u8; 3 * KEY_SIZE] {
+        let mut bytes = [0u8; 3 * KEY_SIZE];
+        bytes[..2 * KEY_SIZE].copy_from_slice(&self.extended_key);
+        bytes[2 * KEY_SIZE..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+
+    /// Borrow the 64-byte extended secret key (kL, kR).
+    pub fn extended_key(&self) -> &[u8; 2 * KEY_SIZE] {
+        &self.extended_key
+    }
+
+    pub fn chain_code(&self) -> &ChainCode {
+        &self.chain_code
+    }
+
+    pub fn derive_from_path(self, path: &DerivationPath) -> Result<Self> {
+        path.iter()
+            .try_fold(self, |key, child_num| key.derive_child(child_num))
+    }
+
+    /// Derive a child key for a particular [`ChildNumber`], where hardened
+    /// children commit to the private key and soft children to the public key.
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<Self> {
+        let index = child_number.0.to_le_bytes();
+        let (z, i) = if child_number.is_hardened() {
+            (
+                hmac(&self.chain_code, &[&[0x00], &self.extended_key, &index])?,
+                hmac(&self.chain_code, &[&[0x01], &self.extended_key, &index])?,
+            )
+        } else {
+            let public_key = self.public_key_bytes();
+            (
+                hmac(&self.chain_code, &[&[0x02], &public_key, &index])?,
+                hmac(&self.chain_code, &[&[0x03], &public_key, &index])?,
+            )
+        };
+
+        let mut extended_key = [0u8; 2 * KEY_SIZE];
+        extended_key[..KEY_SIZE].copy_from_slice(&add_28_mul8(&self.extended_key[..KEY_SIZE], &z));
+        extended_key[KEY_SIZE..]
+            .copy_from_slice(&add_256(&self.extended_key[KEY_SIZE..], &z[KEY_SIZE..]));
+
+        Ok(XprvBip32Ed25519 {
+            extended_key,
+            chain_code: i[KEY_SIZE..].try_into()?,
+        })
+    }
+
+    fn expanded_secret_key(&self) -> ExpandedSecretKey {
+        let mut scalar = [0u8; KEY_SIZE];
+        scalar.copy_from_slice(&self.extended_key[..KEY_SIZE]);
+        let mut hash_prefix = [0u8; KEY_SIZE];
+        hash_prefix.copy_from_slice(&self.extended_key[KEY_SIZE..]);
+        // kL is used as is, as clamping it would break the derivation
+        ExpandedSecretKey {
+            scalar: Scalar::from_bytes_mod_order(scalar),
+            hash_prefix,
+        }
+    }
+
+    fn public_key_bytes(&self) -> [u8; KEY_SIZE] {
+        VerifyingKey::from(&self.expanded_secret_key()).to_bytes()
+    }
+
+    /// Get the extended public key of this key.
+    pub fn public_key(&self) -> XpubBip32Ed25519 {
+        XpubBip32Ed25519 {
+            public_key: self.public_key_bytes(),
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Sign the message with the extended secret key, where kL is the scalar
+    /// and kR the nonce prefix, which yields a standard Ed25519 signature.
+    pub fn sign(&self, msg: &[u8]) -> [u8; 64] {
+        let xsk = self.expanded_secret_key();
+        let pk = VerifyingKey::from(&xsk);
+        let sig: Signature = ed25519_dalek::hazmat::raw_sign::<Sha512>(&xsk, msg, &pk);
+        sig.to_bytes()
+    }
+}
+
+impl XpubBip32Ed25519 {
+    /// Initialize this key from the 64 bytes of the public key and the chain
+    /// code.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 2 * KEY_SIZE {
+            return Err(Error::Decode);
+        }
+        Ok(XpubBip32Ed25519 {
+            public_key: bytes[..KEY_SIZE].try_into()?,
+            chain_code: bytes[KEY_SIZE..].try_into()?,
+        })
+    }
+
+    /// Serialize this key as the 64 bytes of the public key and the chain
+    /// code.
+    pub fn to_bytes(&self) -> [u8; 2 * KEY_SIZE] {
+        let mut bytes = [0u8; 2 * KEY_SIZE];
+        bytes[..KEY_SIZE].copy_from_slice(&self.public_key);
+        bytes[KEY_SIZE..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+
+    /// Borrow the 32-byte Ed25519 public key.
+    pub fn public_key(&self) -> &[u8; KEY_SIZE] {
+        &self.public_key
+    }
+
+    pub fn chain_code(&self) -> &ChainCode {
+        &self.chain_code
+    }
+
+    pub fn derive_from_path(self, path: &DerivationPath) -> Result<Self> {
+        path.iter()
+            .try_fold(self, |key, child_num| key.derive_child(child_num))
+    }
+
+    /// Derive a soft child key for a particular [`ChildNumber`], as public
+    /// keys have no hardened children.
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<Self> {
+        if child_number.is_hardened() {
+            return Err(Error::ChildNumber);
+        }
+        let index = child_number.0.to_le_bytes();
+        let z = hmac(&self.chain_code, &[&[0x02], &self.public_key, &index])?;
+        let i = hmac(&self.chain_code, &[&[0x03], &self.public_key, &index])?;
+
+        let point = CompressedEdwardsY(self.public_key)
+            .decompress()
+            .ok_or(Error::Crypto)?;
+        let tweak = Scalar::from_bytes_mod_order(add_28_mul8(&[0u8; KEY_SIZE], &z));
+        let public_key = (point + G * &tweak).compress().to_bytes();
+
+        Ok(XpubBip32Ed25519 {
+            public_key,
+            chain_code: i[KEY_SIZE..].try_into()?,
+        })
+    }
+}
+
+impl Drop for XprvBip32Ed25519 {
+    fn drop(&mut self) {
+        self.extended_key.zeroize();
+    }
+}
+
+impl Debug for XprvBip32Ed25519 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XprvBip32Ed25519")
+            .field("extended_key", &"...")
+            .field("chain_code", &self.chain_code)
+            .finish()
+    }
+}
+
+/// NOTE: uses [`ConstantTimeEq`] internally
+impl Eq for XprvBip32Ed25519 {}
+
+/// NOTE: uses [`ConstantTimeEq`] internally
+impl PartialEq for XprvBip32Ed25519 {
+    fn eq(&self, other: &Self) -> bool {
+        (self.extended_key.ct_eq(&other.extended_key) & self.chain_code.ct_eq(&other.chain_code))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{Language, Mnemonic};
+    use cml_crypto::{Bip32PrivateKey, Bip32PublicKey, RawBytesEncoding};
+
+    const PHRASE: &str =
+        "eight country switch draw meat scout mystery blade tip drift useless good keep usage title";
+
+    #[test]
+    fn test_icarus_master_key() {
+        // The test vector of CIP-3 without a passphrase
+        let mnemonic = Mnemonic::from_phrase(PHRASE, Language::English).unwrap();
+        let xprv = XprvBip32Ed25519::from_icarus_entropy(mnemonic.entropy(), b"");
+        assert_eq!(
+            "c065afd2832cd8b087c4d9ab7011f481ee1e0721e78ea5dd609f3ab3f156d245\
+             d176bd8fd4ec60b4731c3918a2a72a0226c0cd119ec35b47e4d55884667f552a\
+             23f7fdcd4a10c6cd2c7393ac61d877873e248f417634aa3d812af327ffe9d620",
+            hex::encode(xprv.to_bytes())
+        );
+        assert_eq!(
+            xprv,
+            XprvBip32Ed25519::from_bytes(&xprv.to_bytes()).unwrap()
+        );
+
+        let root = Bip32PrivateKey::from_bip39_entropy(mnemonic.entropy(), b"foo");
+        let xprv = XprvBip32Ed25519::from_icarus_entropy(mnemonic.entropy(), b"foo");
+        assert_eq!(root.to_raw_bytes(), xprv.to_bytes());
+    }
+
+    #[test]
+    fn test_cip1852_derivation() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE, Language::English).unwrap();
+        let root = Bip32PrivateKey::from_bip39_entropy(mnemonic.entropy(), b"");
+        let xprv = XprvBip32Ed25519::from_icarus_entropy(mnemonic.entropy(), b"");
+
+        let account: DerivationPath = "m/1852'/1815'/0'".parse().unwrap();
+        let xprv = xprv.derive_from_path(&account).unwrap();
+        let root = root
+            .derive(1852 | 0x80000000)
+            .derive(1815 | 0x80000000)
+            .derive(0x80000000);
+        assert_eq!(root.to_raw_bytes(), xprv.to_bytes());
+
+        let xpub = xprv.public_key();
+        assert_eq!(root.to_public().to_raw_bytes(), xpub.to_bytes());
+        assert_eq!(
+            xpub,
+            XpubBip32Ed25519::from_bytes(&xpub.to_bytes()).unwrap()
+        );
+
+        // The payment and stake keys, derived from both the private and the
+        // public account key
+        for (role, index) in [(0, 0), (0, 7), (2, 0)] {
+            let path: DerivationPath = format!("m/{role}/{index}").parse().unwrap();
+            let child = xprv.clone().derive_from_path(&path).unwrap();
+            let expected = root.derive(role).derive(index);
+            assert_eq!(expected.to_raw_bytes(), child.to_bytes());
+            assert_eq!(
+                child.public_key(),
+                xpub.clone().derive_from_path(&path).unwrap()
+            );
+            let expected: Bip32PublicKey = root
+                .to_public()
+                .derive(role)
+                .unwrap()
+                .derive(index)
+                .unwrap();
+            assert_eq!(expected.to_raw_bytes(), child.public_key().to_bytes());
+        }
+
+        assert_eq!(
+            Err(Error::ChildNumber),
+            xpub.derive_child(ChildNumber(0x80000000))
+        );
+    }
+
+    #[test]
+    fn test_extended_key_sign() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE, Language::English).unwrap();
+        let path: DerivationPath = "m/1852'/1815'/0'/0/0".parse().unwrap();
+        let xprv = XprvBip32Ed25519::from_icarus_entropy(mnemonic.entropy(), b"")
+            .derive_from_path(&path)
+            .unwrap();
+
+        let msg = b"hello cardano";
+        let sig = xprv.sign(msg);
+        let cml_key = Bip32PrivateKey::from_raw_bytes(&xprv.to_bytes()).unwrap();
+        assert_eq!(cml_key.to_raw_key().sign(msg).to_raw_bytes(), sig);
+
+        let pk = cml_crypto::PublicKey::from_raw_bytes(xprv.public_key().public_key()).unwrap();
+        let sig = cml_crypto::Ed25519Signature::from_raw_bytes(&sig).unwrap();
+        assert!(pk.verify(msg, &sig));
+        assert!(!pk.verify(b"hello world", &sig));
+    }
+}
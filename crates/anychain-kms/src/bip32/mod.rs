@@ -2,9 +2,11 @@ mod child_number;
 mod derivation_path;
 mod error;
 mod extended_key;
+mod icarus;
 mod prefix;
 mod private_key;
 mod public_key;
+mod slip10;
 
 pub use child_number::ChildNumber;
 pub use error::{Error, Result};
@@ -16,9 +18,11 @@ pub use extended_key::{
     extended_private_key::{XprvEd25519, XprvSecp256k1},
     extended_public_key::{XpubEd25519, XpubSecp256k1},
 };
+pub use icarus::{XprvBip32Ed25519, XpubBip32Ed25519};
 pub use prefix::Prefix;
 pub use private_key::PrivateKey;
 pub use public_key::PublicKey;
+pub use slip10::{Slip10Ed25519, Slip10Key, Slip10P256, Slip10PrivateKey};
 
 pub use derivation_path::DerivationPath;
 
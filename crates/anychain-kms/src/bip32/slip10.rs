@@ -0,0 +1,435 @@
+//! SLIP-10 hierarchical derivation of Ed25519 and NIST P-256 keys, which is
+//! what wallets such as Ledger, Trust Wallet and Phantom use on ed25519 chains.
+//!
+//! See <https://github.com/satoshilabs/slips/blob/master/slip-0010.md>
+
+use crate::bip32::{
+    ChildNumber, DerivationPath, Error, ExtendedKeyAttrs, HmacSha512, KeyFingerprint, Result,
+    KEY_SIZE,
+};
+use core::fmt::{self, Debug};
+use hmac::Mac;
+use p256::elliptic_curve::{sec1::ToEncodedPoint, PrimeField};
+use ripemd::{Digest, Ripemd160};
+use sha2::Sha256;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+/// SLIP-10 extended private Ed25519 key, of which all children are hardened.
+pub type Slip10Ed25519 = Slip10PrivateKey<ed25519_dalek::SigningKey>;
+
+/// SLIP-10 extended private NIST P-256 key.
+pub type Slip10P256 = Slip10PrivateKey<p256::SecretKey>;
+
+/// Trait for key types which can be derived using SLIP-10.
+pub trait Slip10Key: Sized + Clone {
+    /// The HMAC key of the master key derivation.
+    const CURVE_SEED: &'static [u8];
+
+    /// Whether non-hardened children can be derived.
+    const NON_HARDENED: bool;
+
+    /// Initialize the master key from the left half of the HMAC output, or
+    /// return `None` if it is not a valid key.
+    fn from_master_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Derive the child key from the left half of the HMAC output, or
+    /// return `None` if the result is not a valid key.
+    fn derive_child(&self, il: &[u8]) -> Option<Self>;
+
+    /// Serialize this key as bytes.
+    fn to_bytes(&self) -> [u8; KEY_SIZE];
+
+    /// Serialize the public key as the 33 bytes SLIP-10 hashes and commits to.
+    fn public_key_bytes(&self) -> [u8; KEY_SIZE + 1];
+}
+
+impl Slip10Key for ed25519_dalek::SigningKey {
+    const CURVE_SEED: &'static [u8] = b"ed25519 seed";
+    const NON_HARDENED: bool = false;
+
+    fn from_master_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(ed25519_dalek::SigningKey::from_bytes(
+            bytes.try_into().ok()?,
+        ))
+    }
+
+    fn derive_child(&self, il: &[u8]) -> Option<Self> {
+        Self::from_master_bytes(il)
+    }
+
+    fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        ed25519_dalek::SigningKey::to_bytes(self)
+    }
+
+    fn public_key_bytes(&self) -> [u8; KEY_SIZE + 1] {
+        let mut bytes = [0u8; KEY_SIZE + 1];
+        bytes[1..].copy_from_slice(self.verifying_key().as_bytes());
+        bytes
+    }
+}
+
+impl Slip10Key for p256::SecretKey {
+    const CURVE_SEED: &'static [u8] = b"Nist256p1 seed";
+    const NON_HARDENED: bool = true;
+
+    fn from_master_bytes(bytes: &[u8]) -> Option<Self> {
+        p256::SecretKey::from_slice(bytes).ok()
+    }
+
+    fn derive_child(&self, il: &[u8]) -> Option<Self> {
+        let repr = p256::FieldBytes::clone_from_slice(il);
+        // IL has to be less than the order of the curve
+        let tweak: Option<p256::Scalar> = p256::Scalar::from_repr(repr).into();
+        let scalar = *self.to_nonzero_scalar() + tweak?;
+        p256::SecretKey::from_bytes(&scalar.to_repr()).ok()
+    }
+
+    fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        p256::SecretKey::to_bytes(self).into()
+    }
+
+    fn public_key_bytes(&self) -> [u8; KEY_SIZE + 1] {
+        self.public_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .try_into()
+            .expect("compressed point")
+    }
+}
+
+/// Extended private keys derived using SLIP-10.
+#[derive(Clone)]
+pub struct Slip10PrivateKey<K: Slip10Key> {
+    /// Derived private key
+    private_key: K,
+
+    /// Extended key attributes.
+    attrs: ExtendedKeyAttrs,
+}
+
+impl<K> Slip10PrivateKey<K>
+where
+    K: Slip10Key,
+{
+    /// Derive a child key from the given [`DerivationPath`].
+    pub fn new_from_path<S>(seed: S, path: &DerivationPath) -> Result<Self>
+    where
+        S: AsRef<[u8]>,
+    {
+        Self::new(seed)?.derive_from_path(path)
+    }
+
+    /// Create the master extended key for the given seed value.
+    pub fn new<S>(seed: S) -> Result<Self>
+    where
+        S: AsRef<[u8]>,
+    {
+        if !(16..=64).contains(&seed.as_ref().len()) {
+            return Err(Error::SeedLength);
+        }
+
+        let mut data = Zeroizing::new(seed.as_ref().to_vec());
+        loop {
+            let mut hmac = HmacSha512::new_from_slice(K::CURVE_SEED)?;
+            hmac.update(&data);
+            let result = hmac.finalize().into_bytes();
+            let (secret_key, chain_code) = result.split_at(KEY_SIZE);
+
+            // An invalid key is hashed again until a valid one comes out
+            match K::from_master_bytes(secret_key) {
+                Some(private_key) => {
+                    let attrs = ExtendedKeyAttrs {
+                        depth: 0,
+                        parent_fingerprint: KeyFingerprint::default(),
+                        child_number: ChildNumber::default(),
+                        chain_code: chain_code.try_into()?,
+                    };
+                    return Ok(Slip10PrivateKey { private_key, attrs });
+                }
+                None => data = Zeroizing::new(result.to_vec()),
+            }
+        }
+    }
+
+    pub fn derive_from_path(self, path: &DerivationPath) -> Result<Self> {
+        path.iter()
+            .try_fold(self, |key, child_num| key.derive_child(child_num))
+    }
+
+    /// Derive a child key for a particular [`ChildNumber`].
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<Self> {
+        if !child_number.is_hardened() && !K::NON_HARDENED {
+            return Err(Error::ChildNumber);
+        }
+        let depth = self.attrs.depth.checked_add(1).ok_or(Error::Depth)?;
+
+        let mut data = Zeroizing::new(match child_number.is_hardened() {
+            true => [&[0u8][..], &self.private_key.to_bytes()].concat(),
+            false => self.private_key.public_key_bytes().to_vec(),
+        });
+        loop {
+            let mut hmac =
+                HmacSha512::new_from_slice(&self.attrs.chain_code).map_err(|_| Error::Crypto)?;
+            hmac.update(&data);
+            hmac.update(&child_number.to_bytes());
+            let result = hmac.finalize().into_bytes();
+            let (child_key, chain_code) = result.split_at(KEY_SIZE);
+
+            // An invalid child is derived again from the right half
+            match self.private_key.derive_child(child_key) {
+                Some(private_key) => {
+                    let attrs = ExtendedKeyAttrs {
+                        parent_fingerprint: self.fingerprint(),
+                        child_number,
+                        chain_code: chain_code.try_into()?,
+                        depth,
+                    };
+                    return Ok(Slip10PrivateKey { private_key, attrs });
+                }
+                None => data = Zeroizing::new([&[1u8][..], chain_code].concat()),
+            }
+        }
+    }
+
+    /// Borrow the derived private key value.
+    pub fn private_key(&self) -> &K {
+        &self.private_key
+    }
+
+    /// Serialize the public key as 33 bytes, which for ed25519 keys are a
+    /// `0x00` followed by the 32-byte public key.
+    pub fn public_key(&self) -> [u8; KEY_SIZE + 1] {
+        self.private_key.public_key_bytes()
+    }
+
+    /// Compute the 4-byte fingerprint of this key, i.e.
+    /// `RIPEMD160(SHA256(public_key))`.
+    pub fn fingerprint(&self) -> KeyFingerprint {
+        let digest = Ripemd160::digest(Sha256::digest(self.public_key()));
+        digest[..4].try_into().expect("digest truncated")
+    }
+
+    /// Get attributes for this key such as depth, parent fingerprint,
+    /// child number, and chain code.
+    pub fn attrs(&self) -> &ExtendedKeyAttrs {
+        &self.attrs
+    }
+
+    /// Serialize the raw private key as a byte array.
+    pub fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        self.private_key.to_bytes()
+    }
+}
+
+impl<K> ConstantTimeEq for Slip10PrivateKey<K>
+where
+    K: Slip10Key,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let key_a = Zeroizing::new(self.to_bytes());
+        let key_b = Zeroizing::new(other.to_bytes());
+
+        key_a.as_slice().ct_eq(key_b.as_slice())
+            & self.attrs.depth.ct_eq(&other.attrs.depth)
+            & self
+                .attrs
+                .parent_fingerprint
+                .ct_eq(&other.attrs.parent_fingerprint)
+            & self.attrs.child_number.0.ct_eq(&other.attrs.child_number.0)
+            & self.attrs.chain_code.ct_eq(&other.attrs.chain_code)
+    }
+}
+
+impl<K> Debug for Slip10PrivateKey<K>
+where
+    K: Slip10Key,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slip10PrivateKey")
+            .field("private_key", &"...")
+            .field("attrs", &self.attrs)
+            .finish()
+    }
+}
+
+/// NOTE: uses [`ConstantTimeEq`] internally
+impl<K> Eq for Slip10PrivateKey<K> where K: Slip10Key {}
+
+/// NOTE: uses [`ConstantTimeEq`] internally
+impl<K> PartialEq for Slip10PrivateKey<K>
+where
+    K: Slip10Key,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (path, fingerprint, chain code, private key, public key)
+    type Vector = (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+    );
+
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    // Test vector 1 for ed25519 of SLIP-10
+    const ED25519_VECTORS: [Vector; 6] = [
+        (
+            "m",
+            "00000000",
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            "00a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed",
+        ),
+        (
+            "m/0'",
+            "ddebc675",
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            "008c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c",
+        ),
+        (
+            "m/0'/1'",
+            "13dab143",
+            "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+            "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+            "001932a5270f335bed617d5b935c80aedb1a35bd9fc1e31acafd5372c30f5c1187",
+        ),
+        (
+            "m/0'/1'/2'",
+            "ebe4cb29",
+            "2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+            "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+            "00ae98736566d30ed0e9d2f4486a64bc95740d89c7db33f52121f8ea8f76ff0fc1",
+        ),
+        (
+            "m/0'/1'/2'/2'",
+            "316ec1c6",
+            "8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+            "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+            "008abae2d66361c879b900d204ad2cc4984fa2aa344dd7ddc46007329ac76c429c",
+        ),
+        (
+            "m/0'/1'/2'/2'/1000000000'",
+            "d6322ccd",
+            "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            "003c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a",
+        ),
+    ];
+
+    // Test vector 1 for nist256p1 of SLIP-10
+    const P256_VECTORS: [Vector; 5] = [
+        (
+            "m",
+            "00000000",
+            "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+            "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+            "0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8",
+        ),
+        (
+            "m/0'",
+            "be6105b5",
+            "3460cea53e6a6bb5fb391eeef3237ffd8724bf0a40e94943c98b83825342ee11",
+            "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c",
+            "0384610f5ecffe8fda089363a41f56a5c7ffc1d81b59a612d0d649b2d22355590c",
+        ),
+        (
+            "m/0'/1",
+            "9b02312f",
+            "4187afff1aafa8445010097fb99d23aee9f599450c7bd140b6826ac22ba21d0c",
+            "284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129",
+            "03526c63f8d0b4bbbf9c80df553fe66742df4676b241dabefdef67733e070f6844",
+        ),
+        (
+            "m/0'/1/2'",
+            "b98005c1",
+            "98c7514f562e64e74170cc3cf304ee1ce54d6b6da4f880f313e8204c2a185318",
+            "694596e8a54f252c960eb771a3c41e7e32496d03b954aeb90f61635b8e092aa7",
+            "0359cf160040778a4b14c5f4d7b76e327ccc8c4a6086dd9451b7482b5a4972dda0",
+        ),
+        (
+            "m/0'/1/2'/2",
+            "0e9f3274",
+            "ba96f776a5c3907d7fd48bde5620ee374d4acfd540378476019eab70790c63a0",
+            "5996c37fd3dd2679039b23ed6f70b506c6b56b3cb5e424681fb0fa64caf82aaa",
+            "029f871f4cb9e1c97f9f4de9ccd0d4a2f2a171110c61178f84430062230833ff20",
+        ),
+    ];
+
+    fn check<K: Slip10Key>(vectors: &[Vector]) {
+        let seed = hex::decode(SEED).unwrap();
+        for (path, fingerprint, chain_code, private_key, public_key) in vectors {
+            let xprv = Slip10PrivateKey::<K>::new_from_path(&seed, &path.parse().unwrap()).unwrap();
+            assert_eq!(*fingerprint, hex::encode(xprv.attrs().parent_fingerprint));
+            assert_eq!(*chain_code, hex::encode(xprv.attrs().chain_code));
+            assert_eq!(*private_key, hex::encode(xprv.to_bytes()));
+            assert_eq!(*public_key, hex::encode(xprv.public_key()));
+        }
+    }
+
+    #[test]
+    fn test_ed25519_vectors() {
+        check::<ed25519_dalek::SigningKey>(&ED25519_VECTORS);
+
+        // Ed25519 keys have no non-hardened children
+        let xprv = Slip10Ed25519::new(hex::decode(SEED).unwrap()).unwrap();
+        assert_eq!(Err(Error::ChildNumber), xprv.derive_child(ChildNumber(1)));
+    }
+
+    #[test]
+    fn test_p256_vectors() {
+        check::<p256::SecretKey>(&P256_VECTORS);
+    }
+
+    #[test]
+    fn test_p256_retry() {
+        // Test derivation retry for nist256p1 of SLIP-10
+        let seed = hex::decode(SEED).unwrap();
+        let xprv = Slip10P256::new_from_path(seed, &"m/28578'".parse().unwrap()).unwrap();
+        assert_eq!(
+            "06f0db126f023755d0b8d86d4591718a5210dd8d024e3e14b6159d63f53aa669",
+            hex::encode(xprv.to_bytes())
+        );
+        // The first IL of m/28578'/33941 is not less than the order
+        let xprv = xprv.derive_child(ChildNumber(33941)).unwrap();
+        assert_eq!(
+            "092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a",
+            hex::encode(xprv.to_bytes())
+        );
+    }
+
+    #[test]
+    fn test_stellar_wallet() {
+        use crate::bip39::{Language, Mnemonic, Seed};
+
+        // The first account of the 12-word vector of SEP-0005
+        let phrase = "illness spike retreat truth genius clock brain pass fit cave bargain toe";
+        let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "");
+        let xprv = Slip10Ed25519::new_from_path(seed, &"m/44'/148'/0'".parse().unwrap()).unwrap();
+        assert_eq!(
+            "4d691bc19b44a1383b1a0a130aaca3e05c3c1a371dbe45930ef9b761f7a74691",
+            hex::encode(xprv.to_bytes())
+        );
+        assert_eq!(
+            "e3726830a0b60cb5f52c844cffcd4eed65eba5c155e89b26411562724e71e544",
+            hex::encode(&xprv.public_key()[1..])
+        );
+    }
+
+    #[test]
+    fn test_seed_length() {
+        assert_eq!(Err(Error::SeedLength), Slip10Ed25519::new([0u8; 15]));
+        assert_eq!(Err(Error::SeedLength), Slip10P256::new([0u8; 65]));
+    }
+}
@@ -8,6 +8,7 @@ pub mod bip32;
 pub mod bip39;
 pub mod crypto;
 pub mod error;
+pub mod sr25519;
 
 use anychain_core::crypto::sha256;
 use anyhow::{anyhow, Result};
@@ -0,0 +1,215 @@
+//! sr25519 keys, signing and verification as used by Substrate chains, and
+//! the derivation of keys from a BIP-39 mnemonic and a Substrate junction
+//! path like `//hard/soft///password`
+
+use crate::bip39::Mnemonic;
+use anyhow::{anyhow, Result};
+use schnorrkel::derive::{ChainCode, Derivation};
+use schnorrkel::{signing_context, ExpansionMode, MiniSecretKey, Signature};
+
+pub use schnorrkel::{Keypair, PublicKey, SecretKey};
+
+/// The signing context of Substrate
+pub const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// A step of a derivation path, given the chain code of the junction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Junction {
+    /// `//junction`, which cannot be derived from the public key
+    Hard([u8; 32]),
+    /// `/junction`
+    Soft([u8; 32]),
+}
+
+impl Junction {
+    /// Returns the chain code of a junction, which is the SCALE encoding of
+    /// the junction as an integer or else as a string, padded to 32 bytes or
+    /// hashed with blake2b-256 if longer
+    fn chain_code(junction: &str) -> [u8; 32] {
+        let encoded = match junction.parse::<u64>() {
+            Ok(n) => n.to_le_bytes().to_vec(),
+            Err(_) => [compact_len(junction.len()), junction.as_bytes().to_vec()].concat(),
+        };
+        let mut chain_code = [0u8; 32];
+        match encoded.len() > 32 {
+            true => chain_code.copy_from_slice(
+                blake2b_simd::Params::new()
+                    .hash_length(32)
+                    .hash(&encoded)
+                    .as_bytes(),
+            ),
+            false => chain_code[..encoded.len()].copy_from_slice(&encoded),
+        }
+        chain_code
+    }
+}
+
+fn compact_len(len: usize) -> Vec<u8> {
+    match len {
+        0..=0x3f => vec![(len << 2) as u8],
+        0x40..=0x3fff => (((len << 2) | 1) as u16).to_le_bytes().to_vec(),
+        _ => (((len << 2) | 2) as u32).to_le_bytes().to_vec(),
+    }
+}
+
+/// Parses a derivation path like `//hard/soft///password` into its junctions
+/// and the optional password
+pub fn parse_path(path: &str) -> Result<(Vec<Junction>, Option<String>)> {
+    let (path, password) = match path.find("///") {
+        Some(i) => (&path[..i], Some(path[i + 3..].to_string())),
+        None => (path, None),
+    };
+    let mut junctions = vec![];
+    let mut rest = path;
+    while !rest.is_empty() {
+        let (hard, junction) = match rest.strip_prefix("//") {
+            Some(junction) => (true, junction),
+            None => match rest.strip_prefix('/') {
+                Some(junction) => (false, junction),
+                None => return Err(anyhow!("Invalid derivation path {path}")),
+            },
+        };
+        let end = junction.find('/').unwrap_or(junction.len());
+        if end == 0 {
+            return Err(anyhow!("Empty junction in derivation path {path}"));
+        }
+        let chain_code = Junction::chain_code(&junction[..end]);
+        junctions.push(match hard {
+            true => Junction::Hard(chain_code),
+            false => Junction::Soft(chain_code),
+        });
+        rest = &junction[end..];
+    }
+    Ok((junctions, password))
+}
+
+/// Returns the keypair of a mnemonic and a derivation path. As in Substrate,
+/// the mini secret key is derived from the entropy of the mnemonic rather
+/// than from its BIP-39 seed
+pub fn keypair_from_mnemonic(mnemonic: &Mnemonic, path: &str) -> Result<Keypair> {
+    let (junctions, password) = parse_path(path)?;
+    let mini_secret = substrate_bip39::mini_secret_from_entropy(
+        mnemonic.entropy(),
+        &password.unwrap_or_default(),
+    )
+    .map_err(|e| anyhow!("{e:?}"))?;
+    let mut secret = mini_secret.expand(ExpansionMode::Ed25519);
+    for junction in junctions {
+        secret = match junction {
+            Junction::Hard(chain_code) => secret
+                .hard_derive_mini_secret_key(Some(ChainCode(chain_code)), b"")
+                .0
+                .expand(ExpansionMode::Ed25519),
+            Junction::Soft(chain_code) => secret.derived_key_simple(ChainCode(chain_code), []).0,
+        };
+    }
+    Ok(secret.to_keypair())
+}
+
+/// Returns the public key of a soft derivation path, given the public key of
+/// its parent
+pub fn derive_public_key(public_key: &PublicKey, path: &str) -> Result<PublicKey> {
+    let (junctions, _) = parse_path(path)?;
+    junctions
+        .into_iter()
+        .try_fold(*public_key, |public_key, junction| match junction {
+            Junction::Soft(chain_code) => {
+                Ok(public_key.derived_key_simple(ChainCode(chain_code), []).0)
+            }
+            Junction::Hard(_) => Err(anyhow!("Hard junctions need the secret key")),
+        })
+}
+
+/// Returns the keypair of a mini secret key of 32 bytes, or of a secret key
+/// of 64 bytes
+pub fn sr25519_keypair(sk: &[u8]) -> Result<Keypair> {
+    match sk.len() {
+        32 => Ok(MiniSecretKey::from_bytes(sk)
+            .map_err(|e| anyhow!("{e}"))?
+            .expand_to_keypair(ExpansionMode::Ed25519)),
+        64 => Ok(SecretKey::from_bytes(sk)
+            .map_err(|e| anyhow!("{e}"))?
+            .to_keypair()),
+        _ => Err(anyhow!("Invalid private key length".to_string())),
+    }
+}
+
+/// Signs `msg` in the Substrate context with a secret key of 32 or 64 bytes.
+/// The signature is randomized, so it differs on each call
+pub fn sr25519_sign(sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let keypair = sr25519_keypair(sk)?;
+    let context = signing_context(SIGNING_CONTEXT);
+    Ok(keypair.sign(context.bytes(msg)).to_bytes().to_vec())
+}
+
+pub fn sr25519_verify(pk: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+    let (Ok(pk), Ok(sig)) = (PublicKey::from_bytes(pk), Signature::from_bytes(sig)) else {
+        return false;
+    };
+    pk.verify_simple(SIGNING_CONTEXT, msg, &sig).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::Language;
+
+    const DEV_PHRASE: &str =
+        "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+    #[test]
+    fn test_substrate_derivation() {
+        let mnemonic = Mnemonic::from_phrase(DEV_PHRASE, Language::English).unwrap();
+
+        let alice = keypair_from_mnemonic(&mnemonic, "//Alice").unwrap();
+        assert_eq!(
+            "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+            hex::encode(alice.public.to_bytes())
+        );
+        let bob = keypair_from_mnemonic(&mnemonic, "//Bob").unwrap();
+        assert_eq!(
+            "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48",
+            hex::encode(bob.public.to_bytes())
+        );
+
+        // Soft junctions can be derived from the public key alone
+        let soft = keypair_from_mnemonic(&mnemonic, "//Alice/soft/1").unwrap();
+        let public = derive_public_key(&alice.public, "/soft/1").unwrap();
+        assert_eq!(soft.public, public);
+        assert!(derive_public_key(&alice.public, "//hard").is_err());
+
+        // A password changes the mini secret key
+        let password = keypair_from_mnemonic(&mnemonic, "//Alice///secret").unwrap();
+        assert_ne!(alice.public, password.public);
+
+        assert!(parse_path("Alice").is_err());
+        assert!(parse_path("//Alice//").is_err());
+        let (junctions, password) = parse_path("//1/x///pass/word").unwrap();
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(Junction::Hard(one), junctions[0]);
+        let mut x = [0u8; 32];
+        x[..2].copy_from_slice(&[4, b'x']);
+        assert_eq!(Junction::Soft(x), junctions[1]);
+        assert_eq!(Some("pass/word".to_string()), password);
+    }
+
+    #[test]
+    fn test_sr25519_sign() {
+        let mnemonic = Mnemonic::from_phrase(DEV_PHRASE, Language::English).unwrap();
+        let alice = keypair_from_mnemonic(&mnemonic, "//Alice").unwrap();
+        let sk = alice.secret.to_bytes();
+        let pk = alice.public.to_bytes();
+
+        let sig = sr25519_sign(&sk, b"hello world").unwrap();
+        assert_eq!(64, sig.len());
+        assert!(sr25519_verify(&pk, b"hello world", &sig));
+        assert!(!sr25519_verify(&pk, b"hello", &sig));
+        assert_ne!(sig, sr25519_sign(&sk, b"hello world").unwrap());
+
+        let sig = sr25519_sign(&[1u8; 32], b"hello world").unwrap();
+        let pk = sr25519_keypair(&[1u8; 32]).unwrap().public.to_bytes();
+        assert!(sr25519_verify(&pk, b"hello world", &sig));
+        assert!(sr25519_sign(&[1u8; 31], b"hello world").is_err());
+    }
+}
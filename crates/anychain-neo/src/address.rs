@@ -1,8 +1,5 @@
 use crate::{NeoFormat, NeoPublicKey};
-use anychain_core::{
-    crypto::{checksum, hash160},
-    Address, AddressError, PublicKey,
-};
+use anychain_core::{crypto::checksum, Address, AddressError, PublicKey};
 use base58::{FromBase58, ToBase58};
 use std::{fmt::Display, str::FromStr};
 
@@ -25,31 +22,23 @@ impl Address for NeoAddress {
         public_key: &Self::PublicKey,
         _format: &Self::Format,
     ) -> Result<Self, AddressError> {
-        let bytes = public_key.serialize_compressed();
-        let bytes = [
-            vec![
-                0x0c, /* PushData1 */
-                0x21, /* compressed key length */
-            ],
-            bytes, /* compressed public key bytes */
-            vec![
-                0x41, /* Opcode.Syscall */
-                0x56, 0xe7, 0xb3, 0x27, /* System.Crypto.CheckSig */
-            ],
-        ]
-        .concat();
-
-        let hash = hash160(&bytes);
-        let payload = [vec![0x35u8 /* version byte */], hash].concat();
+        Self::from_script_hash(&public_key.script_hash())
+    }
+}
+
+impl NeoAddress {
+    pub fn from_script_hash(hash: &[u8]) -> Result<Self, AddressError> {
+        if hash.len() != 20 {
+            return Err(AddressError::InvalidByteLength(hash.len()));
+        }
+        let payload = [vec![0x35u8 /* version byte */], hash.to_vec()].concat();
 
         let checksum = checksum(&payload)[..4].to_vec();
         let res = [payload, checksum].concat();
 
         Ok(Self(res.to_base58()))
     }
-}
 
-impl NeoAddress {
     pub fn to_script_hash(&self) -> Vec<u8> {
         let bytes = self.0.as_str().from_base58().unwrap();
         // strip the version byte and the checksum
@@ -4,8 +4,14 @@ pub use address::*;
 mod format;
 pub use format::*;
 
+mod multisig;
+pub use multisig::*;
+
 mod public_key;
 pub use public_key::*;
 
+mod script;
+pub use script::*;
+
 mod transaction;
 pub use transaction::*;
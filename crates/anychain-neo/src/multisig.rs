@@ -0,0 +1,210 @@
+use crate::{NeoAddress, NeoPublicKey, NeoScriptBuilder, NeoWitness};
+use anychain_core::{crypto::hash160, AddressError, TransactionError};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+/// The most keys a multi-signature account can have
+pub const MAX_MULTISIG_KEYS: usize = 1024;
+
+/// Represents an m-of-n multi-signature account, whose verification script
+/// checks the signatures against the keys sorted by their points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeoMultisigAccount {
+    threshold: usize,
+    public_keys: Vec<NeoPublicKey>,
+}
+
+fn point(public_key: &NeoPublicKey) -> Vec<u8> {
+    public_key.0.to_encoded_point(false).as_bytes()[1..].to_vec()
+}
+
+fn read_push_int(script: &[u8], pos: &mut usize) -> Option<usize> {
+    let opcode = *script.get(*pos)?;
+    *pos += 1;
+    match opcode {
+        0x10..=0x20 => Some((opcode - 0x10) as usize),
+        0x00 => {
+            let n = *script.get(*pos)?;
+            *pos += 1;
+            Some(n as usize)
+        }
+        0x01 => {
+            let n = u16::from_le_bytes(script.get(*pos..*pos + 2)?.try_into().ok()?);
+            *pos += 2;
+            Some(n as usize)
+        }
+        _ => None,
+    }
+}
+
+impl NeoMultisigAccount {
+    pub fn new(threshold: usize, public_keys: &[NeoPublicKey]) -> Result<Self, AddressError> {
+        if public_keys.is_empty() || public_keys.len() > MAX_MULTISIG_KEYS {
+            return Err(AddressError::Message(format!(
+                "Invalid number of keys {}",
+                public_keys.len()
+            )));
+        }
+        if threshold == 0 || threshold > public_keys.len() {
+            return Err(AddressError::Message(format!(
+                "Invalid threshold {} of {} keys",
+                threshold,
+                public_keys.len()
+            )));
+        }
+        let mut public_keys = public_keys.to_vec();
+        // Neo compares points by x and then by y
+        public_keys.sort_by_key(point);
+        if public_keys.windows(2).any(|keys| keys[0] == keys[1]) {
+            return Err(AddressError::Message("Duplicate public keys".to_string()));
+        }
+        Ok(NeoMultisigAccount {
+            threshold,
+            public_keys,
+        })
+    }
+
+    /// Parses the verification script of a multi-signature account
+    pub fn from_verification_script(script: &[u8]) -> Result<Self, AddressError> {
+        let invalid = || AddressError::Message("Invalid multisig verification script".to_string());
+        let mut pos = 0;
+        let threshold = read_push_int(script, &mut pos).ok_or_else(invalid)?;
+        let mut public_keys = vec![];
+        while script.get(pos..pos + 2) == Some(&[0x0c, 0x21]) {
+            let key = script.get(pos + 2..pos + 35).ok_or_else(invalid)?;
+            let key = p256::PublicKey::from_sec1_bytes(key).map_err(|_| invalid())?;
+            public_keys.push(NeoPublicKey(key));
+            pos += 35;
+        }
+        if read_push_int(script, &mut pos) != Some(public_keys.len()) {
+            return Err(invalid());
+        }
+        let account = Self::new(threshold, &public_keys)?;
+        // Rejects scripts of which the keys are not in the canonical order
+        if account.verification_script() != script {
+            return Err(invalid());
+        }
+        Ok(account)
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn public_keys(&self) -> &[NeoPublicKey] {
+        &self.public_keys
+    }
+
+    pub fn verification_script(&self) -> Vec<u8> {
+        let mut builder = NeoScriptBuilder::new();
+        builder.emit_push_int(self.threshold as i128);
+        for public_key in &self.public_keys {
+            builder.emit_push_data(&public_key.serialize_compressed());
+        }
+        builder
+            .emit_push_int(self.public_keys.len() as i128)
+            .emit_syscall("System.Crypto.CheckMultisig");
+        builder.to_bytes()
+    }
+
+    pub fn script_hash(&self) -> Vec<u8> {
+        hash160(&self.verification_script())
+    }
+
+    pub fn address(&self) -> NeoAddress {
+        // Safe to unwrap here, the hash160 of the script has 20 bytes.
+        NeoAddress::from_script_hash(&self.script_hash()).unwrap()
+    }
+
+    /// Returns the witness of the account from the given signatures and the
+    /// keys which made them, which pushes `threshold` signatures in the order
+    /// of the keys in the verification script
+    pub fn witness(
+        &self,
+        signatures: &[(Vec<u8>, NeoPublicKey)],
+    ) -> Result<NeoWitness, TransactionError> {
+        let mut builder = NeoScriptBuilder::new();
+        let mut count = 0;
+        for public_key in &self.public_keys {
+            if count == self.threshold {
+                break;
+            }
+            if let Some((signature, _)) = signatures.iter().find(|(_, pk)| pk == public_key) {
+                builder.emit_push_data(signature);
+                count += 1;
+            }
+        }
+        if count < self.threshold {
+            return Err(TransactionError::Message(format!(
+                "{} signatures for a threshold of {}",
+                count, self.threshold
+            )));
+        }
+        Ok(NeoWitness {
+            invocation: builder.to_bytes(),
+            verification: self.verification_script(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anychain_core::PublicKey;
+
+    fn keys() -> Vec<NeoPublicKey> {
+        (1u8..=3)
+            .map(|i| NeoPublicKey::from_secret_key(&p256::SecretKey::from_slice(&[i; 32]).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_multisig_account() {
+        let keys = keys();
+        let account = NeoMultisigAccount::new(2, &keys).unwrap();
+        let reversed: Vec<_> = keys.iter().rev().cloned().collect();
+        // The order of the keys does not matter
+        assert_eq!(account, NeoMultisigAccount::new(2, &reversed).unwrap());
+
+        let script = account.verification_script();
+        assert_eq!(0x12, script[0]);
+        assert_eq!(1 + 3 * 35 + 1 + 5, script.len());
+        assert_eq!(&[0x13, 0x41, 0x9e, 0xd0, 0xdc, 0x3a], &script[106..]);
+        assert_eq!(
+            account,
+            NeoMultisigAccount::from_verification_script(&script).unwrap()
+        );
+        let address = account.address();
+        assert!(address.to_string().starts_with('N'));
+        assert_eq!(account.script_hash(), address.to_script_hash());
+
+        // The single key account is not a multisig one
+        assert!(
+            NeoMultisigAccount::from_verification_script(&keys[0].verification_script()).is_err()
+        );
+        assert!(NeoMultisigAccount::new(0, &keys).is_err());
+        assert!(NeoMultisigAccount::new(4, &keys).is_err());
+        assert!(NeoMultisigAccount::new(1, &[keys[0].clone(), keys[0].clone()]).is_err());
+        assert!(NeoMultisigAccount::new(1, &[]).is_err());
+    }
+
+    #[test]
+    fn test_multisig_witness() {
+        let keys = keys();
+        let account = NeoMultisigAccount::new(2, &keys).unwrap();
+        let signatures: Vec<_> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, pk)| (vec![i as u8; 64], pk.clone()))
+            .collect();
+
+        let witness = account.witness(&signatures).unwrap();
+        assert_eq!(2 * 66, witness.invocation.len());
+        // The signatures follow the order of the keys in the script
+        let first = keys
+            .iter()
+            .position(|pk| *pk == account.public_keys()[0])
+            .unwrap();
+        assert_eq!(vec![first as u8; 64], witness.invocation[2..66].to_vec());
+        assert!(account.witness(&signatures[..1]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{NeoAddress, NeoFormat};
-use anychain_core::{hex, Address, AddressError, PublicKey, PublicKeyError};
+use anychain_core::{crypto::hash160, hex, Address, AddressError, PublicKey, PublicKeyError};
 use std::{fmt::Display, str::FromStr};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,6 +25,27 @@ impl NeoPublicKey {
     pub fn serialize_compressed(&self) -> Vec<u8> {
         p256::CompressedPoint::from(self.0).as_slice().to_vec()
     }
+
+    /// Returns the verification script of the standard account of the key
+    pub fn verification_script(&self) -> Vec<u8> {
+        [
+            vec![
+                0x0c, /* PushData1 */
+                0x21, /* compressed key length */
+            ],
+            self.serialize_compressed(), /* compressed public key bytes */
+            vec![
+                0x41, /* Opcode.Syscall */
+                0x56, 0xe7, 0xb3, 0x27, /* System.Crypto.CheckSig */
+            ],
+        ]
+        .concat()
+    }
+
+    /// Returns the script hash of the standard account of the key
+    pub fn script_hash(&self) -> Vec<u8> {
+        hash160(&self.verification_script())
+    }
 }
 
 impl FromStr for NeoPublicKey {
@@ -0,0 +1,238 @@
+use anychain_core::{crypto::sha256, TransactionError};
+
+pub const OPCODE_PUSHINT8: u8 = 0x00;
+pub const OPCODE_PUSHINT16: u8 = 0x01;
+pub const OPCODE_PUSHINT32: u8 = 0x02;
+pub const OPCODE_PUSHINT64: u8 = 0x03;
+pub const OPCODE_PUSHINT128: u8 = 0x04;
+pub const OPCODE_PUSHINT256: u8 = 0x05;
+pub const OPCODE_PUSHT: u8 = 0x08;
+pub const OPCODE_PUSHF: u8 = 0x09;
+pub const OPCODE_PUSHNULL: u8 = 0x0b;
+pub const OPCODE_PUSHDATA1: u8 = 0x0c;
+pub const OPCODE_PUSHDATA2: u8 = 0x0d;
+pub const OPCODE_PUSHDATA4: u8 = 0x0e;
+pub const OPCODE_PUSHM1: u8 = 0x0f;
+pub const OPCODE_PUSH0: u8 = 0x10;
+pub const OPCODE_SYSCALL: u8 = 0x41;
+pub const OPCODE_PACK: u8 = 0xc0;
+pub const OPCODE_NEWARRAY0: u8 = 0xc2;
+
+/// The call flags which allow the callee to do anything
+pub const CALL_FLAGS_ALL: u8 = 0x0f;
+
+/// Returns the 4-byte hash a SYSCALL refers to an interop service by, such
+/// as `System.Contract.Call`
+pub fn interop_hash(name: &str) -> [u8; 4] {
+    sha256(name.as_bytes())[..4].try_into().unwrap()
+}
+
+/// Represents an argument of a contract method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NeoContractParam {
+    Null,
+    Boolean(bool),
+    Integer(i128),
+    ByteArray(Vec<u8>),
+    String(String),
+    /// A script hash, such as that of an account or a contract
+    Hash160(Vec<u8>),
+    Array(Vec<NeoContractParam>),
+}
+
+/// Builds NeoVM scripts
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NeoScriptBuilder {
+    script: Vec<u8>,
+}
+
+impl NeoScriptBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn emit(&mut self, opcode: u8) -> &mut Self {
+        self.script.push(opcode);
+        self
+    }
+
+    /// Emits the push of an integer, as the smallest PUSH or PUSHINT opcode
+    pub fn emit_push_int(&mut self, n: i128) -> &mut Self {
+        if (-1..=16).contains(&n) {
+            return self.emit((OPCODE_PUSH0 as i128 + n) as u8);
+        }
+        let (opcode, len) = match n {
+            _ if i8::try_from(n).is_ok() => (OPCODE_PUSHINT8, 1),
+            _ if i16::try_from(n).is_ok() => (OPCODE_PUSHINT16, 2),
+            _ if i32::try_from(n).is_ok() => (OPCODE_PUSHINT32, 4),
+            _ if i64::try_from(n).is_ok() => (OPCODE_PUSHINT64, 8),
+            _ => (OPCODE_PUSHINT128, 16),
+        };
+        self.script.push(opcode);
+        self.script.extend(&n.to_le_bytes()[..len]);
+        self
+    }
+
+    /// Emits the push of a byte string, as the smallest PUSHDATA opcode
+    pub fn emit_push_data(&mut self, data: &[u8]) -> &mut Self {
+        match data.len() {
+            0..=0xff => self.script.extend([OPCODE_PUSHDATA1, data.len() as u8]),
+            0x100..=0xffff => {
+                self.script.push(OPCODE_PUSHDATA2);
+                self.script.extend((data.len() as u16).to_le_bytes());
+            }
+            _ => {
+                self.script.push(OPCODE_PUSHDATA4);
+                self.script.extend((data.len() as u32).to_le_bytes());
+            }
+        }
+        self.script.extend(data);
+        self
+    }
+
+    pub fn emit_push_bool(&mut self, b: bool) -> &mut Self {
+        self.emit(match b {
+            true => OPCODE_PUSHT,
+            false => OPCODE_PUSHF,
+        })
+    }
+
+    pub fn emit_push_param(
+        &mut self,
+        param: &NeoContractParam,
+    ) -> Result<&mut Self, TransactionError> {
+        match param {
+            NeoContractParam::Null => self.emit(OPCODE_PUSHNULL),
+            NeoContractParam::Boolean(b) => self.emit_push_bool(*b),
+            NeoContractParam::Integer(n) => self.emit_push_int(*n),
+            NeoContractParam::ByteArray(data) => self.emit_push_data(data),
+            NeoContractParam::String(s) => self.emit_push_data(s.as_bytes()),
+            NeoContractParam::Hash160(hash) => {
+                if hash.len() != 20 {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid script hash length {}",
+                        hash.len()
+                    )));
+                }
+                self.emit_push_data(hash)
+            }
+            NeoContractParam::Array(items) => return self.emit_pack(items),
+        };
+        Ok(self)
+    }
+
+    /// Emits the pushes of the items in reverse order followed by PACK, which
+    /// leaves the array of the items on the stack
+    pub fn emit_pack(&mut self, items: &[NeoContractParam]) -> Result<&mut Self, TransactionError> {
+        if items.is_empty() {
+            return Ok(self.emit(OPCODE_NEWARRAY0));
+        }
+        for item in items.iter().rev() {
+            self.emit_push_param(item)?;
+        }
+        self.emit_push_int(items.len() as i128);
+        Ok(self.emit(OPCODE_PACK))
+    }
+
+    pub fn emit_syscall(&mut self, name: &str) -> &mut Self {
+        self.script.push(OPCODE_SYSCALL);
+        self.script.extend(interop_hash(name));
+        self
+    }
+
+    /// Emits the call of a method of the contract of the given script hash
+    /// with all call flags, which is how wallets invoke NEP-17 and NEP-11
+    /// contracts
+    pub fn emit_dynamic_call(
+        &mut self,
+        contract: &[u8],
+        method: &str,
+        args: &[NeoContractParam],
+    ) -> Result<&mut Self, TransactionError> {
+        self.emit_pack(args)?;
+        self.emit_push_int(CALL_FLAGS_ALL as i128);
+        self.emit_push_data(method.as_bytes());
+        self.emit_push_param(&NeoContractParam::Hash160(contract.to_vec()))?;
+        Ok(self.emit_syscall("System.Contract.Call"))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.script.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anychain_core::hex;
+
+    #[test]
+    fn test_push() {
+        let mut builder = NeoScriptBuilder::new();
+        builder
+            .emit_push_int(-1)
+            .emit_push_int(16)
+            .emit_push_int(17)
+            .emit_push_int(-129)
+            .emit_push_int(100000000);
+        assert_eq!("0f200011017fff0200e1f505", hex::encode(builder.to_bytes()));
+
+        let script = NeoScriptBuilder::new()
+            .emit_push_data(&[0xaa; 300])
+            .to_bytes();
+        assert_eq!(&[0x0d, 0x2c, 0x01], &script[..3]);
+        assert_eq!(303, script.len());
+    }
+
+    #[test]
+    fn test_interop_hash() {
+        assert_eq!(
+            "627d5b52",
+            hex::encode(interop_hash("System.Contract.Call"))
+        );
+        assert_eq!(
+            "56e7b327",
+            hex::encode(interop_hash("System.Crypto.CheckSig"))
+        );
+        assert_eq!(
+            "9ed0dc3a",
+            hex::encode(interop_hash("System.Crypto.CheckMultisig"))
+        );
+    }
+
+    #[test]
+    fn test_dynamic_call() {
+        let contract = [0x11u8; 20];
+        let mut builder = NeoScriptBuilder::new();
+        builder.emit_dynamic_call(&contract, "symbol", &[]).unwrap();
+        assert_eq!(
+            format!(
+                "c21f0c0673796d626f6c0c14{}41627d5b52",
+                hex::encode(contract)
+            ),
+            hex::encode(builder.to_bytes())
+        );
+
+        // NEP-11 transfer(to, tokenId, data)
+        let mut builder = NeoScriptBuilder::new();
+        builder
+            .emit_dynamic_call(
+                &contract,
+                "transfer",
+                &[
+                    NeoContractParam::Hash160(vec![0x22; 20]),
+                    NeoContractParam::ByteArray(vec![0x01]),
+                    NeoContractParam::Array(vec![NeoContractParam::Boolean(true)]),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            format!("0811c00c01010c14{}13c0", hex::encode([0x22u8; 20])),
+            hex::encode(&builder.to_bytes()[..30])
+        );
+
+        assert!(NeoScriptBuilder::new()
+            .emit_dynamic_call(&[0; 19], "symbol", &[])
+            .is_err());
+    }
+}
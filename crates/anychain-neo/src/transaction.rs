@@ -1,103 +1,374 @@
-use crate::{NeoAddress, NeoFormat, NeoPublicKey};
+use crate::{
+    NeoAddress, NeoContractParam, NeoFormat, NeoMultisigAccount, NeoPublicKey, NeoScriptBuilder,
+};
 use anychain_core::{crypto::sha256, hex, Transaction, TransactionError, TransactionId};
 use std::fmt::{Display, Error};
 
-#[derive(Clone)]
-pub struct TxIn {
-    prev_hash: Vec<u8>,
-    index: u16,
+/// The network magic of Neo N3 mainnet
+pub const NEO_MAINNET_MAGIC: u32 = 860833102;
+
+/// The network magic of the Neo N3 testnet T5
+pub const NEO_TESTNET_MAGIC: u32 = 894710606;
+
+/// The script hash of the NEO token contract
+pub const NEO_TOKEN_HASH: [u8; 20] = [
+    0xf5, 0x63, 0xea, 0x40, 0xbc, 0x28, 0x3d, 0x4d, 0x0e, 0x05, 0xc4, 0x8e, 0xa3, 0x05, 0xb3, 0xf2,
+    0xa0, 0x73, 0x40, 0xef,
+];
+
+/// The script hash of the GAS token contract
+pub const GAS_TOKEN_HASH: [u8; 20] = [
+    0xcf, 0x76, 0xe2, 0x8b, 0xd0, 0x06, 0x2c, 0x4a, 0x47, 0x8e, 0xe3, 0x55, 0x61, 0x01, 0x13, 0x19,
+    0xf3, 0xcf, 0xa4, 0xd2,
+];
+
+pub const WITNESS_SCOPE_NONE: u8 = 0x00;
+pub const WITNESS_SCOPE_CALLED_BY_ENTRY: u8 = 0x01;
+pub const WITNESS_SCOPE_CUSTOM_CONTRACTS: u8 = 0x10;
+pub const WITNESS_SCOPE_CUSTOM_GROUPS: u8 = 0x20;
+pub const WITNESS_SCOPE_WITNESS_RULES: u8 = 0x40;
+pub const WITNESS_SCOPE_GLOBAL: u8 = 0x80;
+
+const ATTRIBUTE_HIGH_PRIORITY: u8 = 0x01;
+const ATTRIBUTE_NOT_VALID_BEFORE: u8 = 0x20;
+const ATTRIBUTE_CONFLICTS: u8 = 0x21;
+
+/// Represents an account which witnesses the transaction, along with the
+/// scopes its witness is valid in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeoSigner {
+    /// The script hash of the account
+    pub account: Vec<u8>,
+    /// The combination of the `WITNESS_SCOPE_*` flags
+    pub scopes: u8,
+    /// The script hashes of the contracts of the `CustomContracts` scope
+    pub allowed_contracts: Vec<Vec<u8>>,
+    /// The compressed public keys of the groups of the `CustomGroups` scope
+    pub allowed_groups: Vec<Vec<u8>>,
 }
 
-#[derive(Clone)]
-pub struct TxOut {
-    asset_id: Vec<u8>,
-    address: String,
-    value: u64,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NeoAttribute {
+    HighPriority,
+    NotValidBefore(u32),
+    /// The hash of a transaction which conflicts with this one
+    Conflicts(Vec<u8>),
 }
 
-impl TxIn {
-    fn serialize(&self) -> Vec<u8> {
-        if self.prev_hash.len() != 32 {
-            panic!("Invalid prev_hash length");
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NeoWitness {
+    pub invocation: Vec<u8>,
+    pub verification: Vec<u8>,
+}
+
+/// Represents the parameters of a Neo N3 transaction, where the fees are in
+/// units of 10^-8 GAS
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NeoTransactionParameters {
+    pub nonce: u32,
+    pub system_fee: i64,
+    pub network_fee: i64,
+    pub valid_until_block: u32,
+    pub signers: Vec<NeoSigner>,
+    pub attributes: Vec<NeoAttribute>,
+    pub script: Vec<u8>,
+}
+
+fn write_var_int(stream: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => stream.push(n as u8),
+        0xfd..=0xffff => {
+            stream.push(0xfd);
+            stream.extend((n as u16).to_le_bytes());
+        }
+        0x10000..=0xffffffff => {
+            stream.push(0xfe);
+            stream.extend((n as u32).to_le_bytes());
+        }
+        _ => {
+            stream.push(0xff);
+            stream.extend(n.to_le_bytes());
         }
-        let prevhash = self.prev_hash.clone();
-        let index = self.index.to_le_bytes().to_vec();
-        [prevhash, index].concat()
     }
 }
 
-impl TxOut {
-    fn serialize(&self) -> Vec<u8> {
-        if self.asset_id.len() != 32 {
-            panic!("Invalid prev_hash length");
-        }
-        let mut asset_id = self.asset_id.clone();
-        asset_id.reverse();
-        let value = self.value.to_le_bytes().to_vec();
-        let address = NeoAddress(self.address.clone());
-        let address = address.to_script_hash();
+fn write_var_bytes(stream: &mut Vec<u8>, bytes: &[u8]) {
+    write_var_int(stream, bytes.len() as u64);
+    stream.extend(bytes);
+}
 
-        [asset_id, value, address].concat()
+fn check_len(bytes: &[u8], len: usize, name: &str) -> Result<(), TransactionError> {
+    match bytes.len() == len {
+        true => Ok(()),
+        false => Err(TransactionError::Message(format!(
+            "Invalid {} length {}",
+            name,
+            bytes.len()
+        ))),
     }
 }
 
-#[derive(Clone)]
-pub struct NeoTransactionParameters {
-    txins: Vec<TxIn>,
-    txouts: Vec<TxOut>,
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
 }
 
-impl NeoTransactionParameters {
-    fn serialize(&self) -> Vec<u8> {
-        let mut ret = vec![0u8; 0];
-        ret.push(0x80); // contract type byte
-        ret.push(0x00); // version byte
+impl<'a> Reader<'a> {
+    fn read(&mut self, len: usize) -> Result<&'a [u8], TransactionError> {
+        if self.bytes.len() - self.pos < len {
+            return Err(TransactionError::Message(
+                "Unexpected end of transaction".to_string(),
+            ));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
 
-        ret.push(0u8); // attribute length, which is 0
+    fn read_u8(&mut self) -> Result<u8, TransactionError> {
+        Ok(self.read(1)?[0])
+    }
 
-        ret.push(self.txins.len() as u8);
+    fn read_u32(&mut self) -> Result<u32, TransactionError> {
+        Ok(u32::from_le_bytes(self.read(4)?.try_into().unwrap()))
+    }
 
-        for txin in &self.txins {
-            ret.extend(txin.serialize());
+    fn read_i64(&mut self) -> Result<i64, TransactionError> {
+        Ok(i64::from_le_bytes(self.read(8)?.try_into().unwrap()))
+    }
+
+    fn read_var_int(&mut self) -> Result<u64, TransactionError> {
+        match self.read_u8()? {
+            0xfd => Ok(u16::from_le_bytes(self.read(2)?.try_into().unwrap()) as u64),
+            0xfe => Ok(self.read_u32()? as u64),
+            0xff => Ok(u64::from_le_bytes(self.read(8)?.try_into().unwrap())),
+            n => Ok(n as u64),
         }
+    }
+
+    fn read_var_bytes(&mut self) -> Result<Vec<u8>, TransactionError> {
+        let len = self.read_var_int()?;
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(TransactionError::Message(
+                "Unexpected end of transaction".to_string(),
+            ));
+        }
+        Ok(self.read(len as usize)?.to_vec())
+    }
+}
 
-        ret.push(self.txouts.len() as u8);
+/// Returns the script which invokes `transfer` of a NEP-17 token contract,
+/// such as `NEO_TOKEN_HASH` or `GAS_TOKEN_HASH`, with `amount` in the
+/// smallest units of the token and no data
+pub fn nep17_transfer_script(
+    contract: &[u8],
+    from: &NeoAddress,
+    to: &NeoAddress,
+    amount: u64,
+) -> Result<Vec<u8>, TransactionError> {
+    let mut builder = NeoScriptBuilder::new();
+    builder.emit_dynamic_call(
+        contract,
+        "transfer",
+        &[
+            NeoContractParam::Hash160(from.to_script_hash()),
+            NeoContractParam::Hash160(to.to_script_hash()),
+            NeoContractParam::Integer(amount as i128),
+            NeoContractParam::Null,
+        ],
+    )?;
+    Ok(builder.to_bytes())
+}
 
-        for txout in &self.txouts {
-            ret.extend(txout.serialize());
+impl NeoSigner {
+    /// Returns the signer of the account with the `CalledByEntry` scope,
+    /// which is what wallets use for plain transfers
+    pub fn new(address: &NeoAddress) -> Self {
+        NeoSigner {
+            account: address.to_script_hash(),
+            scopes: WITNESS_SCOPE_CALLED_BY_ENTRY,
+            allowed_contracts: vec![],
+            allowed_groups: vec![],
         }
+    }
 
-        ret
+    /// Returns the signer of the account with the `Global` scope, which lets
+    /// the witness be used by any contract
+    pub fn global(address: &NeoAddress) -> Self {
+        NeoSigner {
+            scopes: WITNESS_SCOPE_GLOBAL,
+            ..Self::new(address)
+        }
+    }
+
+    /// Adds the `CustomContracts` scope, which lets the witness be used by
+    /// the contracts of the given script hashes
+    pub fn with_allowed_contracts(mut self, contracts: Vec<Vec<u8>>) -> Self {
+        self.scopes |= WITNESS_SCOPE_CUSTOM_CONTRACTS;
+        self.allowed_contracts = contracts;
+        self
+    }
+
+    fn serialize(&self, stream: &mut Vec<u8>) -> Result<(), TransactionError> {
+        check_len(&self.account, 20, "account")?;
+        if self.scopes & WITNESS_SCOPE_WITNESS_RULES != 0 {
+            return Err(TransactionError::Message(
+                "Witness rules are not supported".to_string(),
+            ));
+        }
+        if self.scopes & WITNESS_SCOPE_GLOBAL != 0 && self.scopes != WITNESS_SCOPE_GLOBAL {
+            return Err(TransactionError::Message(
+                "Global scope cannot be combined with other scopes".to_string(),
+            ));
+        }
+        stream.extend(&self.account);
+        stream.push(self.scopes);
+        if self.scopes & WITNESS_SCOPE_CUSTOM_CONTRACTS != 0 {
+            write_var_int(stream, self.allowed_contracts.len() as u64);
+            for contract in &self.allowed_contracts {
+                check_len(contract, 20, "contract hash")?;
+                stream.extend(contract);
+            }
+        }
+        if self.scopes & WITNESS_SCOPE_CUSTOM_GROUPS != 0 {
+            write_var_int(stream, self.allowed_groups.len() as u64);
+            for group in &self.allowed_groups {
+                check_len(group, 33, "group key")?;
+                stream.extend(group);
+            }
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &mut Reader) -> Result<Self, TransactionError> {
+        let account = reader.read(20)?.to_vec();
+        let scopes = reader.read_u8()?;
+        if scopes & WITNESS_SCOPE_WITNESS_RULES != 0 {
+            return Err(TransactionError::Message(
+                "Witness rules are not supported".to_string(),
+            ));
+        }
+        let mut allowed_contracts = vec![];
+        if scopes & WITNESS_SCOPE_CUSTOM_CONTRACTS != 0 {
+            for _ in 0..reader.read_var_int()? {
+                allowed_contracts.push(reader.read(20)?.to_vec());
+            }
+        }
+        let mut allowed_groups = vec![];
+        if scopes & WITNESS_SCOPE_CUSTOM_GROUPS != 0 {
+            for _ in 0..reader.read_var_int()? {
+                allowed_groups.push(reader.read(33)?.to_vec());
+            }
+        }
+        Ok(NeoSigner {
+            account,
+            scopes,
+            allowed_contracts,
+            allowed_groups,
+        })
     }
 }
 
-#[derive(Clone)]
-pub struct NeoSignature {
-    rs: Vec<u8>,
-    public_key: Vec<u8>,
+impl NeoAttribute {
+    fn serialize(&self, stream: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            Self::HighPriority => stream.push(ATTRIBUTE_HIGH_PRIORITY),
+            Self::NotValidBefore(height) => {
+                stream.push(ATTRIBUTE_NOT_VALID_BEFORE);
+                stream.extend(height.to_le_bytes());
+            }
+            Self::Conflicts(hash) => {
+                check_len(hash, 32, "conflicting transaction hash")?;
+                stream.push(ATTRIBUTE_CONFLICTS);
+                stream.extend(hash);
+            }
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &mut Reader) -> Result<Self, TransactionError> {
+        match reader.read_u8()? {
+            ATTRIBUTE_HIGH_PRIORITY => Ok(Self::HighPriority),
+            ATTRIBUTE_NOT_VALID_BEFORE => Ok(Self::NotValidBefore(reader.read_u32()?)),
+            ATTRIBUTE_CONFLICTS => Ok(Self::Conflicts(reader.read(32)?.to_vec())),
+            typ => Err(TransactionError::Message(format!(
+                "Unsupported attribute type {typ}"
+            ))),
+        }
+    }
 }
 
-impl NeoSignature {
-    fn serialize(&self) -> Vec<u8> {
-        let mut stream = vec![];
-        let rs = self.rs.clone();
-        let pk = self.public_key.clone();
+impl NeoWitness {
+    /// Returns the witness of a standard account, which pushes the signature
+    /// for the `CheckSig` verification script of the public key
+    pub fn new(signature: &[u8], public_key: &NeoPublicKey) -> Self {
+        NeoWitness {
+            invocation: NeoScriptBuilder::new().emit_push_data(signature).to_bytes(),
+            verification: public_key.verification_script(),
+        }
+    }
+}
 
-        let rs_script = [vec![rs.len() as u8], rs].concat();
-        let pk_script = [vec![pk.len() as u8], pk, vec![172 /* Opcode::CheckSig */]].concat();
+impl NeoTransactionParameters {
+    /// Returns the serialized transaction without witnesses, which the
+    /// transaction hash commits to
+    pub fn serialize(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut stream = vec![0u8]; // version
+        stream.extend(self.nonce.to_le_bytes());
+        stream.extend(self.system_fee.to_le_bytes());
+        stream.extend(self.network_fee.to_le_bytes());
+        stream.extend(self.valid_until_block.to_le_bytes());
+        write_var_int(&mut stream, self.signers.len() as u64);
+        for signer in &self.signers {
+            signer.serialize(&mut stream)?;
+        }
+        write_var_int(&mut stream, self.attributes.len() as u64);
+        for attribute in &self.attributes {
+            attribute.serialize(&mut stream)?;
+        }
+        write_var_bytes(&mut stream, &self.script);
+        Ok(stream)
+    }
 
-        stream.push(rs_script.len() as u8);
-        stream.extend(rs_script);
-        stream.push(pk_script.len() as u8);
-        stream.extend(pk_script);
-        stream
+    fn deserialize(reader: &mut Reader) -> Result<Self, TransactionError> {
+        let version = reader.read_u8()?;
+        if version != 0 {
+            return Err(TransactionError::Message(format!(
+                "Unsupported transaction version {version}"
+            )));
+        }
+        let nonce = reader.read_u32()?;
+        let system_fee = reader.read_i64()?;
+        let network_fee = reader.read_i64()?;
+        let valid_until_block = reader.read_u32()?;
+        let mut signers = vec![];
+        for _ in 0..reader.read_var_int()? {
+            signers.push(NeoSigner::deserialize(reader)?);
+        }
+        let mut attributes = vec![];
+        for _ in 0..reader.read_var_int()? {
+            attributes.push(NeoAttribute::deserialize(reader)?);
+        }
+        let script = reader.read_var_bytes()?;
+        Ok(NeoTransactionParameters {
+            nonce,
+            system_fee,
+            network_fee,
+            valid_until_block,
+            signers,
+            attributes,
+            script,
+        })
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NeoTransaction {
-    params: NeoTransactionParameters,
-    signatures: Option<Vec<NeoSignature>>,
+    pub params: NeoTransactionParameters,
+    pub witnesses: Vec<NeoWitness>,
+    /// The multi-signature accounts among the signers, of which `sign` needs
+    /// to know the keys
+    pub multisig_accounts: Vec<NeoMultisigAccount>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -106,13 +377,36 @@ pub struct NeoTransactionId {
 }
 
 impl Display for NeoTransactionId {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The hash is displayed in reversed byte order, like block explorers do
+        let mut txid = self.txid.clone();
+        txid.reverse();
+        write!(f, "0x{}", hex::encode(txid))
     }
 }
 
 impl TransactionId for NeoTransactionId {}
 
+impl NeoTransaction {
+    /// Returns the hash of the transaction, i.e. the txid in internal byte
+    /// order
+    pub fn hash(&self) -> Result<Vec<u8>, TransactionError> {
+        Ok(sha256(&self.params.serialize()?).to_vec())
+    }
+
+    /// Returns the data signers sign on the network of the given magic,
+    /// which are hashed by sha256 as part of the ECDSA signature
+    pub fn sign_data(&self, network_magic: u32) -> Result<Vec<u8>, TransactionError> {
+        Ok([network_magic.to_le_bytes().to_vec(), self.hash()?].concat())
+    }
+
+    /// Returns the sha256 digest of the sign data, for signers that take a
+    /// prehashed message
+    pub fn digest(&self, network_magic: u32) -> Result<Vec<u8>, TransactionError> {
+        Ok(sha256(&self.sign_data(network_magic)?).to_vec())
+    }
+}
+
 impl Transaction for NeoTransaction {
     type TransactionId = NeoTransactionId;
     type Format = NeoFormat;
@@ -123,10 +417,14 @@ impl Transaction for NeoTransaction {
     fn new(params: &Self::TransactionParameters) -> Result<Self, TransactionError> {
         Ok(Self {
             params: params.clone(),
-            signatures: None,
+            witnesses: vec![],
+            multisig_accounts: vec![],
         })
     }
 
+    /// Takes the concatenation of the 64-byte signature and the 33-byte
+    /// compressed public key of every signing key, in any order, where the
+    /// keys of a multi-signature signer have to be in `multisig_accounts`
     fn sign(&mut self, rs_pk_s: Vec<u8>, _recid: u8) -> Result<Vec<u8>, TransactionError> {
         if !rs_pk_s.len().is_multiple_of(97) {
             return Err(TransactionError::Message(format!(
@@ -135,52 +433,102 @@ impl Transaction for NeoTransaction {
             )));
         }
 
-        let sigs_cnt = rs_pk_s.len() / 97;
-        let txins_cnt = self.params.txins.len();
-
-        if sigs_cnt != txins_cnt {
-            return Err(TransactionError::Message(format!(
-                "Amount of signatures {} differs with that of tx inputs {}",
-                sigs_cnt, txins_cnt,
-            )));
+        let mut signatures = vec![];
+        for chunk in rs_pk_s.chunks(97) {
+            let public_key = p256::PublicKey::from_sec1_bytes(&chunk[64..])
+                .map_err(|e| TransactionError::Crate("p256", format!("{e:?}")))?;
+            let public_key = NeoPublicKey(public_key);
+            let account = public_key.script_hash();
+            let is_signer = self.params.signers.iter().any(|signer| {
+                signer.account == account
+                    || self.multisig_accounts.iter().any(|multisig| {
+                        multisig.script_hash() == signer.account
+                            && multisig.public_keys().contains(&public_key)
+                    })
+            });
+            if !is_signer {
+                return Err(TransactionError::Message(format!(
+                    "Public key {} is not a signer",
+                    hex::encode(&chunk[64..])
+                )));
+            }
+            signatures.push((chunk[..64].to_vec(), public_key));
         }
 
-        let mut sigs = vec![];
-        for i in 0..sigs_cnt {
-            let start = 97 * i;
-            let divide = start + 64;
-            let end = divide + 33;
-            let sig = NeoSignature {
-                rs: rs_pk_s[start..divide].to_vec(),
-                public_key: rs_pk_s[divide..end].to_vec(),
+        let mut witnesses = vec![];
+        for (i, signer) in self.params.signers.iter().enumerate() {
+            let standard = signatures
+                .iter()
+                .find(|(_, public_key)| public_key.script_hash() == signer.account);
+            let multisig = self
+                .multisig_accounts
+                .iter()
+                .find(|multisig| multisig.script_hash() == signer.account);
+            let witness = match (standard, multisig) {
+                (Some((signature, public_key)), _) => NeoWitness::new(signature, public_key),
+                (None, Some(multisig)) => multisig.witness(&signatures)?,
+                (None, None) => {
+                    return Err(TransactionError::Message(format!(
+                        "Missing signature of signer {i}"
+                    )))
+                }
             };
-            sigs.push(sig);
+            witnesses.push(witness);
         }
 
-        self.signatures = Some(sigs);
+        self.witnesses = witnesses;
         self.to_bytes()
     }
 
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        let mut stream = self.params.serialize();
-        if let Some(sigs) = &self.signatures {
-            stream.push(sigs.len() as u8);
-            for sig in sigs {
-                stream.extend(sig.serialize());
-            }
+        let mut stream = self.params.serialize()?;
+        write_var_int(&mut stream, self.witnesses.len() as u64);
+        for witness in &self.witnesses {
+            write_var_bytes(&mut stream, &witness.invocation);
+            write_var_bytes(&mut stream, &witness.verification);
         }
         Ok(stream)
     }
 
-    fn from_bytes(_tx: &[u8]) -> Result<Self, TransactionError> {
-        todo!()
+    fn from_bytes(tx: &[u8]) -> Result<Self, TransactionError> {
+        let mut reader = Reader { bytes: tx, pos: 0 };
+        let params = NeoTransactionParameters::deserialize(&mut reader)?;
+        let mut witnesses = vec![];
+        for _ in 0..reader.read_var_int()? {
+            let invocation = reader.read_var_bytes()?;
+            let verification = reader.read_var_bytes()?;
+            witnesses.push(NeoWitness {
+                invocation,
+                verification,
+            });
+        }
+        if !witnesses.is_empty() && witnesses.len() != params.signers.len() {
+            return Err(TransactionError::Message(format!(
+                "Amount of witnesses {} differs with that of signers {}",
+                witnesses.len(),
+                params.signers.len()
+            )));
+        }
+        if reader.pos != tx.len() {
+            return Err(TransactionError::Message(
+                "Trailing bytes after transaction".to_string(),
+            ));
+        }
+        let multisig_accounts = witnesses
+            .iter()
+            .filter_map(|witness| {
+                NeoMultisigAccount::from_verification_script(&witness.verification).ok()
+            })
+            .collect();
+        Ok(NeoTransaction {
+            params,
+            witnesses,
+            multisig_accounts,
+        })
     }
 
     fn to_transaction_id(&self) -> Result<Self::TransactionId, TransactionError> {
-        let stream = self.to_bytes()?;
-        Ok(NeoTransactionId {
-            txid: sha256(&stream).to_vec(),
-        })
+        Ok(NeoTransactionId { txid: self.hash()? })
     }
 }
 
@@ -199,16 +547,28 @@ impl Display for NeoTransaction {
 
 #[cfg(test)]
 mod tests {
-    use crate::{TxIn, TxOut};
-
-    use super::{NeoFormat, NeoPublicKey, NeoTransaction, NeoTransactionParameters};
+    use super::*;
+    use anychain_core::PublicKey;
+    use p256::ecdsa::{
+        signature::{hazmat::PrehashVerifier, Signer},
+        Signature, SigningKey, VerifyingKey,
+    };
 
-    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
-
-    use anychain_core::{hex, PublicKey, Transaction};
-    use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+    #[test]
+    fn test_nep17_transfer_script() {
+        let from = NeoAddress("NVEqR4e73afGKpVBzBXLEnY5F5uZSmSKZZ".to_string());
+        let to = NeoAddress("NKvAasuNZqDc8dQDXd6Wm2XccTh6Dt3nr4".to_string());
+        let script = nep17_transfer_script(&GAS_TOKEN_HASH, &from, &to, 1).unwrap();
+        let expected = format!(
+            "0b110c14{}0c14{}14c01f0c087472616e736665720c14{}41627d5b52",
+            hex::encode(to.to_script_hash()),
+            hex::encode(from.to_script_hash()),
+            "cf76e28bd0062c4a478ee35561011319f3cfa4d2",
+        );
+        assert_eq!(expected, hex::encode(script));
+        assert!(nep17_transfer_script(&[0; 19], &from, &to, 1).is_err());
+    }
 
-    #[allow(deprecated)]
     #[test]
     fn test_tx_gen() {
         let sk = [
@@ -218,54 +578,159 @@ mod tests {
         let sk = p256::SecretKey::from_slice(&sk).unwrap();
         let pk = NeoPublicKey::from_secret_key(&sk);
         let format = &NeoFormat::Standard;
-        let _from = pk.to_address(format).unwrap();
-
-        let sk_to = [
-            2u8, 7, 0, 5, 0, 0, 1, 1, 111, 23, 34, 39, 109, 20, 1, 2, 7, 0, 5, 0, 0, 1, 1, 111, 23,
-            34, 39, 109, 203, 1, 5, 55,
-        ];
-        let sk_to = p256::SecretKey::from_slice(&sk_to).unwrap();
-        let pk_to = NeoPublicKey::from_secret_key(&sk_to);
-        let to = pk_to.to_address(format).unwrap();
+        let from = pk.to_address(format).unwrap();
+        let to = NeoAddress("NKvAasuNZqDc8dQDXd6Wm2XccTh6Dt3nr4".to_string());
 
-        let prev_hash = "b3ad3320f8230a8358a4c056ead57182d787ec8607870f70d70a844dc4d049a3";
-        let index = 0;
-
-        let asset_id = "c56f33fc6ecfcd0c225c4ab356fee59390af8560be0e930faebe74a6daff7c9b";
+        let params = NeoTransactionParameters {
+            nonce: 0x12345678,
+            system_fee: 997775,
+            network_fee: 1229540,
+            valid_until_block: 5000000,
+            signers: vec![NeoSigner::new(&from)],
+            attributes: vec![],
+            script: nep17_transfer_script(&NEO_TOKEN_HASH, &from, &to, 10).unwrap(),
+        };
 
-        let mut prev_hash = hex::decode(prev_hash).unwrap();
-        prev_hash.reverse();
+        let mut tx = NeoTransaction::new(&params).unwrap();
+        let unsigned = tx.to_bytes().unwrap();
+        assert_eq!(
+            "00785634128f390f0000000000e4c2120000000000404b4c0001",
+            hex::encode(&unsigned[..26])
+        );
+        assert_eq!(tx, NeoTransaction::from_bytes(&unsigned).unwrap());
+
+        let txid = tx.to_transaction_id().unwrap();
+        let sign_data = tx.sign_data(NEO_MAINNET_MAGIC).unwrap();
+        assert_eq!("4e454f33", hex::encode(&sign_data[..4]));
+
+        let signing_key = SigningKey::from(sk.clone());
+        let sig: Signature = signing_key.sign(&sign_data);
+        VerifyingKey::from(&signing_key)
+            .verify_prehash(&tx.digest(NEO_MAINNET_MAGIC).unwrap(), &sig)
+            .unwrap();
+
+        let mut sig = sig.to_bytes().to_vec();
+        sig.extend(pk.serialize_compressed());
+        let signed = tx.sign(sig.clone(), 0).unwrap();
+        assert_eq!(1, tx.witnesses.len());
+        assert_eq!(pk.verification_script(), tx.witnesses[0].verification);
+        assert_eq!(&[0x0c, 0x40], &tx.witnesses[0].invocation[..2]);
+
+        // The witnesses do not change the txid
+        assert_eq!(txid, tx.to_transaction_id().unwrap());
+        let mut hash = tx.hash().unwrap();
+        hash.reverse();
+        assert_eq!(format!("0x{}", hex::encode(hash)), txid.to_string());
+
+        let decoded = NeoTransaction::from_bytes(&signed).unwrap();
+        assert_eq!(tx, decoded);
+        assert!(NeoTransaction::from_bytes(&signed[..signed.len() - 1]).is_err());
+        assert!(NeoTransaction::from_bytes(&[signed.clone(), vec![0]].concat()).is_err());
+
+        // A key which is not a signer
+        let other =
+            NeoPublicKey::from_secret_key(&p256::SecretKey::from_slice(&[7u8; 32]).unwrap());
+        let mut sig = sig[..64].to_vec();
+        sig.extend(other.serialize_compressed());
+        assert!(tx.sign(sig, 0).is_err());
+    }
 
-        let asset_id = hex::decode(asset_id).unwrap();
+    #[test]
+    fn test_signer_scopes() {
+        let from = NeoAddress("NVEqR4e73afGKpVBzBXLEnY5F5uZSmSKZZ".to_string());
+        let mut signer = NeoSigner::new(&from);
+        signer.scopes = WITNESS_SCOPE_CALLED_BY_ENTRY | WITNESS_SCOPE_CUSTOM_CONTRACTS;
+        signer.allowed_contracts = vec![GAS_TOKEN_HASH.to_vec()];
 
-        let input = TxIn { prev_hash, index };
-        let output = TxOut {
-            asset_id,
-            address: to.0,
-            value: 1000000000,
+        let params = NeoTransactionParameters {
+            signers: vec![signer],
+            attributes: vec![
+                NeoAttribute::HighPriority,
+                NeoAttribute::NotValidBefore(100),
+                NeoAttribute::Conflicts(vec![1; 32]),
+            ],
+            script: vec![0x40],
+            ..Default::default()
         };
+        let tx = NeoTransaction::new(&params).unwrap();
+        assert_eq!(
+            tx,
+            NeoTransaction::from_bytes(&tx.to_bytes().unwrap()).unwrap()
+        );
+
+        let mut params = params;
+        params.signers[0].scopes = WITNESS_SCOPE_GLOBAL | WITNESS_SCOPE_CALLED_BY_ENTRY;
+        assert!(params.serialize().is_err());
+        params.signers[0].scopes = WITNESS_SCOPE_WITNESS_RULES;
+        assert!(params.serialize().is_err());
+    }
+
+    #[test]
+    fn test_multisig_tx() {
+        let sks: Vec<_> = (1u8..=4)
+            .map(|i| p256::SecretKey::from_slice(&[i; 32]).unwrap())
+            .collect();
+        let pks: Vec<_> = sks.iter().map(NeoPublicKey::from_secret_key).collect();
+        let treasury = NeoMultisigAccount::new(2, &pks[..3]).unwrap();
+        let payer = pks[3].to_address(&NeoFormat::Standard).unwrap();
+        let to = NeoAddress("NKvAasuNZqDc8dQDXd6Wm2XccTh6Dt3nr4".to_string());
 
         let params = NeoTransactionParameters {
-            txins: vec![input],
-            txouts: vec![output],
+            nonce: 1,
+            valid_until_block: 100,
+            signers: vec![
+                NeoSigner::new(&payer),
+                NeoSigner::new(&treasury.address())
+                    .with_allowed_contracts(vec![GAS_TOKEN_HASH.to_vec()]),
+            ],
+            script: nep17_transfer_script(&GAS_TOKEN_HASH, &treasury.address(), &to, 100).unwrap(),
+            ..Default::default()
         };
-
         let mut tx = NeoTransaction::new(&params).unwrap();
-        let hash = tx.to_transaction_id().unwrap().txid;
-
-        let signing_key = SigningKey::from(sk);
-
-        // the signature differs with bipay signature, possible cause being that
-        // bipay use a random r as opposed to a fixed r used by this signer
-        let sig: Signature = signing_key.sign(&hash);
-
-        let mut sig = sig.to_bytes().as_slice().to_vec();
-        sig.extend(pk.serialize_compressed());
+        tx.multisig_accounts.push(treasury.clone());
+        let sign_data = tx.sign_data(NEO_TESTNET_MAGIC).unwrap();
+
+        let mut rs_pk_s = vec![];
+        for i in [3, 2, 0] {
+            let sig: Signature = SigningKey::from(sks[i].clone()).sign(&sign_data);
+            rs_pk_s.extend(sig.to_bytes().to_vec());
+            rs_pk_s.extend(pks[i].serialize_compressed());
+        }
+        // One signature is short of the threshold
+        assert!(tx.sign(rs_pk_s[..2 * 97].to_vec(), 0).is_err());
+        let signed = tx.sign(rs_pk_s, 0).unwrap();
+
+        assert_eq!(pks[3].verification_script(), tx.witnesses[0].verification);
+        assert_eq!(treasury.verification_script(), tx.witnesses[1].verification);
+        assert_eq!(2 * 66, tx.witnesses[1].invocation.len());
+        // The signatures follow the order of the signing keys in the script
+        let keys = treasury
+            .public_keys()
+            .iter()
+            .filter(|pk| **pk == pks[0] || **pk == pks[2]);
+        for (signature, key) in tx.witnesses[1].invocation.chunks(66).zip(keys) {
+            let signature = Signature::from_slice(&signature[2..]).unwrap();
+            VerifyingKey::from(&key.0)
+                .verify_prehash(&tx.digest(NEO_TESTNET_MAGIC).unwrap(), &signature)
+                .unwrap();
+        }
 
-        let tx = tx.sign(sig, 0).unwrap();
-        let tx_hex = hex::encode(&tx);
-        let tx_64 = STANDARD_NO_PAD.encode(&tx);
+        let decoded = NeoTransaction::from_bytes(&signed).unwrap();
+        assert_eq!(tx, decoded);
+        assert_eq!(vec![treasury], decoded.multisig_accounts);
+    }
 
-        println!("tx hex = {}\ntx 64 = {}", tx_hex, tx_64);
+    #[test]
+    fn test_signer_constructors() {
+        let address = NeoAddress("NVEqR4e73afGKpVBzBXLEnY5F5uZSmSKZZ".to_string());
+        assert_eq!(WITNESS_SCOPE_GLOBAL, NeoSigner::global(&address).scopes);
+        let signer = NeoSigner::new(&address).with_allowed_contracts(vec![NEO_TOKEN_HASH.to_vec()]);
+        assert_eq!(
+            WITNESS_SCOPE_CALLED_BY_ENTRY | WITNESS_SCOPE_CUSTOM_CONTRACTS,
+            signer.scopes
+        );
+        let mut stream = vec![];
+        signer.serialize(&mut stream).unwrap();
+        assert_eq!(20 + 1 + 1 + 20, stream.len());
     }
 }
@@ -0,0 +1,286 @@
+use crate::transaction::encode;
+use crate::{
+    Era, PolkadotAddress, PolkadotCall, PolkadotMetadata, PolkadotNetwork, PolkadotTransaction,
+    PolkadotTransactionParameters, SignedExtension,
+};
+use anychain_core::{Transaction, TransactionError};
+
+/// Builds the transactions of a runtime given its metadata, from which the
+/// indices of pallets and calls and the signed extensions are resolved
+pub struct PolkadotTransactionBuilder<'a, N: PolkadotNetwork> {
+    metadata: &'a PolkadotMetadata,
+    params: PolkadotTransactionParameters<N>,
+}
+
+impl<'a, N: PolkadotNetwork> PolkadotTransactionBuilder<'a, N> {
+    /// Returns a builder of an immortal transaction of `from` without a tip
+    pub fn new(
+        metadata: &'a PolkadotMetadata,
+        from: PolkadotAddress<N>,
+        nonce: u64,
+        genesis_hash: &str,
+        spec_version: u32,
+        tx_version: u32,
+    ) -> Result<Self, TransactionError> {
+        let params = PolkadotTransactionParameters {
+            to: from.clone(),
+            from,
+            amount: 0,
+            nonce,
+            tip: 0,
+            block_hash: genesis_hash.to_string(),
+            genesis_hash: genesis_hash.to_string(),
+            spec_version,
+            tx_version,
+            era: Era::Immortal,
+            call: None,
+            signed_extensions: metadata.signed_extensions()?,
+            metadata_hash: None,
+            fee_asset: None,
+        };
+        Ok(Self { metadata, params })
+    }
+
+    /// Sets the call `pallet::call`, given its SCALE-encoded arguments
+    pub fn call(mut self, pallet: &str, call: &str, args: &[u8]) -> Result<Self, TransactionError> {
+        let (pallet_index, call_index) = self.metadata.call_index(pallet, call)?;
+        self.params.call = Some([vec![pallet_index, call_index], args.to_vec()].concat());
+        Ok(self)
+    }
+
+    /// Sets a call whose pallet and call indices, and those of the calls
+    /// nested in it, are resolved from the metadata
+    pub fn with_call(mut self, call: &PolkadotCall<N>) -> Result<Self, TransactionError> {
+        let metadata = self.metadata;
+        let call = call.encode_with(&|call| {
+            let (pallet, call) = call.name();
+            metadata.call_index(pallet, call)
+        })?;
+        self.params.call = Some(call);
+        Ok(self)
+    }
+
+    fn transfer(
+        self,
+        call: &str,
+        to: &PolkadotAddress<N>,
+        amount: u64,
+    ) -> Result<Self, TransactionError> {
+        // MultiAddress::Id
+        let args = [vec![0], to.to_payload()?, encode(amount)].concat();
+        let mut builder = self.call("Balances", call, &args)?;
+        builder.params.to = to.clone();
+        builder.params.amount = amount;
+        Ok(builder)
+    }
+
+    /// Sets a transfer of `amount` to `to` that may reap the sender account
+    pub fn transfer_allow_death(
+        self,
+        to: &PolkadotAddress<N>,
+        amount: u64,
+    ) -> Result<Self, TransactionError> {
+        self.transfer("transfer_allow_death", to, amount)
+    }
+
+    /// Sets a transfer of `amount` to `to` that fails if it would reap the
+    /// sender account
+    pub fn transfer_keep_alive(
+        self,
+        to: &PolkadotAddress<N>,
+        amount: u64,
+    ) -> Result<Self, TransactionError> {
+        self.transfer("transfer_keep_alive", to, amount)
+    }
+
+    pub fn tip(mut self, tip: u64) -> Self {
+        self.params.tip = tip;
+        self
+    }
+
+    /// Pays the fee in an asset of the Assets pallet, such as USDT (1984)
+    /// on Asset Hub
+    pub fn fee_asset(mut self, asset_id: u32) -> Result<Self, TransactionError> {
+        if !self
+            .params
+            .signed_extensions
+            .contains(&SignedExtension::ChargeAssetTxPayment)
+        {
+            return Err(TransactionError::Message(
+                "The runtime has no ChargeAssetTxPayment extension".to_string(),
+            ));
+        }
+        self.params.fee_asset = Some(asset_id);
+        Ok(self)
+    }
+
+    /// Makes the transaction valid for `period` blocks, rounded up to a power
+    /// of two, from `current_block`. `block_hash` is the hash of
+    /// `Era::birth(current_block)`, which is `current_block` itself for
+    /// periods of up to 4096 blocks
+    pub fn mortal(mut self, current_block: u64, block_hash: &str, period: u64) -> Self {
+        self.params.era = Era::mortal(current_block, period);
+        self.params.block_hash = block_hash.to_string();
+        self
+    }
+
+    /// Enables the CheckMetadataHash extension, which commits the transaction
+    /// to the RFC-78 hash of the metadata so that a signer can verify the
+    /// metadata it decodes the transaction with
+    pub fn with_metadata_hash(mut self) -> Result<Self, TransactionError> {
+        if !self
+            .params
+            .signed_extensions
+            .contains(&SignedExtension::CheckMetadataHash)
+        {
+            return Err(TransactionError::Message(
+                "The runtime has no CheckMetadataHash extension".to_string(),
+            ));
+        }
+        let hash = self.metadata.metadata_hash(
+            N::NAME,
+            self.params.spec_version,
+            N::VERSION as u16,
+            N::DECIMALS,
+            N::TOKEN_SYMBOL,
+        )?;
+        self.params.metadata_hash = Some(hash);
+        Ok(self)
+    }
+
+    pub fn build(self) -> Result<PolkadotTransaction<N>, TransactionError> {
+        if self.params.call.is_none() {
+            return Err(TransactionError::Message(
+                "No call is set for the transaction".to_string(),
+            ));
+        }
+        PolkadotTransaction::new(&self.params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::tests::test_metadata;
+    use crate::utilities::crypto::blake2b_256;
+    use crate::Westend;
+    use anychain_core::hex;
+    use std::str::FromStr;
+
+    const FROM: &str = "5FnS6tYbCTAtK3QCfNnddwVR61ypLLM7APRrs98paFs7yMSY";
+    const TO: &str = "5DoW9HHuqSfpf55Ux5pLdJbHFWvbngeg8Ynhub9DrdtxmZeV";
+    const GENESIS_HASH: &str = "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+    const BLOCK_HASH: &str = "4ce05abd387b560855a3d486eba6237b9a08c6e9dfe351302a5ceda90be801fe";
+
+    fn builder(metadata: &PolkadotMetadata) -> PolkadotTransactionBuilder<'_, Westend> {
+        let from = PolkadotAddress::<Westend>::from_str(FROM).unwrap();
+        PolkadotTransactionBuilder::new(metadata, from, 3, GENESIS_HASH, 1005000, 24).unwrap()
+    }
+
+    #[test]
+    fn test_builder() {
+        let metadata = PolkadotMetadata::from_bytes(&test_metadata()).unwrap();
+        let to = PolkadotAddress::<Westend>::from_str(TO).unwrap();
+
+        let mut tx = builder(&metadata)
+            .transfer_keep_alive(&to, 1_000_000_000_000)
+            .unwrap()
+            .tip(10)
+            .mortal(42, BLOCK_HASH, 64)
+            .with_metadata_hash()
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let hash = metadata
+            .metadata_hash("westend", 1005000, 42, 12, "WND")
+            .unwrap();
+        let call = [
+            vec![5, 3, 0],
+            to.to_payload().unwrap(),
+            hex::decode("070010a5d4e8").unwrap(),
+        ]
+        .concat();
+        let extra = vec![0xa5, 0x02, 0x0c, 0x28, 0x01];
+        let additional = [
+            1005000u32.to_le_bytes().to_vec(),
+            24u32.to_le_bytes().to_vec(),
+            hex::decode(GENESIS_HASH).unwrap(),
+            hex::decode(BLOCK_HASH).unwrap(),
+            vec![1],
+            hash.to_vec(),
+        ]
+        .concat();
+        let payload = [call.clone(), extra.clone(), additional].concat();
+        assert_eq!(payload, tx.to_bytes().unwrap());
+
+        let signed = tx.sign(vec![7u8; 64], 0).unwrap();
+        let expected = [
+            vec![0x84, 0],
+            tx.params.from.to_payload().unwrap(),
+            vec![2],
+            vec![7u8; 64],
+            vec![0],
+            extra,
+            call,
+        ]
+        .concat();
+        assert_eq!(expected, signed[signed.len() - expected.len()..]);
+
+        // A call without the CheckMetadataHash extension enabled
+        let tx = builder(&metadata)
+            .transfer_allow_death(&to, 1_000_000_000_000)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(5, tx.to_bytes().unwrap()[0]);
+        assert_eq!(0, tx.to_bytes().unwrap()[1]);
+
+        // The indices of a PolkadotCall are resolved from the metadata
+        let call = PolkadotCall::TransferKeepAlive {
+            to: to.clone(),
+            amount: 1,
+        };
+        let tx = builder(&metadata)
+            .with_call(&call)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!([5, 3], tx.to_bytes().unwrap()[..2]);
+        assert_eq!([4, 3], call.encode().unwrap()[..2]);
+        assert!(builder(&metadata).with_call(&PolkadotCall::Chill).is_err());
+        assert!(builder(&metadata).fee_asset(1984).is_err());
+
+        assert!(builder(&metadata).build().is_err());
+        assert!(builder(&metadata).call("Staking", "bond", &[]).is_err());
+    }
+
+    #[test]
+    fn test_long_payload() {
+        let metadata = PolkadotMetadata::from_bytes(&test_metadata()).unwrap();
+
+        // System::remark with 300 bytes
+        let args = [encode(300), vec![1u8; 300]].concat();
+        let tx = builder(&metadata)
+            .call("System", "remark", &args)
+            .unwrap()
+            .build()
+            .unwrap();
+        let payload = tx.to_bytes().unwrap();
+        assert_eq!(32, payload.len());
+
+        let mut params = tx.params.clone();
+        params.call = Some([vec![0, 0], args].concat());
+        let unhashed = [
+            params.call.clone().unwrap(),
+            vec![0, 0x0c, 0, 0],
+            1005000u32.to_le_bytes().to_vec(),
+            24u32.to_le_bytes().to_vec(),
+            hex::decode(GENESIS_HASH).unwrap(),
+            hex::decode(GENESIS_HASH).unwrap(),
+            vec![0],
+        ]
+        .concat();
+        assert_eq!(blake2b_256(&unhashed).to_vec(), payload);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::{PolkadotAddress, PolkadotNetwork};
+use anychain_core::TransactionError;
+use parity_scale_codec::{Compact, Encode};
+
+/// Represents where the staking rewards of a nominator are paid to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RewardDestination<N: PolkadotNetwork> {
+    /// Added to the bonded amount
+    Staked,
+    /// Paid to the stash account without being bonded
+    Stash,
+    Account(PolkadotAddress<N>),
+    None,
+}
+
+/// Represents a call of a runtime, which is encoded with the pallet indices
+/// of the network `N` or with those resolved from the runtime metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolkadotCall<N: PolkadotNetwork> {
+    /// Balances::transfer_allow_death
+    TransferAllowDeath {
+        to: PolkadotAddress<N>,
+        amount: u128,
+    },
+    /// Balances::transfer_keep_alive
+    TransferKeepAlive {
+        to: PolkadotAddress<N>,
+        amount: u128,
+    },
+    /// Balances::transfer_all, which keeps the existential deposit in the
+    /// sender account if `keep_alive`
+    TransferAll {
+        to: PolkadotAddress<N>,
+        keep_alive: bool,
+    },
+    /// Staking::bond
+    Bond {
+        value: u128,
+        payee: RewardDestination<N>,
+    },
+    /// Staking::bond_extra
+    BondExtra { value: u128 },
+    /// Staking::nominate
+    Nominate { targets: Vec<PolkadotAddress<N>> },
+    /// Staking::unbond
+    Unbond { value: u128 },
+    /// Staking::withdraw_unbonded
+    WithdrawUnbonded { num_slashing_spans: u32 },
+    /// Staking::chill
+    Chill,
+    /// NominationPools::join
+    JoinPool { amount: u128, pool_id: u32 },
+    /// NominationPools::claim_payout
+    ClaimPayout,
+    /// Utility::batch_all, which dispatches all of the calls or none of them
+    BatchAll(Vec<PolkadotCall<N>>),
+    /// Proxy::proxy, which dispatches `call` on behalf of `real`. The proxy
+    /// type is runtime specific and is any type allowed if None
+    Proxy {
+        real: PolkadotAddress<N>,
+        force_proxy_type: Option<u8>,
+        call: Box<PolkadotCall<N>>,
+    },
+    /// Assets::transfer of an asset of Asset Hub, such as USDT (1984)
+    AssetTransfer {
+        asset_id: u32,
+        to: PolkadotAddress<N>,
+        amount: u128,
+    },
+}
+
+/// Returns the encoding of `address` as a MultiAddress::Id
+fn multi_address<N: PolkadotNetwork>(
+    address: &PolkadotAddress<N>,
+) -> Result<Vec<u8>, TransactionError> {
+    Ok([vec![0], address.to_payload()?].concat())
+}
+
+fn pallet_index(index: Option<u8>, pallet: &str) -> Result<u8, TransactionError> {
+    index.ok_or(TransactionError::Message(format!(
+        "The runtime has no {pallet} pallet"
+    )))
+}
+
+impl<N: PolkadotNetwork> PolkadotCall<N> {
+    /// Returns the names of the pallet and of the call
+    pub fn name(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::TransferAllowDeath { .. } => ("Balances", "transfer_allow_death"),
+            Self::TransferKeepAlive { .. } => ("Balances", "transfer_keep_alive"),
+            Self::TransferAll { .. } => ("Balances", "transfer_all"),
+            Self::Bond { .. } => ("Staking", "bond"),
+            Self::BondExtra { .. } => ("Staking", "bond_extra"),
+            Self::Nominate { .. } => ("Staking", "nominate"),
+            Self::Unbond { .. } => ("Staking", "unbond"),
+            Self::WithdrawUnbonded { .. } => ("Staking", "withdraw_unbonded"),
+            Self::Chill => ("Staking", "chill"),
+            Self::JoinPool { .. } => ("NominationPools", "join"),
+            Self::ClaimPayout => ("NominationPools", "claim_payout"),
+            Self::BatchAll(_) => ("Utility", "batch_all"),
+            Self::Proxy { .. } => ("Proxy", "proxy"),
+            Self::AssetTransfer { .. } => ("Assets", "transfer"),
+        }
+    }
+
+    /// Returns the pallet and call indices of the call in the runtime of `N`
+    pub fn index(&self) -> Result<(u8, u8), TransactionError> {
+        let staking = || pallet_index(N::PALLET_STAKING, "Staking");
+        let pools = || pallet_index(N::PALLET_NOMINATION_POOLS, "NominationPools");
+        Ok(match self {
+            Self::TransferAllowDeath { .. } => (N::PALLET_ASSET, N::TRANSFER_ALLOW_DEATH),
+            Self::TransferKeepAlive { .. } => (N::PALLET_ASSET, N::TRANSFER_KEEP_ALIVE),
+            Self::TransferAll { .. } => (N::PALLET_ASSET, 4),
+            Self::Bond { .. } => (staking()?, 0),
+            Self::BondExtra { .. } => (staking()?, 1),
+            Self::Unbond { .. } => (staking()?, 2),
+            Self::WithdrawUnbonded { .. } => (staking()?, 3),
+            Self::Nominate { .. } => (staking()?, 5),
+            Self::Chill => (staking()?, 6),
+            Self::JoinPool { .. } => (pools()?, 0),
+            Self::ClaimPayout => (pools()?, 2),
+            Self::BatchAll(_) => (N::PALLET_UTILITY, 2),
+            Self::Proxy { .. } => (N::PALLET_PROXY, 0),
+            Self::AssetTransfer { .. } => (pallet_index(N::PALLET_ASSETS, "Assets")?, 8),
+        })
+    }
+
+    /// Returns the encoded call with the indices of the network `N`
+    pub fn encode(&self) -> Result<Vec<u8>, TransactionError> {
+        self.encode_with(&|call| call.index())
+    }
+
+    /// Returns the encoded call, where `index` returns the pallet and call
+    /// indices of this call and of the calls nested in it
+    pub fn encode_with<F>(&self, index: &F) -> Result<Vec<u8>, TransactionError>
+    where
+        F: Fn(&Self) -> Result<(u8, u8), TransactionError>,
+    {
+        let (pallet_index, call_index) = index(self)?;
+        let args = match self {
+            Self::TransferAllowDeath { to, amount } | Self::TransferKeepAlive { to, amount } => {
+                [multi_address(to)?, Compact(*amount).encode()].concat()
+            }
+            Self::TransferAll { to, keep_alive } => {
+                [multi_address(to)?, vec![*keep_alive as u8]].concat()
+            }
+            Self::Bond { value, payee } => {
+                let payee = match payee {
+                    RewardDestination::Staked => vec![0],
+                    RewardDestination::Stash => vec![1],
+                    RewardDestination::Account(account) => {
+                        [vec![3], account.to_payload()?].concat()
+                    }
+                    RewardDestination::None => vec![4],
+                };
+                [Compact(*value).encode(), payee].concat()
+            }
+            Self::BondExtra { value } | Self::Unbond { value } => Compact(*value).encode(),
+            Self::Nominate { targets } => {
+                let mut args = Compact(targets.len() as u32).encode();
+                for target in targets {
+                    args.extend(multi_address(target)?);
+                }
+                args
+            }
+            Self::WithdrawUnbonded { num_slashing_spans } => num_slashing_spans.encode(),
+            Self::Chill | Self::ClaimPayout => vec![],
+            Self::JoinPool { amount, pool_id } => {
+                [Compact(*amount).encode(), pool_id.encode()].concat()
+            }
+            Self::BatchAll(calls) => {
+                let mut args = Compact(calls.len() as u32).encode();
+                for call in calls {
+                    args.extend(call.encode_with(index)?);
+                }
+                args
+            }
+            Self::Proxy {
+                real,
+                force_proxy_type,
+                call,
+            } => [
+                multi_address(real)?,
+                force_proxy_type.encode(),
+                call.encode_with(index)?,
+            ]
+            .concat(),
+            Self::AssetTransfer {
+                asset_id,
+                to,
+                amount,
+            } => [
+                Compact(*asset_id).encode(),
+                multi_address(to)?,
+                Compact(*amount).encode(),
+            ]
+            .concat(),
+        };
+        Ok([vec![pallet_index, call_index], args].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetHubPolkadot, Polkadot, Rococo, Westend};
+    use anychain_core::hex;
+
+    const VALIDATOR_1: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const VALIDATOR_2: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn address<N: PolkadotNetwork>(payload: &str) -> PolkadotAddress<N> {
+        PolkadotAddress::<N>::from_payload(payload).unwrap()
+    }
+
+    #[test]
+    fn test_staking_calls() {
+        let v1 = address::<Polkadot>(VALIDATOR_1);
+        let v2 = address::<Polkadot>(VALIDATOR_2);
+        let (p1, p2) = (v1.to_payload().unwrap(), v2.to_payload().unwrap());
+
+        let bond = PolkadotCall::<Polkadot>::Bond {
+            value: 10_000_000_000,
+            payee: RewardDestination::Staked,
+        };
+        assert_eq!("07000700e40b540200", hex::encode(bond.encode().unwrap()));
+
+        let bond = PolkadotCall::<Polkadot>::Bond {
+            value: 1,
+            payee: RewardDestination::Account(v1.clone()),
+        };
+        assert_eq!(
+            [vec![7, 0, 4, 3], p1.clone()].concat(),
+            bond.encode().unwrap()
+        );
+
+        let nominate = PolkadotCall::<Polkadot>::Nominate {
+            targets: vec![v1.clone(), v2.clone()],
+        };
+        assert_eq!(
+            [vec![7, 5, 8, 0], p1.clone(), vec![0], p2.clone()].concat(),
+            nominate.encode().unwrap()
+        );
+
+        let batch = PolkadotCall::<Polkadot>::BatchAll(vec![
+            PolkadotCall::BondExtra { value: 100 },
+            PolkadotCall::Unbond { value: 1 },
+            PolkadotCall::WithdrawUnbonded {
+                num_slashing_spans: 2,
+            },
+            PolkadotCall::Chill,
+        ]);
+        assert_eq!(
+            "1a0210070191010702040703020000000706",
+            hex::encode(batch.encode().unwrap())
+        );
+
+        let proxy = PolkadotCall::<Polkadot>::Proxy {
+            real: v2,
+            force_proxy_type: Some(3),
+            call: Box::new(PolkadotCall::Chill),
+        };
+        assert_eq!(
+            [vec![29, 0, 0], p2, vec![1, 3, 7, 6]].concat(),
+            proxy.encode().unwrap()
+        );
+
+        let join = PolkadotCall::<Westend>::JoinPool {
+            amount: 1,
+            pool_id: 12,
+        };
+        assert_eq!("1d00040c000000", hex::encode(join.encode().unwrap()));
+        let claim = PolkadotCall::<Westend>::ClaimPayout;
+        assert_eq!("1d02", hex::encode(claim.encode().unwrap()));
+
+        // Rococo has no staking
+        assert!(PolkadotCall::<Rococo>::Chill.encode().is_err());
+    }
+
+    #[test]
+    fn test_transfer_calls() {
+        let to = address::<AssetHubPolkadot>(VALIDATOR_1);
+        let payload = to.to_payload().unwrap();
+
+        // 1 USDT
+        let transfer = PolkadotCall::AssetTransfer {
+            asset_id: 1984,
+            to: to.clone(),
+            amount: 1_000_000,
+        };
+        assert_eq!(("Assets", "transfer"), transfer.name());
+        assert_eq!(
+            [
+                vec![50, 8, 0x01, 0x1f, 0],
+                payload.clone(),
+                vec![0x02, 0x09, 0x3d, 0x00]
+            ]
+            .concat(),
+            transfer.encode().unwrap()
+        );
+        assert!(PolkadotCall::<Polkadot>::AssetTransfer {
+            asset_id: 1984,
+            to: address(VALIDATOR_1),
+            amount: 1,
+        }
+        .encode()
+        .is_err());
+
+        let transfer = PolkadotCall::TransferKeepAlive {
+            to: to.clone(),
+            amount: 1,
+        };
+        assert_eq!(
+            [vec![10, 3, 0], payload.clone(), vec![4]].concat(),
+            transfer.encode().unwrap()
+        );
+        let transfer = PolkadotCall::TransferAll {
+            to,
+            keep_alive: true,
+        };
+        assert_eq!(
+            [vec![10, 4, 0], payload, vec![1]].concat(),
+            transfer.encode().unwrap()
+        );
+    }
+}
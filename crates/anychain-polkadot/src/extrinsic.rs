@@ -0,0 +1,212 @@
+use anychain_core::TransactionError;
+use parity_scale_codec::Decode;
+
+/// The shortest period of a mortal era
+pub const MIN_ERA_PERIOD: u64 = 4;
+
+/// The longest period of a mortal era
+pub const MAX_ERA_PERIOD: u64 = 1 << 16;
+
+/// Represents the era of a transaction, i.e. the range of blocks it is valid in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Era {
+    #[default]
+    Immortal,
+    /// Valid for `period` blocks from the block whose number modulo `period` is `phase`
+    Mortal { period: u64, phase: u64 },
+}
+
+impl Era {
+    /// Returns a mortal era that begins at `current_block` and lasts `period`
+    /// blocks, rounded up to a power of two between 4 and 65536
+    pub fn mortal(current_block: u64, period: u64) -> Self {
+        let period = period
+            .checked_next_power_of_two()
+            .unwrap_or(MAX_ERA_PERIOD)
+            .clamp(MIN_ERA_PERIOD, MAX_ERA_PERIOD);
+        let quantize_factor = (period >> 12).max(1);
+        let phase = current_block % period / quantize_factor * quantize_factor;
+        Era::Mortal { period, phase }
+    }
+
+    /// Returns the number of the first block of the era, given a block in it.
+    /// The signing payload commits to the hash of this block
+    pub fn birth(&self, current_block: u64) -> u64 {
+        match *self {
+            Era::Immortal => 0,
+            Era::Mortal { period, phase } => {
+                (current_block.max(phase) - phase) / period * period + phase
+            }
+        }
+    }
+
+    pub fn decode(input: &mut &[u8]) -> Result<Self, TransactionError> {
+        let first = u8::decode(input)
+            .map_err(|e| TransactionError::Crate("parity_scale_codec", format!("{e:?}")))?;
+        if first == 0 {
+            return Ok(Era::Immortal);
+        }
+        let second = u8::decode(input)
+            .map_err(|e| TransactionError::Crate("parity_scale_codec", format!("{e:?}")))?;
+        let encoded = u16::from_le_bytes([first, second]);
+        let period = 2u64 << (encoded % (1 << 4));
+        let quantize_factor = (period >> 12).max(1);
+        let phase = (encoded >> 4) as u64 * quantize_factor;
+        if period < MIN_ERA_PERIOD || phase >= period {
+            return Err(TransactionError::Message(format!(
+                "Invalid mortal era {encoded:#06x}"
+            )));
+        }
+        Ok(Era::Mortal { period, phase })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        match *self {
+            Era::Immortal => vec![0],
+            Era::Mortal { period, phase } => {
+                let quantize_factor = (period >> 12).max(1);
+                let encoded = (period.trailing_zeros().saturating_sub(1)).clamp(1, 15) as u16
+                    | ((phase / quantize_factor) << 4) as u16;
+                encoded.to_le_bytes().to_vec()
+            }
+        }
+    }
+}
+
+/// Represents the type of the signature of an extrinsic, which is the
+/// variant of MultiSignature it is encoded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SignatureType {
+    Ed25519 = 0,
+    Sr25519 = 1,
+    /// A secp256k1 signature followed by the recovery id
+    #[default]
+    Ecdsa = 2,
+}
+
+impl SignatureType {
+    pub fn from_u8(signature_type: u8) -> Result<Self, TransactionError> {
+        match signature_type {
+            0 => Ok(Self::Ed25519),
+            1 => Ok(Self::Sr25519),
+            2 => Ok(Self::Ecdsa),
+            _ => Err(TransactionError::Message(format!(
+                "Invalid signature type {signature_type}"
+            ))),
+        }
+    }
+
+    pub fn signature_length(&self) -> usize {
+        match self {
+            Self::Ed25519 | Self::Sr25519 => 64,
+            Self::Ecdsa => 65,
+        }
+    }
+}
+
+/// Represents a signed extension of a runtime, which adds data to a signed
+/// transaction and to its signing payload
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignedExtension {
+    CheckNonZeroSender,
+    CheckSpecVersion,
+    CheckTxVersion,
+    CheckGenesis,
+    CheckMortality,
+    CheckNonce,
+    CheckWeight,
+    ChargeTransactionPayment,
+    /// Pays the fee in the native asset, as no asset id is given
+    ChargeAssetTxPayment,
+    /// RFC-78 verification of the metadata a signer decodes the transaction with
+    CheckMetadataHash,
+    /// An extension that adds no data
+    Other(String),
+}
+
+impl SignedExtension {
+    pub fn from_identifier(identifier: &str) -> Self {
+        match identifier {
+            "CheckNonZeroSender" => Self::CheckNonZeroSender,
+            "CheckSpecVersion" => Self::CheckSpecVersion,
+            "CheckTxVersion" => Self::CheckTxVersion,
+            "CheckGenesis" => Self::CheckGenesis,
+            "CheckMortality" => Self::CheckMortality,
+            "CheckNonce" => Self::CheckNonce,
+            "CheckWeight" => Self::CheckWeight,
+            "ChargeTransactionPayment" => Self::ChargeTransactionPayment,
+            "ChargeAssetTxPayment" => Self::ChargeAssetTxPayment,
+            "CheckMetadataHash" => Self::CheckMetadataHash,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Returns the signed extensions of a runtime without CheckMetadataHash,
+    /// which add the era, nonce and tip to a transaction
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::CheckNonZeroSender,
+            Self::CheckSpecVersion,
+            Self::CheckTxVersion,
+            Self::CheckGenesis,
+            Self::CheckMortality,
+            Self::CheckNonce,
+            Self::CheckWeight,
+            Self::ChargeTransactionPayment,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_era() {
+        assert_eq!(vec![0], Era::Immortal.encode());
+
+        let era = Era::mortal(42, 64);
+        assert_eq!(
+            Era::Mortal {
+                period: 64,
+                phase: 42
+            },
+            era
+        );
+        assert_eq!(vec![0xa5, 0x02], era.encode());
+        assert_eq!(106, era.birth(110));
+
+        // The phase of a long period is quantized
+        let era = Era::mortal(20000, 32768);
+        assert_eq!(vec![0x4e, 0x9c], era.encode());
+
+        // The period is rounded up to a power of two within bounds
+        assert_eq!(
+            Era::Mortal {
+                period: 4,
+                phase: 1
+            },
+            Era::mortal(5, 1)
+        );
+        assert!(matches!(
+            Era::mortal(5, 100),
+            Era::Mortal { period: 128, .. }
+        ));
+        for era in [
+            Era::Immortal,
+            Era::mortal(42, 64),
+            Era::mortal(20000, 32768),
+            Era::mortal(5, 1),
+        ] {
+            assert_eq!(era, Era::decode(&mut era.encode().as_slice()).unwrap());
+        }
+        assert!(Era::decode(&mut [0x10, 0x00].as_slice()).is_err());
+        assert!(Era::decode(&mut [0xf1, 0xff].as_slice()).is_err());
+        assert!(Era::decode(&mut [0x01].as_slice()).is_err());
+
+        assert!(matches!(
+            Era::mortal(5, u64::MAX),
+            Era::Mortal { period: 65536, .. }
+        ));
+    }
+}
@@ -9,7 +9,9 @@ pub enum PolkadotFormat {
 impl Format for PolkadotFormat {}
 
 impl Display for PolkadotFormat {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolkadotFormat::Standard => write!(f, "standard"),
+        }
     }
 }
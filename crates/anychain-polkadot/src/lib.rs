@@ -10,6 +10,18 @@ pub use format::*;
 mod transaction;
 pub use transaction::*;
 
+mod extrinsic;
+pub use extrinsic::*;
+
+mod metadata;
+pub use metadata::*;
+
+mod builder;
+pub use builder::*;
+
+mod call;
+pub use call::*;
+
 mod network;
 pub use network::*;
 
@@ -0,0 +1,476 @@
+use crate::SignedExtension;
+use anychain_core::{hex, TransactionError};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use merkleized_metadata::{generate_metadata_digest, ExtraInfo};
+use parity_scale_codec::Decode;
+use scale_info::{PortableRegistry, TypeDef};
+use std::path::Path;
+
+/// Represents the runtime metadata of a chain in the SCALE-encoded V14 or V15 format
+#[derive(Debug)]
+pub struct PolkadotMetadata {
+    metadata: RuntimeMetadata,
+}
+
+/// The name, index and call type id of a pallet
+struct Pallet<'a> {
+    name: &'a str,
+    index: u8,
+    calls: Option<u32>,
+}
+
+/// The identifier and the types of the extra and additional data of a signed extension
+struct Extension<'a> {
+    identifier: &'a str,
+    ty: u32,
+    additional_signed: u32,
+}
+
+impl PolkadotMetadata {
+    /// Decodes the metadata returned by the `state_getMetadata` RPC method,
+    /// given either as bytes or as a hex string
+    pub fn from_bytes(metadata: &[u8]) -> Result<Self, TransactionError> {
+        let metadata = match metadata.starts_with(b"meta") {
+            true => metadata.to_vec(),
+            false => {
+                let metadata = String::from_utf8_lossy(metadata);
+                let metadata = metadata.trim();
+                hex::decode(metadata.strip_prefix("0x").unwrap_or(metadata))?
+            }
+        };
+        let RuntimeMetadataPrefixed(_, metadata) =
+            RuntimeMetadataPrefixed::decode(&mut metadata.as_slice())
+                .map_err(|e| TransactionError::Crate("parity_scale_codec", format!("{e:?}")))?;
+        match metadata {
+            RuntimeMetadata::V14(_) | RuntimeMetadata::V15(_) => Ok(Self { metadata }),
+            metadata => Err(TransactionError::Message(format!(
+                "Unsupported metadata version {}",
+                metadata.version()
+            ))),
+        }
+    }
+
+    /// Loads the metadata from a local file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TransactionError> {
+        let metadata = std::fs::read(path)
+            .map_err(|e| TransactionError::Crate("std::fs", format!("{e:?}")))?;
+        Self::from_bytes(&metadata)
+    }
+
+    pub fn version(&self) -> u32 {
+        self.metadata.version()
+    }
+
+    pub fn types(&self) -> &PortableRegistry {
+        match &self.metadata {
+            RuntimeMetadata::V14(m) => &m.types,
+            RuntimeMetadata::V15(m) => &m.types,
+            _ => unreachable!(),
+        }
+    }
+
+    fn pallets(&self) -> Vec<Pallet<'_>> {
+        match &self.metadata {
+            RuntimeMetadata::V14(m) => m
+                .pallets
+                .iter()
+                .map(|p| Pallet {
+                    name: &p.name,
+                    index: p.index,
+                    calls: p.calls.as_ref().map(|calls| calls.ty.id),
+                })
+                .collect(),
+            RuntimeMetadata::V15(m) => m
+                .pallets
+                .iter()
+                .map(|p| Pallet {
+                    name: &p.name,
+                    index: p.index,
+                    calls: p.calls.as_ref().map(|calls| calls.ty.id),
+                })
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn extensions(&self) -> Vec<Extension<'_>> {
+        match &self.metadata {
+            RuntimeMetadata::V14(m) => m
+                .extrinsic
+                .signed_extensions
+                .iter()
+                .map(|e| Extension {
+                    identifier: &e.identifier,
+                    ty: e.ty.id,
+                    additional_signed: e.additional_signed.id,
+                })
+                .collect(),
+            RuntimeMetadata::V15(m) => m
+                .extrinsic
+                .signed_extensions
+                .iter()
+                .map(|e| Extension {
+                    identifier: &e.identifier,
+                    ty: e.ty.id,
+                    additional_signed: e.additional_signed.id,
+                })
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the index of `pallet`
+    pub fn pallet_index(&self, pallet: &str) -> Result<u8, TransactionError> {
+        self.pallets()
+            .iter()
+            .find(|p| p.name == pallet)
+            .map(|p| p.index)
+            .ok_or(TransactionError::Message(format!(
+                "Pallet {pallet} not found in metadata"
+            )))
+    }
+
+    /// Returns the pallet index and the call index of `pallet::call`
+    pub fn call_index(&self, pallet: &str, call: &str) -> Result<(u8, u8), TransactionError> {
+        let pallets = self.pallets();
+        let pallet_meta =
+            pallets
+                .iter()
+                .find(|p| p.name == pallet)
+                .ok_or(TransactionError::Message(format!(
+                    "Pallet {pallet} not found in metadata"
+                )))?;
+        let calls = pallet_meta
+            .calls
+            .and_then(|ty| self.types().resolve(ty))
+            .map(|ty| &ty.type_def);
+        match calls {
+            Some(TypeDef::Variant(calls)) => calls
+                .variants
+                .iter()
+                .find(|v| v.name == call)
+                .map(|v| (pallet_meta.index, v.index))
+                .ok_or(TransactionError::Message(format!(
+                    "Call {pallet}::{call} not found in metadata"
+                ))),
+            _ => Err(TransactionError::Message(format!(
+                "Pallet {pallet} has no calls"
+            ))),
+        }
+    }
+
+    /// Returns the signed extensions of the runtime in the order their data
+    /// is encoded in a transaction
+    pub fn signed_extensions(&self) -> Result<Vec<SignedExtension>, TransactionError> {
+        self.extensions()
+            .iter()
+            .map(|e| match SignedExtension::from_identifier(e.identifier) {
+                SignedExtension::Other(identifier)
+                    if !self.is_empty_type(e.ty) || !self.is_empty_type(e.additional_signed) =>
+                {
+                    Err(TransactionError::Message(format!(
+                        "Unsupported signed extension {identifier}"
+                    )))
+                }
+                extension => Ok(extension),
+            })
+            .collect()
+    }
+
+    fn is_empty_type(&self, id: u32) -> bool {
+        match self.types().resolve(id).map(|ty| &ty.type_def) {
+            Some(TypeDef::Composite(c)) => c.fields.iter().all(|f| self.is_empty_type(f.ty.id)),
+            Some(TypeDef::Tuple(t)) => t.fields.iter().all(|f| self.is_empty_type(f.id)),
+            _ => false,
+        }
+    }
+
+    /// Returns the RFC-78 hash of the metadata that the CheckMetadataHash
+    /// extension commits to. Only V15 metadata can be merkleized
+    pub fn metadata_hash(
+        &self,
+        spec_name: &str,
+        spec_version: u32,
+        base58_prefix: u16,
+        decimals: u8,
+        token_symbol: &str,
+    ) -> Result<[u8; 32], TransactionError> {
+        let extra_info = ExtraInfo {
+            spec_version,
+            spec_name: spec_name.to_string(),
+            base58_prefix,
+            decimals,
+            token_symbol: token_symbol.to_string(),
+        };
+        let digest = generate_metadata_digest(&self.metadata, extra_info)
+            .map_err(|e| TransactionError::Crate("merkleized_metadata", e))?;
+        Ok(digest.hash())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use frame_metadata::{v14, v15};
+    use scale_info::{meta_type, TypeInfo};
+    use std::collections::BTreeMap;
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum MultiAddress {
+        Id([u8; 32]),
+        Index(#[codec(compact)] u32),
+        Raw(Vec<u8>),
+        Address32([u8; 32]),
+        Address20([u8; 20]),
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum SystemCall {
+        #[codec(index = 0)]
+        remark { remark: Vec<u8> },
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum BalancesCall {
+        #[codec(index = 0)]
+        transfer_allow_death {
+            dest: MultiAddress,
+            #[codec(compact)]
+            value: u128,
+        },
+        #[codec(index = 3)]
+        transfer_keep_alive {
+            dest: MultiAddress,
+            #[codec(compact)]
+            value: u128,
+        },
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum RuntimeCall {
+        #[codec(index = 0)]
+        System(SystemCall),
+        #[codec(index = 5)]
+        Balances(BalancesCall),
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum MultiSignature {
+        Ed25519([u8; 64]),
+        Sr25519([u8; 64]),
+        Ecdsa([u8; 65]),
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum Era {
+        Immortal,
+        Mortal(u8, u8),
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum Mode {
+        Disabled,
+        Enabled,
+    }
+
+    #[derive(TypeInfo)]
+    struct Unit;
+
+    #[derive(TypeInfo)]
+    struct CheckMortality(#[allow(dead_code)] Era);
+
+    #[derive(TypeInfo)]
+    struct CheckNonce(
+        #[codec(compact)]
+        #[allow(dead_code)]
+        u32,
+    );
+
+    #[derive(TypeInfo)]
+    struct ChargeTransactionPayment(
+        #[codec(compact)]
+        #[allow(dead_code)]
+        u128,
+    );
+
+    #[derive(TypeInfo)]
+    struct CheckMetadataHash {
+        #[allow(dead_code)]
+        mode: Mode,
+    }
+
+    type Extra = (
+        Unit,
+        Unit,
+        Unit,
+        Unit,
+        CheckMortality,
+        CheckNonce,
+        Unit,
+        ChargeTransactionPayment,
+        CheckMetadataHash,
+    );
+
+    fn extensions() -> Vec<v15::SignedExtensionMetadata> {
+        [
+            ("CheckNonZeroSender", meta_type::<Unit>(), meta_type::<()>()),
+            ("CheckSpecVersion", meta_type::<Unit>(), meta_type::<u32>()),
+            ("CheckTxVersion", meta_type::<Unit>(), meta_type::<u32>()),
+            ("CheckGenesis", meta_type::<Unit>(), meta_type::<[u8; 32]>()),
+            (
+                "CheckMortality",
+                meta_type::<CheckMortality>(),
+                meta_type::<[u8; 32]>(),
+            ),
+            ("CheckNonce", meta_type::<CheckNonce>(), meta_type::<()>()),
+            ("CheckWeight", meta_type::<Unit>(), meta_type::<()>()),
+            (
+                "ChargeTransactionPayment",
+                meta_type::<ChargeTransactionPayment>(),
+                meta_type::<()>(),
+            ),
+            (
+                "CheckMetadataHash",
+                meta_type::<CheckMetadataHash>(),
+                meta_type::<Option<[u8; 32]>>(),
+            ),
+        ]
+        .into_iter()
+        .map(
+            |(identifier, ty, additional_signed)| v15::SignedExtensionMetadata {
+                identifier,
+                ty,
+                additional_signed,
+            },
+        )
+        .collect()
+    }
+
+    fn pallet(name: &'static str, index: u8, calls: scale_info::MetaType) -> v15::PalletMetadata {
+        v15::PalletMetadata {
+            name,
+            storage: None,
+            calls: Some(calls.into()),
+            event: None,
+            constants: vec![],
+            error: None,
+            index,
+            docs: vec![],
+        }
+    }
+
+    /// Returns the V15 metadata of a runtime with the System and Balances pallets
+    pub(crate) fn test_metadata() -> Vec<u8> {
+        let pallets = vec![
+            pallet("System", 0, meta_type::<SystemCall>()),
+            pallet("Balances", 5, meta_type::<BalancesCall>()),
+        ];
+        let extrinsic = v15::ExtrinsicMetadata {
+            version: 4,
+            address_ty: meta_type::<MultiAddress>(),
+            call_ty: meta_type::<RuntimeCall>(),
+            signature_ty: meta_type::<MultiSignature>(),
+            extra_ty: meta_type::<Extra>(),
+            signed_extensions: extensions(),
+        };
+        let outer_enums = v15::OuterEnums {
+            call_enum_ty: meta_type::<RuntimeCall>(),
+            event_enum_ty: meta_type::<()>(),
+            error_enum_ty: meta_type::<()>(),
+        };
+        let custom = v15::CustomMetadata {
+            map: BTreeMap::new(),
+        };
+        let metadata = v15::RuntimeMetadataV15::new(
+            pallets,
+            extrinsic,
+            meta_type::<()>(),
+            vec![],
+            outer_enums,
+            custom,
+        );
+        RuntimeMetadataPrefixed::from(metadata).into()
+    }
+
+    #[test]
+    fn test_metadata_v15() {
+        let bytes = test_metadata();
+        let path = std::env::temp_dir().join("anychain_polkadot_metadata.scale");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let metadata = PolkadotMetadata::from_file(&path).unwrap();
+        assert_eq!(15, metadata.version());
+        assert_eq!(5, metadata.pallet_index("Balances").unwrap());
+        assert_eq!(
+            (5, 3),
+            metadata
+                .call_index("Balances", "transfer_keep_alive")
+                .unwrap()
+        );
+        assert_eq!((0, 0), metadata.call_index("System", "remark").unwrap());
+        assert!(metadata.call_index("Balances", "transfer").is_err());
+        assert!(metadata.call_index("Staking", "bond").is_err());
+
+        let mut extensions = SignedExtension::defaults();
+        extensions.push(SignedExtension::CheckMetadataHash);
+        assert_eq!(extensions, metadata.signed_extensions().unwrap());
+
+        let hash = metadata
+            .metadata_hash("westend", 1005000, 42, 12, "WND")
+            .unwrap();
+        assert_ne!(
+            hash,
+            metadata
+                .metadata_hash("westend", 1005001, 42, 12, "WND")
+                .unwrap()
+        );
+
+        // The metadata may be given as hex
+        let hex = format!("0x{}", hex::encode(&bytes));
+        let metadata = PolkadotMetadata::from_bytes(hex.as_bytes()).unwrap();
+        assert_eq!(15, metadata.version());
+    }
+
+    #[test]
+    fn test_metadata_v14() {
+        let pallets = vec![v14::PalletMetadata {
+            name: "Balances",
+            storage: None,
+            calls: Some(meta_type::<BalancesCall>().into()),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 4,
+        }];
+        let extrinsic = v14::ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![v14::SignedExtensionMetadata {
+                identifier: "CheckUnknown",
+                ty: meta_type::<u32>(),
+                additional_signed: meta_type::<()>(),
+            }],
+        };
+        let metadata = v14::RuntimeMetadataV14::new(pallets, extrinsic, meta_type::<()>());
+        let bytes: Vec<u8> = RuntimeMetadataPrefixed::from(metadata).into();
+
+        let metadata = PolkadotMetadata::from_bytes(&bytes).unwrap();
+        assert_eq!(14, metadata.version());
+        assert_eq!(
+            (4, 0),
+            metadata
+                .call_index("Balances", "transfer_allow_death")
+                .unwrap()
+        );
+        // An unknown extension that adds data cannot be encoded
+        assert!(metadata.signed_extensions().is_err());
+        assert!(metadata.metadata_hash("kusama", 1, 2, 12, "KSM").is_err());
+    }
+}
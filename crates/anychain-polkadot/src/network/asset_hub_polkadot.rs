@@ -0,0 +1,42 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::PolkadotNetwork;
+use anychain_core::{Network, NetworkError};
+
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct AssetHubPolkadot;
+
+impl Network for AssetHubPolkadot {
+    /// The spec name of the runtime
+    const NAME: &'static str = "statemint";
+}
+
+impl PolkadotNetwork for AssetHubPolkadot {
+    const VERSION: u8 = 0x00;
+    const PALLET_ASSET: u8 = 10;
+    const TRANSFER_ALLOW_DEATH: u8 = 0;
+    const TRANSFER_KEEP_ALIVE: u8 = 3;
+    const PALLET_STAKING: Option<u8> = None;
+    const PALLET_NOMINATION_POOLS: Option<u8> = None;
+    const PALLET_UTILITY: u8 = 40;
+    const PALLET_PROXY: u8 = 42;
+    const PALLET_ASSETS: Option<u8> = Some(50);
+    const DECIMALS: u8 = 10;
+    const TOKEN_SYMBOL: &'static str = "DOT";
+}
+
+impl FromStr for AssetHubPolkadot {
+    type Err = NetworkError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::NAME => Ok(Self),
+            _ => Err(NetworkError::InvalidNetwork(s.into())),
+        }
+    }
+}
+
+impl Display for AssetHubPolkadot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
@@ -10,6 +10,15 @@ pub use westend::*;
 mod rococo;
 pub use rococo::*;
 
+mod asset_hub_polkadot;
+pub use asset_hub_polkadot::*;
+
+mod asset_hub_kusama;
+pub use asset_hub_kusama::*;
+
+mod asset_hub_westend;
+pub use asset_hub_westend::*;
+
 use anychain_core::Network;
 
 pub trait PolkadotNetwork: Network {
@@ -17,4 +26,34 @@ pub trait PolkadotNetwork: Network {
     const PALLET_ASSET: u8;
     const TRANSFER_ALLOW_DEATH: u8;
     const TRANSFER_KEEP_ALIVE: u8;
+    /// The indices of the pallets the calls in `PolkadotCall` belong to, or
+    /// None if the runtime has no such pallet
+    const PALLET_STAKING: Option<u8>;
+    const PALLET_NOMINATION_POOLS: Option<u8>;
+    const PALLET_UTILITY: u8;
+    const PALLET_PROXY: u8;
+    const PALLET_ASSETS: Option<u8>;
+    /// The number of decimals of the native token
+    const DECIMALS: u8;
+    const TOKEN_SYMBOL: &'static str;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_network_names() {
+        assert_eq!(Polkadot, Polkadot::from_str(&Polkadot.to_string()).unwrap());
+        assert_eq!(Kusama, Kusama::from_str("kusama").unwrap());
+        assert_eq!(Westend, Westend::from_str("westend").unwrap());
+        assert_eq!(Rococo, Rococo::from_str("rococo").unwrap());
+        assert_eq!("statemint", AssetHubPolkadot.to_string());
+        assert_eq!(
+            AssetHubWestend,
+            AssetHubWestend::from_str(&AssetHubWestend.to_string()).unwrap()
+        );
+        assert!(Polkadot::from_str("kusama").is_err());
+    }
 }
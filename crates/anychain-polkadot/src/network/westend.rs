@@ -15,17 +15,27 @@ impl PolkadotNetwork for Westend {
     const PALLET_ASSET: u8 = 4;
     const TRANSFER_ALLOW_DEATH: u8 = 0;
     const TRANSFER_KEEP_ALIVE: u8 = 3;
+    const PALLET_STAKING: Option<u8> = Some(6);
+    const PALLET_NOMINATION_POOLS: Option<u8> = Some(29);
+    const PALLET_UTILITY: u8 = 16;
+    const PALLET_PROXY: u8 = 22;
+    const PALLET_ASSETS: Option<u8> = None;
+    const DECIMALS: u8 = 12;
+    const TOKEN_SYMBOL: &'static str = "WND";
 }
 
 impl FromStr for Westend {
     type Err = NetworkError;
-    fn from_str(_s: &str) -> Result<Self, Self::Err> {
-        todo!()
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::NAME => Ok(Self),
+            _ => Err(NetworkError::InvalidNetwork(s.into())),
+        }
     }
 }
 
 impl Display for Westend {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Self::NAME)
     }
 }
@@ -1,17 +1,19 @@
 use crate::{PolkadotAddress, PolkadotFormat, PolkadotNetwork};
-use anychain_core::{Address, PublicKey, PublicKeyError};
+use anychain_core::{hex, Address, PublicKey, PublicKeyError};
 use sp_core::blake2_256;
 use std::{fmt::Display, marker::PhantomData, str::FromStr};
 
 pub enum PolkadotSecretKey {
     Secp256k1(libsecp256k1::SecretKey),
     Ed25519(ed25519_dalek::SecretKey),
+    Sr25519(schnorrkel::SecretKey),
 }
 
 #[derive(Debug, Clone)]
 pub enum PublicKeyContent {
     Secp256k1(libsecp256k1::PublicKey),
     Ed25519(ed25519_dalek::VerifyingKey),
+    Sr25519(schnorrkel::PublicKey),
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +47,10 @@ impl<N: PolkadotNetwork> PublicKey for PolkadotPublicKey<N> {
                     _network: PhantomData,
                 }
             }
+            Self::SecretKey::Sr25519(sk) => Self {
+                key: PublicKeyContent::Sr25519(sk.to_public()),
+                _network: PhantomData,
+            },
         }
     }
 
@@ -57,30 +63,84 @@ impl<N: PolkadotNetwork> PublicKey for PolkadotPublicKey<N> {
 }
 
 impl<N: PolkadotNetwork> PolkadotPublicKey<N> {
+    pub fn new(key: PublicKeyContent) -> Self {
+        Self {
+            key,
+            _network: PhantomData,
+        }
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
         match self.key {
             PublicKeyContent::Secp256k1(pk) => pk.serialize_compressed().to_vec(),
             PublicKeyContent::Ed25519(pk) => pk.to_bytes().to_vec(),
+            PublicKeyContent::Sr25519(pk) => pk.to_bytes().to_vec(),
+        }
+    }
+
+    /// Returns true if `signature` is a valid signature of `message` by this
+    /// key. A secp256k1 signature is of the blake2b-256 hash of `message`
+    /// and may include the recovery id
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match &self.key {
+            PublicKeyContent::Secp256k1(pk) => {
+                let Ok(signature) = libsecp256k1::Signature::parse_standard_slice(
+                    &signature[..64.min(signature.len())],
+                ) else {
+                    return false;
+                };
+                let message = libsecp256k1::Message::parse(&blake2_256(message));
+                libsecp256k1::verify(&message, &signature, pk)
+            }
+            PublicKeyContent::Ed25519(pk) => {
+                match ed25519_dalek::Signature::from_slice(signature) {
+                    Ok(signature) => pk.verify_strict(message, &signature).is_ok(),
+                    Err(_) => false,
+                }
+            }
+            PublicKeyContent::Sr25519(pk) => match schnorrkel::Signature::from_bytes(signature) {
+                Ok(signature) => pk.verify_simple(b"substrate", message, &signature).is_ok(),
+                Err(_) => false,
+            },
         }
     }
 
     pub fn address_payload(&self) -> Vec<u8> {
         match self.key {
             PublicKeyContent::Secp256k1(_) => blake2_256(&self.serialize()).to_vec(),
-            PublicKeyContent::Ed25519(_) => self.serialize(),
+            PublicKeyContent::Ed25519(_) | PublicKeyContent::Sr25519(_) => self.serialize(),
         }
     }
 }
 
 impl<N: PolkadotNetwork> FromStr for PolkadotPublicKey<N> {
     type Err = PublicKeyError;
-    fn from_str(_s: &str) -> Result<Self, Self::Err> {
-        todo!()
+    /// Parses a hex public key. A key of 33 bytes is a compressed secp256k1
+    /// key and a key of 32 bytes is read as sr25519, whose address is that of
+    /// an ed25519 key of the same bytes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))
+            .map_err(|e| PublicKeyError::Crate("hex", format!("{e:?}")))?;
+        let key = match bytes.len() {
+            33 => PublicKeyContent::Secp256k1(
+                libsecp256k1::PublicKey::parse_slice(&bytes, None)
+                    .map_err(|e| PublicKeyError::Crate("libsecp256k1", format!("{e:?}")))?,
+            ),
+            32 => PublicKeyContent::Sr25519(
+                schnorrkel::PublicKey::from_bytes(&bytes)
+                    .map_err(|e| PublicKeyError::Crate("schnorrkel", format!("{e:?}")))?,
+            ),
+            len => return Err(PublicKeyError::InvalidByteLength(len)),
+        };
+        Ok(Self {
+            key,
+            _network: PhantomData,
+        })
     }
 }
 
 impl<N: PolkadotNetwork> Display for PolkadotPublicKey<N> {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.serialize()))
     }
 }
@@ -1,7 +1,10 @@
 use crate::utilities::crypto::blake2b_256;
-use crate::{PolkadotAddress, PolkadotFormat, PolkadotNetwork, PolkadotPublicKey};
+use crate::{
+    Era, PolkadotAddress, PolkadotFormat, PolkadotNetwork, PolkadotPublicKey, PublicKeyContent,
+    SignatureType, SignedExtension,
+};
 use anychain_core::{hex, Transaction, TransactionError, TransactionId};
-use parity_scale_codec::{Decode, Encode, HasCompact};
+use parity_scale_codec::{Compact, Decode, Encode, HasCompact};
 use std::fmt::Display;
 
 #[derive(Clone)]
@@ -15,23 +18,37 @@ pub struct PolkadotTransactionParameters<N: PolkadotNetwork> {
     pub genesis_hash: String,
     pub spec_version: u32,
     pub tx_version: u32,
+    /// The era of the transaction. `block_hash` is the hash of the first block
+    /// of a mortal era, or the genesis hash for an immortal one
+    pub era: Era,
+    /// The encoded call of the transaction, which replaces the transfer of
+    /// `amount` to `to` if present
+    pub call: Option<Vec<u8>>,
+    /// The signed extensions of the runtime, in metadata order
+    pub signed_extensions: Vec<SignedExtension>,
+    /// The metadata hash committed to by the CheckMetadataHash extension, if enabled
+    pub metadata_hash: Option<[u8; 32]>,
+    /// The id of an asset of the Assets pallet to pay the fee in, which
+    /// requires the ChargeAssetTxPayment extension
+    pub fee_asset: Option<u32>,
 }
 
+/// Signing payloads longer than this are hashed with blake2b-256 before signing
+pub const MAX_SIGNING_PAYLOAD_LENGTH: usize = 256;
+
 struct TxInterim {
     method: Vec<u8>,
-    era: Vec<u8>,
-    nonce: Vec<u8>,
-    tip: Vec<u8>,
-    spec_version: Vec<u8>,
-    genesis_hash: Vec<u8>,
-    block_hash: Vec<u8>,
-    tx_version: Vec<u8>,
+    /// The data the signed extensions add to the transaction
+    extra: Vec<u8>,
+    /// The data the signed extensions add to the signing payload only
+    additional: Vec<u8>,
 }
 
 #[derive(Clone)]
 pub struct PolkadotTransaction<N: PolkadotNetwork> {
     pub params: PolkadotTransactionParameters<N>,
     pub signature: Option<Vec<u8>>,
+    pub signature_type: SignatureType,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -53,7 +70,7 @@ struct CompactWrapper<T: HasCompact> {
     val: T,
 }
 
-fn encode(val: u64) -> Vec<u8> {
+pub(crate) fn encode(val: u64) -> Vec<u8> {
     if val == 0 {
         vec![0]
     } else {
@@ -61,6 +78,15 @@ fn encode(val: u64) -> Vec<u8> {
     }
 }
 
+/// Returns the encoded Some(location) of an asset of the Assets pallet, i.e.
+/// { parents: 0, interior: X2(PalletInstance(pallet), GeneralIndex(asset_id)) }
+fn fee_asset_location<N: PolkadotNetwork>(asset_id: u32) -> Result<Vec<u8>, TransactionError> {
+    let pallet = N::PALLET_ASSETS.ok_or(TransactionError::Message(
+        "The runtime has no Assets pallet to pay the fee with".to_string(),
+    ))?;
+    Ok([vec![1, 0, 2, 4, pallet, 5], encode(asset_id as u64)].concat())
+}
+
 impl<N: PolkadotNetwork> Transaction for PolkadotTransaction<N> {
     type Address = PolkadotAddress<N>;
     type Format = PolkadotFormat;
@@ -72,6 +98,7 @@ impl<N: PolkadotNetwork> Transaction for PolkadotTransaction<N> {
         Ok(PolkadotTransaction {
             params: params.clone(),
             signature: None,
+            signature_type: SignatureType::Ecdsa,
         })
     }
 
@@ -84,53 +111,22 @@ impl<N: PolkadotNetwork> Transaction for PolkadotTransaction<N> {
             )));
         }
         self.signature = Some([rs, vec![recid]].concat());
+        self.signature_type = SignatureType::Ecdsa;
         self.to_bytes()
     }
 
-    fn from_bytes(_tx: &[u8]) -> Result<Self, TransactionError> {
-        todo!()
+    /// Decodes an extrinsic of a runtime with the default signed extensions
+    /// followed by CheckMetadataHash, as those of the relay chains are. The
+    /// extrinsics of other runtimes are decoded by `decode` with the signed
+    /// extensions of their metadata
+    fn from_bytes(tx: &[u8]) -> Result<Self, TransactionError> {
+        let mut signed_extensions = SignedExtension::defaults();
+        signed_extensions.push(SignedExtension::CheckMetadataHash);
+        Self::decode(tx, &signed_extensions, None)
     }
 
-    // Only used for secp256k1 signing scheme
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        match &self.signature {
-            Some(sig) => {
-                let interim = self.to_interim()?;
-                let from = self.params.from.to_payload()?;
-
-                let stream = [
-                    vec![0x84], // version = 0x84
-                    vec![0],
-                    from,
-                    vec![2], // ed25519 = 0, sr25519 = 1, secp256k1 = 2
-                    sig.clone(),
-                    interim.era,
-                    interim.nonce,
-                    interim.tip,
-                    interim.method,
-                ]
-                .concat();
-
-                let len = stream.len() as u64;
-                let len = encode(len);
-
-                Ok([len, stream].concat())
-            }
-            None => {
-                let interim = self.to_interim()?;
-                Ok([
-                    interim.method,
-                    interim.era,
-                    interim.nonce,
-                    interim.tip,
-                    interim.spec_version,
-                    interim.tx_version,
-                    interim.genesis_hash,
-                    interim.block_hash,
-                ]
-                .concat())
-            }
-        }
+        self.serialize(self.signature_type as u8)
     }
 
     fn to_transaction_id(&self) -> Result<Self::TransactionId, TransactionError> {
@@ -144,41 +140,80 @@ impl<N: PolkadotNetwork> PolkadotTransaction<N> {
     fn to_interim(&self) -> Result<TxInterim, TransactionError> {
         let params = &self.params;
 
-        let to = params.to.to_payload()?;
-        let amount = encode(params.amount);
-        let era = vec![0];
-
-        let nonce = encode(params.nonce);
-        let tip = encode(params.tip);
-
-        let spec_version = params.spec_version.to_le_bytes().to_vec();
-        let tx_version = params.tx_version.to_le_bytes().to_vec();
-
-        let genesis_hash = hex::decode(&params.genesis_hash)?;
-        let block_hash = hex::decode(&params.block_hash)?;
-
-        let interim = TxInterim {
-            method: [
+        let method = match &params.call {
+            Some(call) => call.clone(),
+            None => [
                 vec![N::PALLET_ASSET, N::TRANSFER_ALLOW_DEATH],
                 vec![0],
-                to,
-                amount,
+                params.to.to_payload()?,
+                encode(params.amount),
             ]
             .concat(),
-            era,
-            nonce,
-            tip,
-            spec_version,
-            tx_version,
-            genesis_hash,
-            block_hash,
         };
 
-        Ok(interim)
+        let mut extra = vec![];
+        let mut additional = vec![];
+        for extension in &params.signed_extensions {
+            match extension {
+                SignedExtension::CheckSpecVersion => {
+                    additional.extend(params.spec_version.to_le_bytes())
+                }
+                SignedExtension::CheckTxVersion => {
+                    additional.extend(params.tx_version.to_le_bytes())
+                }
+                SignedExtension::CheckGenesis => {
+                    additional.extend(hex::decode(&params.genesis_hash)?)
+                }
+                SignedExtension::CheckMortality => {
+                    extra.extend(params.era.encode());
+                    additional.extend(hex::decode(&params.block_hash)?);
+                }
+                SignedExtension::CheckNonce => extra.extend(encode(params.nonce)),
+                SignedExtension::ChargeTransactionPayment => extra.extend(encode(params.tip)),
+                SignedExtension::ChargeAssetTxPayment => {
+                    extra.extend(encode(params.tip));
+                    match params.fee_asset {
+                        Some(asset_id) => extra.extend(fee_asset_location::<N>(asset_id)?),
+                        None => extra.push(0),
+                    }
+                }
+                SignedExtension::CheckMetadataHash => match params.metadata_hash {
+                    Some(hash) => {
+                        extra.push(1);
+                        additional.push(1);
+                        additional.extend(hash);
+                    }
+                    None => {
+                        extra.push(0);
+                        additional.push(0);
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        Ok(TxInterim {
+            method,
+            extra,
+            additional,
+        })
     }
 
-    // Alternative to to_bytes() when using ed25519 signing scheme
-    fn to_bytes_ed25519(&self) -> Result<Vec<u8>, TransactionError> {
+    /// Returns the payload the signature of the transaction is of, which is
+    /// hashed with blake2b-256 if longer than `MAX_SIGNING_PAYLOAD_LENGTH`
+    fn signing_payload(&self) -> Result<Vec<u8>, TransactionError> {
+        let interim = self.to_interim()?;
+        let payload = [interim.method, interim.extra, interim.additional].concat();
+        match payload.len() > MAX_SIGNING_PAYLOAD_LENGTH {
+            true => Ok(blake2b_256(&payload).to_vec()),
+            false => Ok(payload),
+        }
+    }
+
+    /// Returns the signed transaction, where `signature_type` is 0 for
+    /// ed25519, 1 for sr25519 and 2 for secp256k1, or the signing payload if
+    /// the transaction is unsigned
+    fn serialize(&self, signature_type: u8) -> Result<Vec<u8>, TransactionError> {
         match &self.signature {
             Some(sig) => {
                 let interim = self.to_interim()?;
@@ -188,11 +223,9 @@ impl<N: PolkadotNetwork> PolkadotTransaction<N> {
                     vec![0x84], // version = 0x84
                     vec![0],
                     from,
-                    vec![0], // ed25519 = 0, sr25519 = 1, secp256k1 = 2
+                    vec![signature_type],
                     sig.clone(),
-                    interim.era,
-                    interim.nonce,
-                    interim.tip,
+                    interim.extra,
                     interim.method,
                 ]
                 .concat();
@@ -202,23 +235,15 @@ impl<N: PolkadotNetwork> PolkadotTransaction<N> {
 
                 Ok([len, stream].concat())
             }
-            None => {
-                let interim = self.to_interim()?;
-                Ok([
-                    interim.method,
-                    interim.era,
-                    interim.nonce,
-                    interim.tip,
-                    interim.spec_version,
-                    interim.tx_version,
-                    interim.genesis_hash,
-                    interim.block_hash,
-                ]
-                .concat())
-            }
+            None => self.signing_payload(),
         }
     }
 
+    // Alternative to to_bytes() when using ed25519 signing scheme
+    fn to_bytes_ed25519(&self) -> Result<Vec<u8>, TransactionError> {
+        self.serialize(0)
+    }
+
     // Alternative to sign() when using ed25519 signing scheme
     pub fn sign_ed25519(&mut self, rs: Vec<u8>) -> Result<Vec<u8>, TransactionError> {
         if rs.len() != 64 {
@@ -228,8 +253,197 @@ impl<N: PolkadotNetwork> PolkadotTransaction<N> {
             )));
         }
         self.signature = Some(rs);
+        self.signature_type = SignatureType::Ed25519;
         self.to_bytes_ed25519()
     }
+
+    // Alternative to sign() when using sr25519 signing scheme, which is
+    // what most Polkadot wallets use
+    pub fn sign_sr25519(&mut self, rs: Vec<u8>) -> Result<Vec<u8>, TransactionError> {
+        if rs.len() != 64 {
+            return Err(TransactionError::Message(format!(
+                "Invalid signature length {}",
+                rs.len(),
+            )));
+        }
+        self.signature = Some(rs);
+        self.signature_type = SignatureType::Sr25519;
+        self.to_bytes()
+    }
+
+    /// Decodes a V4 extrinsic of a runtime with `signed_extensions`. As the
+    /// signing payload is not part of the extrinsic, the spec and transaction
+    /// versions and the block and genesis hashes of the result must be set
+    /// before it is verified, and `metadata_hash` is the hash committed to if
+    /// the CheckMetadataHash extension is enabled. A bare (unsigned) extrinsic
+    /// has no sender, so `from` is the all-zero account
+    pub fn decode(
+        tx: &[u8],
+        signed_extensions: &[SignedExtension],
+        metadata_hash: Option<[u8; 32]>,
+    ) -> Result<Self, TransactionError> {
+        let input = &mut &tx[..];
+        let len = decode_compact(input)?;
+        if len != input.len() as u128 {
+            return Err(TransactionError::Message(format!(
+                "Invalid extrinsic length {len}"
+            )));
+        }
+
+        let mut params = PolkadotTransactionParameters {
+            from: PolkadotAddress::from_payload(&hex::encode([0u8; 32]))?,
+            to: PolkadotAddress::from_payload(&hex::encode([0u8; 32]))?,
+            amount: 0,
+            nonce: 0,
+            tip: 0,
+            block_hash: String::new(),
+            genesis_hash: String::new(),
+            spec_version: 0,
+            tx_version: 0,
+            era: Era::Immortal,
+            call: None,
+            signed_extensions: signed_extensions.to_vec(),
+            metadata_hash: None,
+            fee_asset: None,
+        };
+        let mut signature = None;
+        let mut signature_type = SignatureType::Ecdsa;
+
+        match read(input, 1)?[0] {
+            0x84 => {
+                if read(input, 1)?[0] != 0 {
+                    return Err(TransactionError::Message(
+                        "Only MultiAddress::Id senders are supported".to_string(),
+                    ));
+                }
+                params.from = PolkadotAddress::from_payload(&hex::encode(read(input, 32)?))?;
+                signature_type = SignatureType::from_u8(read(input, 1)?[0])?;
+                signature = Some(read(input, signature_type.signature_length())?.to_vec());
+
+                for extension in signed_extensions {
+                    match extension {
+                        SignedExtension::CheckMortality => params.era = Era::decode(input)?,
+                        SignedExtension::CheckNonce => params.nonce = decode_u64(input)?,
+                        SignedExtension::ChargeTransactionPayment => {
+                            params.tip = decode_u64(input)?
+                        }
+                        SignedExtension::ChargeAssetTxPayment => {
+                            params.tip = decode_u64(input)?;
+                            if read(input, 1)?[0] == 1 {
+                                let prefix = read(input, 5)?;
+                                let asset_id = u32::try_from(decode_compact(input)?)
+                                    .map_err(|e| TransactionError::Message(e.to_string()))?;
+                                if prefix != &fee_asset_location::<N>(asset_id)?[1..6] {
+                                    return Err(TransactionError::Message(
+                                        "Unsupported fee asset location".to_string(),
+                                    ));
+                                }
+                                params.fee_asset = Some(asset_id);
+                            }
+                        }
+                        SignedExtension::CheckMetadataHash => match read(input, 1)?[0] {
+                            0 => {}
+                            1 => {
+                                params.metadata_hash = Some(
+                                    metadata_hash.ok_or(TransactionError::Message(
+                                        "The metadata hash of the extrinsic is required"
+                                            .to_string(),
+                                    ))?,
+                                )
+                            }
+                            mode => {
+                                return Err(TransactionError::Message(format!(
+                                    "Invalid metadata hash mode {mode}"
+                                )))
+                            }
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            0x04 => {}
+            version => {
+                return Err(TransactionError::Message(format!(
+                    "Unsupported extrinsic version {version:#04x}"
+                )))
+            }
+        }
+
+        let call = input.to_vec();
+        params.to = params.from.clone();
+        params.call = Some(call.clone());
+
+        // A transfer of the native token is kept in `to` and `amount`
+        if call.len() > 35 && call[..3] == [N::PALLET_ASSET, N::TRANSFER_ALLOW_DEATH, 0] {
+            if let Ok(amount) = decode_u64(&mut &call[35..]) {
+                let to = PolkadotAddress::from_payload(&hex::encode(&call[3..35]))?;
+                if [&call[..35], &encode(amount)[..]].concat() == call {
+                    params.to = to;
+                    params.amount = amount;
+                    params.call = None;
+                }
+            }
+        }
+
+        Ok(PolkadotTransaction {
+            params,
+            signature,
+            signature_type,
+        })
+    }
+
+    /// Returns true if the transaction is signed by its sender
+    pub fn verify(&self) -> Result<bool, TransactionError> {
+        let signature = self.signature.as_ref().ok_or(TransactionError::Message(
+            "The transaction is not signed".to_string(),
+        ))?;
+        let payload = self.signing_payload()?;
+        let from = self.params.from.to_payload()?;
+        let key = match self.signature_type {
+            SignatureType::Ecdsa => {
+                let message = libsecp256k1::Message::parse(&blake2b_256(&payload));
+                let rs = libsecp256k1::Signature::parse_standard_slice(&signature[..64])
+                    .map_err(|e| TransactionError::Crate("libsecp256k1", format!("{e:?}")))?;
+                let recid = libsecp256k1::RecoveryId::parse(signature[64])
+                    .map_err(|e| TransactionError::Crate("libsecp256k1", format!("{e:?}")))?;
+                let Ok(pk) = libsecp256k1::recover(&message, &rs, &recid) else {
+                    return Ok(false);
+                };
+                PublicKeyContent::Secp256k1(pk)
+            }
+            SignatureType::Ed25519 => PublicKeyContent::Ed25519(
+                ed25519_dalek::VerifyingKey::try_from(from.as_slice())
+                    .map_err(|e| TransactionError::Crate("ed25519_dalek", format!("{e:?}")))?,
+            ),
+            SignatureType::Sr25519 => PublicKeyContent::Sr25519(
+                schnorrkel::PublicKey::from_bytes(&from)
+                    .map_err(|e| TransactionError::Crate("schnorrkel", format!("{e:?}")))?,
+            ),
+        };
+        let public_key = PolkadotPublicKey::<N>::new(key);
+        Ok(public_key.address_payload() == from && public_key.verify(&payload, signature))
+    }
+}
+
+fn read<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], TransactionError> {
+    if input.len() < len {
+        return Err(TransactionError::Message(
+            "Unexpected end of extrinsic".to_string(),
+        ));
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+fn decode_compact(input: &mut &[u8]) -> Result<u128, TransactionError> {
+    Ok(Compact::<u128>::decode(input)
+        .map_err(|e| TransactionError::Crate("parity_scale_codec", format!("{e:?}")))?
+        .0)
+}
+
+fn decode_u64(input: &mut &[u8]) -> Result<u64, TransactionError> {
+    u64::try_from(decode_compact(input)?).map_err(|e| TransactionError::Message(e.to_string()))
 }
 
 impl<N: PolkadotNetwork> Display for PolkadotTransaction<N> {
@@ -241,11 +455,14 @@ impl<N: PolkadotNetwork> Display for PolkadotTransaction<N> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        PolkadotAddress, PolkadotFormat, PolkadotNetwork, PolkadotSecretKey, PolkadotTransaction,
-        PolkadotTransactionParameters, Westend,
+        AssetHubPolkadot, Era, PolkadotAddress, PolkadotCall, PolkadotFormat, PolkadotNetwork,
+        PolkadotPublicKey, PolkadotSecretKey, PolkadotTransaction, PolkadotTransactionParameters,
+        SignatureType, SignedExtension, Westend,
     };
-    use anychain_core::Address;
     use anychain_core::{hex, Transaction};
+    use anychain_core::{Address, PublicKey};
+    use anychain_kms::bip39::{Language, Mnemonic};
+    use anychain_kms::sr25519;
     use ed25519_dalek::{
         hazmat::{self, ExpandedSecretKey},
         Signature, VerifyingKey,
@@ -279,6 +496,11 @@ mod tests {
             genesis_hash,
             spec_version,
             tx_version,
+            era: Era::Immortal,
+            call: None,
+            signed_extensions: SignedExtension::defaults(),
+            metadata_hash: None,
+            fee_asset: None,
         };
 
         PolkadotTransaction::<N>::new(&params).unwrap()
@@ -429,4 +651,200 @@ mod tests {
             signed_tx
         );
     }
+
+    #[test]
+    fn test_tx_gen_sr25519() {
+        let mnemonic = Mnemonic::from_phrase(
+            "bottom drive obey lake curtain smoke basket hold race lonely fit walk",
+            Language::English,
+        )
+        .unwrap();
+        let keypair = sr25519::keypair_from_mnemonic(&mnemonic, "//Alice").unwrap();
+        let sk = PolkadotSecretKey::Sr25519(keypair.secret.clone());
+        let from =
+            PolkadotAddress::<Westend>::from_secret_key(&sk, &PolkadotFormat::Standard).unwrap();
+        assert_eq!(
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            from.to_string()
+        );
+        let public_key = hex::encode(keypair.public.to_bytes());
+        let pk = PolkadotPublicKey::<Westend>::from_str(&format!("0x{public_key}")).unwrap();
+        assert_eq!(public_key, pk.to_string());
+        assert_eq!(from, pk.to_address(&PolkadotFormat::Standard).unwrap());
+        assert!(PolkadotPublicKey::<Westend>::from_str("0102").is_err());
+
+        let tx = format!(
+            r#"{{
+            "from": "{from}",
+            "to": "5D1NKGqfc2Q8hw53icrX74YQryjb3MMySWwFBhM71afKbdad",
+            "amount": 1000000000000,
+            "nonce": 5,
+            "tip": 0,
+            "block_hash": "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+            "genesis_hash": "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+            "spec_version": 1005000,
+            "tx_version": 24
+        }}"#
+        );
+
+        let mut tx = tx_from_str::<Westend>(&tx);
+        let msg = tx.to_bytes().unwrap();
+        let sig = sr25519::sr25519_sign(&keypair.secret.to_bytes(), &msg).unwrap();
+        let pk = keypair.public.to_bytes();
+        assert!(sr25519::sr25519_verify(&pk, &msg, &sig));
+
+        let signed_tx = tx.sign_sr25519(sig.clone()).unwrap();
+        // length, version, MultiAddress::Id, public key, MultiSignature::Sr25519
+        let expected = [vec![0x84, 0], pk.to_vec(), vec![1], sig].concat();
+        assert_eq!(expected, signed_tx[2..2 + expected.len()]);
+    }
+
+    #[test]
+    fn test_fee_asset() {
+        let to = PolkadotAddress::<AssetHubPolkadot>::from_payload(
+            "0101010101010101010101010101010101010101010101010101010101010101",
+        )
+        .unwrap();
+        let mut extensions = SignedExtension::defaults();
+        extensions.pop();
+        extensions.push(SignedExtension::ChargeAssetTxPayment);
+
+        let params = PolkadotTransactionParameters::<AssetHubPolkadot> {
+            from: to.clone(),
+            to: to.clone(),
+            amount: 0,
+            nonce: 1,
+            tip: 0,
+            block_hash: "00".repeat(32),
+            genesis_hash: "00".repeat(32),
+            spec_version: 1,
+            tx_version: 1,
+            era: Era::Immortal,
+            call: Some(
+                PolkadotCall::AssetTransfer {
+                    asset_id: 1984,
+                    to,
+                    amount: 1_000_000,
+                }
+                .encode()
+                .unwrap(),
+            ),
+            signed_extensions: extensions,
+            metadata_hash: None,
+            fee_asset: Some(1984),
+        };
+        let mut tx = PolkadotTransaction::new(&params).unwrap();
+        let call_len = params.call.clone().unwrap().len();
+        let payload = tx.to_bytes().unwrap();
+        // era, nonce, tip, Some(parents: 0, X2(PalletInstance(50), GeneralIndex(1984)))
+        assert_eq!(
+            "000400010002043205011f",
+            hex::encode(&payload[call_len..call_len + 11])
+        );
+
+        let signed_tx = tx.sign(vec![1u8; 64], 0).unwrap();
+        assert!(hex::encode(signed_tx).ends_with(&format!(
+            "000400010002043205011f{}",
+            hex::encode(params.call.unwrap())
+        )));
+    }
+
+    #[test]
+    fn test_decode() {
+        let set_payload_data = |tx: &mut PolkadotTransaction<Westend>| {
+            let hash = "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+            tx.params.block_hash = hash.to_string();
+            tx.params.genesis_hash = hash.to_string();
+            tx.params.spec_version = 1005000;
+            tx.params.tx_version = 24;
+        };
+
+        // secp256k1
+        let bytes = hex::decode("41028400a487f8cf0c11fd48eae13f819dbb06e5cb97b7103d2434897bd7cb3ea80963e502ba136449919abc037e45cb36fbeae2b1d5dde212f7cd6f9eef604833811a6ac07eba271bdbb4bfb940f6f0ab810e0afea3d0bcdce0b2a51270a2235d42d3816300000c000400004ce05abd387b560855a3d486eba6237b9a08c6e9dfe351302a5ceda90be801fe070010a5d4e8").unwrap();
+        let mut tx =
+            PolkadotTransaction::<Westend>::decode(&bytes, &SignedExtension::defaults(), None)
+                .unwrap();
+        assert_eq!(bytes, tx.to_bytes().unwrap());
+        assert_eq!(SignatureType::Ecdsa, tx.signature_type);
+        assert_eq!(
+            "5FnS6tYbCTAtK3QCfNnddwVR61ypLLM7APRrs98paFs7yMSY",
+            tx.params.from.to_string()
+        );
+        assert_eq!(
+            "5DoW9HHuqSfpf55Ux5pLdJbHFWvbngeg8Ynhub9DrdtxmZeV",
+            tx.params.to.to_string()
+        );
+        assert_eq!(1000000000000, tx.params.amount);
+        assert_eq!(3, tx.params.nonce);
+        assert_eq!(None, tx.params.call);
+        set_payload_data(&mut tx);
+        assert!(tx.verify().unwrap());
+        tx.params.nonce = 4;
+        assert!(!tx.verify().unwrap());
+
+        // ed25519
+        let bytes = hex::decode("3d0284003aa08b895131d34e7c1364ca80067f282fc6b2417b4eefcf7e2ecf7c19d7f81900aff4f335398d8584150fae80adc6dcaea686b2a5a2c9cb28a82a5a59314b7fd3ceaac142b91c949e482aec06f16202c9ea8dcd1c82a4b250cc72dfae03a6360400140004000029b0b723f2e8b89f1bcdc0cf2b3d0e624454a0cb898a46b5b59368964c5544f5070010a5d4e8").unwrap();
+        let mut tx =
+            PolkadotTransaction::<Westend>::decode(&bytes, &SignedExtension::defaults(), None)
+                .unwrap();
+        assert_eq!(bytes, tx.to_bytes().unwrap());
+        assert_eq!(SignatureType::Ed25519, tx.signature_type);
+        assert_eq!(5, tx.params.nonce);
+        set_payload_data(&mut tx);
+        assert!(tx.verify().unwrap());
+        // The mode byte of CheckMetadataHash is not taken for the call
+        assert!(PolkadotTransaction::<Westend>::from_bytes(&bytes).is_err());
+
+        // A transfer with the CheckMetadataHash extension disabled
+        let key = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        let mut params = tx.params.clone();
+        params.from =
+            PolkadotAddress::from_payload(&hex::encode(key.verifying_key().as_bytes())).unwrap();
+        params
+            .signed_extensions
+            .push(SignedExtension::CheckMetadataHash);
+        let mut tx = PolkadotTransaction::new(&params).unwrap();
+        let signature = ed25519_dalek::Signer::sign(&key, &tx.signing_payload().unwrap());
+        let bytes = tx.sign_ed25519(signature.to_bytes().to_vec()).unwrap();
+        let mut decoded = PolkadotTransaction::<Westend>::from_bytes(&bytes).unwrap();
+        assert_eq!(bytes, decoded.to_bytes().unwrap());
+        assert_eq!(params.to, decoded.params.to);
+        assert_eq!(params.amount, decoded.params.amount);
+        assert_eq!(None, decoded.params.call);
+        set_payload_data(&mut decoded);
+        assert!(decoded.verify().unwrap());
+
+        // A mortal transaction with a tip, the metadata hash and another call
+        let mut params = tx.params.clone();
+        params.era = Era::mortal(1000, 64);
+        params.tip = 7;
+        params.call = Some(PolkadotCall::<Westend>::Chill.encode().unwrap());
+        params
+            .signed_extensions
+            .push(SignedExtension::CheckMetadataHash);
+        params.metadata_hash = Some([9u8; 32]);
+        let mut tx = PolkadotTransaction::new(&params).unwrap();
+        let bytes = tx.sign_sr25519(vec![1u8; 64]).unwrap();
+        let decoded = PolkadotTransaction::<Westend>::decode(
+            &bytes,
+            &params.signed_extensions,
+            params.metadata_hash,
+        )
+        .unwrap();
+        assert_eq!(bytes, decoded.to_bytes().unwrap());
+        assert_eq!(params.era, decoded.params.era);
+        assert_eq!(7, decoded.params.tip);
+        assert_eq!(params.call, decoded.params.call);
+        assert_eq!(SignatureType::Sr25519, decoded.signature_type);
+        assert!(
+            PolkadotTransaction::<Westend>::decode(&bytes, &params.signed_extensions, None)
+                .is_err()
+        );
+        assert!(PolkadotTransaction::<Westend>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // A bare extrinsic
+        let bare = PolkadotTransaction::<Westend>::from_bytes(&[0x0c, 0x04, 0x06, 0x06]).unwrap();
+        assert_eq!(Some(vec![6, 6]), bare.params.call);
+        assert!(bare.verify().is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use base58::{FromBase58, ToBase58};
 use std::{fmt::Display, str::FromStr};
 
-use crate::{RippleFormat, RipplePublicKey};
+use crate::{RippleFormat, RipplePublicKey, RippleSecretKey};
 use anychain_core::{
     crypto::{checksum, hash160},
     Address, AddressError, PublicKey,
@@ -79,7 +79,7 @@ fn to_btc_bs58(s: &str) -> Result<String, AddressError> {
 pub struct RippleAddress(String);
 
 impl Address for RippleAddress {
-    type SecretKey = libsecp256k1::SecretKey;
+    type SecretKey = RippleSecretKey;
     type PublicKey = RipplePublicKey;
     type Format = RippleFormat;
 
@@ -173,11 +173,122 @@ impl RippleAddress {
     }
 }
 
+/// The prefix of a mainnet X-address
+const X_ADDRESS_MAINNET_PREFIX: [u8; 2] = [0x05, 0x44];
+/// The prefix of a testnet X-address
+const X_ADDRESS_TESTNET_PREFIX: [u8; 2] = [0x04, 0x93];
+
+/// Represents an X-address, which packs a classic address, an optional
+/// destination tag and the network into a single string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RippleXAddress {
+    pub address: RippleAddress,
+    pub tag: Option<u32>,
+    pub is_test: bool,
+}
+
+impl RippleXAddress {
+    pub fn new(address: RippleAddress, tag: Option<u32>, is_test: bool) -> Self {
+        Self {
+            address,
+            tag,
+            is_test,
+        }
+    }
+}
+
+impl FromStr for RippleXAddress {
+    type Err = AddressError;
+
+    fn from_str(addr: &str) -> Result<Self, Self::Err> {
+        for c in addr.chars() {
+            if !RIPPLE_ALPHABET.contains(c) {
+                return Err(AddressError::InvalidAddress(
+                    "illegal ripple x-address".to_string(),
+                ));
+            }
+        }
+        let data = to_btc_bs58(addr)?.from_base58()?;
+        if data.len() != 35 {
+            return Err(AddressError::InvalidByteLength(data.len()));
+        }
+
+        let expected_checksum = &checksum(&data[..31])[..4];
+        if *expected_checksum != data[31..] {
+            return Err(AddressError::InvalidChecksum(
+                to_xrp_bs58(
+                    &[data[..31].to_vec(), expected_checksum.to_vec()]
+                        .concat()
+                        .to_base58(),
+                )?,
+                addr.to_string(),
+            ));
+        }
+
+        let is_test = match [data[0], data[1]] {
+            X_ADDRESS_MAINNET_PREFIX => false,
+            X_ADDRESS_TESTNET_PREFIX => true,
+            prefix => {
+                return Err(AddressError::Message(format!(
+                    "Invalid x-address prefix {:02x}{:02x}",
+                    prefix[0], prefix[1]
+                )))
+            }
+        };
+
+        let address = RippleAddress::from_hash160(&data[2..22])?;
+
+        // The tag is encoded as a little-endian 64-bit integer whose upper 32 bits are reserved
+        let mut tag = [0u8; 4];
+        tag.copy_from_slice(&data[23..27]);
+        let tag = u32::from_le_bytes(tag);
+        if data[27..31] != [0u8; 4] {
+            return Err(AddressError::Message(
+                "Unsupported 64-bit x-address tag".to_string(),
+            ));
+        }
+        let tag = match data[22] {
+            0 if tag == 0 => None,
+            1 => Some(tag),
+            flag => {
+                return Err(AddressError::Message(format!(
+                    "Invalid x-address tag flag {}",
+                    flag
+                )))
+            }
+        };
+
+        Ok(Self {
+            address,
+            tag,
+            is_test,
+        })
+    }
+}
+
+impl Display for RippleXAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut data = match self.is_test {
+            true => X_ADDRESS_TESTNET_PREFIX.to_vec(),
+            false => X_ADDRESS_MAINNET_PREFIX.to_vec(),
+        };
+        data.extend(self.address.to_hash160().map_err(|_| std::fmt::Error)?);
+        data.push(self.tag.is_some() as u8);
+        data.extend(self.tag.unwrap_or(0).to_le_bytes());
+        data.extend([0u8; 4]);
+        let checksum = checksum(&data)[..4].to_vec();
+        data.extend(checksum);
+
+        let encoded = to_xrp_bs58(&data.to_base58()).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", encoded)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use super::{Address, RippleAddress, RippleFormat};
+    use super::{Address, RippleAddress, RippleFormat, RippleXAddress};
 
     #[test]
     fn test_from_secret_key() {
@@ -188,7 +299,7 @@ mod tests {
 
         let sk = libsecp256k1::SecretKey::parse_slice(&sk).unwrap();
 
-        let addr = RippleAddress::from_secret_key(&sk, &RippleFormat::Standard).unwrap();
+        let addr = RippleAddress::from_secret_key(&sk.into(), &RippleFormat::Standard).unwrap();
 
         println!("addr = {}", addr);
     }
@@ -214,4 +325,46 @@ mod tests {
         assert!(addr.is_err());
         matches!(addr.unwrap_err(), super::AddressError::InvalidAddress(_));
     }
+
+    #[test]
+    fn test_x_address() {
+        let classic = RippleAddress::from_str("r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59").unwrap();
+        let vectors = [
+            (
+                None,
+                false,
+                "X7AcgcsBL6XDcUb289X4mJ8djcdyKaB5hJDWMArnXr61cqZ",
+            ),
+            (
+                Some(1),
+                false,
+                "X7AcgcsBL6XDcUb289X4mJ8djcdyKaGZMhc9YTE92ehJ2Fu",
+            ),
+            (
+                None,
+                true,
+                "T719a5UwUCnEs54UsxG9CJYYDhwmFCqkr7wxCcNcfZ6p5GZ",
+            ),
+            (
+                Some(1),
+                true,
+                "T719a5UwUCnEs54UsxG9CJYYDhwmFCvbJNZbi37gBGkRkbE",
+            ),
+        ];
+
+        for (tag, is_test, expected) in vectors {
+            let x_address = RippleXAddress::new(classic.clone(), tag, is_test);
+            assert_eq!(expected, x_address.to_string());
+            assert_eq!(x_address, RippleXAddress::from_str(expected).unwrap());
+        }
+
+        let tagged = RippleXAddress::new(classic, Some(u32::MAX), false);
+        assert_eq!(
+            tagged,
+            RippleXAddress::from_str(&tagged.to_string()).unwrap()
+        );
+        assert!(
+            RippleXAddress::from_str("X7AcgcsBL6XDcUb289X4mJ8djcdyKaB5hJDWMArnXr61cqY").is_err()
+        );
+    }
 }
@@ -0,0 +1,432 @@
+use crate::RippleAddress;
+use anychain_core::{hex, AmountError};
+use core::{fmt, str::FromStr};
+
+/// Marks an amount as being a non-XRP (issued currency) amount
+const NOT_XRP_BIT: u64 = 0x8000000000000000;
+/// Marks an amount as being positive
+const POSITIVE_BIT: u64 = 0x4000000000000000;
+
+const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+const MIN_EXPONENT: i32 = -96;
+const MAX_EXPONENT: i32 = 80;
+
+/// Represents the 160-bit code of an issued currency, which is either a
+/// standard 3-letter code like "USD" or an arbitrary nonstandard 160-bit code
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct RippleCurrency(pub [u8; 20]);
+
+impl RippleCurrency {
+    /// Returns the 3-letter code if this is a standard currency code
+    pub fn standard_code(&self) -> Option<String> {
+        let code = &self.0[12..15];
+        let is_standard = self.0[..12].iter().all(|b| *b == 0)
+            && self.0[15..].iter().all(|b| *b == 0)
+            && code.iter().all(|b| b.is_ascii_graphic());
+        match is_standard {
+            true => Some(String::from_utf8_lossy(code).to_string()),
+            false => None,
+        }
+    }
+}
+
+impl FromStr for RippleCurrency {
+    type Err = AmountError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let mut currency = [0u8; 20];
+        if code.len() == 3 && code.bytes().all(|b| b.is_ascii_graphic()) {
+            if code == "XRP" {
+                return Err(AmountError::InvalidAmount(
+                    "XRP is not an issued currency".to_string(),
+                ));
+            }
+            currency[12..15].copy_from_slice(code.as_bytes());
+        } else if code.len() == 40 {
+            let code =
+                hex::decode(code).map_err(|e| AmountError::Crate("hex", format!("{:?}", e)))?;
+            if code[0] == 0 {
+                return Err(AmountError::InvalidAmount(
+                    "nonstandard currency code should not start with 0x00".to_string(),
+                ));
+            }
+            currency.copy_from_slice(&code);
+        } else {
+            return Err(AmountError::InvalidAmount(format!(
+                "invalid currency code {}",
+                code
+            )));
+        }
+        Ok(Self(currency))
+    }
+}
+
+impl fmt::Display for RippleCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.standard_code() {
+            Some(code) => write!(f, "{}", code),
+            None => write!(f, "{}", hex::encode_upper(self.0)),
+        }
+    }
+}
+
+/// Represents the value of an issued currency amount in the canonical
+/// form of XRPL, i.e. mantissa * 10^exponent, where the mantissa has 16
+/// significant digits or is zero
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct IssuedValue {
+    pub negative: bool,
+    pub mantissa: u64,
+    pub exponent: i32,
+}
+
+impl IssuedValue {
+    pub fn zero() -> Self {
+        Self {
+            negative: false,
+            mantissa: 0,
+            exponent: 0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Returns a canonical value given a mantissa and an exponent
+    pub fn new(negative: bool, mut mantissa: u64, mut exponent: i32) -> Result<Self, AmountError> {
+        if mantissa == 0 {
+            return Ok(Self::zero());
+        }
+        while mantissa < MIN_MANTISSA {
+            mantissa *= 10;
+            exponent -= 1;
+        }
+        while mantissa > MAX_MANTISSA {
+            if !mantissa.is_multiple_of(10) {
+                return Err(AmountError::InvalidAmount(
+                    "issued amount exceeds 16 significant digits".to_string(),
+                ));
+            }
+            mantissa /= 10;
+            exponent += 1;
+        }
+        if !(MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent) {
+            return Err(AmountError::AmountOutOfBounds(
+                format!("{}e{}", mantissa, exponent),
+                "the issued amount range".to_string(),
+            ));
+        }
+        Ok(Self {
+            negative,
+            mantissa,
+            exponent,
+        })
+    }
+
+    /// Encodes the value as the leading 64 bits of an issued currency amount
+    pub fn to_u64(&self) -> u64 {
+        if self.is_zero() {
+            return NOT_XRP_BIT;
+        }
+        let sign = match self.negative {
+            true => 0,
+            false => POSITIVE_BIT,
+        };
+        NOT_XRP_BIT | sign | (((self.exponent + 97) as u64) << 54) | self.mantissa
+    }
+
+    /// Decodes the value from the leading 64 bits of an issued currency amount
+    pub fn from_u64(value: u64) -> Result<Self, AmountError> {
+        if value & NOT_XRP_BIT == 0 {
+            return Err(AmountError::InvalidAmount(
+                "not an issued currency amount".to_string(),
+            ));
+        }
+        let mantissa = value & 0x003fffffffffffff;
+        if mantissa == 0 {
+            return Ok(Self::zero());
+        }
+        let negative = value & POSITIVE_BIT == 0;
+        let exponent = ((value >> 54) & 0xff) as i32 - 97;
+        Self::new(negative, mantissa, exponent)
+    }
+}
+
+impl FromStr for IssuedValue {
+    type Err = AmountError;
+
+    /// Parses decimal strings like "-1.25", "1000" or "1.5e-7"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || AmountError::InvalidAmount(format!("invalid issued value {}", value));
+
+        let (negative, rest) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (number, mut exponent) = match rest.find(['e', 'E']) {
+            Some(index) => (
+                &rest[..index],
+                rest[index + 1..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            None => (rest, 0),
+        };
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (number, ""),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        exponent -= fraction.len() as i32;
+        let digits = format!("{}{}", integer, fraction);
+        let digits = digits.trim_start_matches('0');
+        let trimmed = digits.trim_end_matches('0');
+        exponent += (digits.len() - trimmed.len()) as i32;
+
+        if trimmed.is_empty() {
+            return Ok(Self::zero());
+        }
+        if trimmed.len() > 16 {
+            return Err(AmountError::InvalidAmount(
+                "issued amount exceeds 16 significant digits".to_string(),
+            ));
+        }
+        let mantissa = trimmed.parse::<u64>().map_err(|_| invalid())?;
+        Self::new(negative, mantissa, exponent)
+    }
+}
+
+impl fmt::Display for IssuedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut mantissa = self.mantissa;
+        let mut exponent = self.exponent;
+        while mantissa.is_multiple_of(10) {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        let digits = mantissa.to_string();
+        let sign = if self.negative { "-" } else { "" };
+
+        if exponent >= 0 {
+            write!(f, "{}{}{}", sign, digits, "0".repeat(exponent as usize))
+        } else {
+            let point = exponent.unsigned_abs() as usize;
+            if digits.len() > point {
+                let (integer, fraction) = digits.split_at(digits.len() - point);
+                write!(f, "{}{}.{}", sign, integer, fraction)
+            } else {
+                let zeros = "0".repeat(point - digits.len());
+                write!(f, "{}0.{}{}", sign, zeros, digits)
+            }
+        }
+    }
+}
+
+/// Represents an amount of either XRP in drops, or an issued currency
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum RippleAmount {
+    Xrp(u64),
+    Issued {
+        value: IssuedValue,
+        currency: RippleCurrency,
+        issuer: [u8; 20],
+    },
+}
+
+impl Default for RippleAmount {
+    fn default() -> Self {
+        Self::Xrp(0)
+    }
+}
+
+impl From<u64> for RippleAmount {
+    fn from(drops: u64) -> Self {
+        Self::Xrp(drops)
+    }
+}
+
+impl RippleAmount {
+    /// Returns an issued currency amount, e.g. ("1.5", "USD", issuer)
+    pub fn issued(
+        value: &str,
+        currency: &str,
+        issuer: &RippleAddress,
+    ) -> Result<Self, AmountError> {
+        let issuer = issuer
+            .to_hash160()
+            .map_err(|e| AmountError::Crate("address", e.to_string()))?;
+        Ok(Self::Issued {
+            value: IssuedValue::from_str(value)?,
+            currency: RippleCurrency::from_str(currency)?,
+            issuer,
+        })
+    }
+
+    pub fn is_xrp(&self) -> bool {
+        matches!(self, Self::Xrp(_))
+    }
+
+    /// Returns the amount in drops if it is an amount of XRP
+    pub fn drops(&self) -> Option<u64> {
+        match self {
+            Self::Xrp(drops) => Some(*drops),
+            _ => None,
+        }
+    }
+
+    /// Serializes the amount to 64 bits for XRP, or to 384 bits for issued currencies
+    pub fn serialize(&self) -> Vec<u8> {
+        match self {
+            Self::Xrp(drops) => (drops | POSITIVE_BIT).to_be_bytes().to_vec(),
+            Self::Issued {
+                value,
+                currency,
+                issuer,
+            } => [
+                value.to_u64().to_be_bytes().as_slice(),
+                currency.0.as_slice(),
+                issuer.as_slice(),
+            ]
+            .concat(),
+        }
+    }
+
+    /// Deserializes an amount, consuming 8 or 48 bytes of the stream
+    pub fn deserialize(stream: &mut &[u8]) -> Result<Self, AmountError> {
+        let take = |stream: &mut &[u8], len: usize| -> Result<Vec<u8>, AmountError> {
+            if stream.len() < len {
+                return Err(AmountError::InvalidAmount(
+                    "unexpected end of amount".to_string(),
+                ));
+            }
+            let (taken, rest) = stream.split_at(len);
+            *stream = rest;
+            Ok(taken.to_vec())
+        };
+
+        let mut head = [0u8; 8];
+        head.copy_from_slice(&take(stream, 8)?);
+        let head = u64::from_be_bytes(head);
+
+        if head & NOT_XRP_BIT == 0 {
+            if head & POSITIVE_BIT == 0 && head != 0 {
+                return Err(AmountError::InvalidAmount(
+                    "negative XRP amount".to_string(),
+                ));
+            }
+            return Ok(Self::Xrp(head & !POSITIVE_BIT));
+        }
+
+        let value = IssuedValue::from_u64(head)?;
+        let mut currency = [0u8; 20];
+        currency.copy_from_slice(&take(stream, 20)?);
+        let mut issuer = [0u8; 20];
+        issuer.copy_from_slice(&take(stream, 20)?);
+
+        Ok(Self::Issued {
+            value,
+            currency: RippleCurrency(currency),
+            issuer,
+        })
+    }
+}
+
+impl fmt::Display for RippleAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xrp(drops) => write!(f, "{}", drops),
+            Self::Issued {
+                value,
+                currency,
+                issuer,
+            } => match RippleAddress::from_hash160(issuer) {
+                Ok(issuer) => write!(f, "{}/{}/{}", value, currency, issuer),
+                Err(_) => Err(fmt::Error),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issued_value() {
+        let cases = [
+            ("1", "1", 0xd4838d7ea4c68000),
+            ("-1", "-1", 0x94838d7ea4c68000),
+            ("0", "0", 0x8000000000000000),
+            ("1.5", "1.5", 0xd485543df729c000),
+            ("0.001", "0.001", 0xd3c38d7ea4c68000),
+            ("1.2e3", "1200", 0xd5444364c5bb0000),
+            ("00123.4500", "123.45", 0xd50462c56df9a800),
+        ];
+        for (input, output, encoded) in cases {
+            let value = IssuedValue::from_str(input).unwrap();
+            assert_eq!(output, value.to_string());
+            assert_eq!(encoded, value.to_u64(), "{}", input);
+            assert_eq!(value, IssuedValue::from_u64(encoded).unwrap());
+        }
+
+        assert!(IssuedValue::from_str("1.23456789012345678").is_err());
+        assert!(IssuedValue::from_str("1e200").is_err());
+        assert!(IssuedValue::from_str("abc").is_err());
+    }
+
+    #[test]
+    fn test_currency() {
+        let usd = RippleCurrency::from_str("USD").unwrap();
+        assert_eq!(
+            "0000000000000000000000005553440000000000",
+            hex::encode(usd.0)
+        );
+        assert_eq!("USD", usd.to_string());
+
+        let rlusd = "524C555344000000000000000000000000000000";
+        let currency = RippleCurrency::from_str(rlusd).unwrap();
+        assert_eq!(rlusd, currency.to_string());
+
+        assert!(RippleCurrency::from_str("XRP").is_err());
+        assert!(RippleCurrency::from_str("USDT").is_err());
+    }
+
+    #[test]
+    fn test_amount_round_trip() {
+        let issuer = RippleAddress::from_str("rvYAfWj5gh67oV6fW32ZzP3Aw4Eubs59B").unwrap();
+        let amount = RippleAmount::issued("1234.5678", "USD", &issuer).unwrap();
+        let stream = amount.serialize();
+        assert_eq!(48, stream.len());
+
+        let mut slice = stream.as_slice();
+        assert_eq!(amount, RippleAmount::deserialize(&mut slice).unwrap());
+        assert!(slice.is_empty());
+        assert_eq!(
+            "1234.5678/USD/rvYAfWj5gh67oV6fW32ZzP3Aw4Eubs59B",
+            amount.to_string()
+        );
+
+        let xrp = RippleAmount::Xrp(100000000);
+        assert_eq!("4000000005f5e100", hex::encode(xrp.serialize()));
+        let stream = xrp.serialize();
+        assert_eq!(
+            xrp,
+            RippleAmount::deserialize(&mut stream.as_slice()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,1321 @@
+//! The XRPL binary codec, which converts between the canonical JSON form
+//! of a transaction and its binary serialization by consulting the field
+//! definitions table
+
+use std::cmp::{Ord, Ordering};
+
+use crate::definitions::{
+    field_by_code, field_by_name, transaction_type_code, transaction_type_name, FieldDefinition,
+    SerializedTypeID,
+};
+use crate::{IssuedValue, RippleAddress, RippleAmount, RippleCurrency};
+use anychain_core::{hex, no_std::io::Read, TransactionError};
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
+
+/// Represents an issue, i.e. XRP or a currency along with its issuer
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct RippleIssue {
+    /// 160 zero bits represent XRP
+    pub currency: [u8; 20],
+    pub issuer: Option<[u8; 20]>,
+}
+
+/// Represents a step of a payment path
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct PathStep {
+    pub account: Option<[u8; 20]>,
+    pub currency: Option<[u8; 20]>,
+    pub issuer: Option<[u8; 20]>,
+}
+
+const PATH_STEP_ACCOUNT: u8 = 0x01;
+const PATH_STEP_CURRENCY: u8 = 0x10;
+const PATH_STEP_ISSUER: u8 = 0x20;
+const PATH_SEPARATOR: u8 = 0xff;
+const PATHSET_END: u8 = 0x00;
+
+#[derive(Clone, Debug, Eq)]
+pub enum SerializedType {
+    Account {
+        field_value: u32,
+        account_id: [u8; 20],
+    },
+
+    Amount {
+        field_value: u32,
+        amount: RippleAmount,
+    },
+
+    Uint8 {
+        field_value: u32,
+        value: u8,
+    },
+
+    Uint16 {
+        field_value: u32,
+        value: u16,
+    },
+
+    Uint32 {
+        field_value: u32,
+        value: u32,
+    },
+
+    Uint64 {
+        field_value: u32,
+        value: u64,
+    },
+
+    Int32 {
+        field_value: u32,
+        value: i32,
+    },
+
+    Int64 {
+        field_value: u32,
+        value: i64,
+    },
+
+    /// A decimal of `mantissa` times 10 to the `exponent`
+    Number {
+        field_value: u32,
+        mantissa: i64,
+        exponent: i32,
+    },
+
+    /// The fixed-width types, i.e. Hash128/160/192/256 and UInt96/384/512
+    Hash {
+        typ: SerializedTypeID,
+        field_value: u32,
+        buffer: Vec<u8>,
+    },
+
+    Blob {
+        field_value: u32,
+        buffer: Vec<u8>,
+    },
+
+    Currency {
+        field_value: u32,
+        currency: [u8; 20],
+    },
+
+    Issue {
+        field_value: u32,
+        issue: RippleIssue,
+    },
+
+    XChainBridge {
+        field_value: u32,
+        locking_chain_door: [u8; 20],
+        locking_chain_issue: RippleIssue,
+        issuing_chain_door: [u8; 20],
+        issuing_chain_issue: RippleIssue,
+    },
+
+    PathSet {
+        field_value: u32,
+        paths: Vec<Vec<PathStep>>,
+    },
+
+    Vector256 {
+        field_value: u32,
+        hashes: Vec<[u8; 32]>,
+    },
+
+    Array {
+        field_value: u32,
+        elems: Vec<SerializedType>,
+    },
+
+    Object {
+        field_value: u32,
+        members: Vec<SerializedType>,
+    },
+}
+
+impl PartialEq for SerializedType {
+    fn eq(&self, other: &Self) -> bool {
+        let typ0 = self.typ() as u32;
+        let val0 = self.val();
+        let typ1 = other.typ() as u32;
+        let val1 = other.val();
+
+        typ0 == typ1 && val0 == val1
+    }
+}
+
+impl Ord for SerializedType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let typ0 = self.typ() as u32;
+        let val0 = self.val();
+        let typ1 = other.typ() as u32;
+        let val1 = other.val();
+
+        if typ0 < typ1 {
+            Ordering::Less
+        } else if typ0 > typ1 {
+            Ordering::Greater
+        } else if val0 < val1 {
+            Ordering::Less
+        } else if val0 > val1 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+impl PartialOrd for SerializedType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn serialize_len(mut len: u32) -> Result<Vec<u8>, TransactionError> {
+    if len <= 192 {
+        Ok(vec![len as u8])
+    } else if len <= 12480 {
+        len -= 193;
+        let b0 = 193 + (len >> 8) as u8;
+        let b1 = (len & 0xff) as u8;
+        Ok(vec![b0, b1])
+    } else if len <= 918744 {
+        len -= 12481;
+        let b0 = 241 + (len >> 16) as u8;
+        let b1 = ((len >> 8) & 0xff) as u8;
+        let b2 = (len & 0xff) as u8;
+        Ok(vec![b0, b1, b2])
+    } else {
+        Err(TransactionError::Message(
+            "Maximum length exceeded".to_string(),
+        ))
+    }
+}
+
+fn deserialize_len(stream: &mut &[u8]) -> Result<u32, TransactionError> {
+    let mut b = [0u8; 1];
+    let _ = stream.read(&mut b)?;
+
+    match b[0] {
+        (..=192) => Ok(b[0] as u32),
+        (193..=240) => {
+            let mut buf = [0u8; 1];
+            let _ = stream.read(&mut buf)?;
+            Ok(193 + (b[0] as u32 - 193) * 256 + buf[0] as u32)
+        }
+        (241..=254) => {
+            let mut buf = [0u8; 2];
+            let _ = stream.read(&mut buf)?;
+            Ok(12481 + (b[0] as u32 - 241) * 65536 + buf[0] as u32 * 256 + buf[1] as u32)
+        }
+        255 => Err(TransactionError::Message(
+            "Invalid length prefix".to_string(),
+        )),
+    }
+}
+
+pub(crate) fn serialize_type(typ: SerializedTypeID, val: u32) -> Result<Vec<u8>, TransactionError> {
+    let typ = typ as u32;
+
+    if !(..256).contains(&typ) || !(..256).contains(&val) {
+        return Err(TransactionError::Message("Number out of range".to_string()));
+    }
+
+    if typ < 16 {
+        if val < 16 {
+            // common type, common val
+            Ok(vec![((typ << 4) | val) as u8])
+        } else {
+            // common type, uncommon val
+            let b0 = (typ << 4) as u8;
+            let b1 = val as u8;
+            Ok(vec![b0, b1])
+        }
+    } else if val < 16 {
+        // uncommon type, common val
+        let b0 = val as u8;
+        let b1 = typ as u8;
+        Ok(vec![b0, b1])
+    } else {
+        // uncommon type, uncommon val
+        let b0 = 0;
+        let b1 = typ as u8;
+        let b2 = val as u8;
+        Ok(vec![b0, b1, b2])
+    }
+}
+
+fn deserialize_type(stream: &mut &[u8]) -> Result<(u8, u8), TransactionError> {
+    let mut b = [0u8; 1];
+    let _ = stream.read(&mut b)?;
+
+    match (b[0] & 0xf0 == 0, b[0] & 0x0f == 0) {
+        // both higher and lower 4 bits are zero
+        (true, true) => {
+            let mut buf = [0u8; 2];
+            let _ = stream.read(&mut buf)?;
+            Ok((buf[0], buf[1]))
+        }
+        // only higher 4 bits are zero
+        (true, false) => {
+            let mut buf = [0u8; 1];
+            let _ = stream.read(&mut buf)?;
+            Ok((buf[0], b[0]))
+        }
+        // only lower 4 bits are zero
+        (false, true) => {
+            let mut buf = [0u8; 1];
+            let _ = stream.read(&mut buf)?;
+            Ok((b[0] >> 4, buf[0]))
+        }
+        // neither higher 4 bits nor lower 4 bits are zero
+        (false, false) => Ok(((b[0] & 0xf0) >> 4, b[0] & 0x0f)),
+    }
+}
+
+/// Reads exactly `len` bytes from the stream
+fn read_exact(stream: &mut &[u8], len: usize) -> Result<Vec<u8>, TransactionError> {
+    if stream.len() < len {
+        return Err(TransactionError::Message(
+            "Unexpected end of stream".to_string(),
+        ));
+    }
+    let (taken, rest) = stream.split_at(len);
+    *stream = rest;
+    Ok(taken.to_vec())
+}
+
+fn read_array<const N: usize>(stream: &mut &[u8]) -> Result<[u8; N], TransactionError> {
+    let mut buf = [0u8; N];
+    buf.copy_from_slice(&read_exact(stream, N)?);
+    Ok(buf)
+}
+
+/// Reads a length-prefixed account id of a bridge door
+fn deserialize_door(stream: &mut &[u8]) -> Result<[u8; 20], TransactionError> {
+    match deserialize_len(stream)? {
+        20 => read_array::<20>(stream),
+        len => Err(TransactionError::Message(format!(
+            "Invalid account length {}",
+            len,
+        ))),
+    }
+}
+
+impl RippleIssue {
+    pub fn xrp() -> Self {
+        Self {
+            currency: [0u8; 20],
+            issuer: None,
+        }
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut stream = self.currency.to_vec();
+        if let Some(issuer) = &self.issuer {
+            stream.extend(issuer);
+        }
+        stream
+    }
+
+    fn deserialize(stream: &mut &[u8]) -> Result<Self, TransactionError> {
+        let currency = read_array::<20>(stream)?;
+        let issuer = match currency == [0u8; 20] {
+            true => None,
+            false => Some(read_array::<20>(stream)?),
+        };
+        Ok(Self { currency, issuer })
+    }
+
+    fn to_json(self) -> Result<Value, TransactionError> {
+        let mut map = Map::new();
+        map.insert("currency".to_string(), currency_to_json(&self.currency));
+        if let Some(issuer) = &self.issuer {
+            map.insert("issuer".to_string(), account_to_json(issuer)?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, TransactionError> {
+        let currency = currency_from_json(value.get("currency").unwrap_or(&Value::Null))?;
+        let issuer = match currency == [0u8; 20] {
+            true => None,
+            false => Some(account_from_json(
+                value.get("issuer").unwrap_or(&Value::Null),
+            )?),
+        };
+        Ok(Self { currency, issuer })
+    }
+}
+
+pub(crate) fn account_to_json(account_id: &[u8; 20]) -> Result<Value, TransactionError> {
+    Ok(Value::String(
+        RippleAddress::from_hash160(account_id)?.to_string(),
+    ))
+}
+
+pub(crate) fn account_from_json(value: &Value) -> Result<[u8; 20], TransactionError> {
+    let address = value
+        .as_str()
+        .ok_or_else(|| TransactionError::Message(format!("Invalid account {}", value)))?;
+    Ok(RippleAddress::from_str(address)?.to_hash160()?)
+}
+
+fn currency_to_json(currency: &[u8; 20]) -> Value {
+    match *currency == [0u8; 20] {
+        true => Value::String("XRP".to_string()),
+        false => Value::String(RippleCurrency(*currency).to_string()),
+    }
+}
+
+fn currency_from_json(value: &Value) -> Result<[u8; 20], TransactionError> {
+    match value.as_str() {
+        Some("XRP") => Ok([0u8; 20]),
+        Some(code) => Ok(RippleCurrency::from_str(code)?.0),
+        None => Err(TransactionError::Message(format!(
+            "Invalid currency {}",
+            value
+        ))),
+    }
+}
+
+pub(crate) fn hex_from_json(value: &Value) -> Result<Vec<u8>, TransactionError> {
+    match value.as_str() {
+        Some(s) => Ok(hex::decode(s)?),
+        None => Err(TransactionError::Message(format!(
+            "Invalid hex string {}",
+            value
+        ))),
+    }
+}
+
+pub(crate) fn int_from_json<T: TryFrom<i64>>(value: &Value) -> Result<T, TransactionError> {
+    let n = match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse::<i64>().ok(),
+        _ => None,
+    };
+    n.and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| TransactionError::Message(format!("Invalid integer {}", value)))
+}
+
+/// Formats a number as xrpl.js does, in decimal notation if its exponent is
+/// within -25 and -5 and in scientific notation otherwise
+fn number_to_json(mantissa: i64, exponent: i32) -> String {
+    if mantissa == 0 {
+        return "0".to_string();
+    }
+    if exponent == 0 {
+        return mantissa.to_string();
+    }
+    if !(-25..=-5).contains(&exponent) {
+        return format!("{}e{}", mantissa, exponent);
+    }
+    let sign = if mantissa < 0 { "-" } else { "" };
+    let digits = mantissa.unsigned_abs().to_string();
+    let point = digits.len() as i32 + exponent;
+    let (int, frac) = match point > 0 {
+        true => (
+            digits[..point as usize].to_string(),
+            digits[point as usize..].to_string(),
+        ),
+        false => ("0".to_string(), "0".repeat(-point as usize) + &digits),
+    };
+    match frac.trim_end_matches('0') {
+        "" => format!("{}{}", sign, int),
+        frac => format!("{}{}.{}", sign, int, frac),
+    }
+}
+
+/// Parses a decimal string such as "-1.5" or "15e-1" into a mantissa of 16
+/// digits and an exponent, which is `i32::MIN` for zero
+fn number_from_json(value: &Value) -> Result<(i64, i32), TransactionError> {
+    let invalid = || TransactionError::Message(format!("Invalid number {}", value));
+    let s = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) if n.is_i64() || n.is_u64() => n.to_string(),
+        _ => return Err(invalid()),
+    };
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(&s)),
+    };
+    let (s, mut exponent) = match s.split_once(['e', 'E']) {
+        Some((s, exp)) => (s, exp.parse::<i64>().map_err(|_| invalid())?),
+        None => (s, 0),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if int.is_empty() || !(int.bytes().chain(frac.bytes())).all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    exponent -= frac.len() as i64;
+    let digits = format!("{}{}", int, frac);
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    exponent += (digits.len() - trimmed.len()) as i64;
+    if trimmed.is_empty() {
+        return Ok((0, i32::MIN));
+    }
+    if trimmed.len() > 16 {
+        return Err(TransactionError::Message(format!(
+            "Number {} has more than 16 significant digits",
+            value
+        )));
+    }
+    let mut mantissa = trimmed.parse::<i64>().map_err(|_| invalid())?;
+    while mantissa < 1_000_000_000_000_000 {
+        mantissa *= 10;
+        exponent -= 1;
+    }
+    let exponent = i32::try_from(exponent).map_err(|_| invalid())?;
+    Ok((if negative { -mantissa } else { mantissa }, exponent))
+}
+
+pub(crate) fn amount_to_json(amount: &RippleAmount) -> Result<Value, TransactionError> {
+    match amount {
+        RippleAmount::Xrp(drops) => Ok(Value::String(drops.to_string())),
+        RippleAmount::Issued {
+            value,
+            currency,
+            issuer,
+        } => Ok(json!({
+            "currency": currency.to_string(),
+            "issuer": account_to_json(issuer)?,
+            "value": value.to_string(),
+        })),
+    }
+}
+
+pub(crate) fn amount_from_json(value: &Value) -> Result<RippleAmount, TransactionError> {
+    match value {
+        Value::String(drops) => Ok(RippleAmount::Xrp(drops.parse::<u64>()?)),
+        Value::Object(map) => {
+            let field = |name: &str| map.get(name).unwrap_or(&Value::Null);
+            let amount = field("value")
+                .as_str()
+                .ok_or_else(|| TransactionError::Message(format!("Invalid amount {}", value)))?;
+            Ok(RippleAmount::Issued {
+                value: IssuedValue::from_str(amount)?,
+                currency: RippleCurrency(currency_from_json(field("currency"))?),
+                issuer: account_from_json(field("issuer"))?,
+            })
+        }
+        _ => Err(TransactionError::Message(format!(
+            "Invalid amount {}",
+            value
+        ))),
+    }
+}
+
+impl SerializedType {
+    pub fn typ(&self) -> SerializedTypeID {
+        match self {
+            Self::Account { .. } => SerializedTypeID::Account,
+            Self::Amount { .. } => SerializedTypeID::Amount,
+            Self::Uint8 { .. } => SerializedTypeID::Uint8,
+            Self::Uint16 { .. } => SerializedTypeID::Uint16,
+            Self::Uint32 { .. } => SerializedTypeID::Uint32,
+            Self::Uint64 { .. } => SerializedTypeID::Uint64,
+            Self::Int32 { .. } => SerializedTypeID::Int32,
+            Self::Int64 { .. } => SerializedTypeID::Int64,
+            Self::Number { .. } => SerializedTypeID::Number,
+            Self::Hash { typ, .. } => *typ,
+            Self::Blob { .. } => SerializedTypeID::VL,
+            Self::Currency { .. } => SerializedTypeID::Currency,
+            Self::Issue { .. } => SerializedTypeID::Issue,
+            Self::XChainBridge { .. } => SerializedTypeID::XChainBridge,
+            Self::PathSet { .. } => SerializedTypeID::PathSet,
+            Self::Vector256 { .. } => SerializedTypeID::Vector256,
+            Self::Array { .. } => SerializedTypeID::Array,
+            Self::Object { .. } => SerializedTypeID::Object,
+        }
+    }
+
+    pub fn val(&self) -> u32 {
+        match self {
+            Self::Account { field_value, .. }
+            | Self::Amount { field_value, .. }
+            | Self::Uint8 { field_value, .. }
+            | Self::Uint16 { field_value, .. }
+            | Self::Uint32 { field_value, .. }
+            | Self::Uint64 { field_value, .. }
+            | Self::Int32 { field_value, .. }
+            | Self::Int64 { field_value, .. }
+            | Self::Number { field_value, .. }
+            | Self::Hash { field_value, .. }
+            | Self::Blob { field_value, .. }
+            | Self::Currency { field_value, .. }
+            | Self::Issue { field_value, .. }
+            | Self::XChainBridge { field_value, .. }
+            | Self::PathSet { field_value, .. }
+            | Self::Vector256 { field_value, .. }
+            | Self::Array { field_value, .. }
+            | Self::Object { field_value, .. } => *field_value,
+        }
+    }
+
+    /// Returns the definition of the field this ST represents
+    pub fn field(&self) -> Result<&'static FieldDefinition, TransactionError> {
+        field_by_code(self.typ(), self.val())
+    }
+
+    fn serialize_type(&self) -> Result<Vec<u8>, TransactionError> {
+        serialize_type(self.typ(), self.val())
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, TransactionError> {
+        match self {
+            Self::Amount { amount, .. } => Ok(amount.serialize()),
+            Self::Uint8 { value, .. } => Ok(vec![*value]),
+            Self::Uint16 { value, .. } => Ok(value.to_be_bytes().to_vec()),
+            Self::Uint32 { value, .. } => Ok(value.to_be_bytes().to_vec()),
+            Self::Uint64 { value, .. } => Ok(value.to_be_bytes().to_vec()),
+            Self::Int32 { value, .. } => Ok(value.to_be_bytes().to_vec()),
+            Self::Int64 { value, .. } => Ok(value.to_be_bytes().to_vec()),
+            Self::Number {
+                mantissa, exponent, ..
+            } => Ok([&mantissa.to_be_bytes()[..], &exponent.to_be_bytes()].concat()),
+            Self::Hash { typ, buffer, .. } => {
+                if Some(buffer.len()) != typ.fixed_size() {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid {} length {}",
+                        typ,
+                        buffer.len()
+                    )));
+                }
+                Ok(buffer.clone())
+            }
+            Self::Account { account_id, .. } => {
+                let mut stream = serialize_len(account_id.len() as u32)?;
+                stream.extend(account_id.to_vec());
+                Ok(stream)
+            }
+            Self::Blob { buffer, .. } => {
+                let mut stream = serialize_len(buffer.len() as u32)?;
+                stream.extend(buffer);
+                Ok(stream)
+            }
+            Self::Currency { currency, .. } => Ok(currency.to_vec()),
+            Self::Issue { issue, .. } => Ok(issue.serialize()),
+            Self::XChainBridge {
+                locking_chain_door,
+                locking_chain_issue,
+                issuing_chain_door,
+                issuing_chain_issue,
+                ..
+            } => {
+                let mut stream = serialize_len(20)?;
+                stream.extend(locking_chain_door);
+                stream.extend(locking_chain_issue.serialize());
+                stream.extend(serialize_len(20)?);
+                stream.extend(issuing_chain_door);
+                stream.extend(issuing_chain_issue.serialize());
+                Ok(stream)
+            }
+            Self::PathSet { paths, .. } => {
+                let mut stream = vec![];
+                for (i, path) in paths.iter().enumerate() {
+                    if i > 0 {
+                        stream.push(PATH_SEPARATOR);
+                    }
+                    for step in path {
+                        let mut typ = 0;
+                        let mut body: Vec<u8> = vec![];
+                        if let Some(account) = &step.account {
+                            typ |= PATH_STEP_ACCOUNT;
+                            body.extend(account);
+                        }
+                        if let Some(currency) = &step.currency {
+                            typ |= PATH_STEP_CURRENCY;
+                            body.extend(currency);
+                        }
+                        if let Some(issuer) = &step.issuer {
+                            typ |= PATH_STEP_ISSUER;
+                            body.extend(issuer);
+                        }
+                        stream.push(typ);
+                        stream.extend(body);
+                    }
+                }
+                stream.push(PATHSET_END);
+                Ok(stream)
+            }
+            Self::Vector256 { hashes, .. } => {
+                let mut stream = serialize_len(hashes.len() as u32 * 32)?;
+                for hash in hashes {
+                    stream.extend(hash);
+                }
+                Ok(stream)
+            }
+            Self::Array { elems, .. } => {
+                let mut stream = vec![];
+                for elem in elems {
+                    stream.extend(elem.serialize_type()?);
+                    stream.extend(elem.serialize()?);
+                    stream.extend(serialize_type(SerializedTypeID::Object, 1)?);
+                }
+                Ok(stream)
+            }
+            Self::Object { members, .. } => {
+                let mut stream = vec![];
+                for mem in members {
+                    stream.extend(mem.serialize_type()?);
+                    stream.extend(mem.serialize()?);
+                    match mem.typ() {
+                        SerializedTypeID::Array | SerializedTypeID::Object => {
+                            stream.extend(serialize_type(mem.typ(), 1)?)
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(stream)
+            }
+        }
+    }
+
+    pub fn deserialize(
+        stream: &mut &[u8],
+        typ: SerializedTypeID,
+        field_value: u32,
+    ) -> Result<Self, TransactionError> {
+        match typ {
+            SerializedTypeID::Account => {
+                // Firstly we extract the length of the account id
+                let len = deserialize_len(stream)?;
+                if len != 20 {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid account length {}",
+                        len,
+                    )));
+                }
+
+                // Then we extract the account id
+                let account_id = read_array::<20>(stream)?;
+
+                // Return the ST
+                Ok(SerializedType::Account {
+                    field_value,
+                    account_id,
+                })
+            }
+            SerializedTypeID::Amount => {
+                // An XRP amount takes 8 bytes while an issued currency amount takes 48 bytes
+                let amount = RippleAmount::deserialize(stream)?;
+
+                // Return the ST
+                Ok(SerializedType::Amount {
+                    field_value,
+                    amount,
+                })
+            }
+            SerializedTypeID::Uint8 => {
+                let value = read_array::<1>(stream)?[0];
+                Ok(SerializedType::Uint8 { field_value, value })
+            }
+            SerializedTypeID::Uint16 => {
+                let value = u16::from_be_bytes(read_array::<2>(stream)?);
+                Ok(SerializedType::Uint16 { field_value, value })
+            }
+            SerializedTypeID::Uint32 => {
+                let value = u32::from_be_bytes(read_array::<4>(stream)?);
+                Ok(SerializedType::Uint32 { field_value, value })
+            }
+            SerializedTypeID::Uint64 => {
+                let value = u64::from_be_bytes(read_array::<8>(stream)?);
+                Ok(SerializedType::Uint64 { field_value, value })
+            }
+            SerializedTypeID::Int32 => {
+                let value = i32::from_be_bytes(read_array::<4>(stream)?);
+                Ok(SerializedType::Int32 { field_value, value })
+            }
+            SerializedTypeID::Int64 => {
+                let value = i64::from_be_bytes(read_array::<8>(stream)?);
+                Ok(SerializedType::Int64 { field_value, value })
+            }
+            SerializedTypeID::Hash128
+            | SerializedTypeID::Hash160
+            | SerializedTypeID::Hash192
+            | SerializedTypeID::Hash256
+            | SerializedTypeID::Uint96
+            | SerializedTypeID::Uint384
+            | SerializedTypeID::Uint512 => {
+                let len = typ.fixed_size().unwrap_or_default();
+                let buffer = read_exact(stream, len)?;
+                Ok(SerializedType::Hash {
+                    typ,
+                    field_value,
+                    buffer,
+                })
+            }
+            SerializedTypeID::Number => {
+                let mantissa = i64::from_be_bytes(read_array::<8>(stream)?);
+                let exponent = i32::from_be_bytes(read_array::<4>(stream)?);
+                Ok(SerializedType::Number {
+                    field_value,
+                    mantissa,
+                    exponent,
+                })
+            }
+            SerializedTypeID::VL => {
+                // Firstly we extract the length of the blob
+                let len = deserialize_len(stream)?;
+
+                // Then we extract the blob according to its length
+                let buffer = read_exact(stream, len as usize)?;
+
+                // Return the ST
+                Ok(SerializedType::Blob {
+                    field_value,
+                    buffer,
+                })
+            }
+            SerializedTypeID::Currency => Ok(SerializedType::Currency {
+                field_value,
+                currency: read_array::<20>(stream)?,
+            }),
+            SerializedTypeID::Issue => Ok(SerializedType::Issue {
+                field_value,
+                issue: RippleIssue::deserialize(stream)?,
+            }),
+            SerializedTypeID::XChainBridge => {
+                let locking_chain_door = deserialize_door(stream)?;
+                let locking_chain_issue = RippleIssue::deserialize(stream)?;
+                let issuing_chain_door = deserialize_door(stream)?;
+                let issuing_chain_issue = RippleIssue::deserialize(stream)?;
+                Ok(SerializedType::XChainBridge {
+                    field_value,
+                    locking_chain_door,
+                    locking_chain_issue,
+                    issuing_chain_door,
+                    issuing_chain_issue,
+                })
+            }
+            SerializedTypeID::PathSet => {
+                let mut paths = vec![];
+                let mut path = vec![];
+                loop {
+                    let typ = read_array::<1>(stream)?[0];
+                    match typ {
+                        PATHSET_END => {
+                            paths.push(path);
+                            break;
+                        }
+                        PATH_SEPARATOR => paths.push(std::mem::take(&mut path)),
+                        _ => {
+                            let mut step = PathStep {
+                                account: None,
+                                currency: None,
+                                issuer: None,
+                            };
+                            if typ & PATH_STEP_ACCOUNT != 0 {
+                                step.account = Some(read_array::<20>(stream)?);
+                            }
+                            if typ & PATH_STEP_CURRENCY != 0 {
+                                step.currency = Some(read_array::<20>(stream)?);
+                            }
+                            if typ & PATH_STEP_ISSUER != 0 {
+                                step.issuer = Some(read_array::<20>(stream)?);
+                            }
+                            path.push(step);
+                        }
+                    }
+                }
+                Ok(SerializedType::PathSet { field_value, paths })
+            }
+            SerializedTypeID::Vector256 => {
+                let len = deserialize_len(stream)? as usize;
+                if !len.is_multiple_of(32) {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid Vector256 length {}",
+                        len,
+                    )));
+                }
+                let mut hashes = vec![];
+                for _ in 0..len / 32 {
+                    hashes.push(read_array::<32>(stream)?);
+                }
+                Ok(SerializedType::Vector256 {
+                    field_value,
+                    hashes,
+                })
+            }
+            SerializedTypeID::Array => {
+                let mut array = SerializedType::Array {
+                    field_value,
+                    elems: vec![],
+                };
+                loop {
+                    let (typ, fv) = deserialize_type(stream)?;
+                    let typ = SerializedTypeID::from_u8(typ)?;
+
+                    // we have reached the end of the array
+                    if typ == SerializedTypeID::Array && fv == 1 {
+                        break;
+                    }
+
+                    let st = Self::deserialize(stream, typ, fv as u32)?;
+                    array.add(st)?;
+                }
+                Ok(array)
+            }
+            SerializedTypeID::Object => {
+                let mut obj = SerializedType::Object {
+                    field_value,
+                    members: vec![],
+                };
+                loop {
+                    let (typ, fv) = deserialize_type(stream)?;
+                    let typ = SerializedTypeID::from_u8(typ)?;
+
+                    // we have reached the end of the object
+                    if typ == SerializedTypeID::Object && fv == 1 {
+                        break;
+                    }
+
+                    let st = Self::deserialize(stream, typ, fv as u32)?;
+                    obj.add(st)?;
+                }
+                Ok(obj)
+            }
+        }
+    }
+
+    pub fn add(&mut self, st: SerializedType) -> Result<(), TransactionError> {
+        match self {
+            SerializedType::Object { members, .. } => {
+                members.push(st);
+                Ok(())
+            }
+            SerializedType::Array { elems, .. } => {
+                elems.push(st);
+                Ok(())
+            }
+            _ => Err(TransactionError::Message(
+                "Adding fields to neither an object or an array".to_string(),
+            )),
+        }
+    }
+
+    pub fn sort(&mut self) -> Result<(), TransactionError> {
+        match self {
+            SerializedType::Object { members, .. } => {
+                members.sort();
+                Ok(())
+            }
+            _ => Err(TransactionError::Message(
+                "Sorting non-object ST".to_string(),
+            )),
+        }
+    }
+
+    /// Converts the value of this ST to its JSON form
+    pub fn to_json(&self) -> Result<Value, TransactionError> {
+        match self {
+            Self::Account { account_id, .. } => account_to_json(account_id),
+            Self::Amount { amount, .. } => amount_to_json(amount),
+            Self::Uint8 { value, .. } => Ok(json!(value)),
+            Self::Uint16 { field_value, value } => {
+                if self.field()?.name == "TransactionType" && *field_value == 2 {
+                    Ok(json!(transaction_type_name(*value)?))
+                } else {
+                    Ok(json!(value))
+                }
+            }
+            Self::Uint32 { value, .. } => Ok(json!(value)),
+            Self::Uint64 { value, .. } => Ok(json!(format!("{:016X}", value))),
+            Self::Int32 { value, .. } => Ok(json!(value)),
+            Self::Int64 { value, .. } => Ok(json!(value.to_string())),
+            Self::Number {
+                mantissa, exponent, ..
+            } => Ok(json!(number_to_json(*mantissa, *exponent))),
+            Self::Hash { buffer, .. } | Self::Blob { buffer, .. } => {
+                Ok(json!(hex::encode_upper(buffer)))
+            }
+            Self::Currency { currency, .. } => Ok(currency_to_json(currency)),
+            Self::Issue { issue, .. } => issue.to_json(),
+            Self::XChainBridge {
+                locking_chain_door,
+                locking_chain_issue,
+                issuing_chain_door,
+                issuing_chain_issue,
+                ..
+            } => Ok(json!({
+                "LockingChainDoor": account_to_json(locking_chain_door)?,
+                "LockingChainIssue": locking_chain_issue.to_json()?,
+                "IssuingChainDoor": account_to_json(issuing_chain_door)?,
+                "IssuingChainIssue": issuing_chain_issue.to_json()?,
+            })),
+            Self::PathSet { paths, .. } => {
+                let mut json_paths = vec![];
+                for path in paths {
+                    let mut json_path = vec![];
+                    for step in path {
+                        let mut map = Map::new();
+                        if let Some(account) = &step.account {
+                            map.insert("account".to_string(), account_to_json(account)?);
+                        }
+                        if let Some(currency) = &step.currency {
+                            map.insert("currency".to_string(), currency_to_json(currency));
+                        }
+                        if let Some(issuer) = &step.issuer {
+                            map.insert("issuer".to_string(), account_to_json(issuer)?);
+                        }
+                        json_path.push(Value::Object(map));
+                    }
+                    json_paths.push(Value::Array(json_path));
+                }
+                Ok(Value::Array(json_paths))
+            }
+            Self::Vector256 { hashes, .. } => Ok(Value::Array(
+                hashes
+                    .iter()
+                    .map(|hash| json!(hex::encode_upper(hash)))
+                    .collect(),
+            )),
+            Self::Array { elems, .. } => {
+                let mut array = vec![];
+                for elem in elems {
+                    let mut map = Map::new();
+                    map.insert(elem.field()?.name.to_string(), elem.to_json()?);
+                    array.push(Value::Object(map));
+                }
+                Ok(Value::Array(array))
+            }
+            Self::Object { members, .. } => {
+                let mut map = Map::new();
+                for mem in members {
+                    map.insert(mem.field()?.name.to_string(), mem.to_json()?);
+                }
+                Ok(Value::Object(map))
+            }
+        }
+    }
+
+    /// Converts the JSON value of the given field to an ST
+    pub fn from_json(field: &FieldDefinition, value: &Value) -> Result<Self, TransactionError> {
+        let field_value = field.nth;
+        match field.typ {
+            SerializedTypeID::Account => Ok(Self::Account {
+                field_value,
+                account_id: account_from_json(value)?,
+            }),
+            SerializedTypeID::Amount => Ok(Self::Amount {
+                field_value,
+                amount: amount_from_json(value)?,
+            }),
+            SerializedTypeID::Uint8 => Ok(Self::Uint8 {
+                field_value,
+                value: int_from_json(value)?,
+            }),
+            SerializedTypeID::Uint16 => {
+                let value = match (field.name, value.as_str()) {
+                    ("TransactionType", Some(name)) => transaction_type_code(name)?,
+                    _ => int_from_json(value)?,
+                };
+                Ok(Self::Uint16 { field_value, value })
+            }
+            SerializedTypeID::Uint32 => Ok(Self::Uint32 {
+                field_value,
+                value: int_from_json(value)?,
+            }),
+            SerializedTypeID::Uint64 => {
+                let value = value.as_str().ok_or_else(|| {
+                    TransactionError::Message(format!("Invalid UInt64 {}", value))
+                })?;
+                Ok(Self::Uint64 {
+                    field_value,
+                    value: u64::from_str_radix(value, 16)?,
+                })
+            }
+            SerializedTypeID::Int32 => Ok(Self::Int32 {
+                field_value,
+                value: int_from_json(value)?,
+            }),
+            SerializedTypeID::Int64 => Ok(Self::Int64 {
+                field_value,
+                value: int_from_json(value)?,
+            }),
+            SerializedTypeID::Hash128
+            | SerializedTypeID::Hash160
+            | SerializedTypeID::Hash192
+            | SerializedTypeID::Hash256
+            | SerializedTypeID::Uint96
+            | SerializedTypeID::Uint384
+            | SerializedTypeID::Uint512 => {
+                let buffer = hex_from_json(value)?;
+                if Some(buffer.len()) != field.typ.fixed_size() {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid {} length {} for field {}",
+                        field.typ,
+                        buffer.len(),
+                        field.name
+                    )));
+                }
+                Ok(Self::Hash {
+                    typ: field.typ,
+                    field_value,
+                    buffer,
+                })
+            }
+            SerializedTypeID::Number => {
+                let (mantissa, exponent) = number_from_json(value)?;
+                Ok(Self::Number {
+                    field_value,
+                    mantissa,
+                    exponent,
+                })
+            }
+            SerializedTypeID::VL => Ok(Self::Blob {
+                field_value,
+                buffer: hex_from_json(value)?,
+            }),
+            SerializedTypeID::Currency => Ok(Self::Currency {
+                field_value,
+                currency: currency_from_json(value)?,
+            }),
+            SerializedTypeID::Issue => Ok(Self::Issue {
+                field_value,
+                issue: RippleIssue::from_json(value)?,
+            }),
+            SerializedTypeID::XChainBridge => {
+                let member = |name: &str| value.get(name).unwrap_or(&Value::Null);
+                Ok(Self::XChainBridge {
+                    field_value,
+                    locking_chain_door: account_from_json(member("LockingChainDoor"))?,
+                    locking_chain_issue: RippleIssue::from_json(member("LockingChainIssue"))?,
+                    issuing_chain_door: account_from_json(member("IssuingChainDoor"))?,
+                    issuing_chain_issue: RippleIssue::from_json(member("IssuingChainIssue"))?,
+                })
+            }
+            SerializedTypeID::PathSet => {
+                let invalid = || TransactionError::Message(format!("Invalid PathSet {}", value));
+                let mut paths = vec![];
+                for path in value.as_array().ok_or_else(invalid)? {
+                    let mut steps = vec![];
+                    for step in path.as_array().ok_or_else(invalid)? {
+                        steps.push(PathStep {
+                            account: step.get("account").map(account_from_json).transpose()?,
+                            currency: step.get("currency").map(currency_from_json).transpose()?,
+                            issuer: step.get("issuer").map(account_from_json).transpose()?,
+                        });
+                    }
+                    paths.push(steps);
+                }
+                Ok(Self::PathSet { field_value, paths })
+            }
+            SerializedTypeID::Vector256 => {
+                let invalid = || TransactionError::Message(format!("Invalid Vector256 {}", value));
+                let mut hashes = vec![];
+                for hash in value.as_array().ok_or_else(invalid)? {
+                    let hash: [u8; 32] = hex_from_json(hash)?.try_into().map_err(|_| invalid())?;
+                    hashes.push(hash);
+                }
+                Ok(Self::Vector256 {
+                    field_value,
+                    hashes,
+                })
+            }
+            SerializedTypeID::Array => {
+                let elems = value.as_array().ok_or_else(|| {
+                    TransactionError::Message(format!("Invalid array {}", field.name))
+                })?;
+                let mut array = Self::Array {
+                    field_value,
+                    elems: vec![],
+                };
+                for elem in elems {
+                    // Each element of an array is an object wrapped by its field name
+                    let wrapper =
+                        elem.as_object()
+                            .filter(|map| map.len() == 1)
+                            .ok_or_else(|| {
+                                TransactionError::Message(format!(
+                                    "Invalid element of array {}",
+                                    field.name
+                                ))
+                            })?;
+                    let (name, inner) = wrapper.iter().next().unwrap();
+                    array.add(Self::from_json(field_by_name(name)?, inner)?)?;
+                }
+                Ok(array)
+            }
+            SerializedTypeID::Object => {
+                let map = value.as_object().ok_or_else(|| {
+                    TransactionError::Message(format!("Invalid object {}", field.name))
+                })?;
+                let mut obj = Self::Object {
+                    field_value,
+                    members: vec![],
+                };
+                for (name, value) in map {
+                    obj.add(Self::from_json(field_by_name(name)?, value)?)?;
+                }
+                obj.sort()?;
+                Ok(obj)
+            }
+        }
+    }
+}
+
+/// The pseudo field of a top-level object, which carries no field header
+const ROOT: FieldDefinition = FieldDefinition {
+    name: "",
+    typ: SerializedTypeID::Object,
+    nth: 0,
+};
+
+/// Encodes the JSON form of a transaction to its canonical binary form.
+/// If `signing` is true, the fields that are not signed are skipped.
+pub fn encode(json: &Value, signing: bool) -> Result<Vec<u8>, TransactionError> {
+    let st = SerializedType::from_json(&ROOT, json)?;
+    match st {
+        SerializedType::Object { members, .. } if signing => {
+            let mut signed = vec![];
+            for mem in members {
+                if mem.field()?.is_signing_field() {
+                    signed.push(mem);
+                }
+            }
+            SerializedType::Object {
+                field_value: 0,
+                members: signed,
+            }
+            .serialize()
+        }
+        st => st.serialize(),
+    }
+}
+
+/// Decodes a binary transaction to its JSON form
+pub fn decode(stream: &[u8]) -> Result<Value, TransactionError> {
+    // Before we deserialize the stream, we should postfix the
+    // stream with a mark of ending for object type
+    let mut stream = stream.to_vec();
+    stream.extend(serialize_type(SerializedTypeID::Object, 1)?);
+
+    let mut stream = stream.as_slice();
+    let st = SerializedType::deserialize(&mut stream, SerializedTypeID::Object, 0)?;
+    if !stream.is_empty() {
+        return Err(TransactionError::Message(
+            "Trailing bytes after the end of the transaction".to_string(),
+        ));
+    }
+    st.to_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_len_prefix() {
+        for len in [0u32, 192, 193, 12480, 12481, 918744] {
+            let stream = serialize_len(len).unwrap();
+            assert_eq!(len, deserialize_len(&mut stream.as_slice()).unwrap());
+        }
+        assert!(serialize_len(918745).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let json = json!({
+            "TransactionType": "OfferCreate",
+            "Account": "rJ6HEKFe8T2mkZQqzuGbFEmE8SKtadxd8n",
+            "Fee": "12",
+            "Flags": 524288,
+            "Sequence": 8,
+            "TickSize": 5,
+            "TakerGets": "15000000000",
+            "TakerPays": {
+                "currency": "USD",
+                "issuer": "rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De",
+                "value": "7072.8"
+            },
+            "Paths": [[
+                {"currency": "USD", "issuer": "rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De"},
+                {"account": "rBbvWBK4pmibqtwNMWhPMTgJUGfaNh71Yk"}
+            ], [
+                {"currency": "XRP"}
+            ]],
+            "NFTokenOffers": [
+                "AED08CC1F50DD5F23A1948AF86153A3F3B7593E5EC77D65A02BB1B29E05AB6AE"
+            ],
+            "Asset": {"currency": "XRP"},
+            "Asset2": {"currency": "USD", "issuer": "rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De"},
+            "Cookie": "00000000000000FF",
+            "EmailHash": "98B4375E1D753E5B91627516F6D70977",
+            "SignerEntries": [
+                {"SignerEntry": {"Account": "rBbvWBK4pmibqtwNMWhPMTgJUGfaNh71Yk", "SignerWeight": 1}}
+            ]
+        });
+
+        let stream = encode(&json, false).unwrap();
+        assert_eq!(json, decode(&stream).unwrap());
+        assert_eq!(stream, encode(&decode(&stream).unwrap(), false).unwrap());
+    }
+
+    #[test]
+    fn test_number() {
+        let field = FieldDefinition {
+            name: "Number",
+            typ: SerializedTypeID::Number,
+            nth: 1,
+        };
+        let st = SerializedType::from_json(&field, &json!("1.5")).unwrap();
+        assert_eq!(
+            [
+                1_500_000_000_000_000i64.to_be_bytes().to_vec(),
+                (-15i32).to_be_bytes().to_vec()
+            ]
+            .concat(),
+            st.serialize().unwrap()
+        );
+
+        for value in [
+            "0",
+            "1.5",
+            "-0.001",
+            "100",
+            "1234500000000000e19",
+            "-9999999999999999e-30",
+        ] {
+            let st = SerializedType::from_json(&field, &json!(value)).unwrap();
+            let stream = st.serialize().unwrap();
+            assert_eq!(12, stream.len());
+            let decoded =
+                SerializedType::deserialize(&mut stream.as_slice(), field.typ, 1).unwrap();
+            assert_eq!(stream, decoded.serialize().unwrap());
+            assert_eq!(json!(value), decoded.to_json().unwrap());
+        }
+        assert_eq!(
+            st.serialize().unwrap(),
+            SerializedType::from_json(&field, &json!("15e-1"))
+                .unwrap()
+                .serialize()
+                .unwrap()
+        );
+        assert!(SerializedType::from_json(&field, &json!("12345678901234567")).is_err());
+        assert!(SerializedType::from_json(&field, &json!("1.5.0")).is_err());
+        assert!(SerializedType::from_json(&field, &json!(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_signing_fields() {
+        let json = json!({
+            "TransactionType": "Payment",
+            "Fee": "10",
+            "TxnSignature": "00",
+        });
+        let all = encode(&json, false).unwrap();
+        let signing = encode(&json, true).unwrap();
+        assert_eq!(all.len(), signing.len() + 3);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let json = json!({"TransactionType": "Payment", "Foo": 1});
+        assert!(encode(&json, false).is_err());
+    }
+}
@@ -0,0 +1,490 @@
+//! Field and transaction type definitions of the XRPL binary format,
+//! mirroring `definitions.json` of xrpl.js and rippled's `SField.h`
+
+use anychain_core::TransactionError;
+use core::fmt;
+
+/// The type codes of serialized XRPL fields
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub enum SerializedTypeID {
+    Uint16 = 1,
+    Uint32 = 2,
+    Uint64 = 3,
+    Hash128 = 4,
+    Hash256 = 5,
+    Amount = 6,
+    VL = 7,
+    Account = 8,
+    Number = 9,
+    Int32 = 10,
+    Int64 = 11,
+    // 12-13 are reserved
+    Object = 14,
+    Array = 15,
+    Uint8 = 16,
+    Hash160 = 17,
+    PathSet = 18,
+    Vector256 = 19,
+    Uint96 = 20,
+    Hash192 = 21,
+    Uint384 = 22,
+    Uint512 = 23,
+    Issue = 24,
+    XChainBridge = 25,
+    Currency = 26,
+}
+
+impl SerializedTypeID {
+    pub fn from_u8(b: u8) -> Result<Self, TransactionError> {
+        match b {
+            1 => Ok(Self::Uint16),
+            2 => Ok(Self::Uint32),
+            3 => Ok(Self::Uint64),
+            4 => Ok(Self::Hash128),
+            5 => Ok(Self::Hash256),
+            6 => Ok(Self::Amount),
+            7 => Ok(Self::VL),
+            8 => Ok(Self::Account),
+            9 => Ok(Self::Number),
+            10 => Ok(Self::Int32),
+            11 => Ok(Self::Int64),
+            14 => Ok(Self::Object),
+            15 => Ok(Self::Array),
+            16 => Ok(Self::Uint8),
+            17 => Ok(Self::Hash160),
+            18 => Ok(Self::PathSet),
+            19 => Ok(Self::Vector256),
+            20 => Ok(Self::Uint96),
+            21 => Ok(Self::Hash192),
+            22 => Ok(Self::Uint384),
+            23 => Ok(Self::Uint512),
+            24 => Ok(Self::Issue),
+            25 => Ok(Self::XChainBridge),
+            26 => Ok(Self::Currency),
+            _ => Err(TransactionError::Message(format!(
+                "Unsupported serialized type id {}",
+                b,
+            ))),
+        }
+    }
+
+    /// Returns the byte width of the fixed-size hash-like types
+    pub fn fixed_size(&self) -> Option<usize> {
+        match self {
+            Self::Hash128 => Some(16),
+            Self::Hash160 => Some(20),
+            Self::Hash192 => Some(24),
+            Self::Hash256 => Some(32),
+            Self::Uint96 => Some(12),
+            Self::Uint384 => Some(48),
+            Self::Uint512 => Some(64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SerializedTypeID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uint16 => write!(f, "UInt16"),
+            Self::Uint32 => write!(f, "UInt32"),
+            Self::Uint64 => write!(f, "UInt64"),
+            Self::Hash128 => write!(f, "Hash128"),
+            Self::Hash256 => write!(f, "Hash256"),
+            Self::Amount => write!(f, "Amount"),
+            Self::VL => write!(f, "Blob"),
+            Self::Account => write!(f, "AccountID"),
+            Self::Number => write!(f, "Number"),
+            Self::Int32 => write!(f, "Int32"),
+            Self::Int64 => write!(f, "Int64"),
+            Self::Object => write!(f, "STObject"),
+            Self::Array => write!(f, "STArray"),
+            Self::Uint8 => write!(f, "UInt8"),
+            Self::Hash160 => write!(f, "Hash160"),
+            Self::PathSet => write!(f, "PathSet"),
+            Self::Vector256 => write!(f, "Vector256"),
+            Self::Uint96 => write!(f, "UInt96"),
+            Self::Hash192 => write!(f, "Hash192"),
+            Self::Uint384 => write!(f, "UInt384"),
+            Self::Uint512 => write!(f, "UInt512"),
+            Self::Issue => write!(f, "Issue"),
+            Self::XChainBridge => write!(f, "XChainBridge"),
+            Self::Currency => write!(f, "Currency"),
+        }
+    }
+}
+
+/// Describes a field of the XRPL binary format
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FieldDefinition {
+    pub name: &'static str,
+    pub typ: SerializedTypeID,
+    pub nth: u32,
+}
+
+impl FieldDefinition {
+    /// Signatures are excluded from the data being signed
+    pub fn is_signing_field(&self) -> bool {
+        !matches!(self.name, "TxnSignature" | "Signers")
+    }
+}
+
+use SerializedTypeID::*;
+
+macro_rules! fields {
+    ($(($name:literal, $typ:ident, $nth:literal)),* $(,)?) => {
+        &[$(FieldDefinition { name: $name, typ: $typ, nth: $nth }),*]
+    };
+}
+
+/// The fields that can appear in transactions
+pub static FIELDS: &[FieldDefinition] = fields![
+    ("TickSize", Uint8, 16),
+    ("WasLockingChainSend", Uint8, 19),
+    ("LedgerEntryType", Uint16, 1),
+    ("TransactionType", Uint16, 2),
+    ("SignerWeight", Uint16, 3),
+    ("TransferFee", Uint16, 4),
+    ("TradingFee", Uint16, 5),
+    ("DiscountedFee", Uint16, 6),
+    ("Version", Uint16, 16),
+    ("NetworkID", Uint32, 1),
+    ("Flags", Uint32, 2),
+    ("SourceTag", Uint32, 3),
+    ("Sequence", Uint32, 4),
+    ("PreviousTxnLgrSeq", Uint32, 5),
+    ("LedgerSequence", Uint32, 6),
+    ("CloseTime", Uint32, 7),
+    ("ParentCloseTime", Uint32, 8),
+    ("SigningTime", Uint32, 9),
+    ("Expiration", Uint32, 10),
+    ("TransferRate", Uint32, 11),
+    ("WalletSize", Uint32, 12),
+    ("OwnerCount", Uint32, 13),
+    ("DestinationTag", Uint32, 14),
+    ("LastUpdateTime", Uint32, 15),
+    ("HighQualityIn", Uint32, 16),
+    ("HighQualityOut", Uint32, 17),
+    ("LowQualityIn", Uint32, 18),
+    ("LowQualityOut", Uint32, 19),
+    ("QualityIn", Uint32, 20),
+    ("QualityOut", Uint32, 21),
+    ("StampEscrow", Uint32, 22),
+    ("BondAmount", Uint32, 23),
+    ("LoadFee", Uint32, 24),
+    ("OfferSequence", Uint32, 25),
+    ("FirstLedgerSequence", Uint32, 26),
+    ("LastLedgerSequence", Uint32, 27),
+    ("TransactionIndex", Uint32, 28),
+    ("OperationLimit", Uint32, 29),
+    ("ReferenceFeeUnits", Uint32, 30),
+    ("ReserveBase", Uint32, 31),
+    ("ReserveIncrement", Uint32, 32),
+    ("SetFlag", Uint32, 33),
+    ("ClearFlag", Uint32, 34),
+    ("SignerQuorum", Uint32, 35),
+    ("CancelAfter", Uint32, 36),
+    ("FinishAfter", Uint32, 37),
+    ("SignerListID", Uint32, 38),
+    ("SettleDelay", Uint32, 39),
+    ("TicketCount", Uint32, 40),
+    ("TicketSequence", Uint32, 41),
+    ("NFTokenTaxon", Uint32, 42),
+    ("MintedNFTokens", Uint32, 43),
+    ("BurnedNFTokens", Uint32, 44),
+    ("HookStateCount", Uint32, 45),
+    ("EmitGeneration", Uint32, 46),
+    ("VoteWeight", Uint32, 48),
+    ("FirstNFTokenSequence", Uint32, 50),
+    ("OracleDocumentID", Uint32, 51),
+    ("IndexNext", Uint64, 1),
+    ("IndexPrevious", Uint64, 2),
+    ("BookNode", Uint64, 3),
+    ("OwnerNode", Uint64, 4),
+    ("BaseFee", Uint64, 5),
+    ("ExchangeRate", Uint64, 6),
+    ("LowNode", Uint64, 7),
+    ("HighNode", Uint64, 8),
+    ("DestinationNode", Uint64, 9),
+    ("Cookie", Uint64, 10),
+    ("ServerVersion", Uint64, 11),
+    ("NFTokenOfferNode", Uint64, 12),
+    ("EmitBurden", Uint64, 13),
+    ("HookOn", Uint64, 16),
+    ("HookInstructionCount", Uint64, 17),
+    ("HookReturnCode", Uint64, 18),
+    ("ReferenceCount", Uint64, 19),
+    ("XChainClaimID", Uint64, 20),
+    ("XChainAccountCreateCount", Uint64, 21),
+    ("XChainAccountClaimCount", Uint64, 22),
+    ("AssetPrice", Uint64, 23),
+    ("EmailHash", Hash128, 1),
+    ("TakerPaysCurrency", Hash160, 1),
+    ("TakerPaysIssuer", Hash160, 2),
+    ("TakerGetsCurrency", Hash160, 3),
+    ("TakerGetsIssuer", Hash160, 4),
+    ("MPTokenIssuanceID", Hash192, 1),
+    ("LedgerHash", Hash256, 1),
+    ("ParentHash", Hash256, 2),
+    ("TransactionHash", Hash256, 3),
+    ("AccountHash", Hash256, 4),
+    ("PreviousTxnID", Hash256, 5),
+    ("LedgerIndex", Hash256, 6),
+    ("WalletLocator", Hash256, 7),
+    ("RootIndex", Hash256, 8),
+    ("AccountTxnID", Hash256, 9),
+    ("NFTokenID", Hash256, 10),
+    ("EmitParentTxnID", Hash256, 11),
+    ("EmitNonce", Hash256, 12),
+    ("EmitHookHash", Hash256, 13),
+    ("AMMID", Hash256, 14),
+    ("BookDirectory", Hash256, 16),
+    ("InvoiceID", Hash256, 17),
+    ("Nickname", Hash256, 18),
+    ("Amendment", Hash256, 19),
+    ("Digest", Hash256, 21),
+    ("Channel", Hash256, 22),
+    ("ConsensusHash", Hash256, 23),
+    ("CheckID", Hash256, 24),
+    ("ValidatedHash", Hash256, 25),
+    ("PreviousPageMin", Hash256, 26),
+    ("NextPageMin", Hash256, 27),
+    ("NFTokenBuyOffer", Hash256, 28),
+    ("NFTokenSellOffer", Hash256, 29),
+    ("HookStateKey", Hash256, 30),
+    ("HookHash", Hash256, 31),
+    ("HookNamespace", Hash256, 32),
+    ("HookSetTxnID", Hash256, 33),
+    ("Amount", Amount, 1),
+    ("Balance", Amount, 2),
+    ("LimitAmount", Amount, 3),
+    ("TakerPays", Amount, 4),
+    ("TakerGets", Amount, 5),
+    ("LowLimit", Amount, 6),
+    ("HighLimit", Amount, 7),
+    ("Fee", Amount, 8),
+    ("SendMax", Amount, 9),
+    ("DeliverMin", Amount, 10),
+    ("Amount2", Amount, 11),
+    ("BidMin", Amount, 12),
+    ("BidMax", Amount, 13),
+    ("MinimumOffer", Amount, 16),
+    ("RippleEscrow", Amount, 17),
+    ("DeliveredAmount", Amount, 18),
+    ("NFTokenBrokerFee", Amount, 19),
+    ("BaseFeeDrops", Amount, 22),
+    ("ReserveBaseDrops", Amount, 23),
+    ("ReserveIncrementDrops", Amount, 24),
+    ("LPTokenOut", Amount, 25),
+    ("LPTokenIn", Amount, 26),
+    ("EPrice", Amount, 27),
+    ("Price", Amount, 28),
+    ("SignatureReward", Amount, 29),
+    ("MinAccountCreateAmount", Amount, 30),
+    ("LPTokenBalance", Amount, 31),
+    ("PublicKey", VL, 1),
+    ("MessageKey", VL, 2),
+    ("SigningPubKey", VL, 3),
+    ("TxnSignature", VL, 4),
+    ("URI", VL, 5),
+    ("Signature", VL, 6),
+    ("Domain", VL, 7),
+    ("FundCode", VL, 8),
+    ("RemoveCode", VL, 9),
+    ("ExpireCode", VL, 10),
+    ("CreateCode", VL, 11),
+    ("MemoType", VL, 12),
+    ("MemoData", VL, 13),
+    ("MemoFormat", VL, 14),
+    ("Fulfillment", VL, 16),
+    ("Condition", VL, 17),
+    ("MasterSignature", VL, 18),
+    ("UNLModifyValidator", VL, 19),
+    ("ValidatorToDisable", VL, 20),
+    ("ValidatorToReEnable", VL, 21),
+    ("HookStateData", VL, 22),
+    ("HookReturnString", VL, 23),
+    ("HookParameterName", VL, 24),
+    ("HookParameterValue", VL, 25),
+    ("DIDDocument", VL, 26),
+    ("Data", VL, 27),
+    ("AssetClass", VL, 28),
+    ("Provider", VL, 29),
+    ("Account", Account, 1),
+    ("Owner", Account, 2),
+    ("Destination", Account, 3),
+    ("Issuer", Account, 4),
+    ("Authorize", Account, 5),
+    ("Unauthorize", Account, 6),
+    ("RegularKey", Account, 8),
+    ("NFTokenMinter", Account, 9),
+    ("EmitCallback", Account, 10),
+    ("HookAccount", Account, 16),
+    ("OtherChainSource", Account, 18),
+    ("OtherChainDestination", Account, 19),
+    ("AttestationSignerAccount", Account, 20),
+    ("AttestationRewardAccount", Account, 21),
+    ("LockingChainDoor", Account, 22),
+    ("IssuingChainDoor", Account, 23),
+    ("ObjectEndMarker", Object, 1),
+    ("TransactionMetaData", Object, 2),
+    ("CreatedNode", Object, 3),
+    ("DeletedNode", Object, 4),
+    ("ModifiedNode", Object, 5),
+    ("PreviousFields", Object, 6),
+    ("FinalFields", Object, 7),
+    ("NewFields", Object, 8),
+    ("TemplateEntry", Object, 9),
+    ("Memo", Object, 10),
+    ("SignerEntry", Object, 11),
+    ("NFToken", Object, 12),
+    ("EmitDetails", Object, 13),
+    ("Hook", Object, 14),
+    ("Signer", Object, 16),
+    ("Majority", Object, 18),
+    ("DisabledValidator", Object, 19),
+    ("EmittedTxn", Object, 20),
+    ("HookExecution", Object, 21),
+    ("HookDefinition", Object, 22),
+    ("HookParameter", Object, 23),
+    ("HookGrant", Object, 24),
+    ("VoteEntry", Object, 25),
+    ("AuctionSlot", Object, 26),
+    ("AuthAccount", Object, 27),
+    ("PriceData", Object, 32),
+    ("ArrayEndMarker", Array, 1),
+    ("Signers", Array, 3),
+    ("SignerEntries", Array, 4),
+    ("Template", Array, 5),
+    ("Necessary", Array, 6),
+    ("Sufficient", Array, 7),
+    ("AffectedNodes", Array, 8),
+    ("Memos", Array, 9),
+    ("NFTokens", Array, 10),
+    ("Hooks", Array, 11),
+    ("VoteSlots", Array, 12),
+    ("Majorities", Array, 16),
+    ("DisabledValidators", Array, 17),
+    ("HookExecutions", Array, 18),
+    ("HookParameters", Array, 19),
+    ("HookGrants", Array, 20),
+    ("PriceDataSeries", Array, 24),
+    ("AuthAccounts", Array, 25),
+    ("Paths", PathSet, 1),
+    ("Indexes", Vector256, 1),
+    ("Hashes", Vector256, 2),
+    ("Amendments", Vector256, 3),
+    ("NFTokenOffers", Vector256, 4),
+    ("LockingChainIssue", Issue, 1),
+    ("IssuingChainIssue", Issue, 2),
+    ("Asset", Issue, 3),
+    ("Asset2", Issue, 4),
+    ("XChainBridge", XChainBridge, 1),
+    ("BaseAsset", Currency, 1),
+    ("QuoteAsset", Currency, 2),
+];
+
+/// The transaction types and their 'TransactionType' codes
+pub static TRANSACTION_TYPES: &[(&str, u16)] = &[
+    ("Payment", 0),
+    ("EscrowCreate", 1),
+    ("EscrowFinish", 2),
+    ("AccountSet", 3),
+    ("EscrowCancel", 4),
+    ("SetRegularKey", 5),
+    ("OfferCreate", 7),
+    ("OfferCancel", 8),
+    ("TicketCreate", 10),
+    ("SignerListSet", 12),
+    ("PaymentChannelCreate", 13),
+    ("PaymentChannelFund", 14),
+    ("PaymentChannelClaim", 15),
+    ("CheckCreate", 16),
+    ("CheckCash", 17),
+    ("CheckCancel", 18),
+    ("DepositPreauth", 19),
+    ("TrustSet", 20),
+    ("AccountDelete", 21),
+    ("SetHook", 22),
+    ("NFTokenMint", 25),
+    ("NFTokenBurn", 26),
+    ("NFTokenCreateOffer", 27),
+    ("NFTokenCancelOffer", 28),
+    ("NFTokenAcceptOffer", 29),
+    ("Clawback", 30),
+    ("AMMCreate", 35),
+    ("AMMDeposit", 36),
+    ("AMMWithdraw", 37),
+    ("AMMVote", 38),
+    ("AMMBid", 39),
+    ("AMMDelete", 40),
+    ("DIDSet", 49),
+    ("DIDDelete", 50),
+    ("OracleSet", 51),
+    ("OracleDelete", 52),
+    ("EnableAmendment", 100),
+    ("SetFee", 101),
+    ("UNLModify", 102),
+];
+
+/// Looks up the definition of a field by its name
+pub fn field_by_name(name: &str) -> Result<&'static FieldDefinition, TransactionError> {
+    FIELDS
+        .iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| TransactionError::Message(format!("Unknown field {}", name)))
+}
+
+/// Looks up the definition of a field by its type code and field code
+pub fn field_by_code(
+    typ: SerializedTypeID,
+    nth: u32,
+) -> Result<&'static FieldDefinition, TransactionError> {
+    FIELDS
+        .iter()
+        .find(|field| field.typ == typ && field.nth == nth)
+        .ok_or_else(|| TransactionError::Message(format!("Unknown field ({}, {})", typ, nth)))
+}
+
+/// Returns the 'TransactionType' code of a transaction type name
+pub fn transaction_type_code(name: &str) -> Result<u16, TransactionError> {
+    TRANSACTION_TYPES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, code)| *code)
+        .ok_or_else(|| TransactionError::Message(format!("Unknown transaction type {}", name)))
+}
+
+/// Returns the transaction type name of a 'TransactionType' code
+pub fn transaction_type_name(code: u16) -> Result<&'static str, TransactionError> {
+    TRANSACTION_TYPES
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map(|(name, _)| *name)
+        .ok_or_else(|| TransactionError::Message(format!("Unknown transaction type {}", code)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_are_unique() {
+        for (i, a) in FIELDS.iter().enumerate() {
+            for b in &FIELDS[i + 1..] {
+                assert_ne!(a.name, b.name);
+                assert!(a.typ != b.typ || a.nth != b.nth, "{} {}", a.name, b.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let field = field_by_name("DestinationTag").unwrap();
+        assert_eq!((SerializedTypeID::Uint32, 14), (field.typ, field.nth));
+        assert_eq!("LimitAmount", field_by_code(Amount, 3).unwrap().name);
+        assert!(!field_by_name("TxnSignature").unwrap().is_signing_field());
+        assert_eq!(20, transaction_type_code("TrustSet").unwrap());
+        assert_eq!("NFTokenAcceptOffer", transaction_type_name(29).unwrap());
+    }
+}
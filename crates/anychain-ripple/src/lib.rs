@@ -1,6 +1,13 @@
 pub mod address;
 pub use self::address::*;
 
+pub mod amount;
+pub use self::amount::*;
+
+pub mod codec;
+
+pub mod definitions;
+
 pub mod format;
 pub use self::format::*;
 
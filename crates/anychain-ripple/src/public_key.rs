@@ -2,26 +2,55 @@ use crate::{RippleAddress, RippleFormat};
 use anychain_core::{hex, Address, AddressError, PublicKey, PublicKeyError};
 use core::{fmt, str::FromStr};
 
+/// The prefix that marks a serialized Ed25519 public key
+pub const ED25519_PREFIX: u8 = 0xed;
+
+/// Represents a Ripple secret key, which is either an secp256k1 or an Ed25519 key
+#[derive(Debug, Clone)]
+pub enum RippleSecretKey {
+    Secp256k1(libsecp256k1::SecretKey),
+    Ed25519(ed25519_dalek::SecretKey),
+}
+
+impl From<libsecp256k1::SecretKey> for RippleSecretKey {
+    fn from(secret_key: libsecp256k1::SecretKey) -> Self {
+        Self::Secp256k1(secret_key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PublicKeyContent {
+    Secp256k1 {
+        public_key: libsecp256k1::PublicKey,
+        /// If true, the public key is serialized in compressed form
+        compressed: bool,
+    },
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
 /// Represents a Ripple public key
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RipplePublicKey {
-    /// The secp256k1 public key
-    public_key: libsecp256k1::PublicKey,
-    /// If true, the public key is serialized in compressed form
-    compressed: bool,
+    key: PublicKeyContent,
 }
 
 impl PublicKey for RipplePublicKey {
-    type SecretKey = libsecp256k1::SecretKey;
+    type SecretKey = RippleSecretKey;
     type Address = RippleAddress;
     type Format = RippleFormat;
 
-    /// Returns a Ripple public key given an secp256k1 secret key.
+    /// Returns a Ripple public key given an secp256k1 or Ed25519 secret key.
     fn from_secret_key(secret_key: &Self::SecretKey) -> Self {
-        Self {
-            public_key: libsecp256k1::PublicKey::from_secret_key(secret_key),
-            compressed: true,
-        }
+        let key = match secret_key {
+            RippleSecretKey::Secp256k1(sk) => PublicKeyContent::Secp256k1 {
+                public_key: libsecp256k1::PublicKey::from_secret_key(sk),
+                compressed: true,
+            },
+            RippleSecretKey::Ed25519(sk) => {
+                PublicKeyContent::Ed25519(ed25519_dalek::SigningKey::from_bytes(sk).verifying_key())
+            }
+        };
+        Self { key }
     }
 
     /// Returns a Ripple address corresponding to this Ripple public key.
@@ -37,27 +66,87 @@ impl RipplePublicKey {
         compressed: bool,
     ) -> Self {
         Self {
-            public_key,
-            compressed,
+            key: PublicKeyContent::Secp256k1 {
+                public_key,
+                compressed,
+            },
+        }
+    }
+
+    /// Returns a Ripple public key given an Ed25519 public key.
+    pub fn from_ed25519_public_key(public_key: ed25519_dalek::VerifyingKey) -> Self {
+        Self {
+            key: PublicKeyContent::Ed25519(public_key),
+        }
+    }
+
+    /// Returns the secp256k1 public key of this Ripple public key, if it is one.
+    pub fn to_secp256k1_public_key(&self) -> Option<libsecp256k1::PublicKey> {
+        match &self.key {
+            PublicKeyContent::Secp256k1 { public_key, .. } => Some(*public_key),
+            PublicKeyContent::Ed25519(_) => None,
         }
     }
 
-    /// Returns the secp256k1 public key of this Ripple public key.
-    pub fn to_secp256k1_public_key(&self) -> libsecp256k1::PublicKey {
-        self.public_key
+    /// Returns the Ed25519 public key of this Ripple public key, if it is one.
+    pub fn to_ed25519_public_key(&self) -> Option<ed25519_dalek::VerifyingKey> {
+        match &self.key {
+            PublicKeyContent::Secp256k1 { .. } => None,
+            PublicKeyContent::Ed25519(public_key) => Some(*public_key),
+        }
     }
 
-    /// Serialize the Ripple public key as a vector of u8
+    /// Serialize the Ripple public key as a vector of u8. An Ed25519 public
+    /// key is prefixed with 0xED so that it is 33 bytes long as well
     pub fn serialize(&self) -> Vec<u8> {
-        match self.compressed {
-            true => self.public_key.serialize_compressed().to_vec(),
-            false => self.public_key.serialize().to_vec(),
+        match &self.key {
+            PublicKeyContent::Secp256k1 {
+                public_key,
+                compressed,
+            } => match compressed {
+                true => public_key.serialize_compressed().to_vec(),
+                false => public_key.serialize().to_vec(),
+            },
+            PublicKeyContent::Ed25519(public_key) => {
+                [&[ED25519_PREFIX][..], public_key.as_bytes()].concat()
+            }
+        }
+    }
+
+    /// Returns a Ripple public key given its serialized form
+    pub fn deserialize(public_key: &[u8]) -> Result<Self, PublicKeyError> {
+        match public_key.first() {
+            Some(&ED25519_PREFIX) if public_key.len() == 33 => {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(&public_key[1..]);
+                let public_key =
+                    ed25519_dalek::VerifyingKey::from_bytes(&bytes).map_err(|error| {
+                        PublicKeyError::Crate("ed25519_dalek", format!("{:?}", error))
+                    })?;
+                Ok(Self::from_ed25519_public_key(public_key))
+            }
+            _ => {
+                let compressed = public_key.len() == 33;
+                let public_key =
+                    libsecp256k1::PublicKey::parse_slice(public_key, None).map_err(|error| {
+                        PublicKeyError::Crate("libsecp256k1", format!("{:?}", error))
+                    })?;
+                Ok(Self::from_secp256k1_public_key(public_key, compressed))
+            }
         }
     }
 
     /// Returns `true` if the public key is in compressed form.
     pub fn is_compressed(&self) -> bool {
-        self.compressed
+        match &self.key {
+            PublicKeyContent::Secp256k1 { compressed, .. } => *compressed,
+            PublicKeyContent::Ed25519(_) => true,
+        }
+    }
+
+    /// Returns `true` if the public key is an Ed25519 public key.
+    pub fn is_ed25519(&self) -> bool {
+        matches!(self.key, PublicKeyContent::Ed25519(_))
     }
 }
 
@@ -65,30 +154,39 @@ impl FromStr for RipplePublicKey {
     type Err = PublicKeyError;
 
     fn from_str(public_key: &str) -> Result<Self, Self::Err> {
-        let compressed = public_key.len() == 66;
         let p = hex::decode(public_key)
             .map_err(|error| PublicKeyError::Crate("hex", format!("{:?}", error)))?;
-        let public_key = libsecp256k1::PublicKey::parse_slice(&p, None)
-            .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{:?}", error)))?;
-
-        Ok(Self {
-            public_key,
-            compressed,
-        })
+        Self::deserialize(&p)
     }
 }
 
 impl fmt::Display for RipplePublicKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.compressed {
-            for s in &self.public_key.serialize_compressed()[..] {
-                write!(f, "{:02x}", s)?;
-            }
-        } else {
-            for s in &self.public_key.serialize()[..] {
-                write!(f, "{:02x}", s)?;
-            }
+        for s in &self.serialize()[..] {
+            write!(f, "{:02x}", s)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_public_key() {
+        let sk = RippleSecretKey::Ed25519([7u8; 32]);
+        let pk = RipplePublicKey::from_secret_key(&sk);
+        assert!(pk.is_ed25519());
+
+        let serialized = pk.serialize();
+        assert_eq!(33, serialized.len());
+        assert_eq!(ED25519_PREFIX, serialized[0]);
+        assert_eq!(pk, RipplePublicKey::from_str(&pk.to_string()).unwrap());
+
+        let sk = RippleSecretKey::from(libsecp256k1::SecretKey::parse(&[7u8; 32]).unwrap());
+        let pk = RipplePublicKey::from_secret_key(&sk);
+        assert!(!pk.is_ed25519());
+        assert_eq!(pk, RipplePublicKey::from_str(&pk.to_string()).unwrap());
+    }
+}
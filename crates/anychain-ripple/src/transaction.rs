@@ -1,30 +1,333 @@
-use std::cmp::{Ord, Ordering};
 use std::{fmt, str::FromStr};
 
-use crate::{RippleAddress, RippleFormat, RipplePublicKey};
+use crate::codec::{
+    self, account_from_json, account_to_json, amount_from_json, amount_to_json, hex_from_json,
+    int_from_json,
+};
+use crate::{
+    RippleAddress, RippleAmount, RippleFormat, RipplePublicKey, RippleXAddress, ED25519_PREFIX,
+};
 use anychain_core::{
     crypto::{hash160, sha512},
-    hex,
-    no_std::io::Read,
-    Transaction, TransactionError, TransactionId,
+    hex, Transaction, TransactionError, TransactionId,
 };
 use libsecp256k1::Signature;
+use serde_json::{json, Map, Value};
+
+/// Payment flag: do not use the default path, only the paths provided
+pub const TF_NO_RIPPLE_DIRECT: u32 = 0x00010000;
+/// Payment flag: deliver less than `amount` if the full amount cannot be delivered
+pub const TF_PARTIAL_PAYMENT: u32 = 0x00020000;
+/// Payment flag: only take paths whose quality is not worse than `amount`/`send_max`
+pub const TF_LIMIT_QUALITY: u32 = 0x00040000;
+
+/// TrustSet flag: authorize the counterparty to hold currency issued by this account
+pub const TF_SET_F_AUTH: u32 = 0x00010000;
+/// TrustSet flag: enable the No Ripple flag on the trust line
+pub const TF_SET_NO_RIPPLE: u32 = 0x00020000;
+/// TrustSet flag: disable the No Ripple flag on the trust line
+pub const TF_CLEAR_NO_RIPPLE: u32 = 0x00040000;
+/// TrustSet flag: freeze the trust line
+pub const TF_SET_FREEZE: u32 = 0x00100000;
+/// TrustSet flag: unfreeze the trust line
+pub const TF_CLEAR_FREEZE: u32 = 0x00200000;
+
+/// OfferCreate flag: do not consume offers that exactly match this one
+pub const TF_PASSIVE: u32 = 0x00010000;
+/// OfferCreate flag: never place the offer in the order book
+pub const TF_IMMEDIATE_OR_CANCEL: u32 = 0x00020000;
+/// OfferCreate flag: cancel the offer unless it is fully filled at once
+pub const TF_FILL_OR_KILL: u32 = 0x00040000;
+/// OfferCreate flag: exchange the entire `taker_gets` even if it means obtaining more than `taker_pays`
+pub const TF_SELL: u32 = 0x00080000;
+
+/// NFTokenMint flag: allow the issuer to burn the token
+pub const TF_BURNABLE: u32 = 0x00000001;
+/// NFTokenMint flag: the token can only be traded for XRP
+pub const TF_ONLY_XRP: u32 = 0x00000002;
+/// NFTokenMint flag: the token can be transferred to others
+pub const TF_TRANSFERABLE: u32 = 0x00000008;
+
+/// NFTokenCreateOffer flag: the offer is a sell offer
+pub const TF_SELL_NFTOKEN: u32 = 0x00000001;
+
+/// AccountSet flag: require a destination tag for incoming payments
+pub const ASF_REQUIRE_DEST: u32 = 1;
+/// AccountSet flag: require authorization for users to hold balances issued by this account
+pub const ASF_REQUIRE_AUTH: u32 = 2;
+/// AccountSet flag: XRP should not be sent to this account
+pub const ASF_DISALLOW_XRP: u32 = 3;
+/// AccountSet flag: disallow the use of the master key pair
+pub const ASF_DISABLE_MASTER: u32 = 4;
+/// AccountSet flag: track the id of this account's most recent transaction
+pub const ASF_ACCOUNT_TXN_ID: u32 = 5;
+/// AccountSet flag: permanently give up the ability to freeze trust lines
+pub const ASF_NO_FREEZE: u32 = 6;
+/// AccountSet flag: freeze all assets issued by this account
+pub const ASF_GLOBAL_FREEZE: u32 = 7;
+/// AccountSet flag: enable rippling on this account's trust lines by default
+pub const ASF_DEFAULT_RIPPLE: u32 = 8;
+/// AccountSet flag: enable deposit authorization on this account
+pub const ASF_DEPOSIT_AUTH: u32 = 9;
+/// AccountSet flag: allow another account to mint tokens on behalf of this account
+pub const ASF_AUTHORIZED_NFTOKEN_MINTER: u32 = 10;
+
+/// The maximum number of entries in a signer list
+pub const MAX_SIGNER_ENTRIES: usize = 32;
+
+/// The maximum NFTokenMint transfer fee, i.e. 50%
+pub const MAX_TRANSFER_FEE: u16 = 50000;
+
+/// Represents a member of the signer list of an account
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct SignerEntry {
+    pub account: [u8; 20],
+    pub signer_weight: u16,
+}
+
+/// Represents a signature contributed to a multi-signed transaction
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct RippleSigner {
+    /// The account of the signer, which is a member of the signer list
+    pub account: [u8; 20],
+    /// The master or regular public key of the signer account
+    pub public_key: [u8; 33],
+    /// The 64-byte signature over `RippleTransaction::multisign_hash`
+    pub signature: [u8; 64],
+}
 
+/// Returns the fee of a multi-signed transaction, which is the base fee
+/// multiplied by one plus the number of signers
+pub fn multisign_fee(base_fee: u32, signer_count: usize) -> Result<u32, TransactionError> {
+    u32::try_from(signer_count)
+        .ok()
+        .and_then(|count| count.checked_add(1))
+        .and_then(|count| base_fee.checked_mul(count))
+        .ok_or(TransactionError::Message(format!(
+            "Multisign fee overflow for {} signers",
+            signer_count
+        )))
+}
+
+/// Represents the type of a Ripple transaction along with the fields
+/// specific to that type
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum RippleTransactionType {
+    /// A payment, which is described by the `destination`, `destination_tag`,
+    /// `amount`, `send_max` and `deliver_min` fields of the transaction parameters
+    Payment,
+    /// Creates or modifies a trust line to the issuer of `limit_amount`
+    TrustSet {
+        limit_amount: RippleAmount,
+        quality_in: Option<u32>,
+        quality_out: Option<u32>,
+    },
+    /// Modifies the properties of the account
+    AccountSet {
+        set_flag: Option<u32>,
+        clear_flag: Option<u32>,
+        /// The ascii domain of the account, an empty domain clears it
+        domain: Option<Vec<u8>>,
+        email_hash: Option<[u8; 16]>,
+        message_key: Option<Vec<u8>>,
+        transfer_rate: Option<u32>,
+        tick_size: Option<u8>,
+        nftoken_minter: Option<[u8; 20]>,
+    },
+    /// Assigns, or removes if `regular_key` is None, the regular key pair of the account
+    SetRegularKey { regular_key: Option<[u8; 20]> },
+    /// Creates, replaces or removes (if `signer_quorum` is 0) the signer list of the account
+    SignerListSet {
+        signer_quorum: u32,
+        signer_entries: Vec<SignerEntry>,
+    },
+    /// Sequesters `amount` until the escrow is finished or cancelled
+    EscrowCreate {
+        destination: [u8; 20],
+        destination_tag: Option<u32>,
+        amount: RippleAmount,
+        finish_after: Option<u32>,
+        cancel_after: Option<u32>,
+        condition: Option<Vec<u8>>,
+    },
+    /// Delivers the escrowed amount to its destination
+    EscrowFinish {
+        owner: [u8; 20],
+        offer_sequence: u32,
+        condition: Option<Vec<u8>>,
+        fulfillment: Option<Vec<u8>>,
+    },
+    /// Returns the escrowed amount to its owner
+    EscrowCancel {
+        owner: [u8; 20],
+        offer_sequence: u32,
+    },
+    /// Places an offer in the decentralized exchange
+    OfferCreate {
+        taker_pays: RippleAmount,
+        taker_gets: RippleAmount,
+        expiration: Option<u32>,
+        /// The sequence of an offer to cancel first
+        offer_sequence: Option<u32>,
+    },
+    /// Removes an offer from the decentralized exchange
+    OfferCancel { offer_sequence: u32 },
+    /// Deletes the account and sends its remaining XRP to `destination`
+    AccountDelete {
+        destination: [u8; 20],
+        destination_tag: Option<u32>,
+    },
+    /// Mints a non-fungible token
+    NFTokenMint {
+        nftoken_taxon: u32,
+        /// The issuer on whose behalf the token is minted
+        issuer: Option<[u8; 20]>,
+        /// The fee charged by the issuer on secondary sales, in 1/100000
+        transfer_fee: Option<u16>,
+        uri: Option<Vec<u8>>,
+    },
+    /// Creates an offer to buy or, with `TF_SELL_NFTOKEN`, sell a non-fungible token
+    NFTokenCreateOffer {
+        nftoken_id: [u8; 32],
+        amount: RippleAmount,
+        /// The owner of the token, required for buy offers
+        owner: Option<[u8; 20]>,
+        destination: Option<[u8; 20]>,
+        expiration: Option<u32>,
+    },
+    /// Accepts a buy offer, a sell offer, or both in brokered mode
+    NFTokenAcceptOffer {
+        nftoken_sell_offer: Option<[u8; 32]>,
+        nftoken_buy_offer: Option<[u8; 32]>,
+        nftoken_broker_fee: Option<RippleAmount>,
+    },
+}
+
+impl RippleTransactionType {
+    /// Returns the value of the 'TransactionType' field
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Payment => 0,
+            Self::EscrowCreate { .. } => 1,
+            Self::EscrowFinish { .. } => 2,
+            Self::AccountSet { .. } => 3,
+            Self::EscrowCancel { .. } => 4,
+            Self::SetRegularKey { .. } => 5,
+            Self::OfferCreate { .. } => 7,
+            Self::OfferCancel { .. } => 8,
+            Self::SignerListSet { .. } => 12,
+            Self::TrustSet { .. } => 20,
+            Self::AccountDelete { .. } => 21,
+            Self::NFTokenMint { .. } => 25,
+            Self::NFTokenCreateOffer { .. } => 27,
+            Self::NFTokenAcceptOffer { .. } => 29,
+        }
+    }
+
+    /// Returns the name of the transaction type in the JSON form
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Payment => "Payment",
+            Self::EscrowCreate { .. } => "EscrowCreate",
+            Self::EscrowFinish { .. } => "EscrowFinish",
+            Self::AccountSet { .. } => "AccountSet",
+            Self::EscrowCancel { .. } => "EscrowCancel",
+            Self::SetRegularKey { .. } => "SetRegularKey",
+            Self::OfferCreate { .. } => "OfferCreate",
+            Self::OfferCancel { .. } => "OfferCancel",
+            Self::SignerListSet { .. } => "SignerListSet",
+            Self::TrustSet { .. } => "TrustSet",
+            Self::AccountDelete { .. } => "AccountDelete",
+            Self::NFTokenMint { .. } => "NFTokenMint",
+            Self::NFTokenCreateOffer { .. } => "NFTokenCreateOffer",
+            Self::NFTokenAcceptOffer { .. } => "NFTokenAcceptOffer",
+        }
+    }
+}
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct RippleTransactionParameters {
+    pub transaction_type: RippleTransactionType,
     pub destination: [u8; 20],
     pub fee: u32,
     pub sequence: u32,
     pub destination_tag: u32,
-    pub amount: u64,
+    pub amount: RippleAmount,
+    /// The maximum amount to spend for a cross-currency payment
+    pub send_max: Option<RippleAmount>,
+    /// The minimum amount to deliver for a partial payment
+    pub deliver_min: Option<RippleAmount>,
+    pub flags: u32,
+    /// The last ledger the transaction can be included in, which wallets
+    /// autofill to bound the time it can stay pending
+    pub last_ledger_sequence: Option<u32>,
+    pub source_tag: Option<u32>,
+    /// The ticket used in place of the sequence, which is then 0
+    pub ticket_sequence: Option<u32>,
+    /// The id of the network, which is required on networks with an id above 1024
+    pub network_id: Option<u32>,
     pub memos: Vec<String>,
     pub public_key: [u8; 33],
+    /// The account of a multi-signed transaction. When set, 'SigningPubKey' is
+    /// left empty, `public_key` is ignored and the transaction is authorized by
+    /// the signers added with `RippleTransaction::add_signer`
+    pub multisig_account: Option<[u8; 20]>,
+}
+
+impl Default for RippleTransactionParameters {
+    fn default() -> Self {
+        Self {
+            transaction_type: RippleTransactionType::Payment,
+            destination: [0u8; 20],
+            fee: 0,
+            sequence: 0,
+            destination_tag: 0,
+            amount: RippleAmount::default(),
+            send_max: None,
+            deliver_min: None,
+            flags: 0,
+            last_ledger_sequence: None,
+            source_tag: None,
+            ticket_sequence: None,
+            network_id: None,
+            memos: vec![],
+            public_key: [0u8; 33],
+            multisig_account: None,
+        }
+    }
+}
+
+impl RippleTransactionParameters {
+    /// Sets the payment destination given a classic address or an X-address.
+    /// The tag of an X-address is extracted into `destination_tag`
+    pub fn set_destination(&mut self, address: &str) -> Result<(), TransactionError> {
+        match address.starts_with('r') {
+            true => {
+                self.destination = RippleAddress::from_str(address)?.to_hash160()?;
+            }
+            false => {
+                let x_address = RippleXAddress::from_str(address)?;
+                if let Some(tag) = x_address.tag {
+                    if self.destination_tag != 0 && self.destination_tag != tag {
+                        return Err(TransactionError::Message(format!(
+                            "Destination tag {} conflicts with the x-address tag {}",
+                            self.destination_tag, tag
+                        )));
+                    }
+                    self.destination_tag = tag;
+                }
+                self.destination = x_address.address.to_hash160()?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct RippleTransaction {
     pub params: RippleTransactionParameters,
     pub signature: Option<Vec<u8>>,
+    /// The signers of a multi-signed transaction
+    pub signers: Vec<RippleSigner>,
 }
 
 impl Transaction for RippleTransaction {
@@ -38,43 +341,48 @@ impl Transaction for RippleTransaction {
         Ok(Self {
             params: parameters.clone(),
             signature: None,
+            signers: vec![],
         })
     }
 
     fn sign(&mut self, rs: Vec<u8>, _recid: u8) -> Result<Vec<u8>, TransactionError> {
+        if self.params.multisig_account.is_some() {
+            return Err(TransactionError::Message(
+                "Multi-signed transactions should be signed by add_signer".to_string(),
+            ));
+        }
         if rs.len() != 64 {
             return Err(TransactionError::Message(format!(
                 "Invalid signature length {}",
                 rs.len(),
             )));
         }
+        // An Ed25519 signature over the digest rather than the payload is rejected
+        if self.params.public_key[0] == ED25519_PREFIX
+            && !verify_signature(&self.params.public_key, &self.signing_payload()?, &rs)?
+        {
+            return Err(TransactionError::Message(
+                "Invalid Ed25519 signature".to_string(),
+            ));
+        }
         self.signature = Some(rs);
         self.to_bytes()
     }
 
     fn from_bytes(stream: &[u8]) -> Result<Self, TransactionError> {
-        // Before we deserialize the stream, we should postfix the
-        // stream with a mark of ending for object type
-        let mut stream = stream.to_vec();
-        stream.extend(serialize_type(SerializedTypeID::Object, 1)?);
-
-        // Deserialize the stream to a SerializedType object, which
-        // is then converted to a Ripple Transaction
-        Self::from_st(&SerializedType::deserialize(
-            &mut stream.as_slice(),
-            SerializedTypeID::Object,
-            0,
-        )?)
+        // Decode the stream to its JSON form, which is then converted
+        // to a Ripple Transaction
+        Self::from_json(&codec::decode(stream)?)
     }
 
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        self.to_st()?.serialize()
+        codec::encode(&self.to_json()?, false)
     }
 
     fn to_transaction_id(&self) -> Result<Self::TransactionId, TransactionError> {
-        match &self.signature {
+        match self.signature.is_some() || !self.signers.is_empty() {
             // compute the txid of the signed tx
-            Some(_) => {
+            true => {
                 // prefix the tx stream with "TXN\0"
                 let mut stream = vec![b'T', b'X', b'N', 0];
                 stream.extend(self.to_bytes()?);
@@ -82,13 +390,14 @@ impl Transaction for RippleTransaction {
                 let txid = sha512(&stream)[..32].to_vec();
                 Ok(RippleTransactionId { txid })
             }
+            // each signer of a multi-signed tx signs its own digest
+            false if self.params.multisig_account.is_some() => Err(TransactionError::Message(
+                "Multi-signed transactions should be signed over multisign_hash".to_string(),
+            )),
             // compute the raw tx's digest for signing
-            None => {
-                // preifx the tx stream with "STX\0"
-                let mut stream = vec![b'S', b'T', b'X', 0];
-                stream.extend(self.to_bytes()?);
+            false => {
                 // we take the first half of the sha512 hash as digest for signing
-                let digest = sha512(&stream)[..32].to_vec();
+                let digest = sha512(&self.signing_payload()?)[..32].to_vec();
                 Ok(RippleTransactionId { txid: digest })
             }
         }
@@ -109,747 +418,802 @@ impl fmt::Display for RippleTransaction {
     }
 }
 
-impl RippleTransaction {
-    fn to_st(&self) -> Result<SerializedType, TransactionError> {
-        let mut account_id = [0u8; 20];
-        account_id.copy_from_slice(&hash160(&self.params.public_key));
-
-        // It is a transaction of type 'payment'
-        let tx_type = SerializedType::Uint16 {
-            field_value: 2,
-            value: 0,
-        };
-
-        let account = SerializedType::Account {
-            field_value: 1,
-            account_id,
-        };
-
-        let dest = SerializedType::Account {
-            field_value: 3,
-            account_id: self.params.destination,
-        };
-
-        let fee = SerializedType::Amount {
-            field_value: 8,
-            value: self.params.fee as u64 | 0x4000000000000000,
-        };
-
-        let sequence = SerializedType::Uint32 {
-            field_value: 4,
-            value: self.params.sequence,
-        };
-
-        let dest_tag = SerializedType::Uint32 {
-            field_value: 14,
-            value: self.params.destination_tag,
-        };
-
-        let amount = SerializedType::Amount {
-            field_value: 1,
-            value: self.params.amount | 0x4000000000000000,
-        };
-
-        let public_key = SerializedType::Blob {
-            field_value: 3,
-            buffer: self.params.public_key.to_vec(),
-        };
-
-        let mut st = SerializedType::Object {
-            field_value: 0,
-            members: vec![
-                tx_type, account, dest, fee, sequence, dest_tag, amount, public_key,
-            ],
-        };
-
-        let memos: Vec<SerializedType> = self
-            .params
-            .memos
-            .iter()
-            .map(|memo| {
-                let memo_type = SerializedType::Blob {
-                    field_value: 12,
-                    buffer: "payment".as_bytes().to_vec(),
-                };
-                let memo = SerializedType::Blob {
-                    field_value: 13,
-                    buffer: memo.as_bytes().to_vec(),
-                };
-                SerializedType::Object {
-                    field_value: 10,
-                    members: vec![memo_type, memo],
-                }
-            })
-            .collect();
-
-        if !memos.is_empty() {
-            st.add(SerializedType::Array {
-                field_value: 9,
-                elems: memos,
-            })?
-        }
-
-        if let Some(sig) = &self.signature {
-            let sig = Signature::parse_standard_slice(sig)
-                .map_err(|error| TransactionError::Crate("libsecp256k1", format!("{:?}", error)))?
-                .serialize_der()
-                .as_ref()
-                .to_vec();
-            let sig = SerializedType::Blob {
-                field_value: 4,
-                buffer: sig,
-            };
-            st.add(sig)?;
-        }
-
-        // Ascendingly sort the object's members by SerializedTypeID. If SerializedTypeIDs
-        // are equal, ascendingly sort them by field value
-        st.sort()?;
-
-        Ok(st)
-    }
-
-    fn from_st(st: &SerializedType) -> Result<Self, TransactionError> {
-        if let SerializedType::Object { members, .. } = st {
-            let mut source = [0u8; 20];
-            let mut destination = [0u8; 20];
-            let mut fee = 0;
-            let mut sequence = 0;
-            let mut destination_tag = 0;
-            let mut amount = 0;
-            let mut memos = vec![];
-            let mut public_key = [0u8; 33];
-            let mut signature: Option<Vec<u8>> = None;
-
-            for mem in members {
-                match mem {
-                    SerializedType::Account {
-                        field_value,
-                        account_id,
-                        ..
-                    } => {
-                        if *field_value == 3 {
-                            destination = *account_id;
-                        } else if *field_value == 1 {
-                            // Store source account (field 1) for later hash160(public_key) verification.
-                            source = *account_id;
-                        } else {
-                            return Err(TransactionError::Message(format!(
-                                "Invalid field value {} for field serialized type 'account'",
-                                *field_value,
-                            )));
-                        }
-                    }
-                    SerializedType::Amount {
-                        field_value, value, ..
-                    } => {
-                        if *field_value == 1 {
-                            amount = *value & !0x4000000000000000;
-                        } else if *field_value == 8 {
-                            fee = *value as u32;
-                        } else {
-                            return Err(TransactionError::Message(format!(
-                                "Invalid field value {} for serialized type 'amount'",
-                                *field_value,
-                            )));
-                        }
-                    }
-                    SerializedType::Array { field_value, elems } => {
-                        if *field_value == 9 {
-                            for elem in elems {
-                                if let SerializedType::Object {
-                                    field_value,
-                                    members,
-                                } = elem
-                                {
-                                    if *field_value == 10 {
-                                        for mem in members {
-                                            if let SerializedType::Blob {
-                                                field_value,
-                                                buffer,
-                                            } = mem
-                                            {
-                                                if *field_value == 12 {
-                                                    // we skip the deserialization of memo_type
-                                                } else if *field_value == 13 {
-                                                    match String::from_utf8(buffer.clone()) {
-                                                        Ok(memo) => memos.push(memo),
-                                                        Err(_) => {
-                                                            return Err(TransactionError::Message(
-                                                                "Invalid memo".to_string(),
-                                                            ))
-                                                        }
-                                                    }
-                                                } else {
-                                                    return Err(TransactionError::Message(format!(
-                                                        "Invalid field value {} for serialized type",
-                                                        *field_value,
-                                                    )));
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        return Err(TransactionError::Message(format!(
-                                            "Invalid field value {} for serialized type 'object'",
-                                            *field_value,
-                                        )));
-                                    }
-                                } else {
-                                    return Err(TransactionError::Message(
-                                        "None object array elements not allowed".to_string(),
-                                    ));
-                                }
-                            }
-                        } else {
-                            return Err(TransactionError::Message(format!(
-                                "Invalid field value {} for serialized type 'array'",
-                                *field_value,
-                            )));
-                        }
-                    }
-                    SerializedType::Blob {
-                        field_value,
-                        buffer,
-                    } => {
-                        if *field_value == 3 {
-                            if buffer.len() != 33 {
-                                return Err(TransactionError::Message(format!(
-                                    "Invalid public key length {}",
-                                    buffer.len(),
-                                )));
-                            }
-                            public_key.copy_from_slice(buffer);
-                        } else if *field_value == 4 {
-                            if !(70..=72).contains(&buffer.len()) {
-                                return Err(TransactionError::Message(format!(
-                                    "Invalid signature length {}",
-                                    buffer.len(),
-                                )));
-                            }
-                            let sig = Signature::parse_der(buffer)
-                                .map_err(|error| {
-                                    TransactionError::Crate("libsecp256k1", format!("{:?}", error))
-                                })?
-                                .serialize()
-                                .to_vec();
-                            signature = Some(sig);
-                        } else {
-                            return Err(TransactionError::Message(format!(
-                                "Invalid field value {} for serialized type 'blob'",
-                                *field_value,
-                            )));
-                        }
-                    }
-                    SerializedType::Uint32 { field_value, value } => {
-                        if *field_value == 4 {
-                            sequence = *value;
-                        } else if *field_value == 14 {
-                            destination_tag = *value;
-                        } else {
-                            return Err(TransactionError::Message(format!(
-                                "Invalid field value {} for serialized type 'integer'",
-                                *field_value,
-                            )));
-                        }
-                    }
-                    SerializedType::Uint16 { .. } => {}
-                    SerializedType::Object { .. } => {
-                        return Err(TransactionError::Message(
-                            "Serialized type 'object' not allowd in first layer deserialization"
-                                .to_string(),
-                        ))
-                    }
-                }
-            }
+/// Takes the fields out of the JSON form of a transaction one by one, so
+/// that the fields left over can be rejected as unsupported
+struct JsonFields(Map<String, Value>);
 
-            // Enforce that Account(source) matches the signer public key hash.
-            let derived_source = hash160(&public_key);
-            if source != derived_source.as_slice() {
-                return Err(TransactionError::Message(format!(
-                    "Invalid source account: expected {}, got {}",
-                    hex::encode(derived_source),
-                    hex::encode(source),
-                )));
-            }
+impl JsonFields {
+    fn take(&mut self, name: &str) -> Option<Value> {
+        self.0.remove(name)
+    }
 
-            let mut tx = RippleTransaction::new(&RippleTransactionParameters {
-                destination,
-                fee,
-                sequence,
-                destination_tag,
-                amount,
-                memos,
-                public_key,
-            })?;
+    fn int<T: TryFrom<i64>>(&mut self, name: &str) -> Result<Option<T>, TransactionError> {
+        self.take(name).map(|v| int_from_json(&v)).transpose()
+    }
 
-            if signature.is_some() {
-                tx.signature = signature;
-            }
+    fn account(&mut self, name: &str) -> Result<Option<[u8; 20]>, TransactionError> {
+        self.take(name).map(|v| account_from_json(&v)).transpose()
+    }
 
-            Ok(tx)
-        } else {
-            Err(TransactionError::Message(
-                "Deserialization of none object serialized type not allowed for Ripple transaction"
-                    .to_string(),
-            ))
-        }
+    fn amount(&mut self, name: &str) -> Result<Option<RippleAmount>, TransactionError> {
+        self.take(name).map(|v| amount_from_json(&v)).transpose()
     }
 
-    pub fn set_public_key(&mut self, pk: Vec<u8>) -> Result<(), TransactionError> {
-        self.params.public_key = pk.try_into().unwrap();
-        Ok(())
+    fn blob(&mut self, name: &str) -> Result<Option<Vec<u8>>, TransactionError> {
+        self.take(name).map(|v| hex_from_json(&v)).transpose()
     }
-}
 
-#[derive(Eq, PartialEq, Hash, Debug, Clone)]
-pub struct RippleTransactionId {
-    pub txid: Vec<u8>,
+    fn hash<const N: usize>(&mut self, name: &str) -> Result<Option<[u8; N]>, TransactionError> {
+        match self.blob(name)? {
+            Some(buffer) => Ok(Some(buffer.try_into().map_err(|_| {
+                TransactionError::Message(format!("Invalid length of field {}", name))
+            })?)),
+            None => Ok(None),
+        }
+    }
 }
 
-impl TransactionId for RippleTransactionId {}
-
-impl fmt::Display for RippleTransactionId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", hex::encode(&self.txid).to_uppercase())
-    }
+fn parse_public_key(public_key: Vec<u8>) -> Result<[u8; 33], TransactionError> {
+    public_key.try_into().map_err(|pk: Vec<u8>| {
+        TransactionError::Message(format!("Invalid public key length {}", pk.len()))
+    })
 }
 
-#[derive(PartialEq)]
-enum SerializedTypeID {
-    Uint16 = 1,
-    Uint32 = 2,
-    Amount = 6,
-    VL = 7,
-    Account = 8,
-    // 9-13 are reserved
-    Object = 14,
-    Array = 15,
+fn missing(name: &str) -> TransactionError {
+    TransactionError::Message(format!("Missing field {}", name))
 }
 
-impl SerializedTypeID {
-    fn from_u8(b: u8) -> Result<Self, TransactionError> {
-        match b {
-            1 => Ok(Self::Uint16),
-            2 => Ok(Self::Uint32),
-            6 => Ok(Self::Amount),
-            7 => Ok(Self::VL),
-            8 => Ok(Self::Account),
-            14 => Ok(Self::Object),
-            15 => Ok(Self::Array),
-            _ => Err(TransactionError::Message(format!(
-                "Unsupported serialized type id {}",
-                b,
-            ))),
-        }
-    }
+fn insert(json: &mut Map<String, Value>, name: &str, value: Value) {
+    json.insert(name.to_string(), value);
 }
 
-impl fmt::Display for SerializedTypeID {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Uint16 => write!(f, "Uint16"),
-            Self::Uint32 => write!(f, "Uint32"),
-            Self::Amount => write!(f, "Amount"),
-            Self::VL => write!(f, "Blob"),
-            Self::Account => write!(f, "Account"),
-            Self::Object => write!(f, "Object"),
-            Self::Array => write!(f, "Array"),
-        }
+fn insert_opt<T>(
+    json: &mut Map<String, Value>,
+    name: &str,
+    value: &Option<T>,
+    f: impl Fn(&T) -> Result<Value, TransactionError>,
+) -> Result<(), TransactionError> {
+    if let Some(value) = value {
+        json.insert(name.to_string(), f(value)?);
     }
+    Ok(())
 }
 
-#[derive(Clone, Debug, Eq)]
-enum SerializedType {
-    Account {
-        field_value: u32,
-        account_id: [u8; 20],
-    },
-
-    Amount {
-        field_value: u32,
-        value: u64,
-    },
-
-    Uint16 {
-        field_value: u32,
-        value: u16,
-    },
-
-    Uint32 {
-        field_value: u32,
-        value: u32,
-    },
-
-    Blob {
-        field_value: u32,
-        buffer: Vec<u8>,
-    },
-
-    Array {
-        field_value: u32,
-        elems: Vec<SerializedType>,
-    },
-
-    Object {
-        field_value: u32,
-        members: Vec<SerializedType>,
-    },
+fn int_json<T: Copy + Into<Value>>(value: &T) -> Result<Value, TransactionError> {
+    Ok((*value).into())
 }
 
-impl PartialEq for SerializedType {
-    fn eq(&self, other: &Self) -> bool {
-        let typ0 = self.typ() as u32;
-        let val0 = self.val();
-        let typ1 = other.typ() as u32;
-        let val1 = other.val();
-
-        typ0 == typ1 && val0 == val1
-    }
+fn hex_json<T: AsRef<[u8]>>(value: &T) -> Result<Value, TransactionError> {
+    Ok(Value::String(hex::encode_upper(value)))
 }
 
-impl Ord for SerializedType {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let typ0 = self.typ() as u32;
-        let val0 = self.val();
-        let typ1 = other.typ() as u32;
-        let val1 = other.val();
-
-        if typ0 < typ1 {
-            Ordering::Less
-        } else if typ0 > typ1 {
-            Ordering::Greater
-        } else if val0 < val1 {
-            Ordering::Less
-        } else if val0 > val1 {
-            Ordering::Greater
-        } else {
-            Ordering::Equal
-        }
+/// Encodes a signature for the 'TxnSignature' field, i.e. in DER form for
+/// secp256k1 and as is for Ed25519
+fn encode_signature(public_key: &[u8; 33], rs: &[u8]) -> Result<Vec<u8>, TransactionError> {
+    if public_key[0] == ED25519_PREFIX {
+        return Ok(rs.to_vec());
     }
+    Ok(Signature::parse_standard_slice(rs)
+        .map_err(|error| TransactionError::Crate("libsecp256k1", format!("{:?}", error)))?
+        .serialize_der()
+        .as_ref()
+        .to_vec())
 }
 
-impl PartialOrd for SerializedType {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Decodes the 'TxnSignature' field to a 64-byte signature
+fn decode_signature(public_key: &[u8; 33], der: &[u8]) -> Result<[u8; 64], TransactionError> {
+    if public_key[0] == ED25519_PREFIX {
+        return der.try_into().map_err(|_| {
+            TransactionError::Message(format!("Invalid signature length {}", der.len()))
+        });
     }
-}
-
-fn serialize_len(mut len: u32) -> Result<Vec<u8>, TransactionError> {
-    if len <= 192 {
-        Ok(vec![len as u8])
-    } else if len <= 12480 {
-        len -= 193;
-        let b0 = 193 + (len >> 8) as u8;
-        let b1 = (len & 0xff) as u8;
-        Ok(vec![b0, b1])
-    } else if len <= 918744 {
-        len -= 12481;
-        let b0 = 241 + (len >> 16) as u8;
-        let b1 = ((len >> 8) & 0xff) as u8;
-        let b2 = (len & 0xff) as u8;
-        Ok(vec![b0, b1, b2])
-    } else {
-        Err(TransactionError::Message(
-            "Maximum length exceeded".to_string(),
-        ))
+    if !(70..=72).contains(&der.len()) {
+        return Err(TransactionError::Message(format!(
+            "Invalid signature length {}",
+            der.len(),
+        )));
     }
+    Ok(Signature::parse_der(der)
+        .map_err(|error| TransactionError::Crate("libsecp256k1", format!("{:?}", error)))?
+        .serialize())
 }
 
-fn deserialize_len(stream: &mut &[u8]) -> Result<u32, TransactionError> {
-    let mut b = [0u8; 1];
-    let _ = stream.read(&mut b)?;
-
-    match b[0] {
-        (..=192) => Ok(b[0] as u32),
-        (193..=240) => {
-            let mut buf = [0u8; 1];
-            let _ = stream.read(&mut buf)?;
-            let mut len = [0u8; 4];
-            len[2] = b[0] - 193;
-            len[3] = buf[0];
-            Ok(u32::from_be_bytes(len))
+/// Verifies a signature over a signing payload. An secp256k1 key signs the first
+/// half of the sha512 hash of the payload while an Ed25519 key signs the payload itself
+fn verify_signature(
+    public_key: &[u8; 33],
+    payload: &[u8],
+    signature: &[u8],
+) -> Result<bool, TransactionError> {
+    let public_key = RipplePublicKey::deserialize(public_key)
+        .map_err(|error| TransactionError::Message(error.to_string()))?;
+    match (
+        public_key.to_secp256k1_public_key(),
+        public_key.to_ed25519_public_key(),
+    ) {
+        (Some(pk), _) => {
+            let digest = libsecp256k1::Message::parse_slice(&sha512(payload)[..32])
+                .map_err(|error| TransactionError::Crate("libsecp256k1", format!("{:?}", error)))?;
+            let sig = Signature::parse_standard_slice(signature)
+                .map_err(|error| TransactionError::Crate("libsecp256k1", format!("{:?}", error)))?;
+            Ok(libsecp256k1::verify(&digest, &sig, &pk))
         }
-        (241..) => {
-            let mut buf = [0u8; 2];
-            let _ = stream.read(&mut buf)?;
-            let mut len = [0u8; 4];
-            len[1] = b[0] - 241;
-            len[2] = buf[0];
-            len[3] = buf[1];
-            Ok(u32::from_be_bytes(len))
+        (None, Some(pk)) => {
+            let sig = ed25519_dalek::Signature::from_slice(signature).map_err(|error| {
+                TransactionError::Crate("ed25519_dalek", format!("{:?}", error))
+            })?;
+            Ok(pk.verify_strict(payload, &sig).is_ok())
         }
+        (None, None) => Ok(false),
     }
 }
 
-fn serialize_type(typ: SerializedTypeID, val: u32) -> Result<Vec<u8>, TransactionError> {
-    let typ = typ as u32;
-
-    if !(..256).contains(&typ) || !(..256).contains(&val) {
-        return Err(TransactionError::Message("Number out of range".to_string()));
-    }
+impl RippleTransaction {
+    /// Checks the fields of the transaction type against the rules enforced by the ledger
+    fn validate(&self) -> Result<(), TransactionError> {
+        match self.params.multisig_account {
+            Some(_) if self.signature.is_some() => {
+                return Err(TransactionError::Message(
+                    "Multi-signed transactions cannot carry a single signature".to_string(),
+                ))
+            }
+            Some(_) if self.signers.len() > MAX_SIGNER_ENTRIES => {
+                return Err(TransactionError::Message(format!(
+                    "Invalid number of signers {}",
+                    self.signers.len()
+                )))
+            }
+            None if !self.signers.is_empty() => {
+                return Err(TransactionError::Message(
+                    "Signers are only allowed in multi-signed transactions".to_string(),
+                ))
+            }
+            _ => {}
+        }
 
-    if typ < 16 {
-        if val < 16 {
-            // common type, common val
-            Ok(vec![((typ << 4) | val) as u8])
-        } else {
-            // common type, uncommon val
-            let b0 = (typ << 4) as u8;
-            let b1 = val as u8;
-            Ok(vec![b0, b1])
+        match &self.params.transaction_type {
+            RippleTransactionType::TrustSet { limit_amount, .. } if limit_amount.is_xrp() => {
+                Err(TransactionError::Message(
+                    "TrustSet requires an issued currency limit amount".to_string(),
+                ))
+            }
+            RippleTransactionType::SignerListSet {
+                signer_quorum,
+                signer_entries,
+            } => {
+                if *signer_quorum == 0 {
+                    return match signer_entries.is_empty() {
+                        true => Ok(()),
+                        false => Err(TransactionError::Message(
+                            "Signer entries must be empty when deleting a signer list".to_string(),
+                        )),
+                    };
+                }
+                if signer_entries.is_empty() || signer_entries.len() > MAX_SIGNER_ENTRIES {
+                    return Err(TransactionError::Message(format!(
+                        "Invalid number of signer entries {}",
+                        signer_entries.len()
+                    )));
+                }
+                for (i, entry) in signer_entries.iter().enumerate() {
+                    if signer_entries[i + 1..]
+                        .iter()
+                        .any(|e| e.account == entry.account)
+                    {
+                        return Err(TransactionError::Message(
+                            "Duplicate signer entries".to_string(),
+                        ));
+                    }
+                }
+                let total: u32 = signer_entries.iter().map(|e| e.signer_weight as u32).sum();
+                match *signer_quorum <= total {
+                    true => Ok(()),
+                    false => Err(TransactionError::Message(format!(
+                        "Signer quorum {} exceeds the total signer weight {}",
+                        signer_quorum, total
+                    ))),
+                }
+            }
+            RippleTransactionType::OfferCreate {
+                taker_pays,
+                taker_gets,
+                ..
+            } if taker_pays.is_xrp() && taker_gets.is_xrp() => Err(TransactionError::Message(
+                "OfferCreate cannot exchange XRP for XRP".to_string(),
+            )),
+            RippleTransactionType::NFTokenMint {
+                transfer_fee: Some(fee),
+                ..
+            } if *fee > MAX_TRANSFER_FEE => Err(TransactionError::Message(format!(
+                "Invalid transfer fee {}",
+                fee
+            ))),
+            RippleTransactionType::NFTokenAcceptOffer {
+                nftoken_sell_offer,
+                nftoken_buy_offer,
+                nftoken_broker_fee,
+            } => match (nftoken_sell_offer, nftoken_buy_offer, nftoken_broker_fee) {
+                (None, None, _) => Err(TransactionError::Message(
+                    "NFTokenAcceptOffer requires a sell offer or a buy offer".to_string(),
+                )),
+                (Some(_), Some(_), _) | (_, _, None) => Ok(()),
+                _ => Err(TransactionError::Message(
+                    "A broker fee is only allowed in brokered mode".to_string(),
+                )),
+            },
+            _ => Ok(()),
         }
-    } else if val < 16 {
-        // uncommon type, common val
-        let b0 = val as u8;
-        let b1 = typ as u8;
-        Ok(vec![b0, b1])
-    } else {
-        // uncommon type, uncommon val
-        let b0 = 0;
-        let b1 = typ as u8;
-        let b2 = val as u8;
-        Ok(vec![b0, b1, b2])
     }
-}
 
-fn deserialize_type(stream: &mut &[u8]) -> Result<(u8, u8), TransactionError> {
-    let mut b = [0u8; 1];
-    let _ = stream.read(&mut b)?;
+    /// Returns the canonical JSON form of the transaction
+    pub fn to_json(&self) -> Result<Value, TransactionError> {
+        self.validate()?;
 
-    match (b[0] & 0xf0 == 0, b[0] & 0x0f == 0) {
-        // both higher and lower 4 bits are zero
-        (true, true) => {
-            let mut buf = [0u8; 2];
-            let _ = stream.read(&mut buf)?;
-            Ok((buf[0], buf[1]))
-        }
-        // only higher 4 bits are zero
-        (true, false) => {
-            let mut buf = [0u8; 1];
-            let _ = stream.read(&mut buf)?;
-            Ok((buf[0], b[0]))
+        let params = &self.params;
+        let (account_id, public_key) = match params.multisig_account {
+            Some(account_id) => (account_id, vec![]),
+            None => {
+                let mut account_id = [0u8; 20];
+                account_id.copy_from_slice(&hash160(&params.public_key));
+                (account_id, params.public_key.to_vec())
+            }
+        };
+
+        let mut json = Map::new();
+        insert(
+            &mut json,
+            "TransactionType",
+            json!(params.transaction_type.name()),
+        );
+        insert(&mut json, "Account", account_to_json(&account_id)?);
+        insert(&mut json, "Fee", json!(params.fee.to_string()));
+        insert(&mut json, "Sequence", json!(params.sequence));
+        insert(&mut json, "SigningPubKey", hex_json(&public_key)?);
+
+        if params.flags != 0 {
+            insert(&mut json, "Flags", json!(params.flags));
         }
-        // only lower 4 bits are zero
-        (false, true) => {
-            let mut buf = [0u8; 1];
-            let _ = stream.read(&mut buf)?;
-            Ok((b[0] >> 4, buf[0]))
+        insert_opt(
+            &mut json,
+            "LastLedgerSequence",
+            &params.last_ledger_sequence,
+            int_json,
+        )?;
+        insert_opt(&mut json, "SourceTag", &params.source_tag, int_json)?;
+        insert_opt(
+            &mut json,
+            "TicketSequence",
+            &params.ticket_sequence,
+            int_json,
+        )?;
+        insert_opt(&mut json, "NetworkID", &params.network_id, int_json)?;
+
+        match &params.transaction_type {
+            RippleTransactionType::Payment => {
+                insert(
+                    &mut json,
+                    "Destination",
+                    account_to_json(&params.destination)?,
+                );
+                insert(&mut json, "DestinationTag", json!(params.destination_tag));
+                insert(&mut json, "Amount", amount_to_json(&params.amount)?);
+                insert_opt(&mut json, "SendMax", &params.send_max, amount_to_json)?;
+                insert_opt(&mut json, "DeliverMin", &params.deliver_min, amount_to_json)?;
+            }
+            RippleTransactionType::TrustSet {
+                limit_amount,
+                quality_in,
+                quality_out,
+            } => {
+                insert(&mut json, "LimitAmount", amount_to_json(limit_amount)?);
+                insert_opt(&mut json, "QualityIn", quality_in, int_json)?;
+                insert_opt(&mut json, "QualityOut", quality_out, int_json)?;
+            }
+            RippleTransactionType::AccountSet {
+                set_flag,
+                clear_flag,
+                domain,
+                email_hash,
+                message_key,
+                transfer_rate,
+                tick_size,
+                nftoken_minter,
+            } => {
+                insert_opt(&mut json, "SetFlag", set_flag, int_json)?;
+                insert_opt(&mut json, "ClearFlag", clear_flag, int_json)?;
+                insert_opt(&mut json, "Domain", domain, hex_json)?;
+                insert_opt(&mut json, "EmailHash", email_hash, hex_json)?;
+                insert_opt(&mut json, "MessageKey", message_key, hex_json)?;
+                insert_opt(&mut json, "TransferRate", transfer_rate, int_json)?;
+                insert_opt(&mut json, "TickSize", tick_size, int_json)?;
+                insert_opt(&mut json, "NFTokenMinter", nftoken_minter, account_to_json)?;
+            }
+            RippleTransactionType::SetRegularKey { regular_key } => {
+                insert_opt(&mut json, "RegularKey", regular_key, account_to_json)?;
+            }
+            RippleTransactionType::SignerListSet {
+                signer_quorum,
+                signer_entries,
+            } => {
+                insert(&mut json, "SignerQuorum", json!(signer_quorum));
+                if !signer_entries.is_empty() {
+                    let mut entries = vec![];
+                    for entry in signer_entries {
+                        entries.push(json!({
+                            "SignerEntry": {
+                                "Account": account_to_json(&entry.account)?,
+                                "SignerWeight": entry.signer_weight,
+                            }
+                        }));
+                    }
+                    insert(&mut json, "SignerEntries", Value::Array(entries));
+                }
+            }
+            RippleTransactionType::EscrowCreate {
+                destination,
+                destination_tag,
+                amount,
+                finish_after,
+                cancel_after,
+                condition,
+            } => {
+                insert(&mut json, "Destination", account_to_json(destination)?);
+                insert_opt(&mut json, "DestinationTag", destination_tag, int_json)?;
+                insert(&mut json, "Amount", amount_to_json(amount)?);
+                insert_opt(&mut json, "FinishAfter", finish_after, int_json)?;
+                insert_opt(&mut json, "CancelAfter", cancel_after, int_json)?;
+                insert_opt(&mut json, "Condition", condition, hex_json)?;
+            }
+            RippleTransactionType::EscrowFinish {
+                owner,
+                offer_sequence,
+                condition,
+                fulfillment,
+            } => {
+                insert(&mut json, "Owner", account_to_json(owner)?);
+                insert(&mut json, "OfferSequence", json!(offer_sequence));
+                insert_opt(&mut json, "Condition", condition, hex_json)?;
+                insert_opt(&mut json, "Fulfillment", fulfillment, hex_json)?;
+            }
+            RippleTransactionType::EscrowCancel {
+                owner,
+                offer_sequence,
+            } => {
+                insert(&mut json, "Owner", account_to_json(owner)?);
+                insert(&mut json, "OfferSequence", json!(offer_sequence));
+            }
+            RippleTransactionType::OfferCreate {
+                taker_pays,
+                taker_gets,
+                expiration,
+                offer_sequence,
+            } => {
+                insert(&mut json, "TakerPays", amount_to_json(taker_pays)?);
+                insert(&mut json, "TakerGets", amount_to_json(taker_gets)?);
+                insert_opt(&mut json, "Expiration", expiration, int_json)?;
+                insert_opt(&mut json, "OfferSequence", offer_sequence, int_json)?;
+            }
+            RippleTransactionType::OfferCancel { offer_sequence } => {
+                insert(&mut json, "OfferSequence", json!(offer_sequence));
+            }
+            RippleTransactionType::AccountDelete {
+                destination,
+                destination_tag,
+            } => {
+                insert(&mut json, "Destination", account_to_json(destination)?);
+                insert_opt(&mut json, "DestinationTag", destination_tag, int_json)?;
+            }
+            RippleTransactionType::NFTokenMint {
+                nftoken_taxon,
+                issuer,
+                transfer_fee,
+                uri,
+            } => {
+                insert(&mut json, "NFTokenTaxon", json!(nftoken_taxon));
+                insert_opt(&mut json, "Issuer", issuer, account_to_json)?;
+                insert_opt(&mut json, "TransferFee", transfer_fee, int_json)?;
+                insert_opt(&mut json, "URI", uri, hex_json)?;
+            }
+            RippleTransactionType::NFTokenCreateOffer {
+                nftoken_id,
+                amount,
+                owner,
+                destination,
+                expiration,
+            } => {
+                insert(&mut json, "NFTokenID", hex_json(nftoken_id)?);
+                insert(&mut json, "Amount", amount_to_json(amount)?);
+                insert_opt(&mut json, "Owner", owner, account_to_json)?;
+                insert_opt(&mut json, "Destination", destination, account_to_json)?;
+                insert_opt(&mut json, "Expiration", expiration, int_json)?;
+            }
+            RippleTransactionType::NFTokenAcceptOffer {
+                nftoken_sell_offer,
+                nftoken_buy_offer,
+                nftoken_broker_fee,
+            } => {
+                insert_opt(&mut json, "NFTokenSellOffer", nftoken_sell_offer, hex_json)?;
+                insert_opt(&mut json, "NFTokenBuyOffer", nftoken_buy_offer, hex_json)?;
+                insert_opt(
+                    &mut json,
+                    "NFTokenBrokerFee",
+                    nftoken_broker_fee,
+                    amount_to_json,
+                )?;
+            }
         }
-        // neither higher 4 bits nor lower 4 bits are zero
-        (false, false) => Ok(((b[0] & 0xf0) >> 4, b[0] & 0x0f)),
-    }
-}
 
-impl SerializedType {
-    fn typ(&self) -> SerializedTypeID {
-        match self {
-            Self::Account { .. } => SerializedTypeID::Account,
-            Self::Amount { .. } => SerializedTypeID::Amount,
-            Self::Uint16 { .. } => SerializedTypeID::Uint16,
-            Self::Uint32 { .. } => SerializedTypeID::Uint32,
-            Self::Blob { .. } => SerializedTypeID::VL,
-            Self::Array { .. } => SerializedTypeID::Array,
-            Self::Object { .. } => SerializedTypeID::Object,
+        if !params.memos.is_empty() {
+            let memos = params
+                .memos
+                .iter()
+                .map(|memo| {
+                    json!({
+                        "Memo": {
+                            "MemoType": hex::encode_upper("payment"),
+                            "MemoData": hex::encode_upper(memo),
+                        }
+                    })
+                })
+                .collect();
+            insert(&mut json, "Memos", Value::Array(memos));
         }
-    }
 
-    fn val(&self) -> u32 {
-        match self {
-            Self::Account { field_value, .. } => *field_value,
-            Self::Amount { field_value, .. } => *field_value,
-            Self::Uint16 { field_value, .. } => *field_value,
-            Self::Uint32 { field_value, .. } => *field_value,
-            Self::Blob { field_value, .. } => *field_value,
-            Self::Array { field_value, .. } => *field_value,
-            Self::Object { field_value, .. } => *field_value,
+        if let Some(sig) = &self.signature {
+            let sig = encode_signature(&params.public_key, sig)?;
+            insert(&mut json, "TxnSignature", hex_json(&sig)?);
         }
-    }
-
-    fn serialize_type(&self) -> Result<Vec<u8>, TransactionError> {
-        serialize_type(self.typ(), self.val())
-    }
 
-    fn serialize(&self) -> Result<Vec<u8>, TransactionError> {
-        match self {
-            Self::Amount { value, .. } => Ok(value.to_be_bytes().to_vec()),
-            Self::Uint16 { value, .. } => Ok(value.to_be_bytes().to_vec()),
-            Self::Uint32 { value, .. } => Ok(value.to_be_bytes().to_vec()),
-            Self::Account { account_id, .. } => {
-                let mut stream = serialize_len(account_id.len() as u32)?;
-                stream.extend(account_id.to_vec());
-                Ok(stream)
-            }
-            Self::Blob { buffer, .. } => {
-                let mut stream = serialize_len(buffer.len() as u32)?;
-                stream.extend(buffer);
-                Ok(stream)
-            }
-            Self::Array { elems, .. } => {
-                let mut stream = vec![];
-                for elem in elems {
-                    stream.extend(elem.serialize_type()?);
-                    stream.extend(elem.serialize()?);
-                    stream.extend(serialize_type(SerializedTypeID::Object, 1)?);
+        if !self.signers.is_empty() {
+            // Signers are sorted by the numeric value of their account ids
+            let mut signers = self.signers.clone();
+            signers.sort_by_key(|signer| signer.account);
+            let mut elems = vec![];
+            for (i, signer) in signers.iter().enumerate() {
+                if i > 0 && signers[i - 1].account == signer.account {
+                    return Err(TransactionError::Message("Duplicate signers".to_string()));
                 }
-                Ok(stream)
-            }
-            Self::Object { members, .. } => {
-                let mut stream = vec![];
-                for mem in members {
-                    stream.extend(mem.serialize_type()?);
-                    stream.extend(mem.serialize()?);
-                    match mem.typ() {
-                        SerializedTypeID::Array | SerializedTypeID::Object => {
-                            stream.extend(serialize_type(mem.typ(), 1)?)
-                        }
-                        _ => {}
+                elems.push(json!({
+                    "Signer": {
+                        "Account": account_to_json(&signer.account)?,
+                        "SigningPubKey": hex_json(&signer.public_key)?,
+                        "TxnSignature": hex_json(&encode_signature(&signer.public_key, &signer.signature)?)?,
                     }
-                }
-                Ok(stream)
+                }));
             }
+            insert(&mut json, "Signers", Value::Array(elems));
         }
+
+        Ok(Value::Object(json))
     }
 
-    fn deserialize(
-        stream: &mut &[u8],
-        typ: SerializedTypeID,
-        field_value: u32,
-    ) -> Result<Self, TransactionError> {
-        match typ {
-            SerializedTypeID::Account => {
-                // Firstly we extract the length of the account id
-                let len = deserialize_len(stream)?;
-                if len != 20 {
+    /// Builds a transaction from its canonical JSON form
+    pub fn from_json(json: &Value) -> Result<Self, TransactionError> {
+        let mut fields = match json {
+            Value::Object(map) => JsonFields(map.clone()),
+            _ => {
+                return Err(TransactionError::Message(
+                    "Ripple transaction should be a JSON object".to_string(),
+                ))
+            }
+        };
+
+        let tx_type = match fields.take("TransactionType") {
+            Some(Value::String(name)) => name,
+            _ => return Err(missing("TransactionType")),
+        };
+        let source = fields.account("Account")?.ok_or(missing("Account"))?;
+        let fee = match fields.amount("Fee")?.ok_or(missing("Fee"))? {
+            RippleAmount::Xrp(drops) if drops <= u32::MAX as u64 => drops as u32,
+            amount => return Err(TransactionError::Message(format!("Invalid fee {}", amount))),
+        };
+        let sequence = fields.int("Sequence")?.ok_or(missing("Sequence"))?;
+        let flags = fields.int("Flags")?.unwrap_or(0);
+        let last_ledger_sequence = fields.int("LastLedgerSequence")?;
+        let source_tag = fields.int("SourceTag")?;
+        let ticket_sequence = fields.int("TicketSequence")?;
+        let network_id = fields.int("NetworkID")?;
+
+        let public_key = fields
+            .blob("SigningPubKey")?
+            .ok_or(missing("SigningPubKey"))?;
+
+        // An empty public key marks a multi-signed transaction
+        let (public_key, multisig_account) = match public_key.is_empty() {
+            true => ([0u8; 33], Some(source)),
+            false => {
+                let public_key = parse_public_key(public_key)?;
+
+                // Enforce that Account(source) matches the signer public key hash.
+                let derived_source = hash160(&public_key);
+                if source != derived_source.as_slice() {
                     return Err(TransactionError::Message(format!(
-                        "Invalid account length {}",
-                        len,
+                        "Invalid source account: expected {}, got {}",
+                        hex::encode(derived_source),
+                        hex::encode(source),
                     )));
                 }
-
-                // Then we extract the account id
-                let mut account_id = [0u8; 20];
-                let _ = stream.read(&mut account_id)?;
-
-                // Return the ST
-                Ok(SerializedType::Account {
-                    field_value,
-                    account_id,
-                })
+                (public_key, None)
             }
-            SerializedTypeID::Amount => {
-                let mut value = [0u8; 8];
-                let _ = stream.read(&mut value)?;
-                let value = u64::from_be_bytes(value);
+        };
 
-                // Return the ST
-                Ok(SerializedType::Amount { field_value, value })
-            }
-            SerializedTypeID::Uint16 => {
-                let mut value = [0u8; 2];
-                let _ = stream.read(&mut value)?;
-                let value = u16::from_be_bytes(value);
+        let signature = match fields.blob("TxnSignature")? {
+            Some(buffer) => Some(decode_signature(&public_key, &buffer)?.to_vec()),
+            None => None,
+        };
 
-                // Return the ST
-                Ok(SerializedType::Uint16 { field_value, value })
+        let mut signers = vec![];
+        if let Some(elems) = fields.take("Signers") {
+            for elem in elems.as_array().ok_or(missing("Signer"))? {
+                let signer = elem.get("Signer").ok_or(missing("Signer"))?;
+                let field = |name: &str| signer.get(name).ok_or(missing(name));
+                let public_key = parse_public_key(hex_from_json(field("SigningPubKey")?)?)?;
+                signers.push(RippleSigner {
+                    account: account_from_json(field("Account")?)?,
+                    public_key,
+                    signature: decode_signature(
+                        &public_key,
+                        &hex_from_json(field("TxnSignature")?)?,
+                    )?,
+                });
             }
-            SerializedTypeID::Uint32 => {
-                let mut value = [0u8; 4];
-                let _ = stream.read(&mut value)?;
-                let value = u32::from_be_bytes(value);
+        }
 
-                // Return the ST
-                Ok(SerializedType::Uint32 { field_value, value })
+        let mut memos = vec![];
+        if let Some(elems) = fields.take("Memos") {
+            for elem in elems.as_array().ok_or(missing("Memo"))? {
+                // we skip the deserialization of memo_type
+                let memo = elem
+                    .get("Memo")
+                    .and_then(|memo| memo.get("MemoData"))
+                    .ok_or(missing("MemoData"))?;
+                match String::from_utf8(hex_from_json(memo)?) {
+                    Ok(memo) => memos.push(memo),
+                    Err(_) => return Err(TransactionError::Message("Invalid memo".to_string())),
+                }
             }
-            SerializedTypeID::VL => {
-                // Firstly we extract the length of the blob
-                let len = deserialize_len(stream)?;
+        }
 
-                // Then we extract the blob according to its length
-                let mut buffer = vec![0u8; len as usize];
-                let _ = stream.read(&mut buffer)?;
+        let mut params = RippleTransactionParameters {
+            fee,
+            sequence,
+            flags,
+            last_ledger_sequence,
+            source_tag,
+            ticket_sequence,
+            network_id,
+            memos,
+            public_key,
+            multisig_account,
+            ..Default::default()
+        };
 
-                // Return the ST
-                Ok(SerializedType::Blob {
-                    field_value,
-                    buffer,
-                })
+        params.transaction_type = match tx_type.as_str() {
+            "Payment" => {
+                params.destination = fields
+                    .account("Destination")?
+                    .ok_or(missing("Destination"))?;
+                params.destination_tag = fields.int("DestinationTag")?.unwrap_or(0);
+                params.amount = fields.amount("Amount")?.ok_or(missing("Amount"))?;
+                params.send_max = fields.amount("SendMax")?;
+                params.deliver_min = fields.amount("DeliverMin")?;
+                RippleTransactionType::Payment
             }
-            SerializedTypeID::Array => {
-                let mut array = SerializedType::Array {
-                    field_value,
-                    elems: vec![],
-                };
-                loop {
-                    let (typ, fv) = deserialize_type(stream)?;
-                    let typ = SerializedTypeID::from_u8(typ)?;
-
-                    // we have reached the end of the array
-                    if typ == SerializedTypeID::Array && fv == 1 {
-                        break;
+            "TrustSet" => RippleTransactionType::TrustSet {
+                limit_amount: fields
+                    .amount("LimitAmount")?
+                    .ok_or(missing("LimitAmount"))?,
+                quality_in: fields.int("QualityIn")?,
+                quality_out: fields.int("QualityOut")?,
+            },
+            "AccountSet" => RippleTransactionType::AccountSet {
+                set_flag: fields.int("SetFlag")?,
+                clear_flag: fields.int("ClearFlag")?,
+                domain: fields.blob("Domain")?,
+                email_hash: fields.hash("EmailHash")?,
+                message_key: fields.blob("MessageKey")?,
+                transfer_rate: fields.int("TransferRate")?,
+                tick_size: fields.int("TickSize")?,
+                nftoken_minter: fields.account("NFTokenMinter")?,
+            },
+            "SetRegularKey" => RippleTransactionType::SetRegularKey {
+                regular_key: fields.account("RegularKey")?,
+            },
+            "SignerListSet" => {
+                let mut signer_entries = vec![];
+                if let Some(entries) = fields.take("SignerEntries") {
+                    for entry in entries.as_array().ok_or(missing("SignerEntry"))? {
+                        let entry = entry.get("SignerEntry").ok_or(missing("SignerEntry"))?;
+                        signer_entries.push(SignerEntry {
+                            account: account_from_json(
+                                entry.get("Account").ok_or(missing("Account"))?,
+                            )?,
+                            signer_weight: int_from_json(
+                                entry.get("SignerWeight").ok_or(missing("SignerWeight"))?,
+                            )?,
+                        });
                     }
-
-                    let st = Self::deserialize(stream, typ, fv as u32)?;
-                    array.add(st)?;
                 }
-                Ok(array)
-            }
-            SerializedTypeID::Object => {
-                let mut obj = SerializedType::Object {
-                    field_value,
-                    members: vec![],
-                };
-                loop {
-                    let (typ, fv) = deserialize_type(stream)?;
-                    let typ = SerializedTypeID::from_u8(typ)?;
-
-                    // we have reached the end of the object
-                    if typ == SerializedTypeID::Object && fv == 1 {
-                        break;
-                    }
-
-                    let st = Self::deserialize(stream, typ, fv as u32)?;
-                    obj.add(st)?;
+                RippleTransactionType::SignerListSet {
+                    signer_quorum: fields.int("SignerQuorum")?.ok_or(missing("SignerQuorum"))?,
+                    signer_entries,
                 }
-                Ok(obj)
             }
+            "EscrowCreate" => RippleTransactionType::EscrowCreate {
+                destination: fields
+                    .account("Destination")?
+                    .ok_or(missing("Destination"))?,
+                destination_tag: fields.int("DestinationTag")?,
+                amount: fields.amount("Amount")?.ok_or(missing("Amount"))?,
+                finish_after: fields.int("FinishAfter")?,
+                cancel_after: fields.int("CancelAfter")?,
+                condition: fields.blob("Condition")?,
+            },
+            "EscrowFinish" => RippleTransactionType::EscrowFinish {
+                owner: fields.account("Owner")?.ok_or(missing("Owner"))?,
+                offer_sequence: fields
+                    .int("OfferSequence")?
+                    .ok_or(missing("OfferSequence"))?,
+                condition: fields.blob("Condition")?,
+                fulfillment: fields.blob("Fulfillment")?,
+            },
+            "EscrowCancel" => RippleTransactionType::EscrowCancel {
+                owner: fields.account("Owner")?.ok_or(missing("Owner"))?,
+                offer_sequence: fields
+                    .int("OfferSequence")?
+                    .ok_or(missing("OfferSequence"))?,
+            },
+            "OfferCreate" => RippleTransactionType::OfferCreate {
+                taker_pays: fields.amount("TakerPays")?.ok_or(missing("TakerPays"))?,
+                taker_gets: fields.amount("TakerGets")?.ok_or(missing("TakerGets"))?,
+                expiration: fields.int("Expiration")?,
+                offer_sequence: fields.int("OfferSequence")?,
+            },
+            "OfferCancel" => RippleTransactionType::OfferCancel {
+                offer_sequence: fields
+                    .int("OfferSequence")?
+                    .ok_or(missing("OfferSequence"))?,
+            },
+            "AccountDelete" => RippleTransactionType::AccountDelete {
+                destination: fields
+                    .account("Destination")?
+                    .ok_or(missing("Destination"))?,
+                destination_tag: fields.int("DestinationTag")?,
+            },
+            "NFTokenMint" => RippleTransactionType::NFTokenMint {
+                nftoken_taxon: fields.int("NFTokenTaxon")?.ok_or(missing("NFTokenTaxon"))?,
+                issuer: fields.account("Issuer")?,
+                transfer_fee: fields.int("TransferFee")?,
+                uri: fields.blob("URI")?,
+            },
+            "NFTokenCreateOffer" => RippleTransactionType::NFTokenCreateOffer {
+                nftoken_id: fields.hash("NFTokenID")?.ok_or(missing("NFTokenID"))?,
+                amount: fields.amount("Amount")?.ok_or(missing("Amount"))?,
+                owner: fields.account("Owner")?,
+                destination: fields.account("Destination")?,
+                expiration: fields.int("Expiration")?,
+            },
+            "NFTokenAcceptOffer" => RippleTransactionType::NFTokenAcceptOffer {
+                nftoken_sell_offer: fields.hash("NFTokenSellOffer")?,
+                nftoken_buy_offer: fields.hash("NFTokenBuyOffer")?,
+                nftoken_broker_fee: fields.amount("NFTokenBrokerFee")?,
+            },
+            _ => {
+                return Err(TransactionError::Message(format!(
+                    "Unsupported transaction type {}",
+                    tx_type
+                )))
+            }
+        };
+
+        if let Some(name) = fields.0.keys().next() {
+            return Err(TransactionError::Message(format!(
+                "Unsupported field {} for transaction type {}",
+                name, tx_type
+            )));
         }
+
+        let mut tx = RippleTransaction::new(&params)?;
+        tx.signature = signature;
+        tx.signers = signers;
+        tx.validate()?;
+
+        Ok(tx)
     }
 
-    fn add(&mut self, st: SerializedType) -> Result<(), TransactionError> {
-        match self {
-            SerializedType::Object { members, .. } => {
-                members.push(st);
-                Ok(())
-            }
-            SerializedType::Array { elems, .. } => {
-                elems.push(st);
-                Ok(())
-            }
-            _ => Err(TransactionError::Message(
-                "Adding fields to neither an object or an array".to_string(),
-            )),
+    /// Returns "STX\0" + signing fields, which an Ed25519 key signs as is and
+    /// whose hash an secp256k1 key signs, see `to_transaction_id`
+    pub fn signing_payload(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut stream = vec![b'S', b'T', b'X', 0];
+        stream.extend(codec::encode(&self.to_json()?, true)?);
+        Ok(stream)
+    }
+
+    /// Returns "SMT\0" + signing fields + the signer's account id, which a signer of
+    /// a multi-signed transaction signs as is with an Ed25519 key
+    pub fn multisign_payload(&self, account: &[u8; 20]) -> Result<Vec<u8>, TransactionError> {
+        if self.params.multisig_account.is_none() {
+            return Err(TransactionError::Message(
+                "Not a multi-signed transaction".to_string(),
+            ));
         }
+        let mut stream = vec![b'S', b'M', b'T', 0];
+        stream.extend(codec::encode(&self.to_json()?, true)?);
+        stream.extend(account);
+        Ok(stream)
     }
 
-    fn sort(&mut self) -> Result<(), TransactionError> {
-        match self {
-            SerializedType::Object { members, .. } => {
-                members.sort();
-                Ok(())
-            }
-            _ => Err(TransactionError::Message(
-                "Sorting non-object ST".to_string(),
-            )),
+    /// Returns the digest a signer of a multi-signed transaction signs with an
+    /// secp256k1 key, i.e. the first half of the sha512 hash of `multisign_payload`
+    pub fn multisign_hash(&self, account: &[u8; 20]) -> Result<Vec<u8>, TransactionError> {
+        Ok(sha512(&self.multisign_payload(account)?)[..32].to_vec())
+    }
+
+    /// Adds the signature of a signer to a multi-signed transaction and
+    /// returns the serialized transaction
+    pub fn add_signer(
+        &mut self,
+        account: [u8; 20],
+        public_key: [u8; 33],
+        rs: Vec<u8>,
+    ) -> Result<Vec<u8>, TransactionError> {
+        let signature: [u8; 64] = rs.try_into().map_err(|rs: Vec<u8>| {
+            TransactionError::Message(format!("Invalid signature length {}", rs.len()))
+        })?;
+        if self.signers.iter().any(|signer| signer.account == account) {
+            return Err(TransactionError::Message(format!(
+                "Duplicate signer {}",
+                hex::encode(account)
+            )));
         }
+
+        if !verify_signature(&public_key, &self.multisign_payload(&account)?, &signature)? {
+            return Err(TransactionError::Message(format!(
+                "Invalid signature of signer {}",
+                hex::encode(account)
+            )));
+        }
+
+        self.signers.push(RippleSigner {
+            account,
+            public_key,
+            signature,
+        });
+        self.signers.sort_by_key(|signer| signer.account);
+        self.to_bytes()
+    }
+
+    pub fn set_public_key(&mut self, pk: Vec<u8>) -> Result<(), TransactionError> {
+        self.params.public_key = pk.try_into().unwrap();
+        Ok(())
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct RippleTransactionId {
+    pub txid: Vec<u8>,
+}
+
+impl TransactionId for RippleTransactionId {}
+
+impl fmt::Display for RippleTransactionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.txid).to_uppercase())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{RippleFormat, RipplePublicKey};
-
-    use super::{RippleTransaction, RippleTransactionParameters};
-    use anychain_core::{PublicKey, Transaction, TransactionError};
+    use crate::{
+        RippleAddress, RippleAmount, RippleFormat, RipplePublicKey, RippleSecretKey, RippleXAddress,
+    };
+
+    use super::{
+        multisign_fee, RippleTransaction, RippleTransactionParameters, RippleTransactionType,
+        SignerEntry, ASF_REQUIRE_DEST, TF_PARTIAL_PAYMENT, TF_SELL, TF_SELL_NFTOKEN,
+        TF_SET_NO_RIPPLE, TF_TRANSFERABLE,
+    };
+    use anychain_core::{hex, PublicKey, Transaction, TransactionError};
     use libsecp256k1::{self, Message, SecretKey};
     use std::str::FromStr;
 
@@ -868,8 +1232,8 @@ mod tests {
         let sk_from = SecretKey::parse(&sk_from).unwrap();
         let sk_to = SecretKey::parse(&sk_to).unwrap();
 
-        let pk_from = RipplePublicKey::from_secret_key(&sk_from);
-        let pk_to = RipplePublicKey::from_secret_key(&sk_to);
+        let pk_from = RipplePublicKey::from_secret_key(&sk_from.into());
+        let pk_to = RipplePublicKey::from_secret_key(&sk_to.into());
 
         let from = pk_from.to_address(&RippleFormat::Standard).unwrap();
         let to = pk_to.to_address(&RippleFormat::Standard).unwrap();
@@ -884,9 +1248,10 @@ mod tests {
             fee: 500000,
             sequence: 39998031,
             destination_tag: 50,
-            amount: 100000000,
+            amount: RippleAmount::Xrp(100000000),
             memos: vec!["guai".to_string()],
             public_key: pk_from.try_into().unwrap(),
+            ..Default::default()
         };
 
         let mut tx = RippleTransaction::new(&params).unwrap();
@@ -918,7 +1283,7 @@ mod tests {
         assert_eq!(tx.params.fee, 500000);
         assert_eq!(tx.params.sequence, 39998031);
         assert_eq!(tx.params.destination_tag, 50);
-        assert_eq!(tx.params.amount, 100000000);
+        assert_eq!(tx.params.amount, RippleAmount::Xrp(100000000));
         assert_eq!(tx.params.memos.len(), 1);
         assert_eq!(
             tx.params.public_key,
@@ -954,4 +1319,537 @@ mod tests {
             TransactionError::Message(msg) if msg.contains("Invalid source account")
         ));
     }
+
+    fn sign_tx(tx: &mut RippleTransaction, sk: &SecretKey) -> Vec<u8> {
+        let txid = tx.to_transaction_id().unwrap().txid;
+        let msg = Message::parse_slice(&txid).unwrap();
+        let sig = libsecp256k1::sign(&msg, sk).0.serialize().to_vec();
+        tx.sign(sig, 0).unwrap()
+    }
+
+    #[test]
+    fn test_issued_payment() {
+        let sk = SecretKey::parse(&[7u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let issuer = RippleAddress::from_str("rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De").unwrap();
+        let to = RippleAddress::from_str("rJ6HEKFe8T2mkZQqzuGbFEmE8SKtadxd8n").unwrap();
+
+        let params = RippleTransactionParameters {
+            destination: to.to_hash160().unwrap(),
+            fee: 12,
+            sequence: 7,
+            destination_tag: 1,
+            amount: RippleAmount::issued("100", "USD", &issuer).unwrap(),
+            send_max: Some(RippleAmount::Xrp(200000000)),
+            deliver_min: Some(RippleAmount::issued("50", "USD", &issuer).unwrap()),
+            flags: TF_PARTIAL_PAYMENT,
+            public_key: pk.serialize().try_into().unwrap(),
+            ..Default::default()
+        };
+
+        let mut tx = RippleTransaction::new(&params).unwrap();
+        let stream = sign_tx(&mut tx, &sk);
+        let encoded = hex::encode(&stream);
+
+        // Flags
+        assert!(encoded.contains("2200020000"));
+        // Amount of 100 USD
+        assert!(encoded.contains(&format!(
+            "61d5038d7ea4c68000{}{}",
+            "0000000000000000000000005553440000000000",
+            hex::encode(issuer.to_hash160().unwrap())
+        )));
+        // SendMax of 200 XRP
+        assert!(encoded.contains("69400000000bebc200"));
+
+        let decoded = RippleTransaction::from_bytes(&stream).unwrap();
+        assert_eq!(decoded.params, params);
+        assert_eq!(decoded.signature, tx.signature);
+        assert_eq!(decoded.to_bytes().unwrap(), stream);
+    }
+
+    #[test]
+    fn test_trust_set() {
+        let sk = SecretKey::parse(&[9u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let issuer = RippleAddress::from_str("rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De").unwrap();
+
+        let params = RippleTransactionParameters {
+            transaction_type: RippleTransactionType::TrustSet {
+                limit_amount: RippleAmount::issued(
+                    "1000000000",
+                    "524C555344000000000000000000000000000000",
+                    &issuer,
+                )
+                .unwrap(),
+                quality_in: None,
+                quality_out: Some(1000000000),
+            },
+            fee: 12,
+            sequence: 3,
+            flags: TF_SET_NO_RIPPLE,
+            public_key: pk.serialize().try_into().unwrap(),
+            ..Default::default()
+        };
+
+        let mut tx = RippleTransaction::new(&params).unwrap();
+        let stream = sign_tx(&mut tx, &sk);
+        let encoded = hex::encode(&stream);
+
+        // TransactionType of TrustSet
+        assert!(encoded.starts_with("120014"));
+        // QualityOut
+        assert!(encoded.contains("20153b9aca00"));
+        // No Destination or Amount
+        assert!(!encoded.contains("8314"));
+
+        let decoded = RippleTransaction::from_bytes(&stream).unwrap();
+        assert_eq!(decoded.params, params);
+        assert_eq!(decoded.to_bytes().unwrap(), stream);
+
+        let params = RippleTransactionParameters {
+            transaction_type: RippleTransactionType::TrustSet {
+                limit_amount: RippleAmount::Xrp(1),
+                quality_in: None,
+                quality_out: None,
+            },
+            ..params
+        };
+        assert!(RippleTransaction::new(&params).unwrap().to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_tx_types() {
+        let sk = SecretKey::parse(&[5u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let issuer = RippleAddress::from_str("rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De")
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        let other = RippleAddress::from_str("rJ6HEKFe8T2mkZQqzuGbFEmE8SKtadxd8n")
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        let usd = RippleAmount::issued(
+            "7072.8",
+            "USD",
+            &RippleAddress::from_hash160(&issuer).unwrap(),
+        )
+        .unwrap();
+        let nftoken_id = [0x2au8; 32];
+
+        let cases = [
+            (
+                RippleTransactionType::AccountSet {
+                    set_flag: Some(ASF_REQUIRE_DEST),
+                    clear_flag: None,
+                    domain: Some(b"example.com".to_vec()),
+                    email_hash: Some([0x98; 16]),
+                    message_key: None,
+                    transfer_rate: Some(1002000000),
+                    tick_size: Some(5),
+                    nftoken_minter: None,
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::SetRegularKey {
+                    regular_key: Some(other),
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::SetRegularKey { regular_key: None },
+                0,
+            ),
+            (
+                RippleTransactionType::SignerListSet {
+                    signer_quorum: 3,
+                    signer_entries: vec![
+                        SignerEntry {
+                            account: issuer,
+                            signer_weight: 2,
+                        },
+                        SignerEntry {
+                            account: other,
+                            signer_weight: 1,
+                        },
+                    ],
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::EscrowCreate {
+                    destination: other,
+                    destination_tag: Some(23480),
+                    amount: RippleAmount::Xrp(10000),
+                    finish_after: Some(533171558),
+                    cancel_after: Some(533257958),
+                    condition: Some(
+                        hex::decode("A0258020E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855810100")
+                            .unwrap(),
+                    ),
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::EscrowFinish {
+                    owner: other,
+                    offer_sequence: 7,
+                    condition: None,
+                    fulfillment: Some(hex::decode("A0028000").unwrap()),
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::EscrowCancel {
+                    owner: other,
+                    offer_sequence: 7,
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::OfferCreate {
+                    taker_pays: usd,
+                    taker_gets: RippleAmount::Xrp(15000000000),
+                    expiration: Some(595640108),
+                    offer_sequence: Some(1752791),
+                },
+                TF_SELL,
+            ),
+            (
+                RippleTransactionType::OfferCancel {
+                    offer_sequence: 6,
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::AccountDelete {
+                    destination: other,
+                    destination_tag: Some(13),
+                },
+                0,
+            ),
+            (
+                RippleTransactionType::NFTokenMint {
+                    nftoken_taxon: 0,
+                    issuer: None,
+                    transfer_fee: Some(314),
+                    uri: Some(b"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf4dfuylqabf3oclgtqy55fbzdi".to_vec()),
+                },
+                TF_TRANSFERABLE,
+            ),
+            (
+                RippleTransactionType::NFTokenCreateOffer {
+                    nftoken_id,
+                    amount: RippleAmount::Xrp(1000000),
+                    owner: None,
+                    destination: Some(other),
+                    expiration: None,
+                },
+                TF_SELL_NFTOKEN,
+            ),
+            (
+                RippleTransactionType::NFTokenAcceptOffer {
+                    nftoken_sell_offer: Some(nftoken_id),
+                    nftoken_buy_offer: Some([0x3bu8; 32]),
+                    nftoken_broker_fee: Some(RippleAmount::Xrp(100)),
+                },
+                0,
+            ),
+        ];
+
+        for (transaction_type, flags) in cases {
+            let code = transaction_type.code();
+            let params = RippleTransactionParameters {
+                transaction_type,
+                fee: 12,
+                sequence: 11,
+                flags,
+                public_key: pk.serialize().try_into().unwrap(),
+                ..Default::default()
+            };
+
+            let mut tx = RippleTransaction::new(&params).unwrap();
+            let stream = sign_tx(&mut tx, &sk);
+            assert_eq!(stream[..3], [0x12, (code >> 8) as u8, code as u8]);
+
+            let decoded = RippleTransaction::from_bytes(&stream).unwrap();
+            assert_eq!(decoded, tx);
+            assert_eq!(decoded.to_bytes().unwrap(), stream);
+
+            let json = tx.to_json().unwrap();
+            assert_eq!(json["TransactionType"], params.transaction_type.name());
+            assert_eq!(RippleTransaction::from_json(&json).unwrap(), tx);
+        }
+    }
+
+    #[test]
+    fn test_tx_json() {
+        let sk = SecretKey::parse(&[5u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let account = pk.to_address(&RippleFormat::Standard).unwrap();
+
+        let json = serde_json::json!({
+            "TransactionType": "OfferCancel",
+            "Account": account.to_string(),
+            "Fee": "12",
+            "Sequence": 7,
+            "OfferSequence": 6,
+            "SigningPubKey": hex::encode_upper(pk.serialize()),
+        });
+        let tx = RippleTransaction::from_json(&json).unwrap();
+        assert_eq!(
+            tx.params.transaction_type,
+            RippleTransactionType::OfferCancel { offer_sequence: 6 }
+        );
+        assert_eq!(tx.to_json().unwrap(), json);
+        assert_eq!(
+            "120008240000000720190000000668400000000000000c7321",
+            &hex::encode(tx.to_bytes().unwrap())[..50]
+        );
+
+        let mut unsupported = json.clone();
+        unsupported["Destination"] = serde_json::json!(account.to_string());
+        assert!(RippleTransaction::from_json(&unsupported).is_err());
+
+        let mut missing = json;
+        missing.as_object_mut().unwrap().remove("OfferSequence");
+        assert!(RippleTransaction::from_json(&missing).is_err());
+    }
+
+    #[test]
+    fn test_autofilled_payment() {
+        let sk = SecretKey::parse(&[5u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let account = pk.to_address(&RippleFormat::Standard).unwrap();
+
+        // A payment autofilled by xrpl.js, which uses a ticket
+        let json = serde_json::json!({
+            "TransactionType": "Payment",
+            "Account": account.to_string(),
+            "Destination": "rBbvWBK4pmibqtwNMWhPMTgJUGfaNh71Yk",
+            "DestinationTag": 7,
+            "Amount": "1000000",
+            "Fee": "12",
+            "Sequence": 0,
+            "LastLedgerSequence": 91234567,
+            "SourceTag": 12,
+            "TicketSequence": 42,
+            "NetworkID": 21338,
+            "SigningPubKey": hex::encode_upper(pk.serialize()),
+        });
+        let mut tx = RippleTransaction::from_json(&json).unwrap();
+        assert_eq!(Some(91234567), tx.params.last_ledger_sequence);
+        assert_eq!(Some(12), tx.params.source_tag);
+        assert_eq!(Some(42), tx.params.ticket_sequence);
+        assert_eq!(Some(21338), tx.params.network_id);
+        assert_eq!(json, tx.to_json().unwrap());
+
+        let signed = sign_tx(&mut tx, &sk);
+        let decoded = RippleTransaction::from_bytes(&signed).unwrap();
+        assert_eq!(tx.params, decoded.params);
+        assert_eq!(signed, decoded.to_bytes().unwrap());
+        // NetworkID, SourceTag, LastLedgerSequence and TicketSequence
+        let stream = hex::encode(tx.signing_payload().unwrap());
+        for field in ["210000535a", "230000000c", "201b05702107", "20290000002a"] {
+            assert!(stream.contains(field), "{field}");
+        }
+    }
+
+    #[test]
+    fn test_tx_validation() {
+        let sk = SecretKey::parse(&[5u8; 32]).unwrap();
+        let pk = RipplePublicKey::from_secret_key(&sk.into());
+        let entry = SignerEntry {
+            account: [1u8; 20],
+            signer_weight: 1,
+        };
+
+        let invalid = [
+            RippleTransactionType::SignerListSet {
+                signer_quorum: 2,
+                signer_entries: vec![entry],
+            },
+            RippleTransactionType::SignerListSet {
+                signer_quorum: 1,
+                signer_entries: vec![entry, entry],
+            },
+            RippleTransactionType::SignerListSet {
+                signer_quorum: 0,
+                signer_entries: vec![entry],
+            },
+            RippleTransactionType::OfferCreate {
+                taker_pays: RippleAmount::Xrp(1),
+                taker_gets: RippleAmount::Xrp(2),
+                expiration: None,
+                offer_sequence: None,
+            },
+            RippleTransactionType::NFTokenMint {
+                nftoken_taxon: 0,
+                issuer: None,
+                transfer_fee: Some(50001),
+                uri: None,
+            },
+            RippleTransactionType::NFTokenAcceptOffer {
+                nftoken_sell_offer: Some([0u8; 32]),
+                nftoken_buy_offer: None,
+                nftoken_broker_fee: Some(RippleAmount::Xrp(1)),
+            },
+        ];
+
+        for transaction_type in invalid {
+            let params = RippleTransactionParameters {
+                transaction_type,
+                public_key: pk.serialize().try_into().unwrap(),
+                ..Default::default()
+            };
+            assert!(RippleTransaction::new(&params).unwrap().to_bytes().is_err());
+        }
+    }
+
+    #[test]
+    fn test_multisign() {
+        let sk_a = SecretKey::parse(&[0x11u8; 32]).unwrap();
+        let sk_b = SecretKey::parse(&[0x22u8; 32]).unwrap();
+        let pk_a = RipplePublicKey::from_secret_key(&sk_a.into());
+        let pk_b = RipplePublicKey::from_secret_key(&sk_b.into());
+        let account_a = pk_a
+            .to_address(&RippleFormat::Standard)
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        let account_b = pk_b
+            .to_address(&RippleFormat::Standard)
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        let treasury = RippleAddress::from_str("rJ6HEKFe8T2mkZQqzuGbFEmE8SKtadxd8n")
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        let to = RippleAddress::from_str("rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De").unwrap();
+
+        let params = RippleTransactionParameters {
+            destination: to.to_hash160().unwrap(),
+            fee: multisign_fee(10, 2).unwrap(),
+            sequence: 5,
+            amount: RippleAmount::Xrp(1000000),
+            multisig_account: Some(treasury),
+            ..Default::default()
+        };
+        assert_eq!(params.fee, 30);
+        assert!(multisign_fee(u32::MAX / 2 + 1, 1).is_err());
+        assert!(multisign_fee(1, u32::MAX as usize).is_err());
+
+        let mut tx = RippleTransaction::new(&params).unwrap();
+        assert!(tx.to_transaction_id().is_err());
+        assert!(tx.clone().sign(vec![0u8; 64], 0).is_err());
+
+        let digest_a = tx.multisign_hash(&account_a).unwrap();
+        let digest_b = tx.multisign_hash(&account_b).unwrap();
+        assert_ne!(digest_a, digest_b);
+
+        let sign = |digest: &[u8], sk: &SecretKey| {
+            let msg = Message::parse_slice(digest).unwrap();
+            libsecp256k1::sign(&msg, sk).0.serialize().to_vec()
+        };
+        let pk_a: [u8; 33] = pk_a.serialize().try_into().unwrap();
+        let pk_b: [u8; 33] = pk_b.serialize().try_into().unwrap();
+
+        // a signature over another signer's digest is rejected
+        assert!(tx
+            .add_signer(account_a, pk_a, sign(&digest_b, &sk_a))
+            .is_err());
+
+        tx.add_signer(account_b, pk_b, sign(&digest_b, &sk_b))
+            .unwrap();
+        // adding signers does not change the digests
+        assert_eq!(tx.multisign_hash(&account_a).unwrap(), digest_a);
+        let stream = tx
+            .add_signer(account_a, pk_a, sign(&digest_a, &sk_a))
+            .unwrap();
+        assert!(tx
+            .add_signer(account_a, pk_a, sign(&digest_a, &sk_a))
+            .is_err());
+
+        let encoded = hex::encode(&stream);
+        // empty SigningPubKey
+        assert!(encoded.contains("7300"));
+        // Fee of 30 drops
+        assert!(encoded.contains("68400000000000001e"));
+
+        let json = tx.to_json().unwrap();
+        let signers = json["Signers"].as_array().unwrap();
+        assert_eq!(signers.len(), 2);
+        let first = RippleAddress::from_str(signers[0]["Signer"]["Account"].as_str().unwrap())
+            .unwrap()
+            .to_hash160()
+            .unwrap();
+        assert_eq!(first, account_a.min(account_b));
+
+        let decoded = RippleTransaction::from_bytes(&stream).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.params.multisig_account, Some(treasury));
+        assert_eq!(decoded.to_bytes().unwrap(), stream);
+        assert_eq!(
+            decoded.to_transaction_id().unwrap(),
+            tx.to_transaction_id().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ed25519_payment() {
+        let pk = RipplePublicKey::from_str(
+            "ED01FA53FA5A7E77798F882ECE20B1ABC00BB358A9E55A202D0D0676BD0CE37A63",
+        )
+        .unwrap();
+        assert_eq!(
+            "rLUEXYuLiQptky37CqLcm9USQpPiz5rkpD",
+            pk.to_address(&RippleFormat::Standard).unwrap().to_string()
+        );
+
+        let sk = [3u8; 32];
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&sk);
+        let pk = RipplePublicKey::from_secret_key(&RippleSecretKey::Ed25519(sk));
+        let to = RippleAddress::from_str("rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De").unwrap();
+
+        let mut params = RippleTransactionParameters {
+            fee: 12,
+            sequence: 1,
+            amount: RippleAmount::Xrp(1000000),
+            public_key: pk.serialize().try_into().unwrap(),
+            ..Default::default()
+        };
+        params
+            .set_destination(&RippleXAddress::new(to.clone(), Some(12345), false).to_string())
+            .unwrap();
+        assert_eq!(params.destination, to.to_hash160().unwrap());
+        assert_eq!(params.destination_tag, 12345);
+
+        // A conflicting tag is rejected while a classic address keeps the tag
+        let mut conflicting = params.clone();
+        let x_address = RippleXAddress::new(to.clone(), Some(1), false).to_string();
+        assert!(conflicting.set_destination(&x_address).is_err());
+        conflicting.set_destination(&to.to_string()).unwrap();
+        assert_eq!(conflicting, params);
+
+        let mut tx = RippleTransaction::new(&params).unwrap();
+
+        // Ed25519 keys sign the payload itself rather than its digest
+        let digest = tx.to_transaction_id().unwrap().txid;
+        let wrong = ed25519_dalek::Signer::sign(&signing_key, &digest);
+        assert!(tx.clone().sign(wrong.to_bytes().to_vec(), 0).is_err());
+
+        let payload = tx.signing_payload().unwrap();
+        let sig = ed25519_dalek::Signer::sign(&signing_key, &payload);
+        let stream = tx.sign(sig.to_bytes().to_vec(), 0).unwrap();
+        let encoded = hex::encode(&stream);
+        assert!(encoded.contains(&format!("7321{}", hex::encode(pk.serialize()))));
+        assert!(encoded.contains(&format!("7440{}", hex::encode(sig.to_bytes()))));
+
+        let decoded = RippleTransaction::from_bytes(&stream).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.to_bytes().unwrap(), stream);
+    }
 }
@@ -19,10 +19,45 @@ impl StellarAddress {
         Ok(Self(pk.to_string().as_str().to_string()))
     }
 
+    /// Returns the muxed account address (M...) of the account `pk` with the given id
+    pub fn from_muxed(pk: [u8; 32], id: u64) -> Self {
+        let muxed = stellar_strkey::ed25519::MuxedAccount { ed25519: pk, id };
+        Self(muxed.to_string().as_str().to_string())
+    }
+
+    /// Returns the ed25519 public key of the account, which for a muxed account
+    /// is the key of its underlying account
     pub fn to_array(&self) -> Result<[u8; 32], AddressError> {
-        let pk = stellar_strkey::ed25519::PublicKey::from_str(&self.0)
-            .map_err(|e| AddressError::Crate("to_array", format!("{e:?}")))?;
-        Ok(pk.0)
+        match self.0.starts_with('M') {
+            true => {
+                let muxed = stellar_strkey::ed25519::MuxedAccount::from_str(&self.0)
+                    .map_err(|e| AddressError::Crate("to_array", format!("{e:?}")))?;
+                Ok(muxed.ed25519)
+            }
+            false => {
+                let pk = stellar_strkey::ed25519::PublicKey::from_str(&self.0)
+                    .map_err(|e| AddressError::Crate("to_array", format!("{e:?}")))?;
+                Ok(pk.0)
+            }
+        }
+    }
+
+    /// Returns the id of a muxed account address, or None for an account address
+    pub fn muxed_id(&self) -> Option<u64> {
+        stellar_strkey::ed25519::MuxedAccount::from_str(&self.0)
+            .ok()
+            .map(|muxed| muxed.id)
+    }
+
+    /// Returns true if this is a muxed account address (M...)
+    pub fn is_muxed(&self) -> bool {
+        self.muxed_id().is_some()
+    }
+
+    /// Returns the account address (G...) underlying a muxed account address,
+    /// or the address itself if it is an account address
+    pub fn to_base_address(&self) -> Result<Self, AddressError> {
+        Self::from_array(self.to_array()?)
     }
 }
 
@@ -56,9 +91,17 @@ impl FromStr for StellarAddress {
     type Err = AddressError;
 
     fn from_str(addr: &str) -> Result<Self, Self::Err> {
-        let public_key = stellar_strkey::ed25519::PublicKey::from_str(addr)
-            .map_err(|e| AddressError::Crate("from", format!("{e:?}")))?;
-        Ok(Self(public_key.to_string().as_str().to_string()))
+        match stellar_strkey::Strkey::from_str(addr)
+            .map_err(|e| AddressError::Crate("from", format!("{e:?}")))?
+        {
+            stellar_strkey::Strkey::PublicKeyEd25519(pk) => {
+                Ok(Self(pk.to_string().as_str().to_string()))
+            }
+            stellar_strkey::Strkey::MuxedAccountEd25519(muxed) => {
+                Ok(Self(muxed.to_string().as_str().to_string()))
+            }
+            _ => Err(AddressError::InvalidAddress(addr.to_string())),
+        }
     }
 }
 
@@ -99,4 +142,35 @@ mod tests {
         ));
         assert!(!StellarAddress::is_valid("foo"))
     }
+
+    #[test]
+    fn test_muxed_address() {
+        let base = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let muxed = [
+            (
+                "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ",
+                0,
+            ),
+            (
+                "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK",
+                9223372036854775808,
+            ),
+        ];
+        let pk = StellarAddress::from_str(base).unwrap().to_array().unwrap();
+
+        for (address, id) in muxed {
+            let addr = StellarAddress::from_str(address).unwrap();
+            assert!(addr.is_muxed());
+            assert_eq!(Some(id), addr.muxed_id());
+            assert_eq!(pk, addr.to_array().unwrap());
+            assert_eq!(base, addr.to_base_address().unwrap().to_string());
+            assert_eq!(addr, StellarAddress::from_muxed(pk, id));
+        }
+        assert_eq!(None, StellarAddress::from_str(base).unwrap().muxed_id());
+
+        // Contract addresses are not account addresses
+        assert!(!StellarAddress::is_valid(
+            "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE"
+        ));
+    }
 }
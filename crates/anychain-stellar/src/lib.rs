@@ -1,11 +1,17 @@
 pub mod address;
 pub mod amount;
 pub mod format;
+pub mod operation;
 pub mod public_key;
+pub mod sep;
+pub mod soroban;
 pub mod transaction;
 
 pub use self::address::*;
 pub use self::amount::*;
 pub use self::format::*;
+pub use self::operation::*;
 pub use self::public_key::*;
+pub use self::sep::*;
+pub use self::soroban::*;
 pub use self::transaction::*;
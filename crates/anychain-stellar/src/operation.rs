@@ -0,0 +1,280 @@
+//! Helpers for building the assets, memos and operations of a Stellar transaction
+
+use crate::address::StellarAddress;
+use anychain_core::transaction::TransactionError;
+use stellar_xdr::{
+    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, BytesM, ChangeTrustAsset,
+    ChangeTrustOp, CreateAccountOp, DataValue, Hash, ManageDataOp, Memo, MuxedAccount,
+    MuxedAccountMed25519, Operation, OperationBody, PathPaymentStrictReceiveOp,
+    PathPaymentStrictSendOp, PaymentOp, PublicKey, SetOptionsOp, String64, StringM, Uint256,
+};
+
+/// The maximum number of intermediate assets of a path payment
+pub const MAX_PATH_LENGTH: usize = 5;
+
+/// The maximum byte length of a text memo
+pub const MAX_MEMO_TEXT_LENGTH: usize = 28;
+
+pub(crate) fn to_account_id(address: &StellarAddress) -> Result<AccountId, TransactionError> {
+    if address.is_muxed() {
+        return Err(TransactionError::Message(format!(
+            "A muxed account is not allowed here: {address}"
+        )));
+    }
+    Ok(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+        address.to_array()?,
+    ))))
+}
+
+pub(crate) fn to_muxed_account(address: &StellarAddress) -> Result<MuxedAccount, TransactionError> {
+    let ed25519 = Uint256(address.to_array()?);
+    Ok(match address.muxed_id() {
+        Some(id) => MuxedAccount::MuxedEd25519(MuxedAccountMed25519 { id, ed25519 }),
+        None => MuxedAccount::Ed25519(ed25519),
+    })
+}
+
+pub(crate) fn from_muxed_account(
+    account: &MuxedAccount,
+) -> Result<StellarAddress, TransactionError> {
+    Ok(match account {
+        MuxedAccount::Ed25519(Uint256(pk)) => StellarAddress::from_array(*pk)?,
+        MuxedAccount::MuxedEd25519(MuxedAccountMed25519 {
+            id,
+            ed25519: Uint256(pk),
+        }) => StellarAddress::from_muxed(*pk, *id),
+    })
+}
+
+/// Returns a credit asset given its code and issuer. Codes of up to 4
+/// characters are CreditAlphanum4 assets, the others are CreditAlphanum12
+pub fn credit_asset(code: &str, issuer: &StellarAddress) -> Result<Asset, TransactionError> {
+    if code.is_empty() || code.len() > 12 || !code.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Err(TransactionError::Message(format!(
+            "Invalid asset code: {code}"
+        )));
+    }
+    let issuer = to_account_id(issuer)?;
+    match code.len() {
+        1..=4 => {
+            let mut asset_code = [0u8; 4];
+            asset_code[..code.len()].copy_from_slice(code.as_bytes());
+            Ok(Asset::CreditAlphanum4(AlphaNum4 {
+                asset_code: AssetCode4(asset_code),
+                issuer,
+            }))
+        }
+        _ => {
+            let mut asset_code = [0u8; 12];
+            asset_code[..code.len()].copy_from_slice(code.as_bytes());
+            Ok(Asset::CreditAlphanum12(AlphaNum12 {
+                asset_code: AssetCode12(asset_code),
+                issuer,
+            }))
+        }
+    }
+}
+
+/// Returns a text memo, which takes at most 28 bytes
+pub fn memo_text(text: &str) -> Result<Memo, TransactionError> {
+    if text.len() > MAX_MEMO_TEXT_LENGTH {
+        return Err(TransactionError::Message(format!(
+            "Memo text exceeds {MAX_MEMO_TEXT_LENGTH} bytes: {text}"
+        )));
+    }
+    let text = StringM::try_from(text.as_bytes())
+        .map_err(|e| TransactionError::Crate("memo_text", format!("{e:?}")))?;
+    Ok(Memo::Text(text))
+}
+
+/// Returns an id memo
+pub fn memo_id(id: u64) -> Memo {
+    Memo::Id(id)
+}
+
+/// Returns a hash memo
+pub fn memo_hash(hash: [u8; 32]) -> Memo {
+    Memo::Hash(Hash(hash))
+}
+
+fn operation(
+    source: Option<&StellarAddress>,
+    body: OperationBody,
+) -> Result<Operation, TransactionError> {
+    Ok(Operation {
+        source_account: source.map(to_muxed_account).transpose()?,
+        body,
+    })
+}
+
+/// Returns an operation that creates and funds the account `destination`
+pub fn create_account(
+    source: Option<&StellarAddress>,
+    destination: &StellarAddress,
+    starting_balance: i64,
+) -> Result<Operation, TransactionError> {
+    operation(
+        source,
+        OperationBody::CreateAccount(CreateAccountOp {
+            destination: to_account_id(destination)?,
+            starting_balance,
+        }),
+    )
+}
+
+/// Returns an operation that sends `amount` of `asset` to `destination`
+pub fn payment(
+    source: Option<&StellarAddress>,
+    destination: &StellarAddress,
+    asset: Asset,
+    amount: i64,
+) -> Result<Operation, TransactionError> {
+    operation(
+        source,
+        OperationBody::Payment(PaymentOp {
+            destination: to_muxed_account(destination)?,
+            asset,
+            amount,
+        }),
+    )
+}
+
+/// Returns an operation that creates, updates or, with a zero `limit`, removes a trustline
+pub fn change_trust(
+    source: Option<&StellarAddress>,
+    asset: Asset,
+    limit: i64,
+) -> Result<Operation, TransactionError> {
+    let line = match asset {
+        Asset::Native => {
+            return Err(TransactionError::Message(
+                "Cannot establish a trustline to the native asset".to_string(),
+            ))
+        }
+        Asset::CreditAlphanum4(asset) => ChangeTrustAsset::CreditAlphanum4(asset),
+        Asset::CreditAlphanum12(asset) => ChangeTrustAsset::CreditAlphanum12(asset),
+    };
+    operation(
+        source,
+        OperationBody::ChangeTrust(ChangeTrustOp { line, limit }),
+    )
+}
+
+fn path(path: Vec<Asset>) -> Result<stellar_xdr::VecM<Asset, 5>, TransactionError> {
+    if path.len() > MAX_PATH_LENGTH {
+        return Err(TransactionError::Message(format!(
+            "Path exceeds {MAX_PATH_LENGTH} assets"
+        )));
+    }
+    path.try_into()
+        .map_err(|e| TransactionError::Crate("path", format!("{e:?}")))
+}
+
+/// Returns an operation that sends exactly `send_amount` of `send_asset` and
+/// delivers at least `dest_min` of `dest_asset` to `destination`
+pub fn path_payment_strict_send(
+    source: Option<&StellarAddress>,
+    send_asset: Asset,
+    send_amount: i64,
+    destination: &StellarAddress,
+    dest_asset: Asset,
+    dest_min: i64,
+    path_assets: Vec<Asset>,
+) -> Result<Operation, TransactionError> {
+    operation(
+        source,
+        OperationBody::PathPaymentStrictSend(PathPaymentStrictSendOp {
+            send_asset,
+            send_amount,
+            destination: to_muxed_account(destination)?,
+            dest_asset,
+            dest_min,
+            path: path(path_assets)?,
+        }),
+    )
+}
+
+/// Returns an operation that delivers exactly `dest_amount` of `dest_asset` to
+/// `destination` and sends at most `send_max` of `send_asset`
+pub fn path_payment_strict_receive(
+    source: Option<&StellarAddress>,
+    send_asset: Asset,
+    send_max: i64,
+    destination: &StellarAddress,
+    dest_asset: Asset,
+    dest_amount: i64,
+    path_assets: Vec<Asset>,
+) -> Result<Operation, TransactionError> {
+    operation(
+        source,
+        OperationBody::PathPaymentStrictReceive(PathPaymentStrictReceiveOp {
+            send_asset,
+            send_max,
+            destination: to_muxed_account(destination)?,
+            dest_asset,
+            dest_amount,
+            path: path(path_assets)?,
+        }),
+    )
+}
+
+/// Returns an operation that sets the flags, thresholds, home domain or signers of an account
+pub fn set_options(
+    source: Option<&StellarAddress>,
+    options: SetOptionsOp,
+) -> Result<Operation, TransactionError> {
+    operation(source, OperationBody::SetOptions(options))
+}
+
+/// Returns an operation that sets, or removes if `value` is None, a data entry of an account
+pub fn manage_data(
+    source: Option<&StellarAddress>,
+    name: &str,
+    value: Option<&[u8]>,
+) -> Result<Operation, TransactionError> {
+    let data_name = StringM::try_from(name.as_bytes())
+        .map_err(|e| TransactionError::Crate("manage_data", format!("{e:?}")))?;
+    let data_value = value
+        .map(|value| BytesM::try_from(value).map(DataValue))
+        .transpose()
+        .map_err(|e| TransactionError::Crate("manage_data", format!("{e:?}")))?;
+    operation(
+        source,
+        OperationBody::ManageData(ManageDataOp {
+            data_name: String64(data_name),
+            data_value,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const ISSUER: &str = "GDB7S3TDJIJSHHRZ3FNFGSAXH7JNXN44PXPPOG6ISIRMPMNFZHBS2AHQ";
+
+    #[test]
+    fn test_credit_asset() {
+        let issuer = StellarAddress::from_str(ISSUER).unwrap();
+        assert!(matches!(
+            credit_asset("USDC", &issuer).unwrap(),
+            Asset::CreditAlphanum4(AlphaNum4 { asset_code: AssetCode4(code), .. }) if &code == b"USDC"
+        ));
+        assert!(matches!(
+            credit_asset("yUSDC", &issuer).unwrap(),
+            Asset::CreditAlphanum12(AlphaNum12 { asset_code: AssetCode12(code), .. }) if &code[..6] == b"yUSDC\0"
+        ));
+        assert!(credit_asset("", &issuer).is_err());
+        assert!(credit_asset("ABCDEFGHIJKLM", &issuer).is_err());
+        assert!(credit_asset("US-D", &issuer).is_err());
+        assert!(change_trust(None, Asset::Native, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_memo() {
+        assert!(memo_text("1234567890123456789012345678").is_ok());
+        assert!(memo_text("12345678901234567890123456789").is_err());
+        assert_eq!(memo_id(7), Memo::Id(7));
+    }
+}
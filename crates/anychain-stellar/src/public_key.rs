@@ -27,6 +27,18 @@ impl PublicKey for StellarPublicKey {
     }
 }
 
+impl StellarPublicKey {
+    /// Returns the public key encoded in a Stellar address
+    pub fn from_address(address: &StellarAddress) -> Result<Self, PublicKeyError> {
+        let public_key = address
+            .to_array()
+            .map_err(|e| PublicKeyError::Crate("from_address", format!("{e:?}")))?;
+        let public_key = VerifyingKey::from_bytes(&public_key)
+            .map_err(|e| PublicKeyError::Crate("from_address", format!("{e:?}")))?;
+        Ok(StellarPublicKey(public_key))
+    }
+}
+
 impl FromStr for StellarPublicKey {
     type Err = PublicKeyError;
 
@@ -0,0 +1,343 @@
+//! SEP-0007 transaction URIs and SEP-0010 web authentication challenges
+
+use crate::address::StellarAddress;
+use crate::operation::from_muxed_account;
+use crate::public_key::StellarPublicKey;
+use crate::transaction::{network_passphrase, StellarTransaction};
+use anychain_core::transaction::TransactionError;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::str::FromStr;
+use stellar_xdr::{
+    Limits, ManageDataOp, Memo, Operation, OperationBody, TransactionEnvelope, WriteXdr,
+};
+
+/// The name of the challenge operation that carries the web auth domain
+pub const WEB_AUTH_DOMAIN_KEY: &str = "web_auth_domain";
+
+/// The name of the challenge operation that carries the client domain
+pub const CLIENT_DOMAIN_KEY: &str = "client_domain";
+
+/// The byte length of the random nonce of a challenge, before base64 encoding
+pub const CHALLENGE_NONCE_LENGTH: usize = 48;
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+impl StellarTransaction {
+    /// Returns the SEP-0007 `tx` URI that asks a wallet to sign the transaction
+    /// and, if `callback` is given, to post the signed transaction to it
+    pub fn to_sep7_uri(&self, callback: Option<&str>) -> Result<String, TransactionError> {
+        let envelope = TransactionEnvelope::Tx(self.to_envelope()?)
+            .to_xdr(Limits::none())
+            .map_err(|e| TransactionError::Crate("to_sep7_uri", format!("{e:?}")))?;
+
+        let mut uri = format!(
+            "web+stellar:tx?xdr={}",
+            percent_encode(&STANDARD.encode(envelope))
+        );
+        if let Some(callback) = callback {
+            uri.push_str(&format!(
+                "&callback={}",
+                percent_encode(&format!("url:{callback}"))
+            ));
+        }
+        // The public network is implied
+        if self.params.network_id != 0 {
+            uri.push_str(&format!(
+                "&network_passphrase={}",
+                percent_encode(network_passphrase(self.params.network_id)?)
+            ));
+        }
+        Ok(uri)
+    }
+}
+
+/// Represents a SEP-0010 challenge issued by the web auth server of an anchor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarChallenge {
+    pub transaction: StellarTransaction,
+    /// The account that is to prove its ownership by signing the challenge
+    pub client_account: StellarAddress,
+    /// The home domain of the anchor the challenge was issued for
+    pub home_domain: String,
+    /// The memo that identifies a user of a shared client account
+    pub memo: Option<u64>,
+}
+
+impl StellarChallenge {
+    /// Parses and validates a base64 challenge of `server_account`, issued for
+    /// one of `home_domains` on the network `network_id`, at unix time `now`
+    pub fn from_base64(
+        challenge: &str,
+        server_account: &StellarAddress,
+        network_id: u8,
+        home_domains: &[&str],
+        web_auth_domain: &str,
+        now: u64,
+    ) -> Result<Self, TransactionError> {
+        let mut transaction = StellarTransaction::from_str(challenge)?;
+        transaction.params.network_id = network_id;
+        let params = &transaction.params;
+
+        if &params.from != server_account {
+            return Err(TransactionError::Message(
+                "The challenge source account is not the server account".to_string(),
+            ));
+        }
+        if params.nonce != -1 {
+            return Err(TransactionError::Message(
+                "The challenge sequence number is not 0".to_string(),
+            ));
+        }
+        match &params.time_bounds {
+            Some(tb) if tb.max_time.0 != 0 && tb.min_time.0 <= now && now <= tb.max_time.0 => {}
+            _ => {
+                return Err(TransactionError::Message(
+                    "The challenge has expired or has no time bounds".to_string(),
+                ))
+            }
+        }
+        let memo = match params.memo {
+            Memo::None => None,
+            Memo::Id(id) => Some(id),
+            _ => {
+                return Err(TransactionError::Message(
+                    "The challenge memo is not an id memo".to_string(),
+                ))
+            }
+        };
+
+        // A challenge without manage data operations is decoded into a payment
+        let operations = &params.operations;
+        if operations.is_empty() {
+            return Err(TransactionError::Message(
+                "The challenge has no manage data operation".to_string(),
+            ));
+        }
+
+        let (client_account, name, nonce) = manage_data(&operations[0])?;
+        let client_account = client_account.ok_or(TransactionError::Message(
+            "The first challenge operation has no source account".to_string(),
+        ))?;
+        // A muxed client account already identifies the user
+        if client_account.is_muxed() && memo.is_some() {
+            return Err(TransactionError::Message(
+                "The challenge has both a muxed client account and a memo".to_string(),
+            ));
+        }
+        let home_domain = home_domains
+            .iter()
+            .find(|domain| name == format!("{domain} auth"))
+            .ok_or(TransactionError::Message(format!(
+                "The challenge is not issued for the home domains: {name}"
+            )))?
+            .to_string();
+        let nonce = nonce.map(|nonce| STANDARD.decode(nonce));
+        if !matches!(nonce, Some(Ok(nonce)) if nonce.len() == CHALLENGE_NONCE_LENGTH) {
+            return Err(TransactionError::Message(
+                "Invalid challenge nonce".to_string(),
+            ));
+        }
+
+        for op in &operations[1..] {
+            let (source, name, value) = manage_data(op)?;
+            // The client domain operation is the only one the server does not source
+            if name != CLIENT_DOMAIN_KEY && source.as_ref() != Some(server_account) {
+                return Err(TransactionError::Message(
+                    "A challenge operation is not sourced by the server account".to_string(),
+                ));
+            }
+            if name == WEB_AUTH_DOMAIN_KEY && value != Some(web_auth_domain.as_bytes()) {
+                return Err(TransactionError::Message(
+                    "The challenge web auth domain does not match".to_string(),
+                ));
+            }
+        }
+
+        let server = StellarPublicKey::from_address(server_account)
+            .map_err(|e| TransactionError::Crate("from_base64", format!("{e:?}")))?;
+        if !transaction.is_signed_by(&server)? {
+            return Err(TransactionError::Message(
+                "The challenge is not signed by the server account".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            transaction,
+            client_account,
+            home_domain,
+            memo,
+        })
+    }
+
+    /// Returns the hash the client account and its other signers sign
+    pub fn hash(&self) -> Result<[u8; 32], TransactionError> {
+        self.transaction.hash()
+    }
+
+    /// Verifies the signature of `signer` over the challenge and adds it
+    pub fn sign(
+        &mut self,
+        signer: &StellarPublicKey,
+        signature: Vec<u8>,
+    ) -> Result<(), TransactionError> {
+        self.transaction.add_signature(signer, signature)
+    }
+
+    /// Returns the base64 signed challenge to be submitted to the web auth server
+    pub fn to_base64(&self) -> Result<String, TransactionError> {
+        let envelope = TransactionEnvelope::Tx(self.transaction.to_envelope()?)
+            .to_xdr(Limits::none())
+            .map_err(|e| TransactionError::Crate("to_base64", format!("{e:?}")))?;
+        Ok(STANDARD.encode(envelope))
+    }
+}
+
+/// The source account, name and value of a manage data operation
+type ManageData<'a> = (Option<StellarAddress>, String, Option<&'a [u8]>);
+
+/// Returns the source account, name and value of a manage data operation
+fn manage_data(op: &Operation) -> Result<ManageData<'_>, TransactionError> {
+    let source = op
+        .source_account
+        .as_ref()
+        .map(from_muxed_account)
+        .transpose()?;
+    match &op.body {
+        OperationBody::ManageData(ManageDataOp {
+            data_name,
+            data_value,
+        }) => Ok((
+            source,
+            data_name.0.to_utf8_string_lossy(),
+            data_value.as_ref().map(|value| value.0.as_slice()),
+        )),
+        _ => Err(TransactionError::Message(
+            "The challenge has an operation other than manage data".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation::manage_data as manage_data_op;
+    use crate::transaction::StellarTransactionParameters;
+    use anychain_core::Transaction;
+    use ed25519_dalek::{Signer, SigningKey};
+    use stellar_xdr::{TimeBounds, TimePoint};
+
+    fn account(key: &SigningKey) -> (StellarPublicKey, StellarAddress) {
+        let public_key = StellarPublicKey(key.verifying_key());
+        let address = StellarAddress::from_array(key.verifying_key().to_bytes()).unwrap();
+        (public_key, address)
+    }
+
+    fn new_challenge(server: &SigningKey, client: &StellarAddress, domain: &str) -> String {
+        let (_, server_account) = account(server);
+        let nonce = STANDARD.encode([9u8; CHALLENGE_NONCE_LENGTH]);
+        let operations = vec![
+            manage_data_op(
+                Some(client),
+                &format!("{domain} auth"),
+                Some(nonce.as_bytes()),
+            )
+            .unwrap(),
+            manage_data_op(
+                Some(&server_account),
+                WEB_AUTH_DOMAIN_KEY,
+                Some(b"auth.example.com"),
+            )
+            .unwrap(),
+        ];
+        let mut params =
+            StellarTransactionParameters::with_operations(server_account, -1, 1, 100, operations)
+                .unwrap();
+        params.time_bounds = Some(TimeBounds {
+            min_time: TimePoint(1000),
+            max_time: TimePoint(1900),
+        });
+
+        let mut tx = StellarTransaction::new(&params).unwrap();
+        let sig = server.sign(&tx.hash().unwrap()).to_bytes().to_vec();
+        tx.sign(sig, 0).unwrap();
+        STANDARD.encode(tx.to_bytes().unwrap())
+    }
+
+    #[test]
+    fn test_challenge() {
+        let server = SigningKey::from_bytes(&[1u8; 32]);
+        let client = SigningKey::from_bytes(&[2u8; 32]);
+        let (_, server_account) = account(&server);
+        let (client_key, client_account) = account(&client);
+        let domains = ["example.com"];
+
+        let tx = new_challenge(&server, &client_account, "example.com");
+        let read = |tx: &str, now| {
+            StellarChallenge::from_base64(tx, &server_account, 1, &domains, "auth.example.com", now)
+        };
+
+        let mut challenge = read(&tx, 1500).unwrap();
+        assert_eq!(client_account, challenge.client_account);
+        assert_eq!("example.com", challenge.home_domain);
+        assert_eq!(None, challenge.memo);
+
+        let sig = client.sign(&challenge.hash().unwrap()).to_bytes().to_vec();
+        assert!(challenge.sign(&client_key, sig[..].to_vec()).is_ok());
+        assert!(challenge.sign(&client_key, vec![0u8; 64]).is_err());
+        let signed = challenge.to_base64().unwrap();
+        let signed = read(&signed, 1500).unwrap();
+        assert_eq!(2, signed.transaction.signatures.len());
+        assert!(signed.transaction.is_signed_by(&client_key).unwrap());
+
+        // expired, wrong network, wrong home domain, wrong server
+        assert!(read(&tx, 2000).is_err());
+        assert!(StellarChallenge::from_base64(
+            &tx,
+            &server_account,
+            0,
+            &domains,
+            "auth.example.com",
+            1500
+        )
+        .is_err());
+        let other = new_challenge(&server, &client_account, "other.com");
+        assert!(read(&other, 1500).is_err());
+        assert!(StellarChallenge::from_base64(
+            &tx,
+            &client_account,
+            1,
+            &domains,
+            "auth.example.com",
+            1500
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_sep7_uri() {
+        let server = SigningKey::from_bytes(&[1u8; 32]);
+        let (_, account) = account(&server);
+        let params = StellarTransactionParameters::with_operations(
+            account.clone(),
+            1,
+            1,
+            100,
+            vec![manage_data_op(None, "key", None).unwrap()],
+        )
+        .unwrap();
+        let tx = StellarTransaction::new(&params).unwrap();
+        let uri = tx.to_sep7_uri(Some("https://example.com/tx")).unwrap();
+        assert!(uri.starts_with("web+stellar:tx?xdr=AAAAA"));
+        assert!(uri.contains("&callback=url%3Ahttps%3A%2F%2Fexample.com%2Ftx"));
+        assert!(uri.ends_with("&network_passphrase=Test%20SDF%20Network%20%3B%20September%202015"));
+    }
+}
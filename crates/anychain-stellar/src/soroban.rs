@@ -0,0 +1,261 @@
+//! Helpers for building Soroban contract invocations, such as transfers of
+//! Stellar Asset Contract (SAC) and SEP-41 tokens
+
+use crate::address::StellarAddress;
+use crate::operation::{to_account_id, to_muxed_account};
+use anychain_core::transaction::TransactionError;
+use std::str::FromStr;
+use stellar_xdr::{
+    ContractId, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, LedgerFootprint,
+    LedgerKey, Limits, MuxedEd25519Account, Operation, OperationBody, ReadXdr, ScAddress, ScSymbol,
+    ScVal, SorobanAuthorizationEntry, SorobanAuthorizedFunction, SorobanAuthorizedInvocation,
+    SorobanCredentials, SorobanResources, SorobanTransactionData, SorobanTransactionDataExt,
+    StringM, Uint256,
+};
+
+/// Returns the Soroban address of an account (G...), muxed account (M...) or
+/// contract (C...)
+pub fn sc_address(address: &str) -> Result<ScAddress, TransactionError> {
+    let strkey = stellar_strkey::Strkey::from_str(address)
+        .map_err(|e| TransactionError::Crate("sc_address", format!("{e:?}")))?;
+    match strkey {
+        stellar_strkey::Strkey::PublicKeyEd25519(pk) => Ok(ScAddress::Account(to_account_id(
+            &StellarAddress::from_array(pk.0)?,
+        )?)),
+        stellar_strkey::Strkey::MuxedAccountEd25519(muxed) => {
+            Ok(ScAddress::MuxedAccount(MuxedEd25519Account {
+                id: muxed.id,
+                ed25519: Uint256(muxed.ed25519),
+            }))
+        }
+        stellar_strkey::Strkey::Contract(contract) => {
+            Ok(ScAddress::Contract(ContractId(Hash(contract.0))))
+        }
+        _ => Err(TransactionError::Message(format!(
+            "Unsupported Soroban address: {address}"
+        ))),
+    }
+}
+
+/// Returns the Soroban data of a transaction given the footprint and resources
+/// reported by the simulation of the transaction
+pub fn soroban_data(
+    read_only: Vec<LedgerKey>,
+    read_write: Vec<LedgerKey>,
+    instructions: u32,
+    disk_read_bytes: u32,
+    write_bytes: u32,
+    resource_fee: i64,
+) -> Result<SorobanTransactionData, TransactionError> {
+    let footprint = LedgerFootprint {
+        read_only: read_only
+            .try_into()
+            .map_err(|e| TransactionError::Crate("soroban_data", format!("{e:?}")))?,
+        read_write: read_write
+            .try_into()
+            .map_err(|e| TransactionError::Crate("soroban_data", format!("{e:?}")))?,
+    };
+    Ok(SorobanTransactionData {
+        ext: SorobanTransactionDataExt::V0,
+        resources: SorobanResources {
+            footprint,
+            instructions,
+            disk_read_bytes,
+            write_bytes,
+        },
+        resource_fee,
+    })
+}
+
+/// Returns the Soroban data of a transaction given the base64 `transactionData`
+/// returned by the `simulateTransaction` RPC method
+pub fn soroban_data_from_base64(data: &str) -> Result<SorobanTransactionData, TransactionError> {
+    SorobanTransactionData::from_xdr_base64(data, Limits::none())
+        .map_err(|e| TransactionError::Crate("soroban_data_from_base64", format!("{e:?}")))
+}
+
+/// Returns an operation that invokes `function` of `contract` with `args`.
+/// `auth` holds the authorizations returned by the simulation of the call
+pub fn invoke_contract(
+    source: Option<&StellarAddress>,
+    contract: &str,
+    function: &str,
+    args: Vec<ScVal>,
+    auth: Vec<SorobanAuthorizationEntry>,
+) -> Result<Operation, TransactionError> {
+    let source_account = source.map(to_muxed_account).transpose()?;
+    Ok(Operation {
+        source_account,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(contract_args(contract, function, args)?),
+            auth: auth
+                .try_into()
+                .map_err(|e| TransactionError::Crate("invoke_contract", format!("{e:?}")))?,
+        }),
+    })
+}
+
+fn contract_args(
+    contract: &str,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<InvokeContractArgs, TransactionError> {
+    let contract_address = match sc_address(contract)? {
+        address @ ScAddress::Contract(_) => address,
+        _ => {
+            return Err(TransactionError::Message(format!(
+                "Not a contract address: {contract}"
+            )))
+        }
+    };
+    let function_name = StringM::try_from(function.as_bytes())
+        .map_err(|e| TransactionError::Crate("contract_args", format!("{e:?}")))?;
+    Ok(InvokeContractArgs {
+        contract_address,
+        function_name: ScSymbol(function_name),
+        args: args
+            .try_into()
+            .map_err(|e| TransactionError::Crate("contract_args", format!("{e:?}")))?,
+    })
+}
+
+/// Returns an invocation of a token contract authorized by the source account
+/// of the operation, or of the transaction if the operation has none
+fn token_invocation(
+    source: Option<&StellarAddress>,
+    contract: &str,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<Operation, TransactionError> {
+    let auth = SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::SourceAccount,
+        root_invocation: SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(contract_args(
+                contract,
+                function,
+                args.clone(),
+            )?),
+            sub_invocations: Default::default(),
+        },
+    };
+    invoke_contract(source, contract, function, args, vec![auth])
+}
+
+/// Returns an operation that transfers `amount` of the token `contract` from
+/// `from` to `to`, which is an account, muxed account or contract address.
+/// `from` must be the source account of the operation or of the transaction
+pub fn token_transfer(
+    source: Option<&StellarAddress>,
+    contract: &str,
+    from: &StellarAddress,
+    to: &str,
+    amount: i128,
+) -> Result<Operation, TransactionError> {
+    let args = vec![
+        ScVal::Address(sc_address(&from.to_base_address()?.0)?),
+        ScVal::Address(sc_address(to)?),
+        ScVal::from(amount),
+    ];
+    token_invocation(source, contract, "transfer", args)
+}
+
+/// Returns an operation that allows `spender` to transfer up to `amount` of the
+/// token `contract` on behalf of `from` until `expiration_ledger`. `from` must
+/// be the source account of the operation or of the transaction
+pub fn token_approve(
+    source: Option<&StellarAddress>,
+    contract: &str,
+    from: &StellarAddress,
+    spender: &str,
+    amount: i128,
+    expiration_ledger: u32,
+) -> Result<Operation, TransactionError> {
+    let spender = match sc_address(spender)? {
+        ScAddress::MuxedAccount(_) => {
+            return Err(TransactionError::Message(format!(
+                "A muxed account cannot be a spender: {spender}"
+            )))
+        }
+        spender => spender,
+    };
+    let args = vec![
+        ScVal::Address(sc_address(&from.to_base_address()?.0)?),
+        ScVal::Address(spender),
+        ScVal::from(amount),
+        ScVal::U32(expiration_ledger),
+    ];
+    token_invocation(source, contract, "approve", args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{StellarTransaction, StellarTransactionParameters};
+    use anychain_core::Transaction;
+    use stellar_xdr::{ContractDataDurability, LedgerKeyContractData, ScVal};
+
+    const FROM: &str = "GDB7S3TDJIJSHHRZ3FNFGSAXH7JNXN44PXPPOG6ISIRMPMNFZHBS2AHQ";
+    const TO: &str = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK";
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    #[test]
+    fn test_token_transfer() {
+        let from = StellarAddress::from_str(FROM).unwrap();
+        let op = token_transfer(None, CONTRACT, &from, TO, 1_0000000).unwrap();
+        let OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(args),
+            auth,
+        }) = &op.body
+        else {
+            panic!("not a contract invocation");
+        };
+        assert_eq!("transfer", args.function_name.0.to_utf8_string_lossy());
+        assert!(matches!(
+            args.args[1],
+            ScVal::Address(ScAddress::MuxedAccount(_))
+        ));
+        assert_eq!(ScVal::from(1_0000000i128), args.args[2]);
+        assert_eq!(SorobanCredentials::SourceAccount, auth[0].credentials);
+
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: sc_address(CONTRACT).unwrap(),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        let data = soroban_data(vec![key], vec![], 1000000, 2000, 500, 50000).unwrap();
+        let mut params = StellarTransactionParameters::with_operations(
+            from.clone(),
+            7,
+            1,
+            100,
+            vec![op.clone()],
+        )
+        .unwrap();
+
+        // Soroban data is required and the fee must cover the resource fee
+        assert!(StellarTransaction::new(&params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        params.soroban_data = Some(data);
+        assert!(StellarTransaction::new(&params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+        params.fee = 50100;
+
+        let tx = StellarTransaction::new(&params).unwrap();
+        let decoded = StellarTransaction::from_bytes(&tx.to_bytes().unwrap()).unwrap();
+        assert_eq!(tx, decoded);
+
+        params.operations.push(op);
+        assert!(StellarTransaction::new(&params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+
+        assert!(token_approve(None, CONTRACT, &from, TO, 1, 100).is_err());
+        assert!(token_approve(None, FROM, &from, CONTRACT, 1, 100).is_err());
+        assert!(token_approve(None, CONTRACT, &from, CONTRACT, 1, 100).is_ok());
+    }
+}
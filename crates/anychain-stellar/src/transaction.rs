@@ -1,5 +1,6 @@
 use crate::address::StellarAddress;
 use crate::format::StellarFormat;
+use crate::operation::{from_muxed_account, to_account_id, to_muxed_account};
 use crate::StellarPublicKey;
 use anychain_core::{
     crypto::sha256,
@@ -7,18 +8,26 @@ use anychain_core::{
 };
 use base64::{engine::general_purpose::STANDARD, Engine};
 use core::fmt;
+use ed25519_dalek::Verifier;
 use std::str::FromStr;
 use stellar_xdr::{
-    AccountId, Asset, BytesM, CreateAccountOp, DecoratedSignature, Hash, Limits, Memo,
-    MuxedAccount, Operation, OperationBody, PaymentOp, Preconditions, PublicKey, ReadXdr,
-    SequenceNumber, Signature, SignatureHint, Transaction as Tx, TransactionEnvelope,
-    TransactionExt, TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
-    TransactionV1Envelope, Uint256, VecM, WriteXdr,
+    AccountId, Asset, BytesM, CreateAccountOp, DecoratedSignature, FeeBumpTransaction,
+    FeeBumpTransactionEnvelope, FeeBumpTransactionExt, FeeBumpTransactionInnerTx, Hash, Limits,
+    Memo, Operation, OperationBody, PaymentOp, Preconditions, PublicKey, ReadXdr, SequenceNumber,
+    Signature, SignatureHint, SorobanTransactionData, TimeBounds, Transaction as Tx,
+    TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
 };
 
 const MAINNET_NETWORK_ID: &str = "Public Global Stellar Network ; September 2015";
 const TESTNET_NETWORK_ID: &str = "Test SDF Network ; September 2015";
 
+/// The maximum number of operations of a transaction
+pub const MAX_OPERATIONS: usize = 100;
+
+/// The maximum number of signatures of a transaction envelope
+pub const MAX_SIGNATURES: usize = 20;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct StellarTransactionParameters {
     pub from: StellarAddress,
@@ -28,12 +37,52 @@ pub struct StellarTransactionParameters {
     pub fee: u32,
     pub nonce: i64,
     pub network_id: u8,
+    pub memo: Memo,
+    /// The time window in which the transaction is valid, in unix timestamps
+    pub time_bounds: Option<TimeBounds>,
+    /// The operations of the transaction, see the `operation` module. If empty, the
+    /// transaction carries a single native payment, or account creation if
+    /// `has_account` is false, of `amount` from `from` to `to`
+    pub operations: Vec<Operation>,
+    /// The footprint and resources of a Soroban transaction, see the `soroban`
+    /// module. `fee` must cover its resource fee on top of the inclusion fee
+    pub soroban_data: Option<SorobanTransactionData>,
+}
+
+impl StellarTransactionParameters {
+    /// Returns the parameters of a transaction carrying the given operations. The fee
+    /// is `base_fee` per operation and `to` is set to `from` since it is unused
+    pub fn with_operations(
+        from: StellarAddress,
+        nonce: i64,
+        network_id: u8,
+        base_fee: u32,
+        operations: Vec<Operation>,
+    ) -> Result<Self, TransactionError> {
+        let fee = base_fee
+            .checked_mul(operations.len() as u32)
+            .ok_or(TransactionError::Message("Fee overflow".to_string()))?;
+        Ok(Self {
+            to: from.clone(),
+            from,
+            has_account: true,
+            amount: 0,
+            fee,
+            nonce,
+            network_id,
+            memo: Memo::None,
+            time_bounds: None,
+            operations,
+            soroban_data: None,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct StellarTransaction {
     pub params: StellarTransactionParameters,
-    pub signatures: Option<Vec<Vec<u8>>>,
+    /// The signatures of the transaction, each carrying the hint of its signer
+    pub signatures: Vec<DecoratedSignature>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -59,188 +108,436 @@ impl Transaction for StellarTransaction {
     fn new(params: &Self::TransactionParameters) -> Result<Self, anychain_core::TransactionError> {
         Ok(Self {
             params: params.clone(),
-            signatures: None,
+            signatures: vec![],
         })
     }
 
+    /// Adds the signature of the source account over the transaction hash
     fn sign(&mut self, rs: Vec<u8>, _: u8) -> Result<Vec<u8>, anychain_core::TransactionError> {
-        if rs.len() != 64 {
-            return Err(TransactionError::Message(format!(
-                "Invalid signature length: {}",
-                rs.len()
-            )));
-        }
-        self.signatures = Some(vec![rs]);
+        let signer = StellarPublicKey::from_address(&self.params.from)
+            .map_err(|e| TransactionError::Crate("sign", format!("{e:?}")))?;
+        self.add_signature(&signer, rs)?;
         self.to_bytes()
     }
 
     fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
-        let from = StellarPublicKey::from_str(&self.params.from.to_string())
-            .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?
-            .0
-            .to_bytes();
-        let to = StellarPublicKey::from_str(&self.params.to.to_string())
-            .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?
-            .0
-            .to_bytes();
-
-        let source_account = MuxedAccount::Ed25519(Uint256(from));
-        let destination = MuxedAccount::Ed25519(Uint256(to));
-        let amount = self.params.amount;
-
-        let fee = self.params.fee;
-        let seq_num = SequenceNumber(self.params.nonce + 1);
-        let network_id = match self.params.network_id {
-            0 => MAINNET_NETWORK_ID,
-            1 => TESTNET_NETWORK_ID,
+        let stream = match self.signatures.is_empty() {
+            true => self.signature_payload()?.to_xdr(Limits::none()),
+            false => TransactionEnvelope::Tx(self.to_envelope()?).to_xdr(Limits::none()),
+        };
+        stream.map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))
+    }
+
+    fn from_bytes(tx: &[u8]) -> Result<Self, TransactionError> {
+        // A signed transaction is a transaction envelope, while an
+        // unsigned one is the payload to be signed
+        if let Ok(payload) = TransactionSignaturePayload::from_xdr(tx, Limits::none()) {
+            let network_id = network_id_from_hash(&payload.network_id)?;
+            return match payload.tagged_transaction {
+                TransactionSignaturePayloadTaggedTransaction::Tx(tx) => {
+                    Self::from_tx(tx, network_id, vec![])
+                }
+                _ => Err(TransactionError::Message(
+                    "Unsupported transaction payload type".to_string(),
+                )),
+            };
+        }
+
+        let envelope = TransactionEnvelope::from_xdr(tx, Limits::none())
+            .map_err(|e| TransactionError::Crate("from_bytes", format!("{e:?}")))?;
+
+        match envelope {
+            // Network ID is not included in the envelope
+            TransactionEnvelope::Tx(envelope) => Self::from_envelope(envelope, 0),
+            _ => Err(TransactionError::Message(
+                "Unsupported transaction envelope type".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the transaction hash, which does not depend on the signatures
+    fn to_transaction_id(&self) -> Result<Self::TransactionId, TransactionError> {
+        Ok(StellarTransactionId {
+            txid: self.hash()?.to_vec(),
+        })
+    }
+}
+
+fn network_hash(network_id: u8) -> Result<[u8; 32], TransactionError> {
+    Ok(sha256(network_passphrase(network_id)?.as_bytes()))
+}
+
+fn network_id_from_hash(hash: &Hash) -> Result<u8, TransactionError> {
+    match hash.0 {
+        hash if hash == sha256(MAINNET_NETWORK_ID.as_bytes()) => Ok(0),
+        hash if hash == sha256(TESTNET_NETWORK_ID.as_bytes()) => Ok(1),
+        _ => Err(TransactionError::Message(
+            "Unknown network passphrase".to_string(),
+        )),
+    }
+}
+
+/// Returns the passphrase of the network with the given ID
+pub fn network_passphrase(network_id: u8) -> Result<&'static str, TransactionError> {
+    match network_id {
+        0 => Ok(MAINNET_NETWORK_ID),
+        1 => Ok(TESTNET_NETWORK_ID),
+        _ => Err(TransactionError::Message(format!(
+            "Invalid network ID: {}",
+            network_id
+        ))),
+    }
+}
+
+/// Returns the hint of a signature, which is the last 4 bytes of the signer's public key
+pub fn signature_hint(signer: &StellarPublicKey) -> SignatureHint {
+    let mut hint = [0u8; 4];
+    hint.copy_from_slice(&signer.0.as_bytes()[28..]);
+    SignatureHint(hint)
+}
+
+/// Returns true if `signature` is a valid signature of `signer` over `hash`
+pub fn verify_signature(signer: &StellarPublicKey, hash: &[u8; 32], signature: &[u8]) -> bool {
+    match ed25519_dalek::Signature::from_slice(signature) {
+        Ok(signature) => signer.0.verify(hash, &signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Verifies `signature` and appends it to `signatures` unless it is already there
+fn add_signature(
+    signatures: &mut Vec<DecoratedSignature>,
+    hash: &[u8; 32],
+    signer: &StellarPublicKey,
+    signature: Vec<u8>,
+) -> Result<(), TransactionError> {
+    if !verify_signature(signer, hash, &signature) {
+        return Err(TransactionError::Message(format!(
+            "Invalid signature of {}",
+            signer
+        )));
+    }
+    let signature = DecoratedSignature {
+        hint: signature_hint(signer),
+        signature: Signature(
+            BytesM::try_from(signature)
+                .map_err(|e| TransactionError::Crate("add_signature", format!("{e:?}")))?,
+        ),
+    };
+    if signatures.contains(&signature) {
+        return Ok(());
+    }
+    if signatures.len() == MAX_SIGNATURES {
+        return Err(TransactionError::Message(format!(
+            "A transaction takes at most {MAX_SIGNATURES} signatures"
+        )));
+    }
+    signatures.push(signature);
+    Ok(())
+}
+
+/// Returns true if one of `signatures` is a valid signature of `signer` over `hash`
+fn is_signed_by(
+    signatures: &[DecoratedSignature],
+    hash: &[u8; 32],
+    signer: &StellarPublicKey,
+) -> bool {
+    let hint = signature_hint(signer);
+    signatures
+        .iter()
+        .any(|sig| sig.hint == hint && verify_signature(signer, hash, &sig.signature.0))
+}
+
+impl StellarTransaction {
+    /// Returns the hash of the signature payload, which is what every signer signs
+    pub fn hash(&self) -> Result<[u8; 32], TransactionError> {
+        let payload = self
+            .signature_payload()?
+            .to_xdr(Limits::none())
+            .map_err(|e| TransactionError::Crate("hash", format!("{e:?}")))?;
+        Ok(sha256(&payload))
+    }
+
+    /// Verifies the signature of `signer` over the transaction hash and adds it
+    /// along with the signer's hint, so that several accounts can sign
+    pub fn add_signature(
+        &mut self,
+        signer: &StellarPublicKey,
+        signature: Vec<u8>,
+    ) -> Result<(), TransactionError> {
+        let hash = self.hash()?;
+        add_signature(&mut self.signatures, &hash, signer, signature)
+    }
+
+    /// Returns true if the transaction carries a valid signature of `signer`
+    pub fn is_signed_by(&self, signer: &StellarPublicKey) -> Result<bool, TransactionError> {
+        Ok(is_signed_by(&self.signatures, &self.hash()?, signer))
+    }
+
+    fn signature_payload(&self) -> Result<TransactionSignaturePayload, TransactionError> {
+        Ok(TransactionSignaturePayload {
+            network_id: Hash(network_hash(self.params.network_id)?),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(self.to_tx()?),
+        })
+    }
+
+    pub(crate) fn to_envelope(&self) -> Result<TransactionV1Envelope, TransactionError> {
+        Ok(TransactionV1Envelope {
+            tx: self.to_tx()?,
+            signatures: self
+                .signatures
+                .clone()
+                .try_into()
+                .map_err(|e| TransactionError::Crate("to_envelope", format!("{e:?}")))?,
+        })
+    }
+
+    fn from_envelope(
+        envelope: TransactionV1Envelope,
+        network_id: u8,
+    ) -> Result<Self, TransactionError> {
+        Self::from_tx(envelope.tx, network_id, envelope.signatures.to_vec())
+    }
+
+    fn to_tx(&self) -> Result<Tx, TransactionError> {
+        let params = &self.params;
+        let source_account = to_muxed_account(&params.from)?;
+
+        let operations = match params.operations.is_empty() {
+            true => {
+                let op_body = match params.has_account {
+                    true => OperationBody::Payment(PaymentOp {
+                        destination: to_muxed_account(&params.to)?,
+                        asset: Asset::Native,
+                        amount: params.amount,
+                    }),
+                    false => OperationBody::CreateAccount(CreateAccountOp {
+                        destination: to_account_id(&params.to)?,
+                        starting_balance: params.amount,
+                    }),
+                };
+                vec![Operation {
+                    source_account: None,
+                    body: op_body,
+                }]
+            }
+            false => params.operations.clone(),
+        };
+        if operations.len() > MAX_OPERATIONS {
+            return Err(TransactionError::Message(format!(
+                "Too many operations: {}",
+                operations.len()
+            )));
+        }
+
+        // A Soroban operation must be the only operation of a transaction,
+        // which carries the resources the operation may use
+        let is_soroban = operations.iter().any(|op| {
+            matches!(
+                op.body,
+                OperationBody::InvokeHostFunction(_)
+                    | OperationBody::ExtendFootprintTtl(_)
+                    | OperationBody::RestoreFootprint(_)
+            )
+        });
+        let ext = match (&params.soroban_data, is_soroban) {
+            (Some(data), true) if operations.len() == 1 => {
+                if data.resource_fee < 0 || (params.fee as i64) < data.resource_fee {
+                    return Err(TransactionError::Message(format!(
+                        "The fee {} does not cover the resource fee {}",
+                        params.fee, data.resource_fee
+                    )));
+                }
+                TransactionExt::V1(data.clone())
+            }
+            (None, false) => TransactionExt::V0,
+            (None, true) => {
+                return Err(TransactionError::Message(
+                    "A Soroban transaction requires Soroban data".to_string(),
+                ))
+            }
             _ => {
-                return Err(TransactionError::Message(format!(
-                    "Invalid network ID: {}",
-                    self.params.network_id
-                )))
+                return Err(TransactionError::Message(
+                    "A Soroban transaction takes exactly one Soroban operation".to_string(),
+                ))
             }
         };
 
-        let op_body = match self.params.has_account {
-            true => OperationBody::Payment(PaymentOp {
-                destination: destination.clone(),
-                asset: Asset::Native,
-                amount,
-            }),
-            false => {
-                let destination = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(to)));
-                OperationBody::CreateAccount(CreateAccountOp {
-                    destination,
-                    starting_balance: amount,
-                })
+        let cond = match &params.time_bounds {
+            Some(time_bounds) => {
+                if time_bounds.max_time.0 != 0 && time_bounds.max_time.0 < time_bounds.min_time.0 {
+                    return Err(TransactionError::Message("Invalid time bounds".to_string()));
+                }
+                Preconditions::Time(time_bounds.clone())
             }
+            None => Preconditions::None,
         };
 
-        let tx = Tx {
+        Ok(Tx {
             source_account,
-            fee,
-            seq_num,
-            cond: Preconditions::None,
-            memo: Memo::None,
-            ext: TransactionExt::V0,
-            operations: [Operation {
+            fee: params.fee,
+            seq_num: SequenceNumber(params.nonce + 1),
+            cond,
+            memo: params.memo.clone(),
+            ext,
+            operations: operations
+                .try_into()
+                .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?,
+        })
+    }
+
+    fn from_tx(
+        tx: Tx,
+        network_id: u8,
+        signatures: Vec<DecoratedSignature>,
+    ) -> Result<Self, TransactionError> {
+        let from = from_muxed_account(&tx.source_account)?;
+
+        let time_bounds = match tx.cond {
+            Preconditions::None => None,
+            Preconditions::Time(time_bounds) => Some(time_bounds),
+            Preconditions::V2(_) => {
+                return Err(TransactionError::Message(
+                    "Unsupported transaction preconditions".to_string(),
+                ));
+            }
+        };
+
+        // A single native payment or account creation is described by `to`
+        // and `amount`, any other combination of operations is kept as is
+        let single = match tx.operations.as_slice() {
+            [Operation {
                 source_account: None,
-                body: op_body,
-            }]
-            .try_into()
-            .unwrap(),
+                body,
+            }] => match body {
+                OperationBody::Payment(PaymentOp {
+                    destination,
+                    asset: Asset::Native,
+                    amount,
+                }) => Some((from_muxed_account(destination)?, *amount, true)),
+                OperationBody::CreateAccount(CreateAccountOp {
+                    destination: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(pk))),
+                    starting_balance,
+                }) => Some((StellarAddress::from_array(*pk)?, *starting_balance, false)),
+                _ => None,
+            },
+            _ => None,
         };
 
-        match &self.signatures {
-            Some(sigs) => {
-                let mut hint = [0u8; 4];
-                let pk = self.params.from.to_array()?;
-                hint.copy_from_slice(&pk[28..]);
-                let hint = SignatureHint(hint);
-
-                let sig = sigs[0].clone();
-                let sig = BytesM::try_from(sig)
-                    .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?;
-                let sig = Signature(sig);
-                let sig = DecoratedSignature {
-                    hint,
-                    signature: sig,
-                };
+        let (to, amount, has_account, operations) = match single {
+            Some((to, amount, has_account)) => (to, amount, has_account, vec![]),
+            None => (from.clone(), 0, true, tx.operations.to_vec()),
+        };
 
-                let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
-                    tx,
-                    signatures: VecM::try_from(vec![sig])
-                        .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?,
-                });
+        let soroban_data = match tx.ext {
+            TransactionExt::V0 => None,
+            TransactionExt::V1(data) => Some(data),
+        };
 
-                let stream = envelope
-                    .to_xdr(Limits::none())
-                    .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?;
+        Ok(Self {
+            params: StellarTransactionParameters {
+                from,
+                to,
+                has_account,
+                amount,
+                fee: tx.fee,
+                nonce: tx.seq_num.0 - 1,
+                network_id,
+                memo: tx.memo,
+                time_bounds,
+                operations,
+                soroban_data,
+            },
+            signatures,
+        })
+    }
+}
 
-                Ok(stream)
-            }
-            None => {
-                let tagged_transaction = TransactionSignaturePayloadTaggedTransaction::Tx(tx);
-                let network_id = Hash(sha256(network_id.as_bytes()));
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StellarFeeBumpTransactionParameters {
+    /// The account that pays the fee in place of the source account of `inner`
+    pub fee_source: StellarAddress,
+    /// The total fee, which must be at least the fee rate of `inner` times the
+    /// number of operations of `inner` plus one
+    pub fee: i64,
+    /// The signed transaction whose fee is paid
+    pub inner: StellarTransaction,
+}
 
-                let tx = TransactionSignaturePayload {
-                    network_id,
-                    tagged_transaction,
-                };
+/// Represents a fee bump transaction, by which an account pays the fee of
+/// another account's transaction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StellarFeeBumpTransaction {
+    pub params: StellarFeeBumpTransactionParameters,
+    /// The signatures of the fee bump transaction, each carrying the hint of its signer
+    pub signatures: Vec<DecoratedSignature>,
+}
 
-                let stream = tx
-                    .to_xdr(Limits::none())
-                    .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?;
+impl Transaction for StellarFeeBumpTransaction {
+    type Address = StellarAddress;
+    type Format = StellarFormat;
+    type PublicKey = StellarPublicKey;
+    type TransactionId = StellarTransactionId;
+    type TransactionParameters = StellarFeeBumpTransactionParameters;
+
+    fn new(params: &Self::TransactionParameters) -> Result<Self, TransactionError> {
+        let tx = Self {
+            params: params.clone(),
+            signatures: vec![],
+        };
+        tx.to_fee_bump_tx()?;
+        Ok(tx)
+    }
+
+    /// Adds the signature of the fee source over the fee bump transaction hash
+    fn sign(&mut self, rs: Vec<u8>, _: u8) -> Result<Vec<u8>, TransactionError> {
+        let signer = StellarPublicKey::from_address(&self.params.fee_source)
+            .map_err(|e| TransactionError::Crate("sign", format!("{e:?}")))?;
+        self.add_signature(&signer, rs)?;
+        self.to_bytes()
+    }
 
-                Ok(stream)
+    fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let tx = self.to_fee_bump_tx()?;
+        let stream = match self.signatures.is_empty() {
+            true => TransactionSignaturePayload {
+                network_id: Hash(network_hash(self.params.inner.params.network_id)?),
+                tagged_transaction: TransactionSignaturePayloadTaggedTransaction::TxFeeBump(tx),
             }
-        }
+            .to_xdr(Limits::none()),
+            false => TransactionEnvelope::TxFeeBump(FeeBumpTransactionEnvelope {
+                tx,
+                signatures: self
+                    .signatures
+                    .clone()
+                    .try_into()
+                    .map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))?,
+            })
+            .to_xdr(Limits::none()),
+        };
+        stream.map_err(|e| TransactionError::Crate("to_bytes", format!("{e:?}")))
     }
 
     fn from_bytes(tx: &[u8]) -> Result<Self, TransactionError> {
+        if let Ok(payload) = TransactionSignaturePayload::from_xdr(tx, Limits::none()) {
+            let network_id = network_id_from_hash(&payload.network_id)?;
+            return match payload.tagged_transaction {
+                TransactionSignaturePayloadTaggedTransaction::TxFeeBump(tx) => {
+                    Self::from_fee_bump_tx(tx, network_id, vec![])
+                }
+                _ => Err(TransactionError::Message(
+                    "Unsupported transaction payload type".to_string(),
+                )),
+            };
+        }
+
         let envelope = TransactionEnvelope::from_xdr(tx, Limits::none())
             .map_err(|e| TransactionError::Crate("from_bytes", format!("{e:?}")))?;
 
         match envelope {
-            TransactionEnvelope::Tx(TransactionV1Envelope { tx, .. }) => {
-                let source_account = match tx.source_account {
-                    MuxedAccount::Ed25519(Uint256(pk)) => pk,
-                    _ => {
-                        return Err(TransactionError::Message(
-                            "Unsupported source account type".to_string(),
-                        ));
-                    }
-                };
-
-                let (destination, amount, has_account) = match &tx.operations[0].body {
-                    OperationBody::Payment(PaymentOp {
-                        destination,
-                        amount,
-                        ..
-                    }) => match destination {
-                        MuxedAccount::Ed25519(Uint256(pk)) => (pk, *amount, true),
-                        _ => {
-                            return Err(TransactionError::Message(
-                                "Unsupported destination account type".to_string(),
-                            ));
-                        }
-                    },
-                    OperationBody::CreateAccount(CreateAccountOp {
-                        destination,
-                        starting_balance,
-                        ..
-                    }) => match destination {
-                        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(pk))) => {
-                            (pk, *starting_balance, false)
-                        }
-                    },
-                    _ => {
-                        return Err(TransactionError::Message(
-                            "Unsupported operation type".to_string(),
-                        ));
-                    }
-                };
-
-                let from = StellarAddress::from_array(source_account)
-                    .map_err(|e| TransactionError::Crate("from_bytes", format!("{e:?}")))?;
-                let to = StellarAddress::from_array(*destination)
-                    .map_err(|e| TransactionError::Crate("from_bytes", format!("{e:?}")))?;
-
-                let fee = tx.fee;
-                let nonce = tx.seq_num.0 - 1;
-
-                Ok(Self {
-                    params: StellarTransactionParameters {
-                        from,
-                        to,
-                        has_account,
-                        amount,
-                        fee,
-                        nonce,
-                        network_id: 0, // Network ID is not included in the envelope
-                    },
-                    signatures: None, // Signatures are not included in the envelope
-                })
+            // Network ID is not included in the envelope
+            TransactionEnvelope::TxFeeBump(FeeBumpTransactionEnvelope { tx, signatures }) => {
+                Self::from_fee_bump_tx(tx, 0, signatures.to_vec())
             }
             _ => Err(TransactionError::Message(
                 "Unsupported transaction envelope type".to_string(),
@@ -248,10 +545,86 @@ impl Transaction for StellarTransaction {
         }
     }
 
+    /// Returns the fee bump transaction hash, which differs from the hash of the inner transaction
     fn to_transaction_id(&self) -> Result<Self::TransactionId, TransactionError> {
-        let stream = self.to_bytes()?;
-        let txid = sha256(&stream).to_vec();
-        Ok(StellarTransactionId { txid })
+        Ok(StellarTransactionId {
+            txid: self.hash()?.to_vec(),
+        })
+    }
+}
+
+impl StellarFeeBumpTransaction {
+    /// Returns the hash of the signature payload, which is what the fee source signs
+    pub fn hash(&self) -> Result<[u8; 32], TransactionError> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(network_hash(self.params.inner.params.network_id)?),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::TxFeeBump(
+                self.to_fee_bump_tx()?,
+            ),
+        }
+        .to_xdr(Limits::none())
+        .map_err(|e| TransactionError::Crate("hash", format!("{e:?}")))?;
+        Ok(sha256(&payload))
+    }
+
+    /// Verifies the signature of `signer` over the fee bump transaction hash and
+    /// adds it along with the signer's hint
+    pub fn add_signature(
+        &mut self,
+        signer: &StellarPublicKey,
+        signature: Vec<u8>,
+    ) -> Result<(), TransactionError> {
+        let hash = self.hash()?;
+        add_signature(&mut self.signatures, &hash, signer, signature)
+    }
+
+    /// Returns true if the fee bump transaction carries a valid signature of `signer`
+    pub fn is_signed_by(&self, signer: &StellarPublicKey) -> Result<bool, TransactionError> {
+        Ok(is_signed_by(&self.signatures, &self.hash()?, signer))
+    }
+
+    fn to_fee_bump_tx(&self) -> Result<FeeBumpTransaction, TransactionError> {
+        let params = &self.params;
+        let inner = params.inner.to_envelope()?;
+        if inner.signatures.is_empty() {
+            return Err(TransactionError::Message(
+                "The inner transaction is not signed".to_string(),
+            ));
+        }
+
+        // The fee rate of the fee bump transaction, which counts as an extra
+        // operation, cannot be lower than the fee rate of the inner transaction
+        let ops = inner.tx.operations.len() as i128;
+        if params.fee <= 0 || (params.fee as i128) * ops < (inner.tx.fee as i128) * (ops + 1) {
+            return Err(TransactionError::Message(format!(
+                "Insufficient fee bump fee: {}",
+                params.fee
+            )));
+        }
+
+        Ok(FeeBumpTransaction {
+            fee_source: to_muxed_account(&params.fee_source)?,
+            fee: params.fee,
+            inner_tx: FeeBumpTransactionInnerTx::Tx(inner),
+            ext: FeeBumpTransactionExt::V0,
+        })
+    }
+
+    fn from_fee_bump_tx(
+        tx: FeeBumpTransaction,
+        network_id: u8,
+        signatures: Vec<DecoratedSignature>,
+    ) -> Result<Self, TransactionError> {
+        let FeeBumpTransactionInnerTx::Tx(inner) = tx.inner_tx;
+
+        Ok(Self {
+            params: StellarFeeBumpTransactionParameters {
+                fee_source: from_muxed_account(&tx.fee_source)?,
+                fee: tx.fee,
+                inner: StellarTransaction::from_envelope(inner, network_id)?,
+            },
+            signatures,
+        })
     }
 }
 
@@ -261,15 +634,178 @@ impl FromStr for StellarTransaction {
     fn from_str(tx: &str) -> Result<Self, Self::Err> {
         let tx = STANDARD
             .decode(tx)
-            .map_err(|e| TransactionError::Message(e.to_string()))
-            .unwrap();
+            .map_err(|e| TransactionError::Message(e.to_string()))?;
         StellarTransaction::from_bytes(&tx)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::operation::{
+        change_trust, credit_asset, memo_text, path_payment_strict_send, payment,
+    };
+    use ed25519_dalek::{Signer, SigningKey};
+    use stellar_xdr::TimePoint;
+
+    const FROM: &str = "GDB7S3TDJIJSHHRZ3FNFGSAXH7JNXN44PXPPOG6ISIRMPMNFZHBS2AHQ";
+    const TO: &str = "GAVMFTW4Y7EFICACWXKKDEIOKAD3YNXUPMZ77VLDRRZB25FJSHLU6WZR";
+
+    fn params() -> StellarTransactionParameters {
+        StellarTransactionParameters {
+            from: StellarAddress::from_str(FROM).unwrap(),
+            to: StellarAddress::from_str(TO).unwrap(),
+            has_account: true,
+            amount: 10000000,
+            fee: 100,
+            nonce: 1234,
+            network_id: 1,
+            memo: Memo::None,
+            time_bounds: None,
+            operations: vec![],
+            soroban_data: None,
+        }
+    }
+
+    #[test]
+    fn test_tx_gen() {
+        let mut params = params();
+        params.memo = memo_text("invoice 42").unwrap();
+        params.time_bounds = Some(TimeBounds {
+            min_time: TimePoint(0),
+            max_time: TimePoint(1700000000),
+        });
+
+        let tx = StellarTransaction::new(&params).unwrap();
+        let stream = tx.to_bytes().unwrap();
+        assert_eq!(tx, StellarTransaction::from_bytes(&stream).unwrap());
+
+        let decoded = StellarTransaction::from_bytes(&stream).unwrap();
+        assert_eq!(params.memo, decoded.params.memo);
+        assert_eq!(params.time_bounds, decoded.params.time_bounds);
+
+        // Payments to and from muxed accounts
+        let mut muxed = params.clone();
+        muxed.from = StellarAddress::from_muxed(muxed.from.to_array().unwrap(), 1);
+        muxed.to = StellarAddress::from_muxed(muxed.to.to_array().unwrap(), u64::MAX);
+        let muxed = StellarTransaction::new(&muxed).unwrap();
+        assert_eq!(
+            muxed,
+            StellarTransaction::from_bytes(&muxed.to_bytes().unwrap()).unwrap()
+        );
+
+        // The signature must be made by the source account
+        let mut signed = tx.clone();
+        assert!(signed.sign(vec![1u8; 64], 0).is_err());
+
+        params.time_bounds = Some(TimeBounds {
+            min_time: TimePoint(10),
+            max_time: TimePoint(5),
+        });
+        assert!(StellarTransaction::new(&params)
+            .unwrap()
+            .to_bytes()
+            .is_err());
+    }
+
+    #[test]
+    fn test_operations() {
+        let from = StellarAddress::from_str(FROM).unwrap();
+        let to = StellarAddress::from_str(TO).unwrap();
+        let usdc = credit_asset("USDC", &to).unwrap();
+
+        let operations = vec![
+            change_trust(None, usdc.clone(), i64::MAX).unwrap(),
+            payment(Some(&to), &from, usdc.clone(), 5000000).unwrap(),
+            path_payment_strict_send(Some(&from), Asset::Native, 1000, &to, usdc, 900, vec![])
+                .unwrap(),
+        ];
+        let mut params =
+            StellarTransactionParameters::with_operations(from, 99, 0, 100, operations.clone())
+                .unwrap();
+        params.memo = Memo::Id(7);
+        assert_eq!(300, params.fee);
+
+        let tx = StellarTransaction::new(&params).unwrap();
+        let decoded = StellarTransaction::from_bytes(&tx.to_bytes().unwrap()).unwrap();
+        assert_eq!(tx, decoded);
+        assert_eq!(operations, decoded.params.operations);
+        assert!(decoded.params.operations[1].source_account.is_some());
+    }
+
+    fn key(seed: u8) -> (SigningKey, StellarPublicKey, StellarAddress) {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let public_key = StellarPublicKey(key.verifying_key());
+        let address = StellarAddress::from_array(key.verifying_key().to_bytes()).unwrap();
+        (key, public_key, address)
+    }
+
+    #[test]
+    fn test_multisig() {
+        let (source, source_pk, from) = key(1);
+        let (cosigner, cosigner_pk, _) = key(2);
+        let mut params = params();
+        params.from = from;
+
+        let mut tx = StellarTransaction::new(&params).unwrap();
+        let hash = tx.hash().unwrap();
+        tx.sign(source.sign(&hash).to_bytes().to_vec(), 0).unwrap();
+        tx.add_signature(&cosigner_pk, cosigner.sign(&hash).to_bytes().to_vec())
+            .unwrap();
+        // Adding the same signature twice has no effect
+        tx.add_signature(&cosigner_pk, cosigner.sign(&hash).to_bytes().to_vec())
+            .unwrap();
+        assert!(tx
+            .add_signature(&cosigner_pk, source.sign(&hash).to_bytes().to_vec())
+            .is_err());
+
+        assert_eq!(2, tx.signatures.len());
+        assert_eq!(signature_hint(&source_pk), tx.signatures[0].hint);
+        assert_eq!(signature_hint(&cosigner_pk), tx.signatures[1].hint);
+        assert_eq!(hash.to_vec(), tx.to_transaction_id().unwrap().txid);
+
+        let mut decoded = StellarTransaction::from_bytes(&tx.to_bytes().unwrap()).unwrap();
+        decoded.params.network_id = 1;
+        assert_eq!(tx, decoded);
+        assert!(decoded.is_signed_by(&source_pk).unwrap());
+        assert!(decoded.is_signed_by(&cosigner_pk).unwrap());
+    }
 
     #[test]
-    fn test_tx_gen() {}
+    fn test_fee_bump() {
+        let (source, _, from) = key(1);
+        let (sponsor, sponsor_pk, fee_source) = key(3);
+        let mut params = params();
+        params.from = from;
+
+        let mut inner = StellarTransaction::new(&params).unwrap();
+        let mut fee_bump_params = StellarFeeBumpTransactionParameters {
+            fee_source,
+            fee: 200,
+            inner: inner.clone(),
+        };
+        // The inner transaction must be signed
+        assert!(StellarFeeBumpTransaction::new(&fee_bump_params).is_err());
+
+        let sig = source.sign(&inner.hash().unwrap()).to_bytes().to_vec();
+        inner.sign(sig, 0).unwrap();
+        fee_bump_params.inner = inner.clone();
+        fee_bump_params.fee = 199;
+        assert!(StellarFeeBumpTransaction::new(&fee_bump_params).is_err());
+        fee_bump_params.fee = 200;
+
+        let mut tx = StellarFeeBumpTransaction::new(&fee_bump_params).unwrap();
+        let stream = tx.to_bytes().unwrap();
+        assert_eq!(tx, StellarFeeBumpTransaction::from_bytes(&stream).unwrap());
+
+        let hash = tx.hash().unwrap();
+        assert_ne!(hash, inner.hash().unwrap());
+        tx.sign(sponsor.sign(&hash).to_bytes().to_vec(), 0).unwrap();
+        assert!(tx.is_signed_by(&sponsor_pk).unwrap());
+
+        let mut decoded = StellarFeeBumpTransaction::from_bytes(&tx.to_bytes().unwrap()).unwrap();
+        decoded.params.inner.params.network_id = 1;
+        assert_eq!(tx, decoded);
+        assert!(StellarTransaction::from_bytes(&tx.to_bytes().unwrap()).is_err());
+    }
 }
@@ -12,4 +12,6 @@ pub mod protocol;
 pub mod transaction;
 pub use transaction::*;
 pub mod abi;
+pub mod tip191;
+pub mod tip712;
 pub mod trx;
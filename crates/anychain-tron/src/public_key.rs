@@ -29,6 +29,25 @@ impl TronPublicKey {
     pub fn to_secp256k1_public_key(&self) -> libsecp256k1::PublicKey {
         self.0
     }
+
+    /// Recovers the public key that produced the signature `(r, s, v)` over a 32-byte digest,
+    /// `v` can either be the raw recovery id (0/1) or the TronWeb form (27/28)
+    pub fn recover(digest: &[u8], rs: &[u8], v: u8) -> Result<Self, PublicKeyError> {
+        let recid = match v {
+            0 | 1 => v,
+            27 | 28 => v - 27,
+            _ => return Err(PublicKeyError::InvalidRecoveryId(v)),
+        };
+        let msg = libsecp256k1::Message::parse_slice(digest)
+            .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{:?}", error)))?;
+        let sig = libsecp256k1::Signature::parse_standard_slice(rs)
+            .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{:?}", error)))?;
+        let recid = libsecp256k1::RecoveryId::parse(recid)
+            .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{:?}", error)))?;
+        let public_key = libsecp256k1::recover(&msg, &sig, &recid)
+            .map_err(|error| PublicKeyError::Crate("libsecp256k1", format!("{:?}", error)))?;
+        Ok(Self(public_key))
+    }
 }
 
 impl FromStr for TronPublicKey {
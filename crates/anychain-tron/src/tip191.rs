@@ -0,0 +1,97 @@
+use crate::{TronAddress, TronFormat, TronPublicKey};
+use anychain_core::utilities::crypto::keccak256;
+use anychain_core::{PublicKey, TransactionError};
+
+/// The prefix TronWeb's `signMessageV2` prepends to a message before hashing it
+pub const TRON_MESSAGE_PREFIX: &str = "\x19TRON Signed Message:\n";
+
+/// Returns the digest to be signed for a `signMessageV2` message, i.e.
+/// keccak256("\x19TRON Signed Message:\n" + len(message) + message)
+pub fn hash_message(message: &[u8]) -> [u8; 32] {
+    let stream = [
+        TRON_MESSAGE_PREFIX.as_bytes(),
+        message.len().to_string().as_bytes(),
+        message,
+    ]
+    .concat();
+    keccak256(&stream)
+}
+
+/// Encodes a secp256k1 signature the way TronWeb does, i.e. r || s || (recid + 27)
+pub fn encode_signature(rs: &[u8], recid: u8) -> Vec<u8> {
+    let mut signature = rs.to_vec();
+    signature.push(recid + 27);
+    signature
+}
+
+/// Recovers the address of the signer of a 65-byte (r || s || v) signature over a digest
+pub fn recover_address(digest: &[u8], signature: &[u8]) -> Result<TronAddress, TransactionError> {
+    if signature.len() != 65 {
+        return Err(TransactionError::Message(format!(
+            "invalid signature length {}",
+            signature.len()
+        )));
+    }
+    let public_key = TronPublicKey::recover(digest, &signature[..64], signature[64])
+        .map_err(|e| TransactionError::Message(e.to_string()))?;
+    Ok(public_key.to_address(&TronFormat::Standard)?)
+}
+
+/// Returns the address of the signer of a `signMessageV2` signature
+pub fn recover_message_address(
+    message: &[u8],
+    signature: &[u8],
+) -> Result<TronAddress, TransactionError> {
+    recover_address(&hash_message(message), signature)
+}
+
+/// Checks whether a `signMessageV2` signature was produced by the given address
+pub fn verify_message(
+    message: &[u8],
+    signature: &[u8],
+    address: &TronAddress,
+) -> Result<bool, TransactionError> {
+    Ok(recover_message_address(message, signature)? == *address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anychain_core::Address;
+
+    #[test]
+    fn test_hash_message() {
+        let digest = hash_message("hello world".as_bytes());
+        assert_eq!(
+            "cf02daeb2bea196ed5692322a66ed50080ce74ff8cb711199f1b04f3c13bc10d",
+            hex::encode(digest)
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_message() {
+        let sk = "3d98c2d5a7f737693b470114816000645419af49bd21258cc99142f6ef5fd60a";
+        let sk = libsecp256k1::SecretKey::parse_slice(&hex::decode(sk).unwrap()).unwrap();
+        let address = TronAddress::from_secret_key(&sk, &TronFormat::Standard).unwrap();
+
+        let message = "hello world".as_bytes();
+        let digest = libsecp256k1::Message::parse(&hash_message(message));
+        let (sig, recid) = libsecp256k1::sign(&digest, &sk);
+        let signature = encode_signature(&sig.serialize(), recid.serialize());
+
+        assert_eq!(65, signature.len());
+        assert!(signature[64] == 27 || signature[64] == 28);
+        assert!(verify_message(message, &signature, &address).unwrap());
+        assert!(!verify_message("hello tron".as_bytes(), &signature, &address).unwrap());
+        assert!(recover_message_address(message, &signature[..64]).is_err());
+
+        let mut invalid = signature.clone();
+        invalid[64] = 29;
+        assert_eq!(
+            "invalid recovery id: 29",
+            recover_message_address(message, &invalid)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,421 @@
+use crate::{tip191, TronAddress};
+use anychain_core::utilities::crypto::keccak256;
+use anychain_core::TransactionError;
+use ethereum_types::U256;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// A member of a TIP-712 struct type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// Represents TIP-712 typed data in the JSON form accepted by TronWeb's
+/// `_signTypedData` and TronLink's `tron_signTypedData`.
+///
+/// TIP-712 is EIP-712 with two differences: `address` values are Tron addresses
+/// (encoded by their 20-byte body), and `trcToken` is accepted as an alias of `uint256`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    pub primary_type: String,
+    pub domain: Map<String, Value>,
+    pub message: Value,
+}
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// The domain fields in the order TronWeb lays them out when the
+/// caller does not provide an explicit `EIP712Domain` type
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+impl FromStr for TypedData {
+    type Err = TransactionError;
+
+    fn from_str(json: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl TypedData {
+    /// Returns the digest to be signed, i.e.
+    /// keccak256("\x19\x01" || domainSeparator || hashStruct(message))
+    pub fn digest(&self) -> Result<[u8; 32], TransactionError> {
+        let mut stream = vec![0x19, 0x01];
+        stream.extend(self.domain_separator()?);
+        if self.primary_type != DOMAIN_TYPE {
+            stream.extend(self.hash_struct(&self.primary_type, &self.message)?);
+        }
+        Ok(keccak256(&stream))
+    }
+
+    /// Returns hashStruct(domain)
+    pub fn domain_separator(&self) -> Result<[u8; 32], TransactionError> {
+        self.hash_struct(DOMAIN_TYPE, &Value::Object(self.domain.clone()))
+    }
+
+    /// Returns hashStruct(value) of the given struct type
+    pub fn hash_struct(&self, type_: &str, value: &Value) -> Result<[u8; 32], TransactionError> {
+        Ok(keccak256(&self.encode_data(type_, value)?))
+    }
+
+    /// Returns keccak256(encodeType(type_))
+    pub fn type_hash(&self, type_: &str) -> Result<[u8; 32], TransactionError> {
+        Ok(keccak256(self.encode_type(type_)?.as_bytes()))
+    }
+
+    /// Returns encodeType(type_), the primary type followed by
+    /// the types it references in alphabetical order
+    pub fn encode_type(&self, type_: &str) -> Result<String, TransactionError> {
+        let mut deps = BTreeSet::new();
+        self.dependencies(type_, &mut deps)?;
+        deps.remove(type_);
+
+        let mut encoded = String::new();
+        for dep in [type_.to_string()].iter().chain(deps.iter()) {
+            let fields = self
+                .fields(dep)
+                .iter()
+                .map(|field| format!("{} {}", field.type_, field.name))
+                .collect::<Vec<String>>()
+                .join(",");
+            encoded.push_str(&format!("{}({})", dep, fields));
+        }
+        Ok(encoded)
+    }
+
+    /// Returns encodeData(value) of the given struct type, prefixed by its type hash
+    pub fn encode_data(&self, type_: &str, value: &Value) -> Result<Vec<u8>, TransactionError> {
+        let object = value.as_object().ok_or_else(|| {
+            TransactionError::Message(format!("value of type {} is not an object", type_))
+        })?;
+
+        let mut encoded = self.type_hash(type_)?.to_vec();
+        for field in self.fields(type_) {
+            let value = object.get(&field.name).unwrap_or(&Value::Null);
+            encoded.extend(self.encode_value(&field.type_, value)?);
+        }
+        Ok(encoded)
+    }
+
+    fn fields(&self, type_: &str) -> Vec<TypedDataField> {
+        match self.types.get(type_) {
+            Some(fields) => fields.clone(),
+            None if type_ == DOMAIN_TYPE => DOMAIN_FIELDS
+                .iter()
+                .filter(|(name, _)| self.domain.contains_key(*name))
+                .map(|(name, type_)| TypedDataField {
+                    name: name.to_string(),
+                    type_: type_.to_string(),
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn is_struct(&self, type_: &str) -> bool {
+        self.types.contains_key(type_) || type_ == DOMAIN_TYPE
+    }
+
+    fn dependencies(
+        &self,
+        type_: &str,
+        deps: &mut BTreeSet<String>,
+    ) -> Result<(), TransactionError> {
+        let type_ = base_type(type_);
+        if deps.contains(type_) || !self.is_struct(type_) {
+            return Ok(());
+        }
+        deps.insert(type_.to_string());
+        for field in self.fields(type_) {
+            self.dependencies(&field.type_, deps)?;
+        }
+        Ok(())
+    }
+
+    fn encode_value(&self, type_: &str, value: &Value) -> Result<[u8; 32], TransactionError> {
+        if let Some(inner) = array_item_type(type_) {
+            let items = value.as_array().ok_or_else(|| {
+                TransactionError::Message(format!("value of type {} is not an array", type_))
+            })?;
+            let mut stream = vec![];
+            for item in items {
+                stream.extend(self.encode_value(inner, item)?);
+            }
+            return Ok(keccak256(&stream));
+        }
+
+        if self.is_struct(type_) {
+            return self.hash_struct(type_, value);
+        }
+
+        match type_ {
+            "string" => Ok(keccak256(as_str(type_, value)?.as_bytes())),
+            "bytes" => Ok(keccak256(&decode_hex(as_str(type_, value)?)?)),
+            "bool" => {
+                let value = value.as_bool().ok_or_else(|| {
+                    TransactionError::Message(format!("invalid bool value {}", value))
+                })?;
+                Ok(to_word(U256::from(value as u8)))
+            }
+            "address" => {
+                let address = parse_address(as_str(type_, value)?)?;
+                let mut word = [0u8; 32];
+                word[12..].copy_from_slice(&address.as_bytes()[1..]);
+                Ok(word)
+            }
+            "trcToken" => Ok(to_word(parse_uint(value)?)),
+            _ if type_.starts_with("uint") => Ok(to_word(parse_uint(value)?)),
+            _ if type_.starts_with("int") => Ok(to_word(parse_int(value)?)),
+            _ if type_.starts_with("bytes") => {
+                let len = type_[5..].parse::<usize>()?;
+                let bytes = decode_hex(as_str(type_, value)?)?;
+                if len == 0 || len > 32 || bytes.len() != len {
+                    return Err(TransactionError::Message(format!(
+                        "invalid {} value of {} bytes",
+                        type_,
+                        bytes.len()
+                    )));
+                }
+                let mut word = [0u8; 32];
+                word[..len].copy_from_slice(&bytes);
+                Ok(word)
+            }
+            _ => Err(TransactionError::Message(format!(
+                "unsupported type {}",
+                type_
+            ))),
+        }
+    }
+}
+
+/// Returns the element type of an array type, e.g. `Person` for `Person[]` or `Person[2]`
+fn array_item_type(type_: &str) -> Option<&str> {
+    match type_.ends_with(']') {
+        true => type_.rfind('[').map(|index| &type_[..index]),
+        false => None,
+    }
+}
+
+/// Strips all array suffixes from a type, e.g. `Person` for `Person[][2]`
+fn base_type(type_: &str) -> &str {
+    match type_.find('[') {
+        Some(index) => &type_[..index],
+        None => type_,
+    }
+}
+
+fn as_str<'a>(type_: &str, value: &'a Value) -> Result<&'a str, TransactionError> {
+    value
+        .as_str()
+        .ok_or_else(|| TransactionError::Message(format!("invalid {} value {}", type_, value)))
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, TransactionError> {
+    Ok(hex::decode(s.strip_prefix("0x").unwrap_or(s))?)
+}
+
+fn to_word(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// Accepts base58 Tron addresses, 41-prefixed hex and 0x-prefixed 20-byte hex
+fn parse_address(s: &str) -> Result<TronAddress, TransactionError> {
+    if s.len() == 42 && (s.starts_with("0x") || s.starts_with("0X")) {
+        let mut raw = vec![0x41];
+        raw.extend(hex::decode(&s[2..])?);
+        return Ok(TronAddress::try_from(raw)?);
+    }
+    Ok(TronAddress::from_str(s)?)
+}
+
+fn parse_uint(value: &Value) -> Result<U256, TransactionError> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| TransactionError::Message(format!("invalid uint value {}", n))),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(s) => U256::from_str_radix(s, 16)
+                .map_err(|e| TransactionError::Message(format!("{:?}", e))),
+            None => U256::from_dec_str(s).map_err(|e| TransactionError::Message(e.to_string())),
+        },
+        _ => Err(TransactionError::Message(format!(
+            "invalid uint value {}",
+            value
+        ))),
+    }
+}
+
+fn parse_int(value: &Value) -> Result<U256, TransactionError> {
+    let (negative, magnitude) = match value {
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => (n < 0, Value::from(n.unsigned_abs())),
+            None => {
+                return Err(TransactionError::Message(format!(
+                    "invalid int value {}",
+                    n
+                )))
+            }
+        },
+        Value::String(s) => match s.strip_prefix('-') {
+            Some(s) => (true, Value::from(s)),
+            None => (false, value.clone()),
+        },
+        _ => (false, value.clone()),
+    };
+    let magnitude = parse_uint(&magnitude)?;
+    match negative {
+        // two's complement
+        true => Ok((!magnitude).overflowing_add(U256::one()).0),
+        false => Ok(magnitude),
+    }
+}
+
+/// Recovers the address of the signer of a TIP-712 signature
+pub fn recover_typed_data_address(
+    typed_data: &TypedData,
+    signature: &[u8],
+) -> Result<TronAddress, TransactionError> {
+    tip191::recover_address(&typed_data.digest()?, signature)
+}
+
+/// Checks whether a TIP-712 signature was produced by the given address
+pub fn verify_typed_data(
+    typed_data: &TypedData,
+    signature: &[u8],
+    address: &TronAddress,
+) -> Result<bool, TransactionError> {
+    Ok(recover_typed_data_address(typed_data, signature)? == *address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The `Mail` example of EIP-712, with the addresses written as Tron addresses
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "41cccccccccccccccccccccccccccccccccccccccc"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "41bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_encode_type() {
+        let typed_data = TypedData::from_str(MAIL).unwrap();
+        assert_eq!(
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+            typed_data.encode_type("Mail").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_digest() {
+        let typed_data = TypedData::from_str(MAIL).unwrap();
+        assert_eq!(
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f",
+            hex::encode(typed_data.domain_separator().unwrap())
+        );
+        assert_eq!(
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+            hex::encode(typed_data.digest().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_implicit_domain_type() {
+        let mut typed_data = TypedData::from_str(MAIL).unwrap();
+        typed_data.types.remove(DOMAIN_TYPE);
+        assert_eq!(
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+            hex::encode(typed_data.digest().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_verify_typed_data() {
+        let typed_data = TypedData::from_str(MAIL).unwrap();
+        let signature = "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+                         07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562\
+                         1c";
+        let signature = hex::decode(signature).unwrap();
+        let signer = TronAddress::from_str("41cd2a3d9f938e13cd947ec05abc7fe734df8dd826").unwrap();
+
+        assert_eq!(
+            signer,
+            recover_typed_data_address(&typed_data, &signature).unwrap()
+        );
+        assert!(verify_typed_data(&typed_data, &signature, &signer).unwrap());
+    }
+
+    #[test]
+    fn test_trc_token_and_arrays() {
+        let json = r#"{
+            "types": {
+                "Transfer": [
+                    {"name": "tokenId", "type": "trcToken"},
+                    {"name": "amounts", "type": "int64[]"},
+                    {"name": "memo", "type": "bytes"}
+                ]
+            },
+            "primaryType": "Transfer",
+            "domain": {"name": "TRC", "chainId": "0x2b6653dc"},
+            "message": {"tokenId": "1002000", "amounts": [1, -1], "memo": "0x0102"}
+        }"#;
+        let typed_data = TypedData::from_str(json).unwrap();
+        assert_eq!(
+            "Transfer(trcToken tokenId,int64[] amounts,bytes memo)",
+            typed_data.encode_type("Transfer").unwrap()
+        );
+
+        let encoded = typed_data
+            .encode_data("Transfer", &typed_data.message)
+            .unwrap();
+        assert_eq!(4 * 32, encoded.len());
+        assert_eq!(U256::from(1002000), U256::from_big_endian(&encoded[32..64]));
+
+        let minus_one = typed_data.encode_value("int64", &Value::from(-1)).unwrap();
+        assert_eq!([0xffu8; 32], minus_one);
+        assert!(typed_data.digest().is_ok());
+    }
+}